@@ -1,6 +1,101 @@
 use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use sflow_parser::encode::encode_datagram;
+use sflow_parser::models::record_counters::GenericInterfaceCounters;
+use sflow_parser::models::record_flows::SampledHeader;
+use sflow_parser::models::*;
 use sflow_parser::parse_datagram;
 use std::hint::black_box;
+use std::net::Ipv4Addr;
+
+/// Encode a datagram holding one sample
+fn single_sample_datagram(sample_type: u32, sample_data: SampleData) -> Vec<u8> {
+    let agent = Address::IPv4(Ipv4Addr::new(192, 0, 2, 1));
+    let mut datagram = SFlowDatagram::new(agent, 0, 1, 1000);
+    datagram.samples.push(SampleRecord {
+        sample_type: DataFormat::new(0, sample_type),
+        sample_data,
+    });
+    encode_datagram(&datagram).expect("benchmark fixtures are encodable")
+}
+
+/// Encode a datagram holding one flow sample with the given records
+fn flow_datagram(records: impl Iterator<Item = FlowData>) -> Vec<u8> {
+    let sample = FlowSample {
+        sequence_number: 1,
+        source_id: DataSource(1),
+        sampling_rate: 1024,
+        sample_pool: 1024,
+        drops: 0,
+        input: Interface(1),
+        output: Interface(2),
+        flow_records: records
+            .map(|flow_data| FlowRecord {
+                flow_format: flow_data.data_format(),
+                flow_data,
+            })
+            .collect(),
+    };
+    single_sample_datagram(1, SampleData::FlowSample(sample))
+}
+
+/// Datagram with one counter sample holding `count` generic interface records
+fn counters_fixture(count: u32) -> Vec<u8> {
+    let counters = (1..=count)
+        .map(|if_index| {
+            let counters = GenericInterfaceCounters {
+                if_index,
+                if_type: 6,
+                if_speed: 1_000_000_000,
+                if_direction: 1,
+                if_status: 3,
+                if_in_octets: 10_000,
+                if_in_ucast_pkts: 100,
+                if_in_multicast_pkts: 10,
+                if_in_broadcast_pkts: 5,
+                if_in_discards: 0,
+                if_in_errors: 0,
+                if_in_unknown_protos: 0,
+                if_out_octets: 20_000,
+                if_out_ucast_pkts: 200,
+                if_out_multicast_pkts: 20,
+                if_out_broadcast_pkts: 10,
+                if_out_discards: 0,
+                if_out_errors: 0,
+                if_promiscuous_mode: 0,
+            };
+            CounterRecord {
+                counter_format: DataFormat::new(0, 1),
+                counter_data: CounterData::GenericInterface(counters),
+            }
+        })
+        .collect();
+    let sample = CountersSample {
+        sequence_number: 1,
+        source_id: DataSource(1),
+        counters,
+    };
+    single_sample_datagram(2, SampleData::CountersSample(sample))
+}
+
+/// Datagram with one flow sample holding `count` sampled header records
+fn sampled_header_fixture(count: usize, header_length: usize) -> Vec<u8> {
+    let record = SampledHeader {
+        protocol: HeaderProtocol::EthernetIso88023,
+        frame_length: 1500,
+        stripped: 4,
+        header: (0..header_length).map(|i| i as u8).collect(),
+    };
+    flow_datagram(std::iter::repeat_n(FlowData::SampledHeader(record), count))
+}
+
+/// Datagram with one flow sample holding `count` records of an unknown vendor format
+fn unknown_records_fixture(count: usize) -> Vec<u8> {
+    let record = FlowData::Unknown {
+        format: DataFormat::new(9999, 1),
+        data: vec![0xAB; 64],
+    };
+    flow_datagram(std::iter::repeat_n(record, count))
+}
 
 /// Benchmark parsing a real sFlow datagram from test data
 fn bench_parse_real_datagram(c: &mut Criterion) {
@@ -67,10 +162,34 @@ fn bench_memory_allocation(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark parsing synthetic datagrams
+fn bench_parse_fixtures(c: &mut Criterion) {
+    let fixtures = [
+        ("counters_50_generic_interface", counters_fixture(50)),
+        (
+            "flows_50_sampled_header_128b",
+            sampled_header_fixture(50, 128),
+        ),
+        ("flows_50_unknown", unknown_records_fixture(50)),
+    ];
+
+    let mut group = c.benchmark_group("fixtures");
+
+    for (name, data) in &fixtures {
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        group.bench_function(*name, |b| {
+            b.iter(|| parse_datagram(black_box(data)));
+        });
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_parse_real_datagram,
     bench_parse_multiple_datagrams,
-    bench_memory_allocation
+    bench_memory_allocation,
+    bench_parse_fixtures
 );
 criterion_main!(benches);
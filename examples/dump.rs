@@ -0,0 +1,58 @@
+//! Dump the sFlow datagrams of a file
+//!
+//! The file holds one or more raw datagrams back to back, as written by
+//! `tcpdump -w` payload extraction or a collector's raw dump:
+//!
+//! ```text
+//! cargo run --example dump -- tests/data/sflow.bin
+//! cargo run --example dump --features pretty -- tests/data/sflow.bin
+//! ```
+//!
+//! Each datagram is printed with its `Display` summary and one line per
+//! sample, or as a full tree with the `pretty` feature.
+
+use sflow_parser::{parse_datagrams, SFlowDatagram};
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+/// Parse the datagrams of `path` and write them to `out`
+///
+/// Returns the number of datagrams written.
+pub fn dump(path: &Path, out: &mut impl Write) -> Result<usize, Box<dyn std::error::Error>> {
+    let data = std::fs::read(path)?;
+    let datagrams = parse_datagrams(&data)?;
+    for datagram in &datagrams {
+        write_datagram(datagram, out)?;
+    }
+    Ok(datagrams.len())
+}
+
+#[cfg(feature = "pretty")]
+fn write_datagram(datagram: &SFlowDatagram, out: &mut impl Write) -> io::Result<()> {
+    write!(out, "{}", sflow_parser::pretty::pretty_print(datagram))
+}
+
+#[cfg(not(feature = "pretty"))]
+fn write_datagram(datagram: &SFlowDatagram, out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "{}", datagram)?;
+    for sample in &datagram.samples {
+        writeln!(out, "  sample {}", sample.sample_type)?;
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let Some(path) = std::env::args_os().nth(1) else {
+        eprintln!("usage: dump <file>");
+        return ExitCode::FAILURE;
+    };
+
+    match dump(Path::new(&path), &mut io::stdout().lock()) {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}: {}", Path::new(&path).display(), e);
+            ExitCode::FAILURE
+        }
+    }
+}
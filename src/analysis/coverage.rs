@@ -0,0 +1,113 @@
+//! Parser coverage gaps
+//!
+//! Records the parser does not know are kept as `Unknown` variants. Counting
+//! them over real traffic shows which formats are worth supporting next.
+
+use crate::models::*;
+use std::collections::HashMap;
+
+/// Kind of an unknown format: flow and counter records share the numbering
+/// of their (enterprise, format) pairs, samples have their own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FormatKind {
+    /// Sample format
+    Sample,
+    /// Flow record format
+    Flow,
+    /// Counter record format
+    Counter,
+}
+
+/// Accumulator of the unknown formats seen in parsed datagrams
+///
+/// Unknown samples, flow records and counter records are counted by kind
+/// and (enterprise, format) pair: flow format (0,2000) and counter format
+/// (0,2000) are different records. Flow records nested in other records
+/// (the PDUs of an 802.11 aggregation) are counted too.
+#[derive(Debug, Clone, Default)]
+pub struct CoverageReport {
+    unknown: HashMap<(FormatKind, u32, u32), usize>,
+}
+
+impl CoverageReport {
+    /// Create an empty report
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count the unknown samples and records of a datagram
+    pub fn observe(&mut self, datagram: &SFlowDatagram) {
+        for sample in &datagram.samples {
+            match &sample.sample_data {
+                SampleData::FlowSample(s) => self.observe_flows(&s.flow_records),
+                SampleData::FlowSampleExpanded(s) => self.observe_flows(&s.flow_records),
+                SampleData::DiscardedPacket(s) => self.observe_flows(&s.flow_records),
+                SampleData::CountersSample(s) => self.observe_counters(&s.counters),
+                SampleData::CountersSampleExpanded(s) => self.observe_counters(&s.counters),
+                SampleData::Unknown { format, .. } => self.count(FormatKind::Sample, *format),
+                _ => {}
+            }
+        }
+    }
+
+    fn observe_flows(&mut self, records: &[FlowRecord]) {
+        for record in records {
+            match &record.flow_data {
+                FlowData::Unknown { format, .. } => self.count(FormatKind::Flow, *format),
+                FlowData::Extended80211Aggregation(aggregation) => {
+                    for pdu in &aggregation.pdus {
+                        self.observe_flows(&pdu.flow_records);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn observe_counters(&mut self, records: &[CounterRecord]) {
+        for record in records {
+            if let CounterData::Unknown { format, .. } = &record.counter_data {
+                self.count(FormatKind::Counter, *format);
+            }
+        }
+    }
+
+    /// Unknown (enterprise, format) pairs with their number of occurrences
+    ///
+    /// Samples, flow records and counter records sharing a pair are added
+    /// up, see [`unknown_formats_by_kind`](Self::unknown_formats_by_kind) to
+    /// tell them apart. Sorted by decreasing count, ties by format.
+    pub fn unknown_formats(&self) -> Vec<((u32, u32), usize)> {
+        let mut totals: HashMap<(u32, u32), usize> = HashMap::new();
+        for (&(_, enterprise, format), count) in &self.unknown {
+            *totals.entry((enterprise, format)).or_default() += count;
+        }
+        sorted(totals)
+    }
+
+    /// Unknown (kind, enterprise, format) triples with their number of occurrences
+    ///
+    /// Sorted by decreasing count, ties by kind and format.
+    pub fn unknown_formats_by_kind(&self) -> Vec<((FormatKind, u32, u32), usize)> {
+        sorted(self.unknown.clone())
+    }
+
+    /// Total number of unknown samples and records seen
+    pub fn total_unknown(&self) -> usize {
+        self.unknown.values().sum()
+    }
+
+    fn count(&mut self, kind: FormatKind, format: DataFormat) {
+        *self
+            .unknown
+            .entry((kind, format.enterprise(), format.format()))
+            .or_default() += 1;
+    }
+}
+
+/// Counts sorted by decreasing count, ties by key
+fn sorted<K: Ord>(counts: HashMap<K, usize>) -> Vec<(K, usize)> {
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    counts
+}
@@ -0,0 +1,14 @@
+//! Analysis helpers over parsed sFlow data
+//!
+//! These helpers are stateful and work on already parsed datagrams, or on
+//! records handed over by the visitor API.
+
+mod coverage;
+mod octets;
+mod rates;
+mod store;
+
+pub use coverage::{CoverageReport, FormatKind};
+pub use octets::{InterfaceOctetAggregator, InterfaceOctets};
+pub use rates::{CounterRateCalculator, InterfaceRates, RateKey};
+pub use store::CounterStore;
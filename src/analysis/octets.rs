@@ -0,0 +1,110 @@
+//! Interface octet aggregation through the visitor API
+//!
+//! Collectors that only chart interface traffic do not need whole
+//! datagrams. [`InterfaceOctetAggregator`] is a [`DatagramVisitor`] that
+//! keeps the latest octet counters of each interface and skips every other
+//! record without decoding it.
+
+use crate::models::record_counters::GenericInterfaceCounters;
+use crate::models::*;
+use crate::parsers::DatagramVisitor;
+use std::collections::HashMap;
+
+/// Latest octet counters of an interface
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InterfaceOctets {
+    /// Received octets (ifInOctets)
+    pub in_octets: u64,
+
+    /// Transmitted octets (ifOutOctets)
+    pub out_octets: u64,
+}
+
+/// Visitor collecting interface octet counters per (agent, ifIndex)
+///
+/// Feed it datagrams with [`parse_datagram_visit`](crate::parsers::parse_datagram_visit).
+/// Only generic interface counters records (0,1) are decoded; each one
+/// replaces the values stored for its interface, so the aggregator holds
+/// the most recently received counters.
+///
+/// ```
+/// use sflow_parser::analysis::InterfaceOctetAggregator;
+/// use sflow_parser::parsers::{parse_datagram_visit, ParseOptions};
+///
+/// let options = ParseOptions::default();
+/// let mut octets = InterfaceOctetAggregator::new();
+/// # let datagrams: Vec<Vec<u8>> = Vec::new();
+/// for data in &datagrams {
+///     parse_datagram_visit(data, &options, &mut octets)?;
+/// }
+/// for ((agent, if_index), counters) in octets.iter() {
+///     println!("{} {}: in {} out {}", agent, if_index, counters.in_octets, counters.out_octets);
+/// }
+/// # Ok::<(), sflow_parser::ParseError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InterfaceOctetAggregator {
+    agent: Option<Address>,
+    octets: HashMap<(Address, u32), InterfaceOctets>,
+}
+
+impl InterfaceOctetAggregator {
+    /// Create an empty aggregator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Latest octet counters of an interface
+    pub fn get(&self, agent: &Address, if_index: u32) -> Option<InterfaceOctets> {
+        self.octets.get(&(agent.clone(), if_index)).copied()
+    }
+
+    /// Iterate over the latest counters of every interface
+    pub fn iter(&self) -> impl Iterator<Item = ((&Address, u32), InterfaceOctets)> {
+        self.octets
+            .iter()
+            .map(|((agent, if_index), octets)| ((agent, *if_index), *octets))
+    }
+
+    /// Number of tracked interfaces
+    pub fn len(&self) -> usize {
+        self.octets.len()
+    }
+
+    /// Whether no interface is tracked
+    pub fn is_empty(&self) -> bool {
+        self.octets.is_empty()
+    }
+
+    fn update(&mut self, counters: &GenericInterfaceCounters) {
+        if let Some(agent) = &self.agent {
+            self.octets.insert(
+                (agent.clone(), counters.if_index),
+                InterfaceOctets {
+                    in_octets: counters.if_in_octets,
+                    out_octets: counters.if_out_octets,
+                },
+            );
+        }
+    }
+}
+
+impl DatagramVisitor for InterfaceOctetAggregator {
+    fn on_header(&mut self, header: &DatagramHeader) {
+        self.agent = Some(header.agent_address.clone());
+    }
+
+    fn wants_flow_record(&mut self, _format: DataFormat) -> bool {
+        false
+    }
+
+    fn wants_counter_record(&mut self, format: DataFormat) -> bool {
+        format == DataFormat::new(0, 1)
+    }
+
+    fn on_counter_record(&mut self, record: &CounterData) {
+        if let CounterData::GenericInterface(counters) = record {
+            self.update(counters);
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! Counter rate computation
+//!
+//! Counter samples carry cumulative counters. Rates are computed from the
+//! difference between two successive samples of the same data source,
+//! divided by the elapsed agent uptime.
+
+use crate::models::record_counters::{CounterWidth, GenericInterfaceCounters};
+use crate::models::*;
+use std::collections::HashMap;
+
+/// Largest uptime step across the 2^32 ms wraparound still read as a wrap
+///
+/// A backwards uptime jump is a wrap when the agent uptime reaches 2^32 ms
+/// (about 49.7 days) and restarts from 0, so that the forward distance
+/// modulo 2^32 is one sampling interval. A larger distance means the agent
+/// restarted.
+const UPTIME_WRAP_WINDOW_MS: u32 = 600_000;
+
+/// Key identifying a series of counter records
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RateKey {
+    /// Agent that exported the counters
+    pub agent: Address,
+
+    /// Sub-agent of the agent that exported the counters
+    pub sub_agent_id: u32,
+
+    /// Data source of the counters sample, compact sources are expanded
+    pub source_id: DataSourceExpanded,
+
+    /// Counter record type
+    pub record_type: DataFormat,
+}
+
+/// Per-second rates computed for a generic interface counters record (0,1)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InterfaceRates {
+    /// Interface index
+    pub if_index: u32,
+
+    /// Elapsed time between the two samples in seconds
+    pub interval_secs: f64,
+
+    /// Received octets per second
+    pub in_octets_per_sec: f64,
+
+    /// Transmitted octets per second
+    pub out_octets_per_sec: f64,
+
+    /// Received packets (unicast + multicast + broadcast) per second
+    pub in_packets_per_sec: f64,
+
+    /// Transmitted packets (unicast + multicast + broadcast) per second
+    pub out_packets_per_sec: f64,
+}
+
+/// Snapshot of the previous counters of a series
+#[derive(Debug, Clone)]
+struct Snapshot {
+    uptime: u32,
+    counters: GenericInterfaceCounters,
+}
+
+/// Stateful calculator turning successive counter samples into rates
+///
+/// Counters are tracked per [`RateKey`]. The first sample of a series only
+/// initializes the state; rates are returned from the second sample on.
+///
+/// An uptime that wrapped around 2^32 ms is measured modulo 2^32. Any other
+/// uptime lower than the previous one means the agent restarted and its
+/// counters were reset: the sample becomes the new baseline and no rate is
+/// returned for it. Counter wraparound is handled according to the counter
+/// width (32-bit packet counters, 64-bit octet counters), see
+/// [`CounterWidth`].
+#[derive(Debug, Clone, Default)]
+pub struct CounterRateCalculator {
+    previous: HashMap<RateKey, Snapshot>,
+}
+
+impl CounterRateCalculator {
+    /// Create an empty calculator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ingest a counters sample received from `agent` and `sub_agent_id`
+    /// at `uptime` (milliseconds)
+    ///
+    /// Returns the rates of every generic interface record that has a
+    /// previous snapshot with a strictly older uptime.
+    pub fn update(
+        &mut self,
+        agent: &Address,
+        sub_agent_id: u32,
+        uptime: u32,
+        sample: &CountersSample,
+    ) -> Vec<InterfaceRates> {
+        let source_id = sample.source_id.into();
+        self.update_records(agent, sub_agent_id, uptime, source_id, &sample.counters)
+    }
+
+    /// Ingest an expanded counters sample, see [`update`](Self::update)
+    ///
+    /// Expanded and compact samples of the same data source share a series.
+    pub fn update_expanded(
+        &mut self,
+        agent: &Address,
+        sub_agent_id: u32,
+        uptime: u32,
+        sample: &CountersSampleExpanded,
+    ) -> Vec<InterfaceRates> {
+        self.update_records(
+            agent,
+            sub_agent_id,
+            uptime,
+            sample.source_id,
+            &sample.counters,
+        )
+    }
+
+    /// Ingest every counters sample of a datagram, compact or expanded
+    pub fn update_datagram(&mut self, datagram: &SFlowDatagram) -> Vec<InterfaceRates> {
+        let agent = &datagram.agent_address;
+        let mut rates = Vec::new();
+        for sample in &datagram.samples {
+            match &sample.sample_data {
+                SampleData::CountersSample(counters) => {
+                    rates.extend(self.update(
+                        agent,
+                        datagram.sub_agent_id,
+                        datagram.uptime,
+                        counters,
+                    ));
+                }
+                SampleData::CountersSampleExpanded(counters) => {
+                    rates.extend(self.update_expanded(
+                        agent,
+                        datagram.sub_agent_id,
+                        datagram.uptime,
+                        counters,
+                    ));
+                }
+                _ => {}
+            }
+        }
+        rates
+    }
+
+    fn update_records(
+        &mut self,
+        agent: &Address,
+        sub_agent_id: u32,
+        uptime: u32,
+        source_id: DataSourceExpanded,
+        records: &[CounterRecord],
+    ) -> Vec<InterfaceRates> {
+        let mut rates = Vec::new();
+
+        for record in records {
+            let CounterData::GenericInterface(counters) = &record.counter_data else {
+                continue;
+            };
+
+            let key = RateKey {
+                agent: agent.clone(),
+                sub_agent_id,
+                source_id,
+                record_type: record.counter_format,
+            };
+
+            let current = Snapshot {
+                uptime,
+                counters: counters.clone(),
+            };
+            // The new snapshot replaces the previous one in every case, so
+            // after a reset it is the baseline of the next interval
+            if let Some(previous) = self.previous.insert(key, current) {
+                if let Some(rate) = interface_rates(&previous, uptime, counters) {
+                    rates.push(rate);
+                }
+            }
+        }
+
+        rates
+    }
+
+    /// Number of tracked series
+    pub fn len(&self) -> usize {
+        self.previous.len()
+    }
+
+    /// Whether no series is tracked
+    pub fn is_empty(&self) -> bool {
+        self.previous.is_empty()
+    }
+
+    /// Forget all tracked series
+    pub fn clear(&mut self) {
+        self.previous.clear();
+    }
+}
+
+/// Difference between two readings of a 32-bit counter, modulo 2^32
+fn delta32(previous: u32, current: u32) -> u64 {
+    CounterWidth::Counter32.delta(previous.into(), current.into())
+}
+
+/// Difference between two readings of a 64-bit counter, modulo 2^64
+fn delta64(previous: u64, current: u64) -> u64 {
+    CounterWidth::Counter64.delta(previous, current)
+}
+
+fn interface_rates(
+    previous: &Snapshot,
+    uptime: u32,
+    current: &GenericInterfaceCounters,
+) -> Option<InterfaceRates> {
+    // The agent restarted: its counters restarted too, a delta against the
+    // previous snapshot would read as a huge wraparound
+    if uptime < previous.uptime && uptime.wrapping_sub(previous.uptime) > UPTIME_WRAP_WINDOW_MS {
+        return None;
+    }
+    let elapsed = uptime_delta(previous.uptime, uptime);
+    if elapsed.is_zero() {
+        return None;
+    }
+    let interval_secs = elapsed.as_secs_f64();
+    let prev = &previous.counters;
+
+    // The field types pick the modulus: a u64 field does not fit `delta32`
+    let in_packets = delta32(prev.if_in_ucast_pkts, current.if_in_ucast_pkts)
+        + delta32(prev.if_in_multicast_pkts, current.if_in_multicast_pkts)
+        + delta32(prev.if_in_broadcast_pkts, current.if_in_broadcast_pkts);
+    let out_packets = delta32(prev.if_out_ucast_pkts, current.if_out_ucast_pkts)
+        + delta32(prev.if_out_multicast_pkts, current.if_out_multicast_pkts)
+        + delta32(prev.if_out_broadcast_pkts, current.if_out_broadcast_pkts);
+    let in_octets = delta64(prev.if_in_octets, current.if_in_octets);
+    let out_octets = delta64(prev.if_out_octets, current.if_out_octets);
+
+    Some(InterfaceRates {
+        if_index: current.if_index,
+        interval_secs,
+        in_octets_per_sec: in_octets as f64 / interval_secs,
+        out_octets_per_sec: out_octets as f64 / interval_secs,
+        in_packets_per_sec: in_packets as f64 / interval_secs,
+        out_packets_per_sec: out_packets as f64 / interval_secs,
+    })
+}
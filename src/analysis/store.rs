@@ -0,0 +1,165 @@
+//! Latest counter snapshots
+//!
+//! Agents export counter samples periodically, possibly spread over several
+//! datagrams sent in a burst. The store keeps only the most recent counters
+//! sample of each data source.
+
+use crate::models::*;
+use std::collections::HashMap;
+
+/// How far back the uptime of a sample may go before the agent is
+/// considered restarted, in milliseconds
+///
+/// Datagrams reordered in flight are at most a few seconds apart; a larger
+/// step back in uptime means the agent rebooted and restarted its sequence
+/// numbers.
+const REORDER_WINDOW_MS: u32 = 10_000;
+
+/// Key of a data source: agent address, sub-agent and source identifier
+type SourceKey = (Address, u32, DataSourceExpanded);
+
+#[derive(Debug, Clone)]
+struct Stored {
+    uptime: u32,
+    sample: CountersSampleExpanded,
+}
+
+/// Store of the most recent counters sample per (agent, sub-agent, data source)
+///
+/// Samples are ordered by sequence number using serial number arithmetic,
+/// so a sequence number that wrapped around is still considered newer. An
+/// older or duplicate sample leaves the stored one in place, unless the
+/// datagram uptime shows the agent restarted since the stored sample.
+///
+/// Compact and expanded samples of the same source share an entry; samples
+/// are stored in expanded form.
+#[derive(Debug, Clone, Default)]
+pub struct CounterStore {
+    latest: HashMap<SourceKey, Stored>,
+}
+
+impl CounterStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ingest a counters sample received from `agent` in a datagram with
+    /// the given sub-agent and uptime (milliseconds)
+    ///
+    /// Returns `true` if the sample is now the latest of its data source.
+    pub fn update(
+        &mut self,
+        agent: &Address,
+        sub_agent_id: u32,
+        uptime: u32,
+        sample: &CountersSample,
+    ) -> bool {
+        self.insert(agent, sub_agent_id, uptime, sample.clone().into())
+    }
+
+    /// Ingest an expanded counters sample, see [`update`](Self::update)
+    pub fn update_expanded(
+        &mut self,
+        agent: &Address,
+        sub_agent_id: u32,
+        uptime: u32,
+        sample: &CountersSampleExpanded,
+    ) -> bool {
+        self.insert(agent, sub_agent_id, uptime, sample.clone())
+    }
+
+    /// Ingest every counters sample of a datagram, compact or expanded
+    ///
+    /// Returns the number of samples that replaced a stored one or started a
+    /// new data source.
+    pub fn update_datagram(&mut self, datagram: &SFlowDatagram) -> usize {
+        let mut updated = 0;
+        for sample in &datagram.samples {
+            let sample = match &sample.sample_data {
+                SampleData::CountersSample(counters) => counters.clone().into(),
+                SampleData::CountersSampleExpanded(counters) => counters.clone(),
+                _ => continue,
+            };
+            if self.insert(
+                &datagram.agent_address,
+                datagram.sub_agent_id,
+                datagram.uptime,
+                sample,
+            ) {
+                updated += 1;
+            }
+        }
+        updated
+    }
+
+    fn insert(
+        &mut self,
+        agent: &Address,
+        sub_agent_id: u32,
+        uptime: u32,
+        sample: CountersSampleExpanded,
+    ) -> bool {
+        let key = (agent.clone(), sub_agent_id, sample.source_id);
+        match self.latest.get_mut(&key) {
+            Some(stored) if !is_newer(&sample, uptime, stored) => false,
+            Some(stored) => {
+                *stored = Stored { uptime, sample };
+                true
+            }
+            None => {
+                self.latest.insert(key, Stored { uptime, sample });
+                true
+            }
+        }
+    }
+
+    /// Most recent counters sample of a data source
+    pub fn latest(
+        &self,
+        agent: &Address,
+        sub_agent_id: u32,
+        source_id: impl Into<DataSourceExpanded>,
+    ) -> Option<&CountersSampleExpanded> {
+        self.latest
+            .get(&(agent.clone(), sub_agent_id, source_id.into()))
+            .map(|stored| &stored.sample)
+    }
+
+    /// Iterate over the latest sample of every data source, with its agent
+    /// and sub-agent
+    pub fn iter(&self) -> impl Iterator<Item = (&Address, u32, &CountersSampleExpanded)> {
+        self.latest
+            .iter()
+            .map(|((agent, sub_agent_id, _), stored)| (agent, *sub_agent_id, &stored.sample))
+    }
+
+    /// Number of tracked data sources
+    pub fn len(&self) -> usize {
+        self.latest.len()
+    }
+
+    /// Whether no data source is tracked
+    pub fn is_empty(&self) -> bool {
+        self.latest.is_empty()
+    }
+
+    /// Forget all stored samples
+    pub fn clear(&mut self) {
+        self.latest.clear();
+    }
+}
+
+/// Whether `sample`, received at `uptime`, replaces the stored one
+///
+/// A sample from a restarted agent is newer whatever its sequence number.
+/// Otherwise sequence numbers are compared with wraparound.
+fn is_newer(sample: &CountersSampleExpanded, uptime: u32, stored: &Stored) -> bool {
+    if stored.uptime.saturating_sub(uptime) > REORDER_WINDOW_MS {
+        return true;
+    }
+    let distance = sample
+        .sequence_number
+        .wrapping_sub(stored.sample.sequence_number);
+    distance != 0 && distance < 1 << 31
+}
@@ -0,0 +1,1735 @@
+//! sFlow v5 encoder
+//!
+//! Writes the model types back into XDR bytes, e.g. to forward datagrams
+//! after filtering them or to store them in a normalized form. Every
+//! length field is computed from the data written, nothing is copied
+//! from the datagram the model was parsed from.
+//!
+//! Record lengths are measured by running the same code that writes the
+//! record with a sink that only counts bytes, so they never disagree with
+//! the data and measuring does not allocate.
+
+use crate::models::record_counters::*;
+use crate::models::record_flows::*;
+use crate::models::{
+    Address, CounterData, CounterRecord, CountersSample, CountersSampleExpanded, DataFormat,
+    DataSourceExpanded, DiscardedPacket, FlowData, FlowRecord, FlowSample, FlowSampleExpanded,
+    MacAddress, SFlowDatagram, SampleData, SampleRecord,
+};
+use crate::parsers::{parse_datagram, ParseError};
+use std::fmt;
+
+/// Destination of XDR encoded data
+pub(crate) trait Sink {
+    /// Append raw bytes
+    fn put(&mut self, bytes: &[u8]);
+
+    fn put_u32(&mut self, value: u32) {
+        self.put(&value.to_be_bytes());
+    }
+
+    fn put_i32(&mut self, value: i32) {
+        self.put(&value.to_be_bytes());
+    }
+
+    fn put_u64(&mut self, value: u64) {
+        self.put(&value.to_be_bytes());
+    }
+
+    /// Append the zero padding following `len` bytes of opaque data
+    fn put_padding(&mut self, len: usize) {
+        self.put(&[0; 3][..(4 - len % 4) % 4]);
+    }
+
+    /// Append length-prefixed opaque data with XDR padding
+    fn put_opaque(&mut self, data: &[u8]) {
+        self.put_u32(data.len() as u32);
+        self.put(data);
+        self.put_padding(data.len());
+    }
+
+    fn put_address(&mut self, address: &Address) {
+        match address {
+            Address::Unknown => self.put_u32(0),
+            Address::IPv4(addr) => {
+                self.put_u32(1);
+                self.put(&addr.octets());
+            }
+            Address::IPv6(addr) => {
+                self.put_u32(2);
+                self.put(&addr.octets());
+            }
+        }
+    }
+
+    /// Append a MAC address padded to 8 bytes, as in `opaque mac[6]`
+    fn put_mac_padded(&mut self, mac: &MacAddress) {
+        self.put(mac.as_bytes());
+        self.put(&[0; 2]);
+    }
+
+    /// Append a variable-length array of unsigned integers
+    fn put_u32_array(&mut self, values: &[u32]) {
+        self.put_u32(values.len() as u32);
+        for value in values {
+            self.put_u32(*value);
+        }
+    }
+
+    /// Append a variable-length array of signed integers
+    fn put_i32_array(&mut self, values: &[i32]) {
+        self.put_u32(values.len() as u32);
+        for value in values {
+            self.put_i32(*value);
+        }
+    }
+}
+
+impl Sink for Vec<u8> {
+    fn put(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes);
+    }
+}
+
+/// Sink counting the bytes written to it
+#[derive(Debug, Default)]
+pub(crate) struct Length(pub(crate) usize);
+
+impl Sink for Length {
+    fn put(&mut self, bytes: &[u8]) {
+        self.0 += bytes.len();
+    }
+}
+
+pub(crate) fn put_data_source_expanded<S: Sink>(s: &mut S, source: &DataSourceExpanded) {
+    s.put_u32(source.source_id_type);
+    s.put_u32(source.source_id_index);
+}
+
+/// Encoder error type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodeError {
+    /// Sample skipped while parsing, its data is not available
+    SkippedSample {
+        /// Format of the skipped sample
+        format: DataFormat,
+    },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::SkippedSample { format } => write!(
+                f,
+                "Cannot encode sample ({},{}): it was skipped while parsing",
+                format.enterprise(),
+                format.format()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+/// Write Generic Interface Counters - Format (0,1)
+pub(crate) fn put_generic_interface_counters<S: Sink>(s: &mut S, r: &GenericInterfaceCounters) {
+    s.put_u32(r.if_index);
+    s.put_u32(r.if_type);
+    s.put_u64(r.if_speed);
+    s.put_u32(r.if_direction);
+    s.put_u32(r.if_status);
+    s.put_u64(r.if_in_octets);
+    s.put_u32(r.if_in_ucast_pkts);
+    s.put_u32(r.if_in_multicast_pkts);
+    s.put_u32(r.if_in_broadcast_pkts);
+    s.put_u32(r.if_in_discards);
+    s.put_u32(r.if_in_errors);
+    s.put_u32(r.if_in_unknown_protos);
+    s.put_u64(r.if_out_octets);
+    s.put_u32(r.if_out_ucast_pkts);
+    s.put_u32(r.if_out_multicast_pkts);
+    s.put_u32(r.if_out_broadcast_pkts);
+    s.put_u32(r.if_out_discards);
+    s.put_u32(r.if_out_errors);
+    s.put_u32(r.if_promiscuous_mode);
+}
+
+/// Write Ethernet Interface Counters - Format (0,2)
+fn put_ethernet_interface_counters<S: Sink>(s: &mut S, r: &EthernetInterfaceCounters) {
+    s.put_u32(r.dot3_stats_alignment_errors);
+    s.put_u32(r.dot3_stats_fcs_errors);
+    s.put_u32(r.dot3_stats_single_collision_frames);
+    s.put_u32(r.dot3_stats_multiple_collision_frames);
+    s.put_u32(r.dot3_stats_sqe_test_errors);
+    s.put_u32(r.dot3_stats_deferred_transmissions);
+    s.put_u32(r.dot3_stats_late_collisions);
+    s.put_u32(r.dot3_stats_excessive_collisions);
+    s.put_u32(r.dot3_stats_internal_mac_transmit_errors);
+    s.put_u32(r.dot3_stats_carrier_sense_errors);
+    s.put_u32(r.dot3_stats_frame_too_longs);
+    s.put_u32(r.dot3_stats_internal_mac_receive_errors);
+    s.put_u32(r.dot3_stats_symbol_errors);
+}
+
+/// Write Token Ring Counters - Format (0,3)
+fn put_token_ring_counters<S: Sink>(s: &mut S, r: &TokenRingCounters) {
+    s.put_u32(r.dot5_stats_line_errors);
+    s.put_u32(r.dot5_stats_burst_errors);
+    s.put_u32(r.dot5_stats_ac_errors);
+    s.put_u32(r.dot5_stats_abort_trans_errors);
+    s.put_u32(r.dot5_stats_internal_errors);
+    s.put_u32(r.dot5_stats_lost_frame_errors);
+    s.put_u32(r.dot5_stats_receive_congestions);
+    s.put_u32(r.dot5_stats_frame_copied_errors);
+    s.put_u32(r.dot5_stats_token_errors);
+    s.put_u32(r.dot5_stats_soft_errors);
+    s.put_u32(r.dot5_stats_hard_errors);
+    s.put_u32(r.dot5_stats_signal_loss);
+    s.put_u32(r.dot5_stats_transmit_beacons);
+    s.put_u32(r.dot5_stats_recoverys);
+    s.put_u32(r.dot5_stats_lobe_wires);
+    s.put_u32(r.dot5_stats_removes);
+    s.put_u32(r.dot5_stats_singles);
+    s.put_u32(r.dot5_stats_freq_errors);
+}
+
+/// Write 100BaseVG Interface Counters - Format (0,4)
+fn put_vg100_interface_counters<S: Sink>(s: &mut S, r: &Vg100InterfaceCounters) {
+    s.put_u32(r.dot12_in_high_priority_frames);
+    s.put_u64(r.dot12_in_high_priority_octets);
+    s.put_u32(r.dot12_in_norm_priority_frames);
+    s.put_u64(r.dot12_in_norm_priority_octets);
+    s.put_u32(r.dot12_in_ipm_errors);
+    s.put_u32(r.dot12_in_oversize_frame_errors);
+    s.put_u32(r.dot12_in_data_errors);
+    s.put_u32(r.dot12_in_null_addressed_frames);
+    s.put_u32(r.dot12_out_high_priority_frames);
+    s.put_u64(r.dot12_out_high_priority_octets);
+    s.put_u32(r.dot12_transition_into_trainings);
+    s.put_u64(r.dot12_hc_in_high_priority_octets);
+    s.put_u64(r.dot12_hc_in_norm_priority_octets);
+    s.put_u64(r.dot12_hc_out_high_priority_octets);
+}
+
+/// Write VLAN Counters - Format (0,5)
+fn put_vlan_counters<S: Sink>(s: &mut S, r: &VlanCounters) {
+    s.put_u32(r.vlan_id);
+    s.put_u64(r.octets);
+    s.put_u32(r.ucast_pkts);
+    s.put_u32(r.multicast_pkts);
+    s.put_u32(r.broadcast_pkts);
+    s.put_u32(r.discards);
+}
+
+/// Write IEEE 802.11 Counters - Format (0,6)
+fn put_ieee80211_counters<S: Sink>(s: &mut S, r: &Ieee80211Counters) {
+    s.put_u32(r.dot11_transmitted_fragment_count);
+    s.put_u32(r.dot11_multicast_transmitted_frame_count);
+    s.put_u32(r.dot11_failed_count);
+    s.put_u32(r.dot11_retry_count);
+    s.put_u32(r.dot11_multiple_retry_count);
+    s.put_u32(r.dot11_frame_duplicate_count);
+    s.put_u32(r.dot11_rts_success_count);
+    s.put_u32(r.dot11_rts_failure_count);
+    s.put_u32(r.dot11_ack_failure_count);
+    s.put_u32(r.dot11_received_fragment_count);
+    s.put_u32(r.dot11_multicast_received_frame_count);
+    s.put_u32(r.dot11_fcs_error_count);
+    s.put_u32(r.dot11_transmitted_frame_count);
+    s.put_u32(r.dot11_wep_undecryptable_count);
+    s.put_u32(r.dot11_qos_discarded_fragment_count);
+    s.put_u32(r.dot11_associated_station_count);
+    s.put_u32(r.dot11_qos_cf_polls_received_count);
+    s.put_u32(r.dot11_qos_cf_polls_unused_count);
+    s.put_u32(r.dot11_qos_cf_polls_unusable_count);
+    s.put_u32(r.dot11_qos_cf_polls_lost_count);
+}
+
+/// Write LAG Port Statistics - Format (0,7)
+fn put_lag_port_stats<S: Sink>(s: &mut S, r: &LagPortStats) {
+    s.put_mac_padded(&r.dot3ad_agg_port_actor_system_id);
+    s.put_mac_padded(&r.dot3ad_agg_port_partner_oper_system_id);
+    s.put_u32(r.dot3ad_agg_port_attached_agg_id);
+    s.put(&r.dot3ad_agg_port_state);
+    s.put_u32(r.dot3ad_agg_port_stats_lacpd_us_rx);
+    s.put_u32(r.dot3ad_agg_port_stats_marker_pdus_rx);
+    s.put_u32(r.dot3ad_agg_port_stats_marker_response_pdus_rx);
+    s.put_u32(r.dot3ad_agg_port_stats_unknown_rx);
+    s.put_u32(r.dot3ad_agg_port_stats_illegal_rx);
+    s.put_u32(r.dot3ad_agg_port_stats_lacpd_us_tx);
+    s.put_u32(r.dot3ad_agg_port_stats_marker_pdus_tx);
+    s.put_u32(r.dot3ad_agg_port_stats_marker_response_pdus_tx);
+}
+
+/// Write Slow Path Counts - Format (0,8)
+fn put_slow_path_counts<S: Sink>(s: &mut S, r: &SlowPathCounts) {
+    s.put_u32(r.unknown);
+    s.put_u32(r.other);
+    s.put_u32(r.cam_miss);
+    s.put_u32(r.cam_full);
+    s.put_u32(r.no_hw_support);
+    s.put_u32(r.cntrl);
+}
+
+/// Write InfiniBand Counters - Format (0,9)
+fn put_infiniband_counters<S: Sink>(s: &mut S, r: &InfiniBandCounters) {
+    s.put_u64(r.port_xmit_pkts);
+    s.put_u64(r.port_rcv_pkts);
+    s.put_u32(r.symbol_error_counter);
+    s.put_u32(r.link_error_recovery_counter);
+    s.put_u32(r.link_downed_counter);
+    s.put_u32(r.port_rcv_errors);
+    s.put_u32(r.port_rcv_remote_physical_errors);
+    s.put_u32(r.port_rcv_switch_relay_errors);
+    s.put_u32(r.port_xmit_discards);
+    s.put_u32(r.port_xmit_constraint_errors);
+    s.put_u32(r.port_rcv_constraint_errors);
+    s.put_u32(r.local_link_integrity_errors);
+    s.put_u32(r.excessive_buffer_overrun_errors);
+    s.put_u32(r.vl15_dropped);
+}
+
+/// Write Optical Lane
+fn put_lane<S: Sink>(s: &mut S, r: &Lane) {
+    s.put_u32(r.index);
+    s.put_u32(r.tx_bias_current);
+    s.put_u32(r.tx_power);
+    s.put_u32(r.tx_power_min);
+    s.put_u32(r.tx_power_max);
+    s.put_u32(r.tx_wavelength);
+    s.put_u32(r.rx_power);
+    s.put_u32(r.rx_power_min);
+    s.put_u32(r.rx_power_max);
+    s.put_u32(r.rx_wavelength);
+}
+
+/// Write Optical SFP/QSFP Counters - Format (0,10)
+fn put_optical_sfp_qsfp<S: Sink>(s: &mut S, r: &OpticalSfpQsfp) {
+    s.put_u32(r.module_id);
+    s.put_u32(r.module_num_lanes);
+    s.put_u32(r.module_supply_voltage);
+    s.put_i32(r.module_temperature);
+    s.put_u32(r.lanes.len() as u32);
+    for lane in &r.lanes {
+        put_lane(s, lane);
+    }
+}
+
+/// Write Processor Counters - Format (0,1001)
+fn put_processor_counters<S: Sink>(s: &mut S, r: &ProcessorCounters) {
+    s.put_u32(r.cpu_5s);
+    s.put_u32(r.cpu_1m);
+    s.put_u32(r.cpu_5m);
+    s.put_u64(r.total_memory);
+    s.put_u64(r.free_memory);
+}
+
+/// Write Radio Utilization - Format (0,1002)
+fn put_radio_utilization<S: Sink>(s: &mut S, r: &RadioUtilization) {
+    s.put_u32(r.elapsed_time);
+    s.put_u32(r.on_channel_time);
+    s.put_u32(r.on_channel_busy_time);
+}
+
+/// Write Queue Length - Format (0,1003)
+fn put_queue_length<S: Sink>(s: &mut S, r: &QueueLength) {
+    s.put_u32(r.queue_index);
+    s.put_u32(r.segment_size);
+    s.put_u32(r.queue_segments);
+    s.put_u32(r.queue_length_0);
+    s.put_u32(r.queue_length_1);
+    s.put_u32(r.queue_length_2);
+    s.put_u32(r.queue_length_4);
+    s.put_u32(r.queue_length_8);
+    s.put_u32(r.queue_length_32);
+    s.put_u32(r.queue_length_128);
+    s.put_u32(r.queue_length_1024);
+    s.put_u32(r.queue_length_more);
+    s.put_u32(r.dropped);
+}
+
+/// Write OpenFlow Port - Format (0,1004)
+fn put_openflow_port<S: Sink>(s: &mut S, r: &OpenFlowPort) {
+    s.put_u64(r.datapath_id);
+    s.put_u32(r.port_no);
+}
+
+/// Write OpenFlow Port Name - Format (0,1005)
+fn put_openflow_port_name<S: Sink>(s: &mut S, r: &OpenFlowPortName) {
+    s.put_opaque(r.port_name.as_bytes());
+}
+
+/// Write Host Description - Format (0,2000)
+fn put_host_description<S: Sink>(s: &mut S, r: &HostDescription) {
+    s.put_opaque(r.hostname.as_bytes());
+    s.put(&r.uuid);
+    s.put_u32(r.machine_type as u32);
+    s.put_u32(r.os_name as u32);
+    s.put_opaque(r.os_release.as_bytes());
+}
+
+/// Write Host Adapters - Format (0,2001)
+fn put_host_adapters<S: Sink>(s: &mut S, r: &HostAdapters) {
+    s.put_u32(r.adapters.len() as u32);
+    for adapter in &r.adapters {
+        s.put_u32(adapter.if_index);
+        s.put_u32(adapter.mac_addresses.len() as u32);
+        for mac in &adapter.mac_addresses {
+            s.put_mac_padded(mac);
+        }
+    }
+}
+
+/// Write Host Parent - Format (0,2002)
+fn put_host_parent<S: Sink>(s: &mut S, r: &HostParent) {
+    s.put_u32(r.container_type);
+    s.put_u32(r.container_index);
+}
+
+/// Write Host CPU - Format (0,2003)
+fn put_host_cpu<S: Sink>(s: &mut S, r: &HostCpu) {
+    s.put_u32(r.load_one);
+    s.put_u32(r.load_five);
+    s.put_u32(r.load_fifteen);
+    s.put_u32(r.proc_run);
+    s.put_u32(r.proc_total);
+    s.put_u32(r.cpu_num);
+    s.put_u32(r.cpu_speed);
+    s.put_u32(r.uptime);
+    s.put_u32(r.cpu_user);
+    s.put_u32(r.cpu_nice);
+    s.put_u32(r.cpu_system);
+    s.put_u32(r.cpu_idle);
+    s.put_u32(r.cpu_wio);
+    s.put_u32(r.cpu_intr);
+    s.put_u32(r.cpu_sintr);
+    s.put_u32(r.interrupts);
+    s.put_u32(r.contexts);
+}
+
+/// Write Host Memory - Format (0,2004)
+fn put_host_memory<S: Sink>(s: &mut S, r: &HostMemory) {
+    s.put_u64(r.mem_total);
+    s.put_u64(r.mem_free);
+    s.put_u64(r.mem_shared);
+    s.put_u64(r.mem_buffers);
+    s.put_u64(r.mem_cached);
+    s.put_u64(r.swap_total);
+    s.put_u64(r.swap_free);
+    s.put_u32(r.page_in);
+    s.put_u32(r.page_out);
+    s.put_u32(r.swap_in);
+    s.put_u32(r.swap_out);
+}
+
+/// Write Host Disk I/O - Format (0,2005)
+fn put_host_disk_io<S: Sink>(s: &mut S, r: &HostDiskIo) {
+    s.put_u64(r.disk_total);
+    s.put_u64(r.disk_free);
+    s.put_i32(r.part_max_used);
+    s.put_u32(r.reads);
+    s.put_u64(r.bytes_read);
+    s.put_u32(r.read_time);
+    s.put_u32(r.writes);
+    s.put_u64(r.bytes_written);
+    s.put_u32(r.write_time);
+}
+
+/// Write Host Network I/O - Format (0,2006)
+fn put_host_net_io<S: Sink>(s: &mut S, r: &HostNetIo) {
+    s.put_u64(r.bytes_in);
+    s.put_u32(r.pkts_in);
+    s.put_u32(r.errs_in);
+    s.put_u32(r.drops_in);
+    s.put_u64(r.bytes_out);
+    s.put_u32(r.packets_out);
+    s.put_u32(r.errs_out);
+    s.put_u32(r.drops_out);
+}
+
+/// Write MIB-2 IP Group - Format (0,2007)
+fn put_mib2_ip_group<S: Sink>(s: &mut S, r: &Mib2IpGroup) {
+    s.put_u32(r.ip_forwarding);
+    s.put_u32(r.ip_default_ttl);
+    s.put_u32(r.ip_in_receives);
+    s.put_u32(r.ip_in_hdr_errors);
+    s.put_u32(r.ip_in_addr_errors);
+    s.put_u32(r.ip_forw_datagrams);
+    s.put_u32(r.ip_in_unknown_protos);
+    s.put_u32(r.ip_in_discards);
+    s.put_u32(r.ip_in_delivers);
+    s.put_u32(r.ip_out_requests);
+    s.put_u32(r.ip_out_discards);
+    s.put_u32(r.ip_out_no_routes);
+    s.put_u32(r.ip_reasm_timeout);
+    s.put_u32(r.ip_reasm_reqds);
+    s.put_u32(r.ip_reasm_oks);
+    s.put_u32(r.ip_reasm_fails);
+    s.put_u32(r.ip_frag_oks);
+    s.put_u32(r.ip_frag_fails);
+    s.put_u32(r.ip_frag_creates);
+}
+
+/// Write MIB-2 ICMP Group - Format (0,2008)
+fn put_mib2_icmp_group<S: Sink>(s: &mut S, r: &Mib2IcmpGroup) {
+    s.put_u32(r.icmp_in_msgs);
+    s.put_u32(r.icmp_in_errors);
+    s.put_u32(r.icmp_in_dest_unreachs);
+    s.put_u32(r.icmp_in_time_excds);
+    s.put_u32(r.icmp_in_param_probs);
+    s.put_u32(r.icmp_in_src_quenchs);
+    s.put_u32(r.icmp_in_redirects);
+    s.put_u32(r.icmp_in_echos);
+    s.put_u32(r.icmp_in_echo_reps);
+    s.put_u32(r.icmp_in_timestamps);
+    s.put_u32(r.icmp_in_addr_masks);
+    s.put_u32(r.icmp_in_addr_mask_reps);
+    s.put_u32(r.icmp_out_msgs);
+    s.put_u32(r.icmp_out_errors);
+    s.put_u32(r.icmp_out_dest_unreachs);
+    s.put_u32(r.icmp_out_time_excds);
+    s.put_u32(r.icmp_out_param_probs);
+    s.put_u32(r.icmp_out_src_quenchs);
+    s.put_u32(r.icmp_out_redirects);
+    s.put_u32(r.icmp_out_echos);
+    s.put_u32(r.icmp_out_echo_reps);
+    s.put_u32(r.icmp_out_timestamps);
+    s.put_u32(r.icmp_out_timestamp_reps);
+    s.put_u32(r.icmp_out_addr_masks);
+    s.put_u32(r.icmp_out_addr_mask_reps);
+}
+
+/// Write MIB-2 TCP Group - Format (0,2009)
+fn put_mib2_tcp_group<S: Sink>(s: &mut S, r: &Mib2TcpGroup) {
+    s.put_u32(r.tcp_rto_algorithm);
+    s.put_u32(r.tcp_rto_min);
+    s.put_u32(r.tcp_rto_max);
+    s.put_u32(r.tcp_max_conn);
+    s.put_u32(r.tcp_active_opens);
+    s.put_u32(r.tcp_passive_opens);
+    s.put_u32(r.tcp_attempt_fails);
+    s.put_u32(r.tcp_estab_resets);
+    s.put_u32(r.tcp_curr_estab);
+    s.put_u32(r.tcp_in_segs);
+    s.put_u32(r.tcp_out_segs);
+    s.put_u32(r.tcp_retrans_segs);
+    s.put_u32(r.tcp_in_errs);
+    s.put_u32(r.tcp_out_rsts);
+    s.put_u32(r.tcp_in_csum_errs);
+}
+
+/// Write MIB-2 UDP Group - Format (0,2010)
+fn put_mib2_udp_group<S: Sink>(s: &mut S, r: &Mib2UdpGroup) {
+    s.put_u32(r.udp_in_datagrams);
+    s.put_u32(r.udp_no_ports);
+    s.put_u32(r.udp_in_errors);
+    s.put_u32(r.udp_out_datagrams);
+    s.put_u32(r.udp_rcvbuf_errors);
+    s.put_u32(r.udp_sndbuf_errors);
+    s.put_u32(r.udp_in_csum_errors);
+}
+
+/// Write Energy - Format (0,3000)
+fn put_energy<S: Sink>(s: &mut S, r: &Energy) {
+    s.put_u32(r.voltage);
+    s.put_u32(r.current);
+    s.put_u32(r.real_power);
+    s.put_i32(r.power_factor);
+    s.put_u32(r.energy);
+    s.put_u32(r.errors);
+}
+
+/// Write Virtual Node - Format (0,2100)
+fn put_virtual_node<S: Sink>(s: &mut S, r: &VirtualNode) {
+    s.put_u32(r.mhz);
+    s.put_u32(r.cpus);
+    s.put_u64(r.memory);
+    s.put_u64(r.memory_free);
+    s.put_u32(r.num_domains);
+}
+
+/// Write Virtual CPU - Format (0,2101)
+fn put_virtual_cpu<S: Sink>(s: &mut S, r: &VirtualCpu) {
+    s.put_u32(r.state);
+    s.put_u32(r.cpu_time);
+    s.put_u32(r.nr_virt_cpu);
+}
+
+/// Write Virtual Memory - Format (0,2102)
+fn put_virtual_memory<S: Sink>(s: &mut S, r: &VirtualMemory) {
+    s.put_u64(r.memory);
+    s.put_u64(r.max_memory);
+}
+
+/// Write Virtual Disk I/O - Format (0,2103)
+fn put_virtual_disk_io<S: Sink>(s: &mut S, r: &VirtualDiskIo) {
+    s.put_u64(r.capacity);
+    s.put_u64(r.allocation);
+    s.put_u64(r.available);
+    s.put_u32(r.rd_req);
+    s.put_u64(r.rd_bytes);
+    s.put_u32(r.wr_req);
+    s.put_u64(r.wr_bytes);
+    s.put_u32(r.errs);
+}
+
+/// Write Virtual Network I/O - Format (0,2104)
+fn put_virtual_net_io<S: Sink>(s: &mut S, r: &VirtualNetIo) {
+    s.put_u64(r.rx_bytes);
+    s.put_u32(r.rx_packets);
+    s.put_u32(r.rx_errs);
+    s.put_u32(r.rx_drop);
+    s.put_u64(r.tx_bytes);
+    s.put_u32(r.tx_packets);
+    s.put_u32(r.tx_errs);
+    s.put_u32(r.tx_drop);
+}
+
+/// Write JVM Runtime - Format (0,2105)
+fn put_jvm_runtime<S: Sink>(s: &mut S, r: &JvmRuntime) {
+    s.put_opaque(r.vm_name.as_bytes());
+    s.put_opaque(r.vm_vendor.as_bytes());
+    s.put_opaque(r.vm_version.as_bytes());
+}
+
+/// Write JVM Statistics - Format (0,2106)
+fn put_jvm_statistics<S: Sink>(s: &mut S, r: &JvmStatistics) {
+    s.put_u64(r.heap_initial);
+    s.put_u64(r.heap_used);
+    s.put_u64(r.heap_committed);
+    s.put_u64(r.heap_max);
+    s.put_u64(r.non_heap_initial);
+    s.put_u64(r.non_heap_used);
+    s.put_u64(r.non_heap_committed);
+    s.put_u64(r.non_heap_max);
+    s.put_u32(r.gc_count);
+    s.put_u32(r.gc_time);
+    s.put_u32(r.classes_loaded);
+    s.put_u32(r.classes_total);
+    s.put_u32(r.classes_unloaded);
+    s.put_u32(r.compilation_time);
+    s.put_u32(r.thread_num_live);
+    s.put_u32(r.thread_num_daemon);
+    s.put_u32(r.thread_num_started);
+    s.put_u32(r.fd_open_count);
+    s.put_u32(r.fd_max_count);
+}
+
+/// Write Memcache Counters - Format (0,2200) - DEPRECATED
+fn put_memcache_counters_deprecated<S: Sink>(s: &mut S, r: &MemcacheCountersDeprecated) {
+    s.put_u32(r.uptime);
+    s.put_u32(r.rusage_user);
+    s.put_u32(r.rusage_system);
+    s.put_u32(r.curr_connections);
+    s.put_u32(r.total_connections);
+    s.put_u32(r.connection_structures);
+    s.put_u32(r.cmd_get);
+    s.put_u32(r.cmd_set);
+    s.put_u32(r.cmd_flush);
+    s.put_u32(r.get_hits);
+    s.put_u32(r.get_misses);
+    s.put_u32(r.delete_misses);
+    s.put_u32(r.delete_hits);
+    s.put_u32(r.incr_misses);
+    s.put_u32(r.incr_hits);
+    s.put_u32(r.decr_misses);
+    s.put_u32(r.decr_hits);
+    s.put_u32(r.cas_misses);
+    s.put_u32(r.cas_hits);
+    s.put_u32(r.cas_badval);
+    s.put_u32(r.auth_cmds);
+    s.put_u32(r.auth_errors);
+    s.put_u64(r.bytes_read);
+    s.put_u64(r.bytes_written);
+    s.put_u32(r.limit_maxbytes);
+    s.put_u32(r.accepting_conns);
+    s.put_u32(r.listen_disabled_num);
+    s.put_u32(r.threads);
+    s.put_u32(r.conn_yields);
+    s.put_u64(r.bytes);
+    s.put_u32(r.curr_items);
+    s.put_u32(r.total_items);
+    s.put_u32(r.evictions);
+}
+
+/// Write HTTP Counters - Format (0,2201)
+fn put_http_counters<S: Sink>(s: &mut S, r: &HttpCounters) {
+    s.put_u32(r.method_option_count);
+    s.put_u32(r.method_get_count);
+    s.put_u32(r.method_head_count);
+    s.put_u32(r.method_post_count);
+    s.put_u32(r.method_put_count);
+    s.put_u32(r.method_delete_count);
+    s.put_u32(r.method_trace_count);
+    s.put_u32(r.method_connect_count);
+    s.put_u32(r.method_other_count);
+    s.put_u32(r.status_1xx_count);
+    s.put_u32(r.status_2xx_count);
+    s.put_u32(r.status_3xx_count);
+    s.put_u32(r.status_4xx_count);
+    s.put_u32(r.status_5xx_count);
+    s.put_u32(r.status_other_count);
+}
+
+/// Write App Operations - Format (0,2202)
+fn put_app_operations<S: Sink>(s: &mut S, r: &AppOperations) {
+    s.put_opaque(r.application.as_bytes());
+    s.put_u32(r.success);
+    s.put_u32(r.other);
+    s.put_u32(r.timeout);
+    s.put_u32(r.internal_error);
+    s.put_u32(r.bad_request);
+    s.put_u32(r.forbidden);
+    s.put_u32(r.too_large);
+    s.put_u32(r.not_implemented);
+    s.put_u32(r.not_found);
+    s.put_u32(r.unavailable);
+    s.put_u32(r.unauthorized);
+}
+
+/// Write App Resources - Format (0,2203)
+fn put_app_resources<S: Sink>(s: &mut S, r: &AppResources) {
+    s.put_u32(r.user_time);
+    s.put_u32(r.system_time);
+    s.put_u64(r.mem_used);
+    s.put_u64(r.mem_max);
+    s.put_u32(r.fd_open);
+    s.put_u32(r.fd_max);
+    s.put_u32(r.conn_open);
+    s.put_u32(r.conn_max);
+}
+
+/// Write Memcache Counters - Format (0,2204)
+fn put_memcache_counters<S: Sink>(s: &mut S, r: &MemcacheCounters) {
+    s.put_u32(r.cmd_set);
+    s.put_u32(r.cmd_touch);
+    s.put_u32(r.cmd_flush);
+    s.put_u32(r.get_hits);
+    s.put_u32(r.get_misses);
+    s.put_u32(r.delete_hits);
+    s.put_u32(r.delete_misses);
+    s.put_u32(r.incr_hits);
+    s.put_u32(r.incr_misses);
+    s.put_u32(r.decr_hits);
+    s.put_u32(r.decr_misses);
+    s.put_u32(r.cas_hits);
+    s.put_u32(r.cas_misses);
+    s.put_u32(r.cas_badval);
+    s.put_u32(r.auth_cmds);
+    s.put_u32(r.auth_errors);
+    s.put_u32(r.threads);
+    s.put_u32(r.conn_yields);
+    s.put_u32(r.listen_disabled_num);
+    s.put_u32(r.curr_connections);
+    s.put_u32(r.rejected_connections);
+    s.put_u32(r.total_connections);
+    s.put_u32(r.connection_structures);
+    s.put_u32(r.evictions);
+    s.put_u32(r.reclaimed);
+    s.put_u32(r.curr_items);
+    s.put_u32(r.total_items);
+    s.put_u64(r.bytes_read);
+    s.put_u64(r.bytes_written);
+    s.put_u64(r.bytes);
+    s.put_u64(r.limit_maxbytes);
+}
+
+/// Write App Workers - Format (0,2206)
+fn put_app_workers<S: Sink>(s: &mut S, r: &AppWorkers) {
+    s.put_u32(r.workers_active);
+    s.put_u32(r.workers_idle);
+    s.put_u32(r.workers_max);
+    s.put_u32(r.req_delayed);
+    s.put_u32(r.req_dropped);
+}
+
+/// Write OVS DP Stats - Format (0,2207)
+fn put_ovs_dp_stats<S: Sink>(s: &mut S, r: &OvsDpStats) {
+    s.put_u32(r.hits);
+    s.put_u32(r.misses);
+    s.put_u32(r.lost);
+    s.put_u32(r.mask_hits);
+    s.put_u32(r.flows);
+    s.put_u32(r.masks);
+}
+
+/// Write Temperature - Format (0,3001)
+fn put_temperature<S: Sink>(s: &mut S, r: &Temperature) {
+    s.put_i32(r.minimum);
+    s.put_i32(r.maximum);
+    s.put_u32(r.errors);
+}
+
+/// Write Humidity - Format (0,3002)
+fn put_humidity<S: Sink>(s: &mut S, r: &Humidity) {
+    s.put_i32(r.relative);
+}
+
+/// Write Fans - Format (0,3003)
+fn put_fans<S: Sink>(s: &mut S, r: &Fans) {
+    s.put_u32(r.total);
+    s.put_u32(r.failed);
+    s.put_u32(r.speed);
+}
+
+/// Write Broadcom Device Buffer Utilization - Format (4413,1)
+fn put_broadcom_device_buffers<S: Sink>(s: &mut S, r: &BroadcomDeviceBuffers) {
+    s.put_i32(r.uc_pc);
+    s.put_i32(r.mc_pc);
+}
+
+/// Write Broadcom Port Buffer Utilization - Format (4413,2)
+fn put_broadcom_port_buffers<S: Sink>(s: &mut S, r: &BroadcomPortBuffers) {
+    s.put_i32(r.ingress_uc_pc);
+    s.put_i32(r.ingress_mc_pc);
+    s.put_i32(r.egress_uc_pc);
+    s.put_i32(r.egress_mc_pc);
+    s.put_i32_array(&r.egress_queue_uc_pc);
+    s.put_i32_array(&r.egress_queue_mc_pc);
+}
+
+/// Write Broadcom Switch ASIC Table Utilization - Format (4413,3)
+fn put_broadcom_tables<S: Sink>(s: &mut S, r: &BroadcomTables) {
+    s.put_u32(r.host_entries);
+    s.put_u32(r.host_entries_max);
+    s.put_u32(r.ipv4_entries);
+    s.put_u32(r.ipv4_entries_max);
+    s.put_u32(r.ipv6_entries);
+    s.put_u32(r.ipv6_entries_max);
+    s.put_u32(r.ipv4_ipv6_entries);
+    s.put_u32(r.ipv6_ipv6_entries_max);
+    s.put_u32(r.long_ipv6_entries);
+    s.put_u32(r.long_ipv6_entries_max);
+    s.put_u32(r.total_routes);
+    s.put_u32(r.total_routes_max);
+    s.put_u32(r.ecmp_nexthops);
+    s.put_u32(r.ecmp_nexthops_max);
+    s.put_u32(r.mac_entries);
+    s.put_u32(r.mac_entries_max);
+    s.put_u32(r.ipv4_neighbors);
+    s.put_u32(r.ipv6_neighbors);
+    s.put_u32(r.ipv4_routes);
+    s.put_u32(r.ipv6_routes);
+    s.put_u32(r.acl_ingress_entries);
+    s.put_u32(r.acl_ingress_entries_max);
+    s.put_u32(r.acl_ingress_counters);
+    s.put_u32(r.acl_ingress_counters_max);
+    s.put_u32(r.acl_ingress_meters);
+    s.put_u32(r.acl_ingress_meters_max);
+    s.put_u32(r.acl_ingress_slices);
+    s.put_u32(r.acl_ingress_slices_max);
+    s.put_u32(r.acl_egress_entries);
+    s.put_u32(r.acl_egress_entries_max);
+    s.put_u32(r.acl_egress_counters);
+    s.put_u32(r.acl_egress_counters_max);
+    s.put_u32(r.acl_egress_meters);
+    s.put_u32(r.acl_egress_meters_max);
+    s.put_u32(r.acl_egress_slices);
+    s.put_u32(r.acl_egress_slices_max);
+}
+
+/// Write NVIDIA GPU Statistics - Format (5703,1)
+fn put_nvidia_gpu<S: Sink>(s: &mut S, r: &NvidiaGpu) {
+    s.put_u32(r.device_count);
+    s.put_u32(r.processes);
+    s.put_u32(r.gpu_time);
+    s.put_u32(r.mem_time);
+    s.put_u64(r.mem_total);
+    s.put_u64(r.mem_free);
+    s.put_u32(r.ecc_errors);
+    s.put_u32(r.energy);
+    s.put_u32(r.temperature);
+    s.put_u32(r.fan_speed);
+}
+
+/// Write counter data based on its variant
+fn put_counter_data<S: Sink>(s: &mut S, data: &CounterData) {
+    match data {
+        CounterData::GenericInterface(r) => put_generic_interface_counters(s, r),
+        CounterData::EthernetInterface(r) => put_ethernet_interface_counters(s, r),
+        CounterData::TokenRing(r) => put_token_ring_counters(s, r),
+        CounterData::Vg100Interface(r) => put_vg100_interface_counters(s, r),
+        CounterData::Vlan(r) => put_vlan_counters(s, r),
+        CounterData::Ieee80211(r) => put_ieee80211_counters(s, r),
+        CounterData::LagPortStats(r) => put_lag_port_stats(s, r),
+        CounterData::SlowPathCounts(r) => put_slow_path_counts(s, r),
+        CounterData::InfiniBandCounters(r) => put_infiniband_counters(s, r),
+        CounterData::OpticalSfpQsfp(r) => put_optical_sfp_qsfp(s, r),
+        CounterData::Processor(r) => put_processor_counters(s, r),
+        CounterData::RadioUtilization(r) => put_radio_utilization(s, r),
+        CounterData::QueueLength(r) => put_queue_length(s, r),
+        CounterData::OpenFlowPort(r) => put_openflow_port(s, r),
+        CounterData::OpenFlowPortName(r) => put_openflow_port_name(s, r),
+        CounterData::HostDescription(r) => put_host_description(s, r),
+        CounterData::HostAdapters(r) => put_host_adapters(s, r),
+        CounterData::HostParent(r) => put_host_parent(s, r),
+        CounterData::HostCpu(r) => put_host_cpu(s, r),
+        CounterData::HostMemory(r) => put_host_memory(s, r),
+        CounterData::HostDiskIo(r) => put_host_disk_io(s, r),
+        CounterData::HostNetIo(r) => put_host_net_io(s, r),
+        CounterData::Mib2IpGroup(r) => put_mib2_ip_group(s, r),
+        CounterData::Mib2IcmpGroup(r) => put_mib2_icmp_group(s, r),
+        CounterData::Mib2TcpGroup(r) => put_mib2_tcp_group(s, r),
+        CounterData::Mib2UdpGroup(r) => put_mib2_udp_group(s, r),
+        CounterData::VirtualNode(r) => put_virtual_node(s, r),
+        CounterData::VirtualCpu(r) => put_virtual_cpu(s, r),
+        CounterData::VirtualMemory(r) => put_virtual_memory(s, r),
+        CounterData::VirtualDiskIo(r) => put_virtual_disk_io(s, r),
+        CounterData::VirtualNetIo(r) => put_virtual_net_io(s, r),
+        CounterData::JvmRuntime(r) => put_jvm_runtime(s, r),
+        CounterData::JvmStatistics(r) => put_jvm_statistics(s, r),
+        CounterData::MemcacheCountersDeprecated(r) => put_memcache_counters_deprecated(s, r),
+        CounterData::HttpCounters(r) => put_http_counters(s, r),
+        CounterData::AppOperations(r) => put_app_operations(s, r),
+        CounterData::AppResources(r) => put_app_resources(s, r),
+        CounterData::MemcacheCounters(r) => put_memcache_counters(s, r),
+        CounterData::AppWorkers(r) => put_app_workers(s, r),
+        CounterData::OvsDpStats(r) => put_ovs_dp_stats(s, r),
+        CounterData::Energy(r) => put_energy(s, r),
+        CounterData::Temperature(r) => put_temperature(s, r),
+        CounterData::Humidity(r) => put_humidity(s, r),
+        CounterData::Fans(r) => put_fans(s, r),
+        CounterData::BroadcomDeviceBuffers(r) => put_broadcom_device_buffers(s, r),
+        CounterData::BroadcomPortBuffers(r) => put_broadcom_port_buffers(s, r),
+        CounterData::BroadcomTables(r) => put_broadcom_tables(s, r),
+        CounterData::NvidiaGpu(r) => put_nvidia_gpu(s, r),
+        CounterData::Unknown { data, .. } => s.put(data),
+    }
+}
+
+/// Write a counter record: format, length, data and padding
+fn put_counter_record<S: Sink>(s: &mut S, record: &CounterRecord) {
+    let mut length = Length::default();
+    put_counter_data(&mut length, &record.counter_data);
+    s.put_u32(record.counter_format.0);
+    s.put_u32(length.0 as u32);
+    put_counter_data(s, &record.counter_data);
+    s.put_padding(length.0);
+}
+
+/// Write an array of counter records
+fn put_counter_records<S: Sink>(s: &mut S, counters: &[CounterRecord]) {
+    s.put_u32(counters.len() as u32);
+    for record in counters {
+        put_counter_record(s, record);
+    }
+}
+
+/// Encode the data of a counter record, without the format and length
+///
+/// Unknown records are written back as their raw bytes.
+pub fn encode_counter_data(data: &CounterData) -> Vec<u8> {
+    let mut buf = Vec::new();
+    put_counter_data(&mut buf, data);
+    buf
+}
+
+fn put_counters_sample<S: Sink>(s: &mut S, sample: &CountersSample) {
+    s.put_u32(sample.sequence_number);
+    s.put_u32(sample.source_id.0);
+    put_counter_records(s, &sample.counters);
+}
+
+fn put_counters_sample_expanded<S: Sink>(s: &mut S, sample: &CountersSampleExpanded) {
+    s.put_u32(sample.sequence_number);
+    s.put_u32(sample.source_id.source_id_type);
+    s.put_u32(sample.source_id.source_id_index);
+    put_counter_records(s, &sample.counters);
+}
+
+/// Encode a compact counter sample body (without the sample header)
+pub fn encode_counters_sample(sample: &CountersSample) -> Vec<u8> {
+    let mut buf = Vec::new();
+    put_counters_sample(&mut buf, sample);
+    buf
+}
+
+/// Encode an expanded counter sample body (without the sample header)
+pub fn encode_counters_sample_expanded(sample: &CountersSampleExpanded) -> Vec<u8> {
+    let mut buf = Vec::new();
+    put_counters_sample_expanded(&mut buf, sample);
+    buf
+}
+
+/// Write Sampled Header - Format (0,1)
+pub(crate) fn put_sampled_header<S: Sink>(s: &mut S, r: &SampledHeader) {
+    s.put_u32(r.protocol.as_u32());
+    s.put_u32(r.frame_length);
+    s.put_u32(r.stripped);
+    s.put_opaque(&r.header);
+}
+
+/// Write Sampled Ethernet - Format (0,2)
+pub(crate) fn put_sampled_ethernet<S: Sink>(s: &mut S, r: &SampledEthernet) {
+    s.put_u32(r.length);
+    s.put(r.src_mac.as_bytes());
+    s.put(r.dst_mac.as_bytes());
+    s.put_u32(r.eth_type);
+}
+
+/// Write Sampled IPv4 - Format (0,3)
+pub(crate) fn put_sampled_ipv4<S: Sink>(s: &mut S, r: &SampledIpv4) {
+    s.put_u32(r.length);
+    s.put_u32(r.protocol);
+    s.put(&r.src_ip.octets());
+    s.put(&r.dst_ip.octets());
+    s.put_u32(r.src_port);
+    s.put_u32(r.dst_port);
+    s.put_u32(r.tcp_flags);
+    s.put_u32(r.tos);
+}
+
+/// Write Sampled IPv6 - Format (0,4)
+pub(crate) fn put_sampled_ipv6<S: Sink>(s: &mut S, r: &SampledIpv6) {
+    s.put_u32(r.length);
+    s.put_u32(r.protocol);
+    s.put(&r.src_ip.octets());
+    s.put(&r.dst_ip.octets());
+    s.put_u32(r.src_port);
+    s.put_u32(r.dst_port);
+    s.put_u32(r.tcp_flags);
+    s.put_u32(r.priority);
+}
+
+/// Write Extended Switch - Format (0,1001)
+pub(crate) fn put_extended_switch<S: Sink>(s: &mut S, r: &ExtendedSwitch) {
+    s.put_u32(r.src_vlan);
+    s.put_u32(r.src_priority);
+    s.put_u32(r.dst_vlan);
+    s.put_u32(r.dst_priority);
+}
+
+/// Write Extended Router - Format (0,1002)
+pub(crate) fn put_extended_router<S: Sink>(s: &mut S, r: &ExtendedRouter) {
+    s.put_address(&r.next_hop);
+    s.put_u32(r.src_mask_len);
+    s.put_u32(r.dst_mask_len);
+}
+
+/// Write Extended Gateway - Format (0,1003)
+fn put_extended_gateway<S: Sink>(s: &mut S, r: &ExtendedGateway) {
+    s.put_address(&r.next_hop);
+    s.put_u32(r.as_number);
+    s.put_u32(r.src_as);
+    s.put_u32(r.src_peer_as);
+    s.put_u32(r.dst_as_path.len() as u32);
+    for segment in &r.dst_as_path {
+        s.put_u32(segment.path_type as u32);
+        s.put_u32_array(&segment.path);
+    }
+    s.put_u32_array(&r.communities);
+    s.put_u32(r.local_pref);
+    s.put(&r.trailing);
+}
+
+/// Write Extended User - Format (0,1004)
+pub(crate) fn put_extended_user<S: Sink>(s: &mut S, r: &ExtendedUser) {
+    s.put_u32(r.src_charset);
+    s.put_opaque(r.src_user.as_bytes());
+    s.put_u32(r.dst_charset);
+    s.put_opaque(r.dst_user.as_bytes());
+}
+
+/// Write Extended URL - Format (0,1005) - DEPRECATED
+fn put_extended_url<S: Sink>(s: &mut S, r: &ExtendedUrl) {
+    s.put_u32(r.direction as u32);
+    s.put_opaque(r.url.as_bytes());
+    s.put_opaque(r.host.as_bytes());
+}
+
+/// Write Extended MPLS - Format (0,1006)
+fn put_extended_mpls<S: Sink>(s: &mut S, r: &ExtendedMpls) {
+    s.put_address(&r.next_hop);
+    s.put_u32_array(&r.in_stack);
+    s.put_u32_array(&r.out_stack);
+}
+
+/// Write Extended NAT - Format (0,1007)
+fn put_extended_nat<S: Sink>(s: &mut S, r: &ExtendedNat) {
+    s.put_address(&r.src_address);
+    s.put_address(&r.dst_address);
+}
+
+/// Write Extended MPLS Tunnel - Format (0,1008)
+fn put_extended_mpls_tunnel<S: Sink>(s: &mut S, r: &ExtendedMplsTunnel) {
+    s.put_opaque(r.tunnel_lsp_name.as_bytes());
+    s.put_u32(r.tunnel_id);
+    s.put_u32(r.tunnel_cos);
+}
+
+/// Write Extended MPLS VC - Format (0,1009)
+fn put_extended_mpls_vc<S: Sink>(s: &mut S, r: &ExtendedMplsVc) {
+    s.put_opaque(r.vc_instance_name.as_bytes());
+    s.put_u32(r.vll_vc_id);
+    s.put_u32((r.vc_label << 3) | (r.vc_cos & 0x7));
+}
+
+/// Write Extended MPLS FEC - Format (0,1010)
+fn put_extended_mpls_fec<S: Sink>(s: &mut S, r: &ExtendedMplsFec) {
+    s.put_address(&r.fec_addr_prefix);
+    s.put_u32(r.fec_prefix_len);
+}
+
+/// Write Extended MPLS LVP FEC - Format (0,1011)
+fn put_extended_mpls_lvp_fec<S: Sink>(s: &mut S, r: &ExtendedMplsLvpFec) {
+    s.put_u32(r.mpls_fec_addr_prefix_length);
+}
+
+/// Write Extended VLAN Tunnel - Format (0,1012)
+fn put_extended_vlan_tunnel<S: Sink>(s: &mut S, r: &ExtendedVlanTunnel) {
+    s.put_u32_array(&r.stack);
+}
+
+/// Write Extended 802.11 Payload - Format (0,1013)
+fn put_extended_80211_payload<S: Sink>(s: &mut S, r: &Extended80211Payload) {
+    s.put_u32(r.cipher_suite);
+    s.put_opaque(&r.data);
+}
+
+/// Write Extended 802.11 RX - Format (0,1014)
+fn put_extended_80211_rx<S: Sink>(s: &mut S, r: &Extended80211Rx) {
+    s.put_opaque(r.ssid.as_bytes());
+    s.put_mac_padded(&r.bssid);
+    s.put_u32(r.version);
+    s.put_u32(r.channel);
+    s.put_u64(r.speed);
+    s.put_u32(r.rsni);
+    s.put_u32(r.rcpi);
+    s.put_u32(r.packet_duration);
+}
+
+/// Write Extended 802.11 TX - Format (0,1015)
+fn put_extended_80211_tx<S: Sink>(s: &mut S, r: &Extended80211Tx) {
+    s.put_opaque(r.ssid.as_bytes());
+    s.put_mac_padded(&r.bssid);
+    s.put_u32(r.version);
+    s.put_u32(r.transmissions);
+    s.put_u32(r.packet_duration);
+    s.put_u32(r.retrans_duration);
+    s.put_u32(r.channel);
+    s.put_u64(r.speed);
+    s.put_u32(r.power);
+}
+
+/// Write Extended 802.11 Aggregation - Format (0,1016)
+fn put_extended_80211_aggregation<S: Sink>(s: &mut S, r: &Extended80211Aggregation) {
+    s.put_u32(r.pdus.len() as u32);
+    for pdu in &r.pdus {
+        put_flow_records(s, &pdu.flow_records);
+    }
+}
+
+/// Write Extended OpenFlow v1 - Format (0,1017) - DEPRECATED
+fn put_extended_openflow_v1<S: Sink>(s: &mut S, r: &ExtendedOpenFlowV1) {
+    s.put_u64(r.flow_cookie);
+    s.put_u32(r.flow_match);
+    s.put_u32(r.flow_actions);
+}
+
+/// Write Extended Fiber Channel - Format (0,1018)
+fn put_extended_fc<S: Sink>(s: &mut S, r: &ExtendedFc) {
+    s.put_u32(r.src_mask_len);
+    s.put_u32(r.dst_mask_len);
+    s.put_u32(r.next_hop);
+    s.put_u32(r.metric);
+}
+
+/// Write Extended Queue Length - Format (0,1019)
+fn put_extended_queue_length<S: Sink>(s: &mut S, r: &ExtendedQueueLength) {
+    s.put_u32(r.queue_index);
+    s.put_u32(r.queue_length);
+}
+
+/// Write Extended NAT Port - Format (0,1020)
+fn put_extended_nat_port<S: Sink>(s: &mut S, r: &ExtendedNatPort) {
+    s.put_u32(r.src_port);
+    s.put_u32(r.dst_port);
+}
+
+/// Write Extended L2 Tunnel Egress - Format (0,1021)
+fn put_extended_l2_tunnel_egress<S: Sink>(s: &mut S, r: &ExtendedL2TunnelEgress) {
+    put_sampled_ethernet(s, &r.header);
+}
+
+/// Write Extended L2 Tunnel Ingress - Format (0,1022)
+fn put_extended_l2_tunnel_ingress<S: Sink>(s: &mut S, r: &ExtendedL2TunnelIngress) {
+    put_sampled_ethernet(s, &r.header);
+}
+
+/// Write Extended IPv4 Tunnel Egress - Format (0,1023)
+fn put_extended_ipv4_tunnel_egress<S: Sink>(s: &mut S, r: &ExtendedIpv4TunnelEgress) {
+    put_sampled_ipv4(s, &r.header);
+}
+
+/// Write Extended IPv4 Tunnel Ingress - Format (0,1024)
+fn put_extended_ipv4_tunnel_ingress<S: Sink>(s: &mut S, r: &ExtendedIpv4TunnelIngress) {
+    put_sampled_ipv4(s, &r.header);
+}
+
+/// Write Extended IPv6 Tunnel Egress - Format (0,1025)
+fn put_extended_ipv6_tunnel_egress<S: Sink>(s: &mut S, r: &ExtendedIpv6TunnelEgress) {
+    put_sampled_ipv6(s, &r.header);
+}
+
+/// Write Extended IPv6 Tunnel Ingress - Format (0,1026)
+fn put_extended_ipv6_tunnel_ingress<S: Sink>(s: &mut S, r: &ExtendedIpv6TunnelIngress) {
+    put_sampled_ipv6(s, &r.header);
+}
+
+/// Write Extended Decapsulate Egress - Format (0,1027)
+fn put_extended_decapsulate_egress<S: Sink>(s: &mut S, r: &ExtendedDecapsulateEgress) {
+    s.put_u32(r.inner_header_offset);
+}
+
+/// Write Extended Decapsulate Ingress - Format (0,1028)
+fn put_extended_decapsulate_ingress<S: Sink>(s: &mut S, r: &ExtendedDecapsulateIngress) {
+    s.put_u32(r.inner_header_offset);
+}
+
+/// Write Extended VNI Egress - Format (0,1029)
+fn put_extended_vni_egress<S: Sink>(s: &mut S, r: &ExtendedVniEgress) {
+    s.put_u32(r.vni);
+}
+
+/// Write Extended VNI Ingress - Format (0,1030)
+fn put_extended_vni_ingress<S: Sink>(s: &mut S, r: &ExtendedVniIngress) {
+    s.put_u32(r.vni);
+}
+
+/// Write Extended InfiniBand LRH - Format (0,1031)
+fn put_extended_infiniband_lrh<S: Sink>(s: &mut S, r: &ExtendedInfiniBandLrh) {
+    s.put_u32(r.src_vl);
+    s.put_u32(r.src_sl);
+    s.put_u32(r.src_dlid);
+    s.put_u32(r.src_slid);
+    s.put_u32(r.src_lnh);
+    s.put_u32(r.dst_vl);
+    s.put_u32(r.dst_sl);
+    s.put_u32(r.dst_dlid);
+    s.put_u32(r.dst_slid);
+    s.put_u32(r.dst_lnh);
+}
+
+/// Write Extended InfiniBand GRH - Format (0,1032)
+fn put_extended_infiniband_grh<S: Sink>(s: &mut S, r: &ExtendedInfiniBandGrh) {
+    s.put_u32(r.flow_label);
+    s.put_u32(r.tc);
+    s.put(&r.s_gid);
+    s.put(&r.d_gid);
+    s.put_u32(r.next_header);
+    s.put_u32(r.length);
+}
+
+/// Write Extended InfiniBand BTH - Format (0,1033)
+fn put_extended_infiniband_bth<S: Sink>(s: &mut S, r: &ExtendedInfiniBandBth) {
+    s.put_u32(r.pkey);
+    s.put_u32(r.dst_qp);
+    s.put_u32(r.opcode);
+}
+
+/// Write Extended VLAN In - Format (0,1034)
+fn put_extended_vlan_in<S: Sink>(s: &mut S, r: &ExtendedVlanIn) {
+    s.put_u32_array(&r.stack);
+}
+
+/// Write Extended VLAN Out - Format (0,1035)
+fn put_extended_vlan_out<S: Sink>(s: &mut S, r: &ExtendedVlanOut) {
+    s.put_u32_array(&r.stack);
+}
+
+/// Write Extended Egress Queue - Format (0,1036)
+fn put_extended_egress_queue<S: Sink>(s: &mut S, r: &ExtendedEgressQueue) {
+    s.put_u32(r.queue);
+}
+
+/// Write Extended ACL - Format (0,1037)
+fn put_extended_acl<S: Sink>(s: &mut S, r: &ExtendedAcl) {
+    s.put_u32(r.number);
+    s.put_opaque(r.name.as_bytes());
+    s.put_u32(r.direction);
+}
+
+/// Write Extended Function - Format (0,1038)
+fn put_extended_function<S: Sink>(s: &mut S, r: &ExtendedFunction) {
+    s.put_opaque(r.symbol.as_bytes());
+}
+
+/// Write Extended Transit - Format (0,1039)
+fn put_extended_transit<S: Sink>(s: &mut S, r: &ExtendedTransit) {
+    s.put_u32(r.delay);
+}
+
+/// Write Extended Queue - Format (0,1040)
+fn put_extended_queue<S: Sink>(s: &mut S, r: &ExtendedQueue) {
+    s.put_u32(r.depth);
+}
+
+/// Write Extended HW Trap - Format (0,1041)
+fn put_extended_hw_trap<S: Sink>(s: &mut S, r: &ExtendedHwTrap) {
+    s.put_opaque(r.group.as_bytes());
+    s.put_opaque(r.trap.as_bytes());
+}
+
+/// Write Extended Linux Drop Reason - Format (0,1042)
+fn put_extended_linux_drop_reason<S: Sink>(s: &mut S, r: &ExtendedLinuxDropReason) {
+    s.put_opaque(r.reason.as_bytes());
+}
+
+/// Write Transaction - Format (0,2000)
+fn put_transaction<S: Sink>(s: &mut S, r: &Transaction) {
+    s.put_u32(r.direction as u32);
+    s.put_u32(r.wait);
+    s.put_u32(r.duration);
+    s.put_u32(r.status as u32);
+    s.put_u64(r.bytes_received);
+    s.put_u64(r.bytes_sent);
+}
+
+/// Write Extended NFS Storage Transaction - Format (0,2001)
+fn put_extended_nfs_storage_transaction<S: Sink>(s: &mut S, r: &ExtendedNfsStorageTransaction) {
+    s.put_opaque(&r.path);
+    s.put_u32(r.operation);
+    s.put_u32(r.status);
+}
+
+/// Write Extended SCSI Storage Transaction - Format (0,2002)
+fn put_extended_scsi_storage_transaction<S: Sink>(s: &mut S, r: &ExtendedScsiStorageTransaction) {
+    s.put_u32(r.lun);
+    s.put_u32(r.operation);
+    s.put_u32(r.status);
+}
+
+/// Write Extended HTTP Transaction - Format (0,2003)
+fn put_extended_http_transaction<S: Sink>(s: &mut S, r: &ExtendedHttpTransaction) {
+    s.put_opaque(r.url.as_bytes());
+    s.put_opaque(r.host.as_bytes());
+    s.put_opaque(r.referer.as_bytes());
+    s.put_opaque(r.user_agent.as_bytes());
+    s.put_opaque(r.user.as_bytes());
+    s.put_u32(r.status);
+}
+
+/// Write Extended Socket IPv4 - Format (0,2100)
+fn put_extended_socket_ipv4<S: Sink>(s: &mut S, r: &ExtendedSocketIpv4) {
+    s.put_u32(r.protocol);
+    s.put(&r.local_ip.octets());
+    s.put(&r.remote_ip.octets());
+    s.put_u32(r.local_port);
+    s.put_u32(r.remote_port);
+}
+
+/// Write Extended Socket IPv6 - Format (0,2101)
+fn put_extended_socket_ipv6<S: Sink>(s: &mut S, r: &ExtendedSocketIpv6) {
+    s.put_u32(r.protocol);
+    s.put(&r.local_ip.octets());
+    s.put(&r.remote_ip.octets());
+    s.put_u32(r.local_port);
+    s.put_u32(r.remote_port);
+}
+
+/// Write Extended Proxy Socket IPv4 - Format (0,2102)
+fn put_extended_proxy_socket_ipv4<S: Sink>(s: &mut S, r: &ExtendedProxySocketIpv4) {
+    put_extended_socket_ipv4(s, &r.socket);
+}
+
+/// Write Extended Proxy Socket IPv6 - Format (0,2103)
+fn put_extended_proxy_socket_ipv6<S: Sink>(s: &mut S, r: &ExtendedProxySocketIpv6) {
+    put_extended_socket_ipv6(s, &r.socket);
+}
+
+/// Write Application Context
+fn put_app_context<S: Sink>(s: &mut S, r: &AppContext) {
+    s.put_opaque(r.application.as_bytes());
+    s.put_opaque(r.operation.as_bytes());
+    s.put_opaque(r.attributes.as_bytes());
+}
+
+/// Write Memcache Operation - Format (0,2200)
+fn put_memcache_operation<S: Sink>(s: &mut S, r: &MemcacheOperation) {
+    s.put_u32(r.protocol as u32);
+    s.put_u32(r.cmd as u32);
+    s.put_opaque(r.key.as_bytes());
+    s.put_u32(r.nkeys);
+    s.put_u32(r.value_bytes);
+    s.put_u32(r.duration_us);
+    s.put_u32(r.status as u32);
+}
+
+/// Write HTTP Request - Format (0,2201) - DEPRECATED
+fn put_http_request_deprecated<S: Sink>(s: &mut S, r: &HttpRequestDeprecated) {
+    s.put_u32(r.method as u32);
+    s.put_opaque(r.uri.as_bytes());
+    s.put_opaque(r.host.as_bytes());
+    s.put_opaque(r.referer.as_bytes());
+    s.put_opaque(r.useragent.as_bytes());
+    s.put_opaque(r.xff.as_bytes());
+    s.put_opaque(r.authuser.as_bytes());
+    s.put_opaque(r.mime_type.as_bytes());
+    s.put_u64(r.req_bytes);
+    s.put_u64(r.resp_bytes);
+    s.put_u32(r.duration_us);
+    s.put_i32(r.status);
+}
+
+/// Write Application Operation - Format (0,2202)
+fn put_app_operation<S: Sink>(s: &mut S, r: &AppOperation) {
+    put_app_context(s, &r.context);
+    s.put_opaque(r.status_descr.as_bytes());
+    s.put_u64(r.req_bytes);
+    s.put_u64(r.resp_bytes);
+    s.put_u32(r.duration_us);
+    s.put_u32(r.status as u32);
+}
+
+/// Write Application Parent Context - Format (0,2203)
+fn put_app_parent_context<S: Sink>(s: &mut S, r: &AppParentContext) {
+    put_app_context(s, &r.context);
+}
+
+/// Write Application Initiator - Format (0,2204)
+fn put_app_initiator<S: Sink>(s: &mut S, r: &AppInitiator) {
+    s.put_opaque(r.actor.as_bytes());
+}
+
+/// Write Application Target - Format (0,2205)
+fn put_app_target<S: Sink>(s: &mut S, r: &AppTarget) {
+    s.put_opaque(r.actor.as_bytes());
+}
+
+/// Write HTTP Request - Format (0,2206)
+fn put_http_request<S: Sink>(s: &mut S, r: &HttpRequest) {
+    s.put_u32(r.method as u32);
+    s.put_u32(r.protocol);
+    s.put_opaque(r.uri.as_bytes());
+    s.put_opaque(r.host.as_bytes());
+    s.put_opaque(r.referer.as_bytes());
+    s.put_opaque(r.useragent.as_bytes());
+    s.put_opaque(r.xff.as_bytes());
+    s.put_opaque(r.authuser.as_bytes());
+    s.put_opaque(r.mime_type.as_bytes());
+    s.put_u64(r.req_bytes);
+    s.put_u64(r.resp_bytes);
+    s.put_u32(r.duration_us);
+    s.put_u32(r.status as u32);
+}
+
+/// Write Extended Proxy Request - Format (0,2207)
+fn put_extended_proxy_request<S: Sink>(s: &mut S, r: &ExtendedProxyRequest) {
+    s.put_opaque(r.uri.as_bytes());
+    s.put_opaque(r.host.as_bytes());
+}
+
+/// Write Extended Nav Timing - Format (0,2208)
+fn put_extended_nav_timing<S: Sink>(s: &mut S, r: &ExtendedNavTiming) {
+    s.put_u32(r.nav_type);
+    s.put_u32(r.redirect_count);
+    s.put_u32(r.navigation_start);
+    s.put_u32(r.unload_event_start);
+    s.put_u32(r.unload_event_end);
+    s.put_u32(r.redirect_start);
+    s.put_u32(r.redirect_end);
+    s.put_u32(r.fetch_start);
+    s.put_u32(r.domain_lookup_start);
+    s.put_u32(r.domain_lookup_end);
+    s.put_u32(r.connect_start);
+    s.put_u32(r.connect_end);
+    s.put_u32(r.secure_connection_start);
+    s.put_u32(r.request_start);
+    s.put_u32(r.response_start);
+    s.put_u32(r.response_end);
+    s.put_u32(r.dom_loading);
+    s.put_u32(r.dom_interactive);
+    s.put_u32(r.dom_content_loaded_event_start);
+    s.put_u32(r.dom_content_loaded_event_end);
+    s.put_u32(r.dom_complete);
+    s.put_u32(r.load_event_start);
+    s.put_u32(r.load_event_end);
+}
+
+/// Write Extended TCP Info - Format (0,2209)
+fn put_extended_tcp_info<S: Sink>(s: &mut S, r: &ExtendedTcpInfo) {
+    s.put_u32(r.dir as u32);
+    s.put_u32(r.snd_mss);
+    s.put_u32(r.rcv_mss);
+    s.put_u32(r.unacked);
+    s.put_u32(r.lost);
+    s.put_u32(r.retrans);
+    s.put_u32(r.pmtu);
+    s.put_u32(r.rtt);
+    s.put_u32(r.rttvar);
+    s.put_u32(r.snd_cwnd);
+    s.put_u32(r.reordering);
+    s.put_u32(r.min_rtt);
+}
+
+/// Write Extended Entities - Format (0,2210)
+fn put_extended_entities<S: Sink>(s: &mut S, r: &ExtendedEntities) {
+    put_data_source_expanded(s, &r.src_ds);
+    put_data_source_expanded(s, &r.dst_ds);
+}
+
+/// Write Extended BST Egress Queue - Format (4413,1)
+fn put_extended_bst_egress_queue<S: Sink>(s: &mut S, r: &ExtendedBstEgressQueue) {
+    s.put_u32(r.queue);
+}
+
+/// Write flow data based on its variant
+fn put_flow_data<S: Sink>(s: &mut S, data: &FlowData) {
+    match data {
+        FlowData::SampledHeader(r) => put_sampled_header(s, r),
+        FlowData::SampledEthernet(r) => put_sampled_ethernet(s, r),
+        FlowData::SampledIpv4(r) => put_sampled_ipv4(s, r),
+        FlowData::SampledIpv6(r) => put_sampled_ipv6(s, r),
+        FlowData::ExtendedSwitch(r) => put_extended_switch(s, r),
+        FlowData::ExtendedRouter(r) => put_extended_router(s, r),
+        FlowData::ExtendedGateway(r) => put_extended_gateway(s, r),
+        FlowData::ExtendedUser(r) => put_extended_user(s, r),
+        FlowData::ExtendedUrl(r) => put_extended_url(s, r),
+        FlowData::ExtendedMpls(r) => put_extended_mpls(s, r),
+        FlowData::ExtendedNat(r) => put_extended_nat(s, r),
+        FlowData::ExtendedMplsTunnel(r) => put_extended_mpls_tunnel(s, r),
+        FlowData::ExtendedMplsVc(r) => put_extended_mpls_vc(s, r),
+        FlowData::ExtendedMplsFec(r) => put_extended_mpls_fec(s, r),
+        FlowData::ExtendedMplsLvpFec(r) => put_extended_mpls_lvp_fec(s, r),
+        FlowData::ExtendedVlanTunnel(r) => put_extended_vlan_tunnel(s, r),
+        FlowData::Extended80211Payload(r) => put_extended_80211_payload(s, r),
+        FlowData::Extended80211Rx(r) => put_extended_80211_rx(s, r),
+        FlowData::Extended80211Tx(r) => put_extended_80211_tx(s, r),
+        FlowData::Extended80211Aggregation(r) => put_extended_80211_aggregation(s, r),
+        FlowData::ExtendedOpenFlowV1(r) => put_extended_openflow_v1(s, r),
+        FlowData::ExtendedFc(r) => put_extended_fc(s, r),
+        FlowData::ExtendedQueueLength(r) => put_extended_queue_length(s, r),
+        FlowData::ExtendedNatPort(r) => put_extended_nat_port(s, r),
+        FlowData::ExtendedL2TunnelEgress(r) => put_extended_l2_tunnel_egress(s, r),
+        FlowData::ExtendedL2TunnelIngress(r) => put_extended_l2_tunnel_ingress(s, r),
+        FlowData::ExtendedIpv4TunnelEgress(r) => put_extended_ipv4_tunnel_egress(s, r),
+        FlowData::ExtendedIpv4TunnelIngress(r) => put_extended_ipv4_tunnel_ingress(s, r),
+        FlowData::ExtendedIpv6TunnelEgress(r) => put_extended_ipv6_tunnel_egress(s, r),
+        FlowData::ExtendedIpv6TunnelIngress(r) => put_extended_ipv6_tunnel_ingress(s, r),
+        FlowData::ExtendedDecapsulateEgress(r) => put_extended_decapsulate_egress(s, r),
+        FlowData::ExtendedDecapsulateIngress(r) => put_extended_decapsulate_ingress(s, r),
+        FlowData::ExtendedVniEgress(r) => put_extended_vni_egress(s, r),
+        FlowData::ExtendedVniIngress(r) => put_extended_vni_ingress(s, r),
+        FlowData::ExtendedInfiniBandLrh(r) => put_extended_infiniband_lrh(s, r),
+        FlowData::ExtendedInfiniBandGrh(r) => put_extended_infiniband_grh(s, r),
+        FlowData::ExtendedInfiniBandBth(r) => put_extended_infiniband_bth(s, r),
+        FlowData::ExtendedVlanIn(r) => put_extended_vlan_in(s, r),
+        FlowData::ExtendedVlanOut(r) => put_extended_vlan_out(s, r),
+        FlowData::ExtendedEgressQueue(r) => put_extended_egress_queue(s, r),
+        FlowData::ExtendedAcl(r) => put_extended_acl(s, r),
+        FlowData::ExtendedFunction(r) => put_extended_function(s, r),
+        FlowData::ExtendedTransit(r) => put_extended_transit(s, r),
+        FlowData::ExtendedQueue(r) => put_extended_queue(s, r),
+        FlowData::ExtendedHwTrap(r) => put_extended_hw_trap(s, r),
+        FlowData::ExtendedLinuxDropReason(r) => put_extended_linux_drop_reason(s, r),
+        FlowData::Transaction(r) => put_transaction(s, r),
+        FlowData::ExtendedNfsStorageTransaction(r) => put_extended_nfs_storage_transaction(s, r),
+        FlowData::ExtendedScsiStorageTransaction(r) => put_extended_scsi_storage_transaction(s, r),
+        FlowData::ExtendedHttpTransaction(r) => put_extended_http_transaction(s, r),
+        FlowData::ExtendedSocketIpv4(r) => put_extended_socket_ipv4(s, r),
+        FlowData::ExtendedSocketIpv6(r) => put_extended_socket_ipv6(s, r),
+        FlowData::ExtendedProxySocketIpv4(r) => put_extended_proxy_socket_ipv4(s, r),
+        FlowData::ExtendedProxySocketIpv6(r) => put_extended_proxy_socket_ipv6(s, r),
+        FlowData::MemcacheOperation(r) => put_memcache_operation(s, r),
+        FlowData::HttpRequestDeprecated(r) => put_http_request_deprecated(s, r),
+        FlowData::AppOperation(r) => put_app_operation(s, r),
+        FlowData::AppParentContext(r) => put_app_parent_context(s, r),
+        FlowData::AppInitiator(r) => put_app_initiator(s, r),
+        FlowData::AppTarget(r) => put_app_target(s, r),
+        FlowData::HttpRequest(r) => put_http_request(s, r),
+        FlowData::ExtendedProxyRequest(r) => put_extended_proxy_request(s, r),
+        FlowData::ExtendedNavTiming(r) => put_extended_nav_timing(s, r),
+        FlowData::ExtendedTcpInfo(r) => put_extended_tcp_info(s, r),
+        FlowData::ExtendedEntities(r) => put_extended_entities(s, r),
+        FlowData::ExtendedBstEgressQueue(r) => put_extended_bst_egress_queue(s, r),
+        FlowData::Unknown { data, .. } => s.put(data),
+    }
+}
+
+/// Write a flow record: format, length, data and padding
+fn put_flow_record<S: Sink>(s: &mut S, record: &FlowRecord) {
+    let mut length = Length::default();
+    put_flow_data(&mut length, &record.flow_data);
+    s.put_u32(record.flow_format.0);
+    s.put_u32(length.0 as u32);
+    put_flow_data(s, &record.flow_data);
+    s.put_padding(length.0);
+}
+
+/// Write an array of flow records
+fn put_flow_records<S: Sink>(s: &mut S, records: &[FlowRecord]) {
+    s.put_u32(records.len() as u32);
+    for record in records {
+        put_flow_record(s, record);
+    }
+}
+
+/// Encode the data of a flow record, without the format and length
+///
+/// Unknown records are written back as their raw bytes.
+pub fn encode_flow_data(data: &FlowData) -> Vec<u8> {
+    let mut buf = Vec::new();
+    put_flow_data(&mut buf, data);
+    buf
+}
+
+fn put_flow_sample<S: Sink>(s: &mut S, sample: &FlowSample) {
+    s.put_u32(sample.sequence_number);
+    s.put_u32(sample.source_id.0);
+    s.put_u32(sample.sampling_rate);
+    s.put_u32(sample.sample_pool);
+    s.put_u32(sample.drops);
+    s.put_u32(sample.input.0);
+    s.put_u32(sample.output.0);
+    put_flow_records(s, &sample.flow_records);
+}
+
+fn put_flow_sample_expanded<S: Sink>(s: &mut S, sample: &FlowSampleExpanded) {
+    s.put_u32(sample.sequence_number);
+    put_data_source_expanded(s, &sample.source_id);
+    s.put_u32(sample.sampling_rate);
+    s.put_u32(sample.sample_pool);
+    s.put_u32(sample.drops);
+    s.put_u32(sample.input.format);
+    s.put_u32(sample.input.value);
+    s.put_u32(sample.output.format);
+    s.put_u32(sample.output.value);
+    put_flow_records(s, &sample.flow_records);
+}
+
+fn put_discarded_packet<S: Sink>(s: &mut S, sample: &DiscardedPacket) {
+    s.put_u32(sample.sequence_number);
+    put_data_source_expanded(s, &sample.source_id);
+    s.put_u32(sample.drops);
+    s.put_u32(sample.input_ifindex);
+    s.put_u32(sample.output_ifindex);
+    s.put_u32(sample.reason as u32);
+    put_flow_records(s, &sample.flow_records);
+}
+
+/// Encode a compact flow sample body (without the sample header)
+pub fn encode_flow_sample(sample: &FlowSample) -> Vec<u8> {
+    let mut buf = Vec::new();
+    put_flow_sample(&mut buf, sample);
+    buf
+}
+
+/// Encode an expanded flow sample body (without the sample header)
+pub fn encode_flow_sample_expanded(sample: &FlowSampleExpanded) -> Vec<u8> {
+    let mut buf = Vec::new();
+    put_flow_sample_expanded(&mut buf, sample);
+    buf
+}
+
+/// Write sample data based on its variant
+fn put_sample_data<S: Sink>(s: &mut S, data: &SampleData) -> Result<(), EncodeError> {
+    match data {
+        SampleData::FlowSample(sample) => put_flow_sample(s, sample),
+        SampleData::CountersSample(sample) => put_counters_sample(s, sample),
+        SampleData::FlowSampleExpanded(sample) => put_flow_sample_expanded(s, sample),
+        SampleData::CountersSampleExpanded(sample) => put_counters_sample_expanded(s, sample),
+        SampleData::DiscardedPacket(sample) => put_discarded_packet(s, sample),
+        SampleData::RtMetric { data, .. }
+        | SampleData::RtFlow { data, .. }
+        | SampleData::Unknown { data, .. } => s.put(data),
+        SampleData::Skipped { format, .. } => {
+            return Err(EncodeError::SkippedSample { format: *format })
+        }
+    }
+    Ok(())
+}
+
+/// Write a sample record: format, length, data and padding
+fn put_sample_record<S: Sink>(s: &mut S, sample: &SampleRecord) -> Result<(), EncodeError> {
+    let mut length = Length::default();
+    put_sample_data(&mut length, &sample.sample_data)?;
+    s.put_u32(sample.sample_type.0);
+    s.put_u32(length.0 as u32);
+    put_sample_data(s, &sample.sample_data)?;
+    s.put_padding(length.0);
+    Ok(())
+}
+
+/// Encode the body of a sample (without the sample header)
+///
+/// Fails for samples skipped while parsing, whose data is not available.
+pub fn encode_sample_data(data: &SampleData) -> Result<Vec<u8>, EncodeError> {
+    let mut buf = Vec::new();
+    put_sample_data(&mut buf, data)?;
+    Ok(buf)
+}
+
+fn put_datagram_header<S: Sink>(s: &mut S, datagram: &SFlowDatagram) {
+    s.put_u32(5); // version
+    s.put_address(&datagram.agent_address);
+    s.put_u32(datagram.sub_agent_id);
+    s.put_u32(datagram.sequence_number);
+    s.put_u32(datagram.uptime);
+    s.put_u32(datagram.samples.len() as u32);
+}
+
+/// Encode a datagram
+///
+/// Fails for datagrams holding samples skipped while parsing, see
+/// [`encode_sample_data`].
+pub fn encode_datagram(datagram: &SFlowDatagram) -> Result<Vec<u8>, EncodeError> {
+    let mut buf = Vec::with_capacity(datagram.encoded_len());
+    put_datagram_header(&mut buf, datagram);
+    for sample in &datagram.samples {
+        put_sample_record(&mut buf, sample)?;
+    }
+    Ok(buf)
+}
+
+/// Parse a datagram and encode it again
+///
+/// The encoder computes every sample and record length from the data it
+/// writes, so this rewrites the length fields of captures from agents that
+/// get them wrong, as long as the parser could still make sense of them.
+/// See [`SFlowDatagram::normalize`].
+pub fn reencode(data: &[u8]) -> crate::Result<Vec<u8>> {
+    parse_datagram(data)?
+        .normalize()
+        .map_err(|e| ParseError::InvalidData(e.to_string()))
+}
+
+impl SFlowDatagram {
+    /// Size in bytes of the datagram as written by [`encode_datagram`]
+    ///
+    /// Computed field by field, without allocating. Skipped samples, which
+    /// cannot be encoded, count with the length declared on the wire.
+    pub fn encoded_len(&self) -> usize {
+        let mut length = Length::default();
+        put_datagram_header(&mut length, self);
+        for sample in &self.samples {
+            match sample.sample_data {
+                SampleData::Skipped {
+                    length: declared, ..
+                } => length.0 += 8 + (declared as usize).next_multiple_of(4),
+                // Only skipped samples fail to encode
+                _ => put_sample_record(&mut length, sample).unwrap_or_default(),
+            }
+        }
+        length.0
+    }
+
+    /// Whether the datagram describes more bytes than the `raw_len` received
+    ///
+    /// A diagnostic for collectors logging likely fragmentation loss: the
+    /// header and the samples, skipped ones at their declared length, are
+    /// measured with [`encoded_len`](Self::encoded_len). To check raw bytes
+    /// before parsing them, see
+    /// [`parsers::looks_truncated`](crate::parsers::looks_truncated).
+    pub fn looks_truncated(&self, raw_len: usize) -> bool {
+        self.encoded_len() > raw_len
+    }
+
+    /// Encode the datagram with sample and record lengths recomputed
+    ///
+    /// The model holds no length fields: a datagram parsed from bytes with
+    /// wrong (but still parseable) lengths comes out of [`encode_datagram`]
+    /// with correct ones. Use [`reencode`] to go from bytes to bytes.
+    pub fn normalize(&self) -> Result<Vec<u8>, EncodeError> {
+        encode_datagram(self)
+    }
+}
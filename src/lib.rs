@@ -12,10 +12,51 @@
 //! let datagram = parse_datagram(&data).unwrap();
 //! println!("Parsed {} samples", datagram.samples.len());
 //! ```
+//!
+//! # Errors
+//!
+//! Parsing functions return [`Result`], whose error type [`ParseError`]
+//! implements [`std::error::Error`], so it can be boxed or propagated with
+//! `?`. I/O and UTF-8 failures are kept as the underlying error and exposed
+//! through [`source`](std::error::Error::source):
+//!
+//! ```
+//! use sflow_parser::{parse_datagram, ParseError};
+//!
+//! fn agent(data: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
+//!     Ok(parse_datagram(data)?.agent_address.to_string())
+//! }
+//!
+//! let err = agent(&[]).unwrap_err();
+//! assert!(matches!(
+//!     err.downcast_ref::<ParseError>(),
+//!     Some(ParseError::TooShort { .. })
+//! ));
+//! ```
 
+pub mod analysis;
+pub mod encode;
+pub mod listener;
 pub mod models;
 pub mod parsers;
+#[cfg(feature = "pcap")]
+pub mod pcap;
+#[cfg(feature = "pretty")]
+pub mod pretty;
+#[cfg(feature = "prost")]
+pub mod proto;
+#[cfg(feature = "schemars")]
+pub mod schema;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 
 // Re-export commonly used types
 pub use models::{SFlowDatagram, SampleData, SampleRecord};
-pub use parsers::{parse_datagram, parse_datagrams, ParseError};
+pub use parsers::{
+    parse_counters_only, parse_datagram, parse_datagram_verbose, parse_datagram_visit,
+    parse_datagram_with_options, parse_datagram_with_scratch, parse_datagrams, parse_header,
+    DatagramVisitor, ParseError, ParseOptions, ParseScratch, ParseWarning, Result,
+};
+
+#[cfg(feature = "schemars")]
+pub use schema::sflow_json_schema;
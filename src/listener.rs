@@ -0,0 +1,170 @@
+//! UDP listener for sFlow v5 datagrams
+//!
+//! A minimal blocking collector over [`std::net::UdpSocket`]. Each received
+//! datagram is parsed with the configured [`ParseOptions`].
+//!
+//! The listener always keeps cumulative [`ListenerStats`], see
+//! [`SFlowListener::stats`].
+//!
+//! With the `metrics` feature, the listener reports the following counters
+//! through the [`metrics`](https://docs.rs/metrics) facade (the application
+//! installs the recorder/exporter, e.g. Prometheus):
+//!
+//! - `sflow_datagrams_received`: datagrams received
+//! - `sflow_parse_errors`: datagrams that failed to parse
+//! - `sflow_samples_total`: samples in successfully parsed datagrams
+//! - `sflow_flow_records_total` / `sflow_counter_records_total`: records,
+//!   labeled with their `format` as `enterprise:format`
+
+use crate::models::SFlowDatagram;
+use crate::parsers::{parse_datagram_with_options, ParseOptions, Result};
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+/// Default sFlow collector port
+pub const DEFAULT_PORT: u16 = 6343;
+
+/// Maximum size of a UDP payload
+const MAX_DATAGRAM_SIZE: usize = 65535;
+
+/// Cumulative listener statistics since its creation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ListenerStats {
+    /// Datagrams received
+    pub datagrams: u64,
+    /// Bytes of datagram payload received
+    pub bytes: u64,
+    /// Datagrams that failed to parse
+    pub parse_errors: u64,
+    /// Samples in successfully parsed datagrams
+    pub samples: u64,
+}
+
+/// Blocking UDP listener parsing sFlow v5 datagrams
+pub struct SFlowListener {
+    socket: UdpSocket,
+    options: ParseOptions,
+    buffer: Vec<u8>,
+    stats: ListenerStats,
+}
+
+impl SFlowListener {
+    /// Bind a listener to the given address (e.g. `0.0.0.0:6343`)
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        Ok(Self::from_socket(UdpSocket::bind(addr)?))
+    }
+
+    /// Create a listener from an already bound socket
+    pub fn from_socket(socket: UdpSocket) -> Self {
+        Self {
+            socket,
+            options: ParseOptions::default(),
+            buffer: vec![0u8; MAX_DATAGRAM_SIZE],
+            stats: ListenerStats::default(),
+        }
+    }
+
+    /// Set the options used to parse received datagrams
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Local address the listener is bound to
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Underlying socket (e.g. to set a read timeout)
+    pub fn socket(&self) -> &UdpSocket {
+        &self.socket
+    }
+
+    /// Statistics of the datagrams received or processed so far
+    pub fn stats(&self) -> ListenerStats {
+        self.stats
+    }
+
+    /// Receive and parse a single datagram
+    ///
+    /// The outer error is a socket error, the inner result is the parse result.
+    pub fn recv(&mut self) -> io::Result<(SocketAddr, Result<SFlowDatagram>)> {
+        let (len, peer) = self.socket.recv_from(&mut self.buffer)?;
+        let payload = std::mem::take(&mut self.buffer);
+        let result = self.process(&payload[..len]);
+        self.buffer = payload;
+        Ok((peer, result))
+    }
+
+    /// Receive datagrams forever, calling `handler` with each parse result
+    ///
+    /// Returns only on a socket error.
+    pub fn run<F>(&mut self, mut handler: F) -> io::Result<()>
+    where
+        F: FnMut(SocketAddr, Result<SFlowDatagram>),
+    {
+        loop {
+            let (peer, result) = self.recv()?;
+            handler(peer, result);
+        }
+    }
+
+    /// Parse a datagram payload as if it had been received by the listener
+    ///
+    /// This is the parse step of [`recv`](Self::recv), useful to replay
+    /// captured payloads through the same accounting.
+    pub fn process(&mut self, payload: &[u8]) -> Result<SFlowDatagram> {
+        let result = parse_datagram_with_options(payload, &self.options);
+        self.stats.datagrams += 1;
+        self.stats.bytes += payload.len() as u64;
+        match &result {
+            Ok(datagram) => self.stats.samples += datagram.samples.len() as u64,
+            Err(_) => self.stats.parse_errors += 1,
+        }
+        #[cfg(feature = "metrics")]
+        record_metrics(&result);
+        result
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn record_metrics(result: &Result<SFlowDatagram>) {
+    use crate::models::SampleData;
+
+    metrics::counter!("sflow_datagrams_received").increment(1);
+    let datagram = match result {
+        Ok(datagram) => datagram,
+        Err(_) => {
+            metrics::counter!("sflow_parse_errors").increment(1);
+            return;
+        }
+    };
+
+    metrics::counter!("sflow_samples_total").increment(datagram.samples.len() as u64);
+    for sample in &datagram.samples {
+        let (flows, counters) = match &sample.sample_data {
+            SampleData::FlowSample(s) => (Some(&s.flow_records), None),
+            SampleData::FlowSampleExpanded(s) => (Some(&s.flow_records), None),
+            SampleData::DiscardedPacket(s) => (Some(&s.flow_records), None),
+            SampleData::CountersSample(s) => (None, Some(&s.counters)),
+            SampleData::CountersSampleExpanded(s) => (None, Some(&s.counters)),
+            _ => (None, None),
+        };
+        for record in flows.into_iter().flatten() {
+            let format = record.flow_format;
+            metrics::counter!(
+                "sflow_flow_records_total",
+                "format" => format!("{}:{}", format.enterprise(), format.format())
+            )
+            .increment(1);
+        }
+        for record in counters.into_iter().flatten() {
+            let format = record.counter_format;
+            metrics::counter!(
+                "sflow_counter_records_total",
+                "format" => format!("{}:{}", format.enterprise(), format.format())
+            )
+            .increment(1);
+        }
+    }
+}
@@ -3,13 +3,16 @@
 //! This module contains the data structures representing sFlow v5 datagrams
 //! as defined in <https://sflow.org/sflow_version_5.txt>
 
-use std::net::{Ipv4Addr, Ipv6Addr};
+use crate::models::record_type::{CounterRecordType, FlowRecordType};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
 
 /// MAC address (6 bytes)
 ///
 /// Represents a 48-bit IEEE 802 MAC address.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MacAddress(pub [u8; 6]);
 
 impl MacAddress {
@@ -64,6 +67,7 @@ impl From<MacAddress> for [u8; 6] {
 /// sFlow datagram version
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum DatagramVersion {
     Version5 = 5,
 }
@@ -88,8 +92,9 @@ pub enum DatagramVersion {
 ///       ip_v6 ip;
 /// }
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Address {
     /// Unknown address type
     Unknown,
@@ -99,6 +104,271 @@ pub enum Address {
     IPv6(Ipv6Addr),
 }
 
+impl Address {
+    /// IP address, or `None` for an unknown address type
+    pub fn to_ip_addr(&self) -> Option<IpAddr> {
+        match self {
+            Address::Unknown => None,
+            Address::IPv4(addr) => Some(IpAddr::V4(*addr)),
+            Address::IPv6(addr) => Some(IpAddr::V6(*addr)),
+        }
+    }
+
+    /// Render the address the way sflowtool prints it
+    ///
+    /// IPv4 in dotted-quad notation, IPv6 in its compressed form (RFC 5952)
+    /// and `0.0.0.0` for an unknown address type.
+    pub fn to_canonical_string(&self) -> String {
+        match self {
+            Address::Unknown => Ipv4Addr::UNSPECIFIED.to_string(),
+            Address::IPv4(addr) => addr.to_string(),
+            Address::IPv6(addr) => addr.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_canonical_string())
+    }
+}
+
+/// IP prefix (network address and prefix length)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct IpPrefix {
+    /// Network address, host bits cleared
+    pub addr: IpAddr,
+
+    /// Prefix length in bits
+    pub len: u8,
+}
+
+impl IpPrefix {
+    /// Build the prefix of length `len` containing `addr`
+    ///
+    /// Host bits of `addr` are cleared. Returns `None` if `len` exceeds the
+    /// address width (32 for IPv4, 128 for IPv6).
+    pub fn new(addr: IpAddr, len: u32) -> Option<Self> {
+        let addr = match addr {
+            IpAddr::V4(v4) if len <= 32 => {
+                let mask = u32::MAX.checked_shl(32 - len).unwrap_or(0);
+                IpAddr::V4(Ipv4Addr::from(u32::from(v4) & mask))
+            }
+            IpAddr::V6(v6) if len <= 128 => {
+                let mask = u128::MAX.checked_shl(128 - len).unwrap_or(0);
+                IpAddr::V6(Ipv6Addr::from(u128::from(v6) & mask))
+            }
+            _ => return None,
+        };
+        Some(Self {
+            addr,
+            len: len as u8,
+        })
+    }
+}
+
+impl std::fmt::Display for IpPrefix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.addr, self.len)
+    }
+}
+
+/// IP 5-tuple identifying a flow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct FiveTuple {
+    /// Source IP address
+    pub src_ip: IpAddr,
+
+    /// Destination IP address
+    pub dst_ip: IpAddr,
+
+    /// IP protocol
+    pub protocol: crate::models::record_flows::IpProtocol,
+
+    /// Source port (0 when the protocol has no ports)
+    pub src_port: u16,
+
+    /// Destination port (0 when the protocol has no ports)
+    pub dst_port: u16,
+}
+
+/// Layer 4 ports of a sampled packet before and after NAT
+///
+/// See [`FlowSample::port_translation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortTranslation {
+    /// Source port reported by the sampled packet
+    pub src_port: u32,
+
+    /// Source port after translation
+    pub translated_src_port: u32,
+
+    /// Destination port reported by the sampled packet
+    pub dst_port: u32,
+
+    /// Destination port after translation
+    pub translated_dst_port: u32,
+}
+
+impl PortTranslation {
+    /// Whether the source port was translated
+    pub fn src_translated(&self) -> bool {
+        self.src_port != self.translated_src_port
+    }
+
+    /// Whether the destination port was translated
+    pub fn dst_translated(&self) -> bool {
+        self.dst_port != self.translated_dst_port
+    }
+}
+
+/// Transit delay and queue depth experienced by a sampled packet
+///
+/// See [`FlowSample::transit_info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TransitInfo {
+    /// Transit delay through the switch, `None` if not reported
+    pub delay: Option<TransitDelay>,
+
+    /// Queue depth in bytes, `None` if not reported
+    pub queue_depth: Option<u32>,
+}
+
+/// Transit delay reported by an Extended Transit (0,1039) record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum TransitDelay {
+    /// Measured delay
+    Measured(Duration),
+    /// Delay of 0xffffffff ns (about 4.3s) or more
+    Saturated,
+}
+
+impl TransitDelay {
+    /// Measured delay, `None` if saturated
+    pub fn duration(&self) -> Option<Duration> {
+        match self {
+            Self::Measured(delay) => Some(*delay),
+            Self::Saturated => None,
+        }
+    }
+}
+
+/// Sampled IPv4 (0,3) or Sampled IPv6 (0,4) record
+///
+/// Gives uniform access to the packet fields of either IP version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampledIp<'a> {
+    /// Sampled IPv4 record
+    V4(&'a crate::models::record_flows::SampledIpv4),
+    /// Sampled IPv6 record
+    V6(&'a crate::models::record_flows::SampledIpv6),
+}
+
+impl SampledIp<'_> {
+    /// Source IP address
+    pub fn src_ip(&self) -> IpAddr {
+        match self {
+            SampledIp::V4(ip) => IpAddr::V4(ip.src_ip),
+            SampledIp::V6(ip) => IpAddr::V6(ip.src_ip),
+        }
+    }
+
+    /// Destination IP address
+    pub fn dst_ip(&self) -> IpAddr {
+        match self {
+            SampledIp::V4(ip) => IpAddr::V4(ip.dst_ip),
+            SampledIp::V6(ip) => IpAddr::V6(ip.dst_ip),
+        }
+    }
+
+    /// IP protocol (IPv4 protocol or IPv6 next header)
+    pub fn protocol(&self) -> crate::models::record_flows::IpProtocol {
+        match self {
+            SampledIp::V4(ip) => ip.ip_protocol(),
+            SampledIp::V6(ip) => ip.ip_protocol(),
+        }
+    }
+
+    /// Source port (TCP/UDP)
+    pub fn src_port(&self) -> u32 {
+        match self {
+            SampledIp::V4(ip) => ip.src_port,
+            SampledIp::V6(ip) => ip.src_port,
+        }
+    }
+
+    /// Destination port (TCP/UDP)
+    pub fn dst_port(&self) -> u32 {
+        match self {
+            SampledIp::V4(ip) => ip.dst_port,
+            SampledIp::V6(ip) => ip.dst_port,
+        }
+    }
+}
+
+/// Application transaction reported by a flow sample
+///
+/// Pairs the generic Transaction (0,2000) record with the NFS (0,2001),
+/// SCSI (0,2002) or HTTP (0,2003) record describing the same transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionView<'a> {
+    /// Transaction without a specific detail record
+    Generic(&'a crate::models::record_flows::Transaction),
+    /// NFS storage transaction
+    Nfs(
+        &'a crate::models::record_flows::Transaction,
+        &'a crate::models::record_flows::ExtendedNfsStorageTransaction,
+    ),
+    /// SCSI storage transaction
+    Scsi(
+        &'a crate::models::record_flows::Transaction,
+        &'a crate::models::record_flows::ExtendedScsiStorageTransaction,
+    ),
+    /// HTTP transaction
+    Http(
+        &'a crate::models::record_flows::Transaction,
+        &'a crate::models::record_flows::ExtendedHttpTransaction,
+    ),
+}
+
+impl<'a> TransactionView<'a> {
+    /// The generic transaction record
+    pub fn transaction(&self) -> &'a crate::models::record_flows::Transaction {
+        match *self {
+            TransactionView::Generic(transaction)
+            | TransactionView::Nfs(transaction, _)
+            | TransactionView::Scsi(transaction, _)
+            | TransactionView::Http(transaction, _) => transaction,
+        }
+    }
+
+    /// Time from start of processing to completion
+    pub fn duration(&self) -> Duration {
+        Duration::from_micros(self.transaction().duration as u64)
+    }
+
+    /// Time the transaction was queued before processing started
+    pub fn wait(&self) -> Duration {
+        Duration::from_micros(self.transaction().wait as u64)
+    }
+
+    /// HTTP status code, for HTTP transactions
+    pub fn http_status(&self) -> Option<u32> {
+        match self {
+            TransactionView::Http(_, http) => Some(http.status),
+            _ => None,
+        }
+    }
+}
+
 /// Data format identifier
 ///
 /// Encodes enterprise ID and format number in a single 32-bit value.
@@ -112,19 +382,23 @@ pub enum Address {
 ///    the sFlow specification. For example, the combination of enterprise = 0
 ///    and format = 1 identifies the "sampled_header" flow_data structure. */
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DataFormat(pub u32);
 
 impl DataFormat {
-    pub fn new(enterprise: u32, format: u32) -> Self {
+    /// Pack an enterprise ID and a format number (truncated to 12 bits)
+    pub const fn new(enterprise: u32, format: u32) -> Self {
         Self((enterprise << 12) | (format & 0xFFF))
     }
 
+    /// Enterprise ID (top 20 bits)
     pub fn enterprise(&self) -> u32 {
         self.0 >> 12
     }
 
+    /// Format number (bottom 12 bits)
     pub fn format(&self) -> u32 {
         self.0 & 0xFFF
     }
@@ -143,8 +417,9 @@ impl DataFormat {
 ///    sFlowDataSource (e.g. ifIndex, smonVlanDataSource, entPhysicalEntry) and the lower
 ///    three bytes contain the relevant index value. */
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DataSource(pub u32);
 
 impl DataSource {
@@ -161,6 +436,69 @@ impl DataSource {
     }
 }
 
+impl std::fmt::Display for DataFormat {
+    /// Formats as `enterprise:format`, e.g. `0:3`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.enterprise(), self.format())
+    }
+}
+
+impl std::str::FromStr for DataFormat {
+    type Err = ParseIdError;
+
+    /// Parses `enterprise:format` (20-bit enterprise, 12-bit format)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (enterprise, format) = parse_id_pair(s, 0xFFFFF, 0xFFF)?;
+        Ok(Self::new(enterprise, format))
+    }
+}
+
+impl std::fmt::Display for DataSource {
+    /// Formats as `type:index`, e.g. `0:17`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.source_type(), self.index())
+    }
+}
+
+impl std::str::FromStr for DataSource {
+    type Err = ParseIdError;
+
+    /// Parses `type:index` (8-bit source type, 24-bit index)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (source_type, index) = parse_id_pair(s, 0xFF, 0xFFFFFF)?;
+        Ok(Self::new(source_type as u8, index))
+    }
+}
+
+/// Error returned when parsing a [`DataFormat`] or [`DataSource`] from a string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseIdError(String);
+
+impl std::fmt::Display for ParseIdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid identifier: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseIdError {}
+
+/// Split `a:b` into two integers, checking each against its bit-field maximum
+fn parse_id_pair(s: &str, first_max: u32, second_max: u32) -> Result<(u32, u32), ParseIdError> {
+    let (first, second) = s
+        .split_once(':')
+        .ok_or_else(|| ParseIdError(format!("expected two values separated by ':' in {:?}", s)))?;
+    let parse = |part: &str, max: u32| -> Result<u32, ParseIdError> {
+        let value: u32 = part
+            .parse()
+            .map_err(|_| ParseIdError(format!("{:?} is not an unsigned integer", part)))?;
+        if value > max {
+            return Err(ParseIdError(format!("{} exceeds maximum {}", value, max)));
+        }
+        Ok(value)
+    };
+    Ok((parse(first, first_max)?, parse(second, second_max)?))
+}
+
 /// Expanded data source (for ifIndex >= 2^24)
 ///
 /// Used when the index value exceeds 24 bits (16,777,215).
@@ -173,8 +511,9 @@ impl DataSource {
 ///    unsigned int source_id_index; /* sFlowDataSource index */
 /// }
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DataSourceExpanded {
     /// Source type (e.g., 0 = ifIndex, 1 = smonVlanDataSource, 2 = entPhysicalEntry)
     pub source_id_type: u32,
@@ -182,6 +521,21 @@ pub struct DataSourceExpanded {
     pub source_id_index: u32,
 }
 
+/// Data source identifier normalized across sample encodings
+///
+/// Compact data sources convert into the expanded form, which holds every
+/// compact value, so samples from both encodings share keys.
+pub type DataSourceKey = DataSourceExpanded;
+
+impl From<DataSource> for DataSourceExpanded {
+    fn from(source: DataSource) -> Self {
+        Self {
+            source_id_type: source.source_type() as u32,
+            source_id_index: source.index(),
+        }
+    }
+}
+
 /// Interface identifier
 ///
 /// Compact encoding for interface identification. Top 2 bits indicate format:
@@ -200,8 +554,9 @@ pub struct DataSourceExpanded {
 ///       10 = Multiple destinations
 ///    Bits 29-0: Value */
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Interface(pub u32);
 
 impl Interface {
@@ -244,8 +599,9 @@ impl Interface {
 ///
 /// **ERRATUM:** 0xFFFFFFFF is the maximum value and must be used to indicate traffic
 /// originating or terminating in device (do not use 0x3FFFFFFF value from compact encoding example).
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct InterfaceExpanded {
     /// Interface format (0 = ifIndex, 1 = packet discarded, 2 = multiple destinations)
     pub format: u32,
@@ -254,9 +610,34 @@ pub struct InterfaceExpanded {
     pub value: u32,
 }
 
+impl InterfaceExpanded {
+    /// Whether the packet was discarded (format 1)
+    pub fn is_discarded(&self) -> bool {
+        self.format == 1
+    }
+}
+
+/// Expanded form of a compact interface
+///
+/// The compact value 0x3FFFFFFF for traffic originating or terminating in
+/// the device becomes 0xFFFFFFFF, its expanded equivalent.
+impl From<Interface> for InterfaceExpanded {
+    fn from(interface: Interface) -> Self {
+        let value = match (interface.format(), interface.value()) {
+            (0, 0x3FFFFFFF) => 0xFFFFFFFF,
+            (_, value) => value,
+        };
+        Self {
+            format: interface.format() as u32,
+            value,
+        }
+    }
+}
+
 /// Flow data types
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum FlowData {
     /// Sampled Header - Format (0,1)
     SampledHeader(crate::models::record_flows::SampledHeader),
@@ -394,9 +775,91 @@ pub enum FlowData {
     Unknown { format: DataFormat, data: Vec<u8> },
 }
 
+impl FlowData {
+    /// Enterprise and format number of an unknown record
+    ///
+    /// Returns `None` for decoded records. The full 20-bit enterprise and
+    /// the 12-bit format are taken from the record's data format.
+    pub fn unknown_enterprise_format(&self) -> Option<(u32, u32)> {
+        match self {
+            FlowData::Unknown { format, .. } => Some((format.enterprise(), format.format())),
+            _ => None,
+        }
+    }
+
+    /// Whether the record explains why a packet was dropped
+    ///
+    /// The drop records of the sFlow drops extension: Extended ACL (0,1037),
+    /// Extended Function (0,1038), Extended HW Trap (0,1041) and Extended
+    /// Linux Drop Reason (0,1042).
+    pub fn is_drop_record(&self) -> bool {
+        matches!(
+            self,
+            FlowData::ExtendedAcl(_)
+                | FlowData::ExtendedFunction(_)
+                | FlowData::ExtendedHwTrap(_)
+                | FlowData::ExtendedLinuxDropReason(_)
+        )
+    }
+
+    /// IP addresses carried by the record's own fields
+    ///
+    /// Covers sampled IPv4/IPv6 and tunnel headers, router, gateway and
+    /// MPLS next hops, the MPLS FEC prefix, NAT addresses and (proxy)
+    /// sockets. Raw sampled headers contribute their source and destination
+    /// when [`SampledHeader::decode`](crate::models::record_flows::SampledHeader::decode)
+    /// understands them. Addresses of unknown type are skipped.
+    pub fn ip_addresses(&self) -> Vec<IpAddr> {
+        use crate::models::record_flows::{SampledIpv4, SampledIpv6};
+
+        let v4 = |h: &SampledIpv4| vec![IpAddr::V4(h.src_ip), IpAddr::V4(h.dst_ip)];
+        let v6 = |h: &SampledIpv6| vec![IpAddr::V6(h.src_ip), IpAddr::V6(h.dst_ip)];
+        match self {
+            FlowData::SampledHeader(h) => h
+                .decode()
+                .map(|decoded| vec![decoded.src_ip, decoded.dst_ip])
+                .unwrap_or_default(),
+            FlowData::SampledIpv4(h) => v4(h),
+            FlowData::SampledIpv6(h) => v6(h),
+            FlowData::ExtendedIpv4TunnelEgress(t) => v4(&t.header),
+            FlowData::ExtendedIpv4TunnelIngress(t) => v4(&t.header),
+            FlowData::ExtendedIpv6TunnelEgress(t) => v6(&t.header),
+            FlowData::ExtendedIpv6TunnelIngress(t) => v6(&t.header),
+            FlowData::ExtendedRouter(r) => r.next_hop.to_ip_addr().into_iter().collect(),
+            FlowData::ExtendedGateway(g) => g.next_hop.to_ip_addr().into_iter().collect(),
+            FlowData::ExtendedMpls(m) => m.next_hop.to_ip_addr().into_iter().collect(),
+            FlowData::ExtendedMplsFec(f) => f.fec_addr_prefix.to_ip_addr().into_iter().collect(),
+            FlowData::ExtendedNat(n) => [&n.src_address, &n.dst_address]
+                .into_iter()
+                .filter_map(Address::to_ip_addr)
+                .collect(),
+            FlowData::ExtendedSocketIpv4(s) => {
+                vec![IpAddr::V4(s.local_ip), IpAddr::V4(s.remote_ip)]
+            }
+            FlowData::ExtendedSocketIpv6(s) => {
+                vec![IpAddr::V6(s.local_ip), IpAddr::V6(s.remote_ip)]
+            }
+            FlowData::ExtendedProxySocketIpv4(p) => {
+                vec![
+                    IpAddr::V4(p.socket.local_ip),
+                    IpAddr::V4(p.socket.remote_ip),
+                ]
+            }
+            FlowData::ExtendedProxySocketIpv6(p) => {
+                vec![
+                    IpAddr::V6(p.socket.local_ip),
+                    IpAddr::V6(p.socket.remote_ip),
+                ]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
 /// Flow record containing flow data
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct FlowRecord {
     pub flow_format: DataFormat,
     pub flow_data: FlowData,
@@ -405,6 +868,7 @@ pub struct FlowRecord {
 /// Counter data types
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum CounterData {
     /// Generic Interface Counters - Format (0,1)
     GenericInterface(crate::models::record_counters::GenericInterfaceCounters),
@@ -509,6 +973,7 @@ pub enum CounterData {
 /// Counter record containing counter data
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct CounterRecord {
     pub counter_format: DataFormat,
     pub counter_data: CounterData,
@@ -544,6 +1009,7 @@ pub struct CounterRecord {
 /// **ERRATUM:** Sequence number clarified as incremented per "sFlow Instance" instead of "source_id".
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct FlowSample {
     /// Sequence number incremented with each flow sample generated by this sFlow Instance
     /// **ERRATUM:** Clarified as "sFlow Instance" instead of "source_id"
@@ -564,6 +1030,335 @@ pub struct FlowSample {
     pub flow_records: Vec<FlowRecord>,
 }
 
+/// Inner header offset of a list of flow records, see
+/// [`FlowSample::inner_header_offset`]
+pub(crate) fn inner_header_offset(flow_records: &[FlowRecord]) -> Option<u32> {
+    let offset = |egress: bool| {
+        flow_records
+            .iter()
+            .find_map(|record| match &record.flow_data {
+                FlowData::ExtendedDecapsulateEgress(d) if egress => Some(d.inner_header_offset),
+                FlowData::ExtendedDecapsulateIngress(d) if !egress => Some(d.inner_header_offset),
+                _ => None,
+            })
+    };
+    offset(true).or_else(|| offset(false))
+}
+
+impl FlowSample {
+    /// All flow records of type `T`, in record order
+    ///
+    /// ```
+    /// # use sflow_parser::models::{FlowSample, SampledIpv4};
+    /// # fn example(sample: &FlowSample) {
+    /// for ip in sample.records::<SampledIpv4>() {
+    ///     println!("{} -> {}", ip.src_ip, ip.dst_ip);
+    /// }
+    /// # }
+    /// ```
+    pub fn records<T: FlowRecordType>(&self) -> impl Iterator<Item = &T> {
+        self.flow_records
+            .iter()
+            .filter_map(|record| T::from_flow_data(&record.flow_data))
+    }
+
+    /// VXLAN/overlay network identifiers as (ingress, egress)
+    ///
+    /// Collects the Extended VNI Ingress (0,1030) and Extended VNI Egress
+    /// (0,1029) records. Returns `None` when neither record is present.
+    pub fn vni(&self) -> Option<(Option<u32>, Option<u32>)> {
+        let mut ingress = None;
+        let mut egress = None;
+        for record in &self.flow_records {
+            match &record.flow_data {
+                FlowData::ExtendedVniIngress(vni) => ingress = Some(vni.vni),
+                FlowData::ExtendedVniEgress(vni) => egress = Some(vni.vni),
+                _ => {}
+            }
+        }
+        if ingress.is_none() && egress.is_none() {
+            return None;
+        }
+        Some((ingress, egress))
+    }
+
+    /// Offset of the inner header within the sampled header
+    ///
+    /// Taken from the Extended Decapsulate Egress (0,1027) record, or else
+    /// the Extended Decapsulate Ingress (0,1028) record. Returns `None` when
+    /// neither record is present.
+    pub fn inner_header_offset(&self) -> Option<u32> {
+        inner_header_offset(&self.flow_records)
+    }
+
+    /// Egress queue selected for the sampled packet
+    ///
+    /// Taken from the Extended Egress Queue (0,1036) record, falling back
+    /// to the Broadcom Extended BST Egress Queue (4413,1) record that older
+    /// Broadcom based agents export instead. Returns `None` when neither
+    /// record is present.
+    pub fn egress_queue(&self) -> Option<u32> {
+        use crate::models::record_flows::{ExtendedBstEgressQueue, ExtendedEgressQueue};
+
+        self.records::<ExtendedEgressQueue>()
+            .next()
+            .map(|egress| egress.queue)
+            .or_else(|| {
+                self.records::<ExtendedBstEgressQueue>()
+                    .next()
+                    .map(|egress| egress.queue)
+            })
+    }
+
+    /// Check if the sampled packet was dropped (output interface discarded)
+    pub fn is_discard(&self) -> bool {
+        self.output.is_discarded()
+    }
+
+    /// Reason a discarded packet was dropped, from the drop records
+    ///
+    /// An Extended ACL (0,1037) record means the packet was dropped by an
+    /// access list. Otherwise the trap of an Extended HW Trap (0,1041)
+    /// record is looked up by name, falling back to the `unknown_*` reason
+    /// of its group. A Linux drop reason (0,1042) or the dropping function
+    /// (0,1038) have no equivalent in [`DropReason`] and give
+    /// [`DropReason::Unknown`]; see [`linux_drop_reason`](Self::linux_drop_reason)
+    /// and [`drop_function`](Self::drop_function). Returns `None` if the
+    /// packet was not discarded or no drop record explains it, see
+    /// [`FlowData::is_drop_record`].
+    pub fn discard_explanation(&self) -> Option<DropReason> {
+        use crate::models::record_flows::{ExtendedAcl, ExtendedHwTrap, TrapCategory};
+
+        if !self.is_discard() {
+            return None;
+        }
+        if self.records::<ExtendedAcl>().next().is_some() {
+            return Some(DropReason::Acl);
+        }
+        if let Some(trap) = self.records::<ExtendedHwTrap>().next() {
+            return DropReason::from_name(&trap.trap).or(Some(match trap.group.as_str() {
+                "l3_exceptions" => DropReason::UnknownL3Exception,
+                _ => match trap.category() {
+                    TrapCategory::L2 => DropReason::UnknownL2,
+                    TrapCategory::L3 => DropReason::UnknownL3,
+                    TrapCategory::Buffer => DropReason::UnknownBuffer,
+                    TrapCategory::Tunnel => DropReason::UnknownTunnel,
+                    _ => DropReason::Unknown,
+                },
+            }));
+        }
+        self.flow_records
+            .iter()
+            .any(|record| record.flow_data.is_drop_record())
+            .then_some(DropReason::Unknown)
+    }
+
+    /// Linux drop_monitor reason of the Extended Linux Drop Reason (0,1042) record
+    pub fn linux_drop_reason(&self) -> Option<&str> {
+        use crate::models::record_flows::ExtendedLinuxDropReason;
+
+        self.records::<ExtendedLinuxDropReason>()
+            .next()
+            .map(|drop| drop.reason.as_str())
+    }
+
+    /// Software function that dropped the packet, from the Extended Function (0,1038) record
+    pub fn drop_function(&self) -> Option<&str> {
+        use crate::models::record_flows::ExtendedFunction;
+
+        self.records::<ExtendedFunction>()
+            .next()
+            .map(|function| function.symbol.as_str())
+    }
+
+    /// First Sampled IPv4 (0,3) or Sampled IPv6 (0,4) record of the sample
+    pub fn sampled_ip(&self) -> Option<SampledIp<'_>> {
+        self.flow_records
+            .iter()
+            .find_map(|record| match &record.flow_data {
+                FlowData::SampledIpv4(ip) => Some(SampledIp::V4(ip)),
+                FlowData::SampledIpv6(ip) => Some(SampledIp::V6(ip)),
+                _ => None,
+            })
+    }
+
+    /// Ports of the sampled packet and their NAT translation
+    ///
+    /// Compares the Extended NAT Port (0,1020) record with the ports of the
+    /// Sampled IPv4 (0,3) / Sampled IPv6 (0,4) record; an untranslated port
+    /// is reported unchanged. Returns `None` unless both records are present.
+    pub fn port_translation(&self) -> Option<PortTranslation> {
+        use crate::models::record_flows::ExtendedNatPort;
+
+        let nat = self.records::<ExtendedNatPort>().next()?;
+        let ip = self.sampled_ip()?;
+        Some(PortTranslation {
+            src_port: ip.src_port(),
+            translated_src_port: nat.src_port,
+            dst_port: ip.dst_port(),
+            translated_dst_port: nat.dst_port,
+        })
+    }
+
+    /// Transit delay and queue depth of the sampled packet
+    ///
+    /// Gathers the Extended Transit (0,1039) and Extended Queue (0,1040)
+    /// records. A saturated delay is reported as [`TransitDelay::Saturated`].
+    /// Returns `None` when neither record is present.
+    pub fn transit_info(&self) -> Option<TransitInfo> {
+        use crate::models::record_flows::{ExtendedQueue, ExtendedTransit};
+
+        let transit = self.records::<ExtendedTransit>().next();
+        let queue = self.records::<ExtendedQueue>().next();
+        if transit.is_none() && queue.is_none() {
+            return None;
+        }
+        Some(TransitInfo {
+            delay: transit.map(|transit| match transit.delay_duration() {
+                Some(delay) => TransitDelay::Measured(delay),
+                None => TransitDelay::Saturated,
+            }),
+            queue_depth: queue.map(|queue| queue.depth),
+        })
+    }
+
+    /// IP 5-tuple of the sampled packet, whichever record reports it
+    ///
+    /// Sources are tried in priority order: Sampled IPv4 (0,3) / Sampled
+    /// IPv6 (0,4), then the decoded Sampled Header (0,1), then Extended
+    /// Socket IPv4 (0,2100) / IPv6 (0,2101) with the local endpoint as
+    /// source. Returns `None` when no record provides the 5-tuple.
+    pub fn canonical_five_tuple(&self) -> Option<FiveTuple> {
+        use crate::models::record_flows::IpProtocol;
+
+        let sampled_ip = self
+            .flow_records
+            .iter()
+            .find_map(|record| match &record.flow_data {
+                FlowData::SampledIpv4(ip) => Some(FiveTuple {
+                    src_ip: IpAddr::V4(ip.src_ip),
+                    dst_ip: IpAddr::V4(ip.dst_ip),
+                    protocol: ip.ip_protocol(),
+                    src_port: ip.src_port as u16,
+                    dst_port: ip.dst_port as u16,
+                }),
+                FlowData::SampledIpv6(ip) => Some(FiveTuple {
+                    src_ip: IpAddr::V6(ip.src_ip),
+                    dst_ip: IpAddr::V6(ip.dst_ip),
+                    protocol: ip.ip_protocol(),
+                    src_port: ip.src_port as u16,
+                    dst_port: ip.dst_port as u16,
+                }),
+                _ => None,
+            });
+        let decoded_header = || {
+            self.flow_records
+                .iter()
+                .find_map(|record| match &record.flow_data {
+                    FlowData::SampledHeader(header) => {
+                        let decoded = header.decode()?;
+                        Some(FiveTuple {
+                            src_ip: decoded.src_ip,
+                            dst_ip: decoded.dst_ip,
+                            protocol: decoded.protocol,
+                            src_port: decoded.src_port.unwrap_or(0),
+                            dst_port: decoded.dst_port.unwrap_or(0),
+                        })
+                    }
+                    _ => None,
+                })
+        };
+        let socket = || {
+            self.flow_records
+                .iter()
+                .find_map(|record| match &record.flow_data {
+                    FlowData::ExtendedSocketIpv4(socket) => Some(FiveTuple {
+                        src_ip: IpAddr::V4(socket.local_ip),
+                        dst_ip: IpAddr::V4(socket.remote_ip),
+                        protocol: IpProtocol::from(socket.protocol),
+                        src_port: socket.local_port as u16,
+                        dst_port: socket.remote_port as u16,
+                    }),
+                    FlowData::ExtendedSocketIpv6(socket) => Some(FiveTuple {
+                        src_ip: IpAddr::V6(socket.local_ip),
+                        dst_ip: IpAddr::V6(socket.remote_ip),
+                        protocol: IpProtocol::from(socket.protocol),
+                        src_port: socket.local_port as u16,
+                        dst_port: socket.remote_port as u16,
+                    }),
+                    _ => None,
+                })
+        };
+
+        sampled_ip.or_else(decoded_header).or_else(socket)
+    }
+
+    /// Application transaction described by the sample
+    ///
+    /// Combines the Transaction (0,2000) record with the first NFS (0,2001),
+    /// SCSI (0,2002) or HTTP (0,2003) transaction record of the sample.
+    /// Returns `None` when the sample has no Transaction record.
+    pub fn transaction(&self) -> Option<TransactionView<'_>> {
+        let mut transaction = None;
+        let mut detail = None;
+        for record in &self.flow_records {
+            match &record.flow_data {
+                FlowData::Transaction(t) => transaction = transaction.or(Some(t)),
+                FlowData::ExtendedNfsStorageTransaction(_)
+                | FlowData::ExtendedScsiStorageTransaction(_)
+                | FlowData::ExtendedHttpTransaction(_) => {
+                    detail = detail.or(Some(&record.flow_data))
+                }
+                _ => {}
+            }
+        }
+
+        let transaction = transaction?;
+        Some(match detail {
+            Some(FlowData::ExtendedNfsStorageTransaction(nfs)) => {
+                TransactionView::Nfs(transaction, nfs)
+            }
+            Some(FlowData::ExtendedScsiStorageTransaction(scsi)) => {
+                TransactionView::Scsi(transaction, scsi)
+            }
+            Some(FlowData::ExtendedHttpTransaction(http)) => {
+                TransactionView::Http(transaction, http)
+            }
+            _ => TransactionView::Generic(transaction),
+        })
+    }
+
+    /// Routed source and destination prefixes as (src, dst)
+    ///
+    /// Combines the addresses of the Sampled IPv4 (0,3) or Sampled IPv6
+    /// (0,4) record with the mask lengths of the Extended Router (0,1002)
+    /// record. Returns `None` when either record is missing or a mask length
+    /// does not fit the address family.
+    pub fn routed_prefixes(&self) -> Option<(IpPrefix, IpPrefix)> {
+        let mut addresses = None;
+        let mut router = None;
+        for record in &self.flow_records {
+            match &record.flow_data {
+                FlowData::SampledIpv4(ip) => {
+                    addresses = Some((IpAddr::V4(ip.src_ip), IpAddr::V4(ip.dst_ip)))
+                }
+                FlowData::SampledIpv6(ip) => {
+                    addresses = Some((IpAddr::V6(ip.src_ip), IpAddr::V6(ip.dst_ip)))
+                }
+                FlowData::ExtendedRouter(r) => router = Some(r),
+                _ => {}
+            }
+        }
+
+        let (src, dst) = addresses?;
+        let router = router?;
+        Some((
+            IpPrefix::new(src, router.src_mask_len)?,
+            IpPrefix::new(dst, router.dst_mask_len)?,
+        ))
+    }
+}
+
 /// Compact counters sample - Format (0,2)
 ///
 /// Contains interface and system counter statistics.
@@ -585,6 +1380,7 @@ pub struct FlowSample {
 /// **ERRATUM:** Sequence number clarified as incremented per "sFlow Instance" instead of "source_id".
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct CountersSample {
     /// Sequence number incremented with each counter sample generated by this sFlow Instance
     /// **ERRATUM:** Clarified as "sFlow Instance" instead of "source_id"
@@ -595,6 +1391,15 @@ pub struct CountersSample {
     pub counters: Vec<CounterRecord>,
 }
 
+impl CountersSample {
+    /// All counter records of type `T`, in record order
+    pub fn records<T: CounterRecordType>(&self) -> impl Iterator<Item = &T> {
+        self.counters
+            .iter()
+            .filter_map(|record| T::from_counter_data(&record.counter_data))
+    }
+}
+
 /// Expanded flow sample - Format (0,3)
 ///
 /// Flow sample with expanded encoding for large interface indices (>= 2^24).
@@ -625,6 +1430,7 @@ pub struct CountersSample {
 /// **ERRATUM:** Sequence number clarified as incremented per "sFlow Instance" instead of "source_id".
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct FlowSampleExpanded {
     /// Sequence number incremented with each flow sample generated by this sFlow Instance
     /// **ERRATUM:** Clarified as "sFlow Instance" instead of "source_id"
@@ -666,6 +1472,7 @@ pub struct FlowSampleExpanded {
 /// **ERRATUM:** Sequence number clarified as incremented per "sFlow Instance" instead of "source_id".
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct CountersSampleExpanded {
     /// Sequence number incremented with each counter sample generated by this sFlow Instance
     /// **ERRATUM:** Clarified as "sFlow Instance" instead of "source_id"
@@ -676,6 +1483,17 @@ pub struct CountersSampleExpanded {
     pub counters: Vec<CounterRecord>,
 }
 
+/// Expanded form of a compact counters sample
+impl From<CountersSample> for CountersSampleExpanded {
+    fn from(sample: CountersSample) -> Self {
+        Self {
+            sequence_number: sample.sequence_number,
+            source_id: sample.source_id.into(),
+            counters: sample.counters,
+        }
+    }
+}
+
 /// Drop reason codes for discarded packets
 ///
 /// # XDR Definition ([sFlow Drops](https://sflow.org/sflow_drops.txt))
@@ -773,6 +1591,7 @@ pub struct CountersSampleExpanded {
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[repr(u32)]
 pub enum DropReason {
     NetUnreachable = 0,
@@ -916,6 +1735,158 @@ impl DropReason {
             _ => None,
         }
     }
+
+    /// Look up a drop reason by its specification name (e.g. `"blackhole_route"`)
+    ///
+    /// Linux devlink hardware trap names use the same names, so this also
+    /// maps the trap of an [`ExtendedHwTrap`](crate::models::record_flows::ExtendedHwTrap).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "net_unreachable" => Some(DropReason::NetUnreachable),
+            "host_unreachable" => Some(DropReason::HostUnreachable),
+            "protocol_unreachable" => Some(DropReason::ProtocolUnreachable),
+            "port_unreachable" => Some(DropReason::PortUnreachable),
+            "frag_needed" => Some(DropReason::FragNeeded),
+            "src_route_failed" => Some(DropReason::SrcRouteFailed),
+            "dst_net_unknown" => Some(DropReason::DstNetUnknown),
+            "dst_host_unknown" => Some(DropReason::DstHostUnknown),
+            "src_host_isolated" => Some(DropReason::SrcHostIsolated),
+            "dst_net_prohibited" => Some(DropReason::DstNetProhibited),
+            "dst_host_prohibited" => Some(DropReason::DstHostProhibited),
+            "dst_net_tos_unreachable" => Some(DropReason::DstNetTosUnreachable),
+            "dst_host_tos_unreacheable" => Some(DropReason::DstHostTosUnreachable),
+            "comm_admin_prohibited" => Some(DropReason::CommAdminProhibited),
+            "host_precedence_violation" => Some(DropReason::HostPrecedenceViolation),
+            "precedence_cutoff" => Some(DropReason::PrecedenceCutoff),
+            "unknown" => Some(DropReason::Unknown),
+            "ttl_exceeded" => Some(DropReason::TtlExceeded),
+            "acl" => Some(DropReason::Acl),
+            "no_buffer_space" => Some(DropReason::NoBufferSpace),
+            "red" => Some(DropReason::Red),
+            "traffic_shaping" => Some(DropReason::TrafficShaping),
+            "pkt_too_big" => Some(DropReason::PktTooBig),
+            "src_mac_is_multicast" => Some(DropReason::SrcMacIsMulticast),
+            "vlan_tag_mismatch" => Some(DropReason::VlanTagMismatch),
+            "ingress_vlan_filter" => Some(DropReason::IngressVlanFilter),
+            "ingress_spanning_tree_filter" => Some(DropReason::IngressSpanningTreeFilter),
+            "port_list_is_empty" => Some(DropReason::PortListIsEmpty),
+            "port_loopback_filter" => Some(DropReason::PortLoopbackFilter),
+            "blackhole_route" => Some(DropReason::BlackholeRoute),
+            "non_ip" => Some(DropReason::NonIp),
+            "uc_dip_over_mc_dmac" => Some(DropReason::UcDipOverMcDmac),
+            "dip_is_loopback_address" => Some(DropReason::DipIsLoopbackAddress),
+            "sip_is_mc" => Some(DropReason::SipIsMc),
+            "sip_is_loopback_address" => Some(DropReason::SipIsLoopbackAddress),
+            "ip_header_corrupted" => Some(DropReason::IpHeaderCorrupted),
+            "ipv4_sip_is_limited_bc" => Some(DropReason::Ipv4SipIsLimitedBc),
+            "ipv6_mc_dip_reserved_scope" => Some(DropReason::Ipv6McDipReservedScope),
+            "ipv6_mc_dip_interface_local_scope" => Some(DropReason::Ipv6McDipInterfaceLocalScope),
+            "unresolved_neigh" => Some(DropReason::UnresolvedNeigh),
+            "mc_reverse_path_forwarding" => Some(DropReason::McReversePathForwarding),
+            "non_routable_packet" => Some(DropReason::NonRoutablePacket),
+            "decap_error" => Some(DropReason::DecapError),
+            "overlay_smac_is_mc" => Some(DropReason::OverlaySmacIsMc),
+            "unknown_l2" => Some(DropReason::UnknownL2),
+            "unknown_l3" => Some(DropReason::UnknownL3),
+            "unknown_l3_exception" => Some(DropReason::UnknownL3Exception),
+            "unknown_buffer" => Some(DropReason::UnknownBuffer),
+            "unknown_tunnel" => Some(DropReason::UnknownTunnel),
+            "unknown_l4" => Some(DropReason::UnknownL4),
+            "sip_is_unspecified" => Some(DropReason::SipIsUnspecified),
+            "mlag_port_isolation" => Some(DropReason::MlagPortIsolation),
+            "blackhole_arp_neigh" => Some(DropReason::BlackholeArpNeigh),
+            "src_mac_is_dmac" => Some(DropReason::SrcMacIsDmac),
+            "dmac_is_reserved" => Some(DropReason::DmacIsReserved),
+            "sip_is_class_e" => Some(DropReason::SipIsClassE),
+            "mc_dmac_mismatch" => Some(DropReason::McDmacMismatch),
+            "sip_is_dip" => Some(DropReason::SipIsDip),
+            "dip_is_local_network" => Some(DropReason::DipIsLocalNetwork),
+            "dip_is_link_local" => Some(DropReason::DipIsLinkLocal),
+            "overlay_smac_is_dmac" => Some(DropReason::OverlaySmacIsDmac),
+            "egress_vlan_filter" => Some(DropReason::EgressVlanFilter),
+            "uc_reverse_path_forwarding" => Some(DropReason::UcReversePathForwarding),
+            "split_horizon" => Some(DropReason::SplitHorizon),
+            _ => None,
+        }
+    }
+
+    /// Specification name of the drop reason (e.g. `"blackhole_route"`)
+    ///
+    /// The inverse of [`from_name`](Self::from_name).
+    pub fn name(&self) -> &'static str {
+        match self {
+            DropReason::NetUnreachable => "net_unreachable",
+            DropReason::HostUnreachable => "host_unreachable",
+            DropReason::ProtocolUnreachable => "protocol_unreachable",
+            DropReason::PortUnreachable => "port_unreachable",
+            DropReason::FragNeeded => "frag_needed",
+            DropReason::SrcRouteFailed => "src_route_failed",
+            DropReason::DstNetUnknown => "dst_net_unknown",
+            DropReason::DstHostUnknown => "dst_host_unknown",
+            DropReason::SrcHostIsolated => "src_host_isolated",
+            DropReason::DstNetProhibited => "dst_net_prohibited",
+            DropReason::DstHostProhibited => "dst_host_prohibited",
+            DropReason::DstNetTosUnreachable => "dst_net_tos_unreachable",
+            DropReason::DstHostTosUnreachable => "dst_host_tos_unreacheable",
+            DropReason::CommAdminProhibited => "comm_admin_prohibited",
+            DropReason::HostPrecedenceViolation => "host_precedence_violation",
+            DropReason::PrecedenceCutoff => "precedence_cutoff",
+            DropReason::Unknown => "unknown",
+            DropReason::TtlExceeded => "ttl_exceeded",
+            DropReason::Acl => "acl",
+            DropReason::NoBufferSpace => "no_buffer_space",
+            DropReason::Red => "red",
+            DropReason::TrafficShaping => "traffic_shaping",
+            DropReason::PktTooBig => "pkt_too_big",
+            DropReason::SrcMacIsMulticast => "src_mac_is_multicast",
+            DropReason::VlanTagMismatch => "vlan_tag_mismatch",
+            DropReason::IngressVlanFilter => "ingress_vlan_filter",
+            DropReason::IngressSpanningTreeFilter => "ingress_spanning_tree_filter",
+            DropReason::PortListIsEmpty => "port_list_is_empty",
+            DropReason::PortLoopbackFilter => "port_loopback_filter",
+            DropReason::BlackholeRoute => "blackhole_route",
+            DropReason::NonIp => "non_ip",
+            DropReason::UcDipOverMcDmac => "uc_dip_over_mc_dmac",
+            DropReason::DipIsLoopbackAddress => "dip_is_loopback_address",
+            DropReason::SipIsMc => "sip_is_mc",
+            DropReason::SipIsLoopbackAddress => "sip_is_loopback_address",
+            DropReason::IpHeaderCorrupted => "ip_header_corrupted",
+            DropReason::Ipv4SipIsLimitedBc => "ipv4_sip_is_limited_bc",
+            DropReason::Ipv6McDipReservedScope => "ipv6_mc_dip_reserved_scope",
+            DropReason::Ipv6McDipInterfaceLocalScope => "ipv6_mc_dip_interface_local_scope",
+            DropReason::UnresolvedNeigh => "unresolved_neigh",
+            DropReason::McReversePathForwarding => "mc_reverse_path_forwarding",
+            DropReason::NonRoutablePacket => "non_routable_packet",
+            DropReason::DecapError => "decap_error",
+            DropReason::OverlaySmacIsMc => "overlay_smac_is_mc",
+            DropReason::UnknownL2 => "unknown_l2",
+            DropReason::UnknownL3 => "unknown_l3",
+            DropReason::UnknownL3Exception => "unknown_l3_exception",
+            DropReason::UnknownBuffer => "unknown_buffer",
+            DropReason::UnknownTunnel => "unknown_tunnel",
+            DropReason::UnknownL4 => "unknown_l4",
+            DropReason::SipIsUnspecified => "sip_is_unspecified",
+            DropReason::MlagPortIsolation => "mlag_port_isolation",
+            DropReason::BlackholeArpNeigh => "blackhole_arp_neigh",
+            DropReason::SrcMacIsDmac => "src_mac_is_dmac",
+            DropReason::DmacIsReserved => "dmac_is_reserved",
+            DropReason::SipIsClassE => "sip_is_class_e",
+            DropReason::McDmacMismatch => "mc_dmac_mismatch",
+            DropReason::SipIsDip => "sip_is_dip",
+            DropReason::DipIsLocalNetwork => "dip_is_local_network",
+            DropReason::DipIsLinkLocal => "dip_is_link_local",
+            DropReason::OverlaySmacIsDmac => "overlay_smac_is_dmac",
+            DropReason::EgressVlanFilter => "egress_vlan_filter",
+            DropReason::UcReversePathForwarding => "uc_reverse_path_forwarding",
+            DropReason::SplitHorizon => "split_horizon",
+        }
+    }
+}
+
+impl std::fmt::Display for DropReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
 }
 
 /// Discarded packet sample - Format (0,5)
@@ -951,6 +1922,7 @@ impl DropReason {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DiscardedPacket {
     /// Sequence number incremented with each discarded packet record
     pub sequence_number: u32,
@@ -977,6 +1949,7 @@ pub struct DiscardedPacket {
 /// Sample data types
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum SampleData {
     FlowSample(FlowSample),
     CountersSample(CountersSample),
@@ -995,15 +1968,134 @@ pub enum SampleData {
         format: DataFormat,
         data: Vec<u8>,
     },
+    /// Sample skipped without decoding (see `ParseOptions::skip_flow_samples`)
+    Skipped {
+        format: DataFormat,
+        /// Declared sample length in bytes
+        length: u32,
+    },
     Unknown {
         format: DataFormat,
         data: Vec<u8>,
     },
 }
 
+impl SampleData {
+    /// Common view of a flow sample, compact (0,1) or expanded (0,3)
+    pub fn as_flow(&self) -> Option<FlowSampleCommon<'_>> {
+        match self {
+            SampleData::FlowSample(s) => Some(FlowSampleCommon {
+                sequence_number: s.sequence_number,
+                source_id: s.source_id.into(),
+                sampling_rate: s.sampling_rate,
+                sample_pool: s.sample_pool,
+                drops: s.drops,
+                input: s.input.into(),
+                output: s.output.into(),
+                flow_records: &s.flow_records,
+            }),
+            SampleData::FlowSampleExpanded(s) => Some(FlowSampleCommon {
+                sequence_number: s.sequence_number,
+                source_id: s.source_id,
+                sampling_rate: s.sampling_rate,
+                sample_pool: s.sample_pool,
+                drops: s.drops,
+                input: s.input,
+                output: s.output,
+                flow_records: &s.flow_records,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Whether the sample reports a dropped packet (sFlow drops convention)
+    ///
+    /// Discarded packet samples (0,5) always do. A flow sample, compact or
+    /// expanded, does when its output interface has the discarded format or
+    /// when it carries a drop record, see [`FlowData::is_drop_record`].
+    pub fn is_drop_sample(&self) -> bool {
+        if matches!(self, SampleData::DiscardedPacket(_)) {
+            return true;
+        }
+        let Some(flow) = self.as_flow() else {
+            return false;
+        };
+        flow.output.is_discarded()
+            || flow
+                .flow_records
+                .iter()
+                .any(|record| record.flow_data.is_drop_record())
+    }
+
+    /// Data source of a flow, counters or discarded packet sample
+    pub fn source_id(&self) -> Option<DataSourceKey> {
+        match self {
+            SampleData::FlowSample(s) => Some(s.source_id.into()),
+            SampleData::CountersSample(s) => Some(s.source_id.into()),
+            SampleData::FlowSampleExpanded(s) => Some(s.source_id),
+            SampleData::CountersSampleExpanded(s) => Some(s.source_id),
+            SampleData::DiscardedPacket(s) => Some(s.source_id),
+            _ => None,
+        }
+    }
+
+    /// Common view of a counters sample, compact (0,2) or expanded (0,4)
+    pub fn as_counters(&self) -> Option<CountersSampleCommon<'_>> {
+        match self {
+            SampleData::CountersSample(s) => Some(CountersSampleCommon {
+                sequence_number: s.sequence_number,
+                source_id: s.source_id.into(),
+                counters: &s.counters,
+            }),
+            SampleData::CountersSampleExpanded(s) => Some(CountersSampleCommon {
+                sequence_number: s.sequence_number,
+                source_id: s.source_id,
+                counters: &s.counters,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Flow sample fields shared by the compact and expanded encodings
+///
+/// Identifiers are normalized to their expanded form, which holds every
+/// compact value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlowSampleCommon<'a> {
+    /// Sequence number of the flow sample
+    pub sequence_number: u32,
+    /// Data source
+    pub source_id: DataSourceExpanded,
+    /// Sampling rate (1 in N packets)
+    pub sampling_rate: u32,
+    /// Total packets that could have been sampled
+    pub sample_pool: u32,
+    /// Number of dropped samples due to lack of resources
+    pub drops: u32,
+    /// Input interface
+    pub input: InterfaceExpanded,
+    /// Output interface
+    pub output: InterfaceExpanded,
+    /// Flow records describing the sampled packet
+    pub flow_records: &'a [FlowRecord],
+}
+
+/// Counters sample fields shared by the compact and expanded encodings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CountersSampleCommon<'a> {
+    /// Sequence number of the counters sample
+    pub sequence_number: u32,
+    /// Data source
+    pub source_id: DataSourceExpanded,
+    /// Counter records of the data source
+    pub counters: &'a [CounterRecord],
+}
+
 /// Sample record
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SampleRecord {
     pub sample_type: DataFormat,
     pub sample_data: SampleData,
@@ -1033,6 +2125,7 @@ pub struct SampleRecord {
 /// ```
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SFlowDatagram {
     /// sFlow protocol version (always 5)
     pub version: DatagramVersion,
@@ -1065,4 +2158,162 @@ impl SFlowDatagram {
             samples: Vec::new(),
         }
     }
+
+    /// IP address of the agent, or `None` when the agent address type is unknown
+    pub fn agent_ip(&self) -> Option<IpAddr> {
+        self.agent_address.to_ip_addr()
+    }
+
+    /// Key identifying the sFlow instance that sent the datagram
+    ///
+    /// Returns `(agent IP, sub_agent_id)`, the scope of the datagram
+    /// sequence number. An unknown agent address type maps to `0.0.0.0`.
+    pub fn datagram_key(&self) -> (IpAddr, u32) {
+        let agent = self.agent_ip().unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        (agent, self.sub_agent_id)
+    }
+
+    /// Agent uptime as a `Duration`
+    ///
+    /// The uptime is a 32-bit millisecond counter which wraps after about
+    /// 49.7 days; use [`uptime_delta`] to compare two datagrams.
+    pub fn uptime_duration(&self) -> Duration {
+        Duration::from_millis(self.uptime as u64)
+    }
+
+    /// Samples reporting dropped packets, see [`SampleData::is_drop_sample`]
+    pub fn drop_samples(&self) -> impl Iterator<Item = &SampleRecord> {
+        self.samples
+            .iter()
+            .filter(|sample| sample.sample_data.is_drop_sample())
+    }
+
+    /// Samples grouped by data source, in order of appearance within a group
+    ///
+    /// Compact and expanded samples of the same source share a group.
+    /// Samples without a data source (sFlow-RT, skipped and unknown samples)
+    /// are left out.
+    pub fn group_by_source(&self) -> std::collections::HashMap<DataSourceKey, Vec<&SampleRecord>> {
+        let mut groups = std::collections::HashMap::<_, Vec<_>>::new();
+        for sample in &self.samples {
+            if let Some(source) = sample.sample_data.source_id() {
+                groups.entry(source).or_default().push(sample);
+            }
+        }
+        groups
+    }
+
+    /// Every IP address referenced by the datagram, without duplicates
+    ///
+    /// Starts with the agent address, followed by the addresses of the flow
+    /// records of all flow and discarded packet samples in order of
+    /// appearance, see [`FlowData::ip_addresses`].
+    pub fn all_ip_addresses(&self) -> Vec<IpAddr> {
+        let records = self
+            .samples
+            .iter()
+            .flat_map(|sample| match &sample.sample_data {
+                SampleData::FlowSample(s) => s.flow_records.as_slice(),
+                SampleData::FlowSampleExpanded(s) => s.flow_records.as_slice(),
+                SampleData::DiscardedPacket(s) => s.flow_records.as_slice(),
+                _ => &[],
+            })
+            .flat_map(|record| record.flow_data.ip_addresses());
+
+        let mut seen = std::collections::HashSet::new();
+        self.agent_ip()
+            .into_iter()
+            .chain(records)
+            .filter(|ip| seen.insert(*ip))
+            .collect()
+    }
+
+    /// Interface counters of the datagram with the MAC addresses of their adapter
+    ///
+    /// Within each counter sample, associates the Host Adapters (0,2001)
+    /// records with the Generic Interface Counters (0,1) records by ifIndex,
+    /// see [`HostAdapters::associate`](crate::models::record_counters::HostAdapters::associate).
+    /// Records of different samples, i.e. of different data sources, are
+    /// never paired: ifIndex values are only meaningful within one source.
+    pub fn adapter_interfaces(
+        &self,
+    ) -> Vec<(
+        u32,
+        MacAddress,
+        &crate::models::record_counters::GenericInterfaceCounters,
+    )> {
+        let samples = self
+            .samples
+            .iter()
+            .filter_map(|sample| match &sample.sample_data {
+                SampleData::CountersSample(s) => Some(s.counters.as_slice()),
+                SampleData::CountersSampleExpanded(s) => Some(s.counters.as_slice()),
+                _ => None,
+            });
+
+        let mut associated = Vec::new();
+        for records in samples {
+            let mut adapters = crate::models::record_counters::HostAdapters {
+                adapters: Vec::new(),
+            };
+            let mut interfaces = Vec::new();
+            for record in records {
+                match &record.counter_data {
+                    CounterData::HostAdapters(a) => {
+                        adapters.adapters.extend(a.adapters.iter().cloned())
+                    }
+                    CounterData::GenericInterface(c) => interfaces.push(c),
+                    _ => {}
+                }
+            }
+            associated.extend(adapters.associate(interfaces));
+        }
+        associated
+    }
+}
+
+/// Time elapsed between two agent uptime values, accounting for wraparound
+///
+/// `prev` and `curr` are millisecond uptimes from two datagrams of the same
+/// agent, `curr` being the later one. A `curr` lower than `prev` is taken as
+/// a single 32-bit wrap (an agent reboot cannot be told apart).
+pub fn uptime_delta(prev: u32, curr: u32) -> Duration {
+    Duration::from_millis(curr.wrapping_sub(prev) as u64)
+}
+
+/// One-line summary of the datagram header
+impl std::fmt::Display for SFlowDatagram {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "sFlow v5 agent={} sub_agent_id={} seq={} uptime={}ms samples={}",
+            self.agent_address,
+            self.sub_agent_id,
+            self.sequence_number,
+            self.uptime,
+            self.samples.len()
+        )
+    }
+}
+
+/// sFlow v5 datagram header
+///
+/// The fields of an [`SFlowDatagram`] preceding its samples, with the
+/// declared number of samples.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DatagramHeader {
+    /// sFlow protocol version (always 5)
+    pub version: DatagramVersion,
+    /// IP address of the sFlow agent
+    pub agent_address: Address,
+    /// Sub-agent identifier (distinguishes multiple sFlow instances on same agent)
+    pub sub_agent_id: u32,
+    /// Datagram sequence number (incremented with each datagram from this sub-agent)
+    pub sequence_number: u32,
+    /// Device uptime in milliseconds since last boot
+    pub uptime: u32,
+    /// Number of samples declared in the datagram
+    pub num_samples: u32,
 }
@@ -0,0 +1,379 @@
+//! Decoding of sampled packet headers
+//!
+//! [`SampledHeader`] carries the raw leading bytes of a sampled packet.
+//! [`SampledHeader::decode`] walks the link layer indicated by the header
+//! protocol down to the IP header and extracts the L3/L4 fields.
+
+use crate::models::core::MacAddress;
+use crate::models::record_flows::{HeaderProtocol, IpProtocol, MplsLabel, SampledHeader};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// EtherType of IPv4
+const ETHERTYPE_IPV4: u16 = 0x0800;
+/// EtherType of IPv6
+const ETHERTYPE_IPV6: u16 = 0x86DD;
+/// EtherType of an IEEE 802.1Q VLAN tag
+const ETHERTYPE_VLAN: u16 = 0x8100;
+/// EtherType of an IEEE 802.1ad service VLAN tag
+const ETHERTYPE_QINQ: u16 = 0x88A8;
+/// EtherType of an MPLS unicast label stack
+const ETHERTYPE_MPLS: u16 = 0x8847;
+/// EtherType of an MPLS multicast label stack
+const ETHERTYPE_MPLS_MULTICAST: u16 = 0x8848;
+
+/// PPP protocol number of IPv4
+const PPP_IPV4: u16 = 0x0021;
+/// PPP protocol number of IPv6
+const PPP_IPV6: u16 = 0x0057;
+
+/// IPv6 Hop-by-Hop Options extension header
+const IPV6_HOP_BY_HOP: u8 = 0;
+/// IPv6 Routing extension header
+const IPV6_ROUTING: u8 = 43;
+/// IPv6 Fragment extension header
+const IPV6_FRAGMENT: u8 = 44;
+/// IPv6 Destination Options extension header
+const IPV6_DESTINATION_OPTIONS: u8 = 60;
+
+/// Frame Relay NLPID of IPv4 (RFC 2427)
+const NLPID_IPV4: u8 = 0xCC;
+/// Frame Relay NLPID of IPv6 (RFC 2427)
+const NLPID_IPV6: u8 = 0x8E;
+/// Frame Relay NLPID announcing a SNAP header (RFC 2427)
+const NLPID_SNAP: u8 = 0x80;
+
+/// Fields decoded from a sampled packet header
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DecodedHeader {
+    /// Source MAC address (Ethernet only)
+    pub src_mac: Option<MacAddress>,
+
+    /// Destination MAC address (Ethernet only)
+    pub dst_mac: Option<MacAddress>,
+
+    /// Outermost 802.1Q VLAN ID (Ethernet only)
+    pub vlan: Option<u16>,
+
+    /// MPLS label stack in front of the IP header, outermost label first
+    pub mpls_labels: Vec<MplsLabel>,
+
+    /// Source IP address
+    pub src_ip: IpAddr,
+
+    /// Destination IP address
+    pub dst_ip: IpAddr,
+
+    /// IP protocol (IPv4 protocol / IPv6 next header)
+    pub protocol: IpProtocol,
+
+    /// IPv4 type of service / IPv6 traffic class
+    pub tos: u8,
+
+    /// TCP/UDP source port, if the transport header was captured
+    pub src_port: Option<u16>,
+
+    /// TCP/UDP destination port, if the transport header was captured
+    pub dst_port: Option<u16>,
+
+    /// TCP flags, if the TCP header was captured
+    pub tcp_flags: Option<u8>,
+}
+
+/// Link-layer fields collected before reaching the IP header
+#[derive(Default)]
+struct LinkLayer {
+    src_mac: Option<MacAddress>,
+    dst_mac: Option<MacAddress>,
+    vlan: Option<u16>,
+    mpls_labels: Vec<MplsLabel>,
+}
+
+impl SampledHeader {
+    /// Decode the L3/L4 fields of the sampled packet
+    ///
+    /// Supports Ethernet (with 802.1Q/802.1ad tags), raw IPv4/IPv6, PPP,
+    /// Frame Relay and MPLS headers. MPLS label stacks, bare or carried by
+    /// one of the other link types, are peeled down to the IP header. IPv4
+    /// options and IPv6 extension headers are skipped to reach the
+    /// transport header. Returns `None` if the link type is not supported,
+    /// the payload is not IP, or the header is truncated before the end of
+    /// the IP header. Transport fields are `None` when the sampled bytes
+    /// stop before the TCP/UDP header.
+    pub fn decode(&self) -> Option<DecodedHeader> {
+        let data = self.header.as_slice();
+        match self.protocol {
+            HeaderProtocol::EthernetIso88023 => decode_ethernet(data),
+            HeaderProtocol::Ipv4 => decode_ipv4(data, LinkLayer::default()),
+            HeaderProtocol::Ipv6 => decode_ipv6(data, LinkLayer::default()),
+            HeaderProtocol::Ppp => decode_ppp(data),
+            HeaderProtocol::FrameRelay => decode_frame_relay(data),
+            HeaderProtocol::Mpls => decode_mpls(data, LinkLayer::default()),
+            _ => None,
+        }
+    }
+}
+
+/// Big-endian u16 at `offset` of a packet, `None` past its end
+pub(crate) fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_mac(data: &[u8], offset: usize) -> Option<MacAddress> {
+    let bytes: [u8; 6] = data.get(offset..offset + 6)?.try_into().ok()?;
+    Some(MacAddress::new(bytes))
+}
+
+/// Dispatch on an EtherType (or equivalent) to the IP decoders
+fn decode_ethertype(ethertype: u16, payload: &[u8], link: LinkLayer) -> Option<DecodedHeader> {
+    match ethertype {
+        ETHERTYPE_IPV4 => decode_ipv4(payload, link),
+        ETHERTYPE_IPV6 => decode_ipv6(payload, link),
+        ETHERTYPE_MPLS | ETHERTYPE_MPLS_MULTICAST => decode_mpls(payload, link),
+        _ => None,
+    }
+}
+
+/// MPLS label stack (RFC 3032)
+///
+/// Labels are read up to the one with the bottom-of-stack bit. The stack
+/// does not say what it carries, so the IP version is taken from the first
+/// nibble of the payload.
+fn decode_mpls(data: &[u8], mut link: LinkLayer) -> Option<DecodedHeader> {
+    let mut offset = 0;
+    loop {
+        let bytes: [u8; 4] = data.get(offset..offset + 4)?.try_into().ok()?;
+        let label = MplsLabel::from_entry(u32::from_be_bytes(bytes));
+        link.mpls_labels.push(label);
+        offset += 4;
+        if label.bottom_of_stack {
+            break;
+        }
+    }
+
+    let payload = &data[offset..];
+    match payload.first()? >> 4 {
+        4 => decode_ipv4(payload, link),
+        6 => decode_ipv6(payload, link),
+        _ => None,
+    }
+}
+
+fn decode_ethernet(data: &[u8]) -> Option<DecodedHeader> {
+    let mut link = LinkLayer {
+        dst_mac: Some(read_mac(data, 0)?),
+        src_mac: Some(read_mac(data, 6)?),
+        vlan: None,
+        mpls_labels: Vec::new(),
+    };
+
+    let mut offset = 12;
+    let mut ethertype = read_u16(data, offset)?;
+    offset += 2;
+    while ethertype == ETHERTYPE_VLAN || ethertype == ETHERTYPE_QINQ {
+        let tci = read_u16(data, offset)?;
+        link.vlan.get_or_insert(tci & 0x0FFF);
+        ethertype = read_u16(data, offset + 2)?;
+        offset += 4;
+    }
+
+    decode_ethertype(ethertype, &data[offset..], link)
+}
+
+/// PPP in HDLC-like framing (RFC 1662)
+///
+/// The address/control bytes (0xFF 0x03) and the upper protocol byte may
+/// be compressed away, so both forms are accepted.
+fn decode_ppp(data: &[u8]) -> Option<DecodedHeader> {
+    let mut offset = 0;
+    if data.get(..2)? == [0xFF, 0x03] {
+        offset += 2;
+    }
+
+    // Protocol field compression: an odd first byte is a 1-byte protocol
+    let first = *data.get(offset)?;
+    let protocol = if first & 0x01 != 0 {
+        offset += 1;
+        first as u16
+    } else {
+        let protocol = read_u16(data, offset)?;
+        offset += 2;
+        protocol
+    };
+
+    let payload = &data[offset..];
+    match protocol {
+        PPP_IPV4 => decode_ipv4(payload, LinkLayer::default()),
+        PPP_IPV6 => decode_ipv6(payload, LinkLayer::default()),
+        _ => None,
+    }
+}
+
+/// Frame Relay with a Q.922 address
+///
+/// Handles the RFC 2427 multiprotocol encapsulation (control 0x03 then an
+/// NLPID, optionally via SNAP) and the common Cisco encapsulation where an
+/// EtherType directly follows the address.
+fn decode_frame_relay(data: &[u8]) -> Option<DecodedHeader> {
+    // Q.922 address: 2 to 4 bytes, the last one has the EA bit set
+    let mut offset = 0;
+    loop {
+        let byte = *data.get(offset)?;
+        offset += 1;
+        if byte & 0x01 != 0 {
+            break;
+        }
+        if offset >= 4 {
+            return None;
+        }
+    }
+    if offset < 2 {
+        return None;
+    }
+
+    if *data.get(offset)? != 0x03 {
+        // Cisco encapsulation
+        let ethertype = read_u16(data, offset)?;
+        return decode_ethertype(ethertype, &data[offset + 2..], LinkLayer::default());
+    }
+    offset += 1;
+
+    // Optional pad byte before the NLPID
+    if *data.get(offset)? == 0x00 {
+        offset += 1;
+    }
+    let nlpid = *data.get(offset)?;
+    offset += 1;
+
+    match nlpid {
+        NLPID_IPV4 => decode_ipv4(&data[offset..], LinkLayer::default()),
+        NLPID_IPV6 => decode_ipv6(&data[offset..], LinkLayer::default()),
+        NLPID_SNAP => {
+            // 3-byte OUI followed by the 2-byte protocol identifier
+            let ethertype = read_u16(data, offset + 3)?;
+            decode_ethertype(ethertype, &data[offset + 5..], LinkLayer::default())
+        }
+        _ => None,
+    }
+}
+
+fn decode_ipv4(data: &[u8], link: LinkLayer) -> Option<DecodedHeader> {
+    let header = data.get(..20)?;
+    if header[0] >> 4 != 4 {
+        return None;
+    }
+    let header_len = ((header[0] & 0x0F) as usize) * 4;
+    if header_len < 20 {
+        return None;
+    }
+
+    let src: [u8; 4] = header[12..16].try_into().ok()?;
+    let dst: [u8; 4] = header[16..20].try_into().ok()?;
+    let protocol = header[9];
+    // Only the first fragment carries the transport header
+    let fragment_offset = u16::from_be_bytes([header[6], header[7]]) & 0x1FFF;
+    let transport = match fragment_offset {
+        0 => data.get(header_len..).unwrap_or(&[]),
+        _ => &[],
+    };
+
+    Some(build(
+        link,
+        IpAddr::V4(Ipv4Addr::from(src)),
+        IpAddr::V4(Ipv4Addr::from(dst)),
+        protocol,
+        header[1],
+        transport,
+    ))
+}
+
+fn decode_ipv6(data: &[u8], link: LinkLayer) -> Option<DecodedHeader> {
+    let header = data.get(..40)?;
+    if header[0] >> 4 != 6 {
+        return None;
+    }
+
+    let traffic_class = (header[0] << 4) | (header[1] >> 4);
+    let src: [u8; 16] = header[8..24].try_into().ok()?;
+    let dst: [u8; 16] = header[24..40].try_into().ok()?;
+    let (next_header, transport) = skip_ipv6_extensions(header[6], &data[40..]);
+
+    Some(build(
+        link,
+        IpAddr::V6(Ipv6Addr::from(src)),
+        IpAddr::V6(Ipv6Addr::from(dst)),
+        next_header,
+        traffic_class,
+        transport,
+    ))
+}
+
+/// Walk the IPv6 extension headers to the upper-layer header
+///
+/// Returns the upper-layer protocol and its bytes. If the chain is cut by
+/// the end of the sample, or the packet is a non-first fragment, the last
+/// next header value is returned with no transport bytes.
+pub(crate) fn skip_ipv6_extensions(mut next_header: u8, mut data: &[u8]) -> (u8, &[u8]) {
+    loop {
+        let header_len = match next_header {
+            IPV6_HOP_BY_HOP | IPV6_ROUTING | IPV6_DESTINATION_OPTIONS => match data.get(1) {
+                Some(&len) => (len as usize + 1) * 8,
+                None => return (next_header, &[]),
+            },
+            IPV6_FRAGMENT => {
+                // Only the first fragment carries the transport header
+                match read_u16(data, 2) {
+                    Some(offset) if offset & 0xFFF8 == 0 => 8,
+                    Some(_) => return (data[0], &[]),
+                    None => return (next_header, &[]),
+                }
+            }
+            _ => return (next_header, data),
+        };
+        match data.get(header_len..) {
+            Some(rest) => {
+                next_header = data[0];
+                data = rest;
+            }
+            None => return (next_header, &[]),
+        }
+    }
+}
+
+/// Assemble the decoded header, reading ports from the transport payload
+fn build(
+    link: LinkLayer,
+    src_ip: IpAddr,
+    dst_ip: IpAddr,
+    protocol: u8,
+    tos: u8,
+    transport: &[u8],
+) -> DecodedHeader {
+    let protocol = IpProtocol::from(protocol as u32);
+    let (src_port, dst_port) = match protocol {
+        IpProtocol::Tcp | IpProtocol::Udp | IpProtocol::Sctp => {
+            (read_u16(transport, 0), read_u16(transport, 2))
+        }
+        _ => (None, None),
+    };
+    let tcp_flags = match protocol {
+        IpProtocol::Tcp => transport.get(13).copied(),
+        _ => None,
+    };
+
+    DecodedHeader {
+        src_mac: link.src_mac,
+        dst_mac: link.dst_mac,
+        vlan: link.vlan,
+        mpls_labels: link.mpls_labels,
+        src_ip,
+        dst_ip,
+        protocol,
+        tos,
+        src_port,
+        dst_port,
+        tcp_flags,
+    }
+}
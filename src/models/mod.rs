@@ -7,10 +7,14 @@
 //! - `core`: Core datagram and sample structures (fully parsed)
 //! - `flow_records`: Flow record types (not yet parsed, models defined)
 //! - `counter_records`: Counter record types (not yet parsed, models defined)
+//! - `header`: Decoding of sampled packet headers
+//! - `record_type`: Typed lookup of records by Rust type
 
 pub mod core;
+pub mod header;
 pub mod record_counters;
 pub mod record_flows;
+pub mod record_type;
 
 // Re-export core types for backward compatibility
 pub use core::*;
@@ -20,3 +24,9 @@ pub use record_flows::*;
 
 // Re-export counter record types
 pub use record_counters::*;
+
+// Re-export header decoding types
+pub use header::DecodedHeader;
+
+// Re-export typed record lookup traits
+pub use record_type::{CounterRecordType, FlowRecordType};
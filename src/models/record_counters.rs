@@ -37,6 +37,7 @@
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct GenericInterfaceCounters {
     /// Interface index
     pub if_index: u32,
@@ -96,6 +97,73 @@ pub struct GenericInterfaceCounters {
     pub if_promiscuous_mode: u32,
 }
 
+impl GenericInterfaceCounters {
+    /// Cumulative counters of the record with their wire width, in record order
+    ///
+    /// Octet counters are `unsigned hyper` (counter64), packet, discard and
+    /// error counters are `unsigned int` (counter32). Gauges such as
+    /// `if_speed` or `if_status` are not listed.
+    ///
+    /// This is descriptive metadata, e.g. for exporters labelling series;
+    /// code computing deltas should rely on the field types instead.
+    pub fn counter_widths() -> &'static [(&'static str, CounterWidth)] {
+        &[
+            ("if_in_octets", CounterWidth::Counter64),
+            ("if_in_ucast_pkts", CounterWidth::Counter32),
+            ("if_in_multicast_pkts", CounterWidth::Counter32),
+            ("if_in_broadcast_pkts", CounterWidth::Counter32),
+            ("if_in_discards", CounterWidth::Counter32),
+            ("if_in_errors", CounterWidth::Counter32),
+            ("if_in_unknown_protos", CounterWidth::Counter32),
+            ("if_out_octets", CounterWidth::Counter64),
+            ("if_out_ucast_pkts", CounterWidth::Counter32),
+            ("if_out_multicast_pkts", CounterWidth::Counter32),
+            ("if_out_broadcast_pkts", CounterWidth::Counter32),
+            ("if_out_discards", CounterWidth::Counter32),
+            ("if_out_errors", CounterWidth::Counter32),
+        ]
+    }
+
+    /// Wire width of a cumulative counter, by field name
+    ///
+    /// Returns `None` for fields that are not cumulative counters.
+    pub fn counter_width(field: &str) -> Option<CounterWidth> {
+        Self::counter_widths()
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, width)| *width)
+    }
+}
+
+/// Wire width of a cumulative counter
+///
+/// Agents export counters at their native width, so the difference between
+/// two readings must be taken modulo 2^32 or 2^64 to survive a wraparound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum CounterWidth {
+    /// 32-bit counter (`unsigned int`)
+    Counter32,
+    /// 64-bit counter (`unsigned hyper`)
+    Counter64,
+}
+
+impl CounterWidth {
+    /// Largest value of the counter before it wraps to zero
+    pub fn max_value(self) -> u64 {
+        match self {
+            CounterWidth::Counter32 => u32::MAX as u64,
+            CounterWidth::Counter64 => u64::MAX,
+        }
+    }
+
+    /// Difference between two readings, accounting for wraparound
+    pub fn delta(self, previous: u64, current: u64) -> u64 {
+        current.wrapping_sub(previous) & self.max_value()
+    }
+}
+
 /// Ethernet Interface Counters - Format (0,2)
 ///
 /// Ethernet-specific statistics (RFC 2358)
@@ -124,6 +192,7 @@ pub struct GenericInterfaceCounters {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct EthernetInterfaceCounters {
     /// Alignment errors
     pub dot3_stats_alignment_errors: u32,
@@ -198,6 +267,7 @@ pub struct EthernetInterfaceCounters {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct TokenRingCounters {
     pub dot5_stats_line_errors: u32,
     pub dot5_stats_burst_errors: u32,
@@ -248,6 +318,7 @@ pub struct TokenRingCounters {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Vg100InterfaceCounters {
     pub dot12_in_high_priority_frames: u32,
     pub dot12_in_high_priority_octets: u64,
@@ -286,6 +357,7 @@ pub struct Vg100InterfaceCounters {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VlanCounters {
     /// VLAN ID
     pub vlan_id: u32,
@@ -341,6 +413,7 @@ pub struct VlanCounters {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Ieee80211Counters {
     pub dot11_transmitted_fragment_count: u32,
     pub dot11_multicast_transmitted_frame_count: u32,
@@ -397,6 +470,7 @@ pub struct Ieee80211Counters {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct LagPortStats {
     /// Actor system ID (MAC address)
     pub dot3ad_agg_port_actor_system_id: crate::models::MacAddress,
@@ -439,6 +513,99 @@ pub struct LagPortStats {
     pub dot3ad_agg_port_stats_marker_response_pdus_tx: u32,
 }
 
+impl LagPortStats {
+    /// Actor system ID (dot3adAggPortActorSystemID)
+    pub fn actor_system_id(&self) -> crate::models::MacAddress {
+        self.dot3ad_agg_port_actor_system_id
+    }
+
+    /// Partner operational system ID (dot3adAggPortPartnerOperSystemID)
+    pub fn partner_system_id(&self) -> crate::models::MacAddress {
+        self.dot3ad_agg_port_partner_oper_system_id
+    }
+
+    /// Decoded actor and partner LACP states
+    pub fn port_state(&self) -> PortState {
+        PortState::from(self.dot3ad_agg_port_state)
+    }
+}
+
+/// LACP state bits of a LAG port (IEEE 802.1AX Actor_State / Partner_State)
+///
+/// Bit 0 is the least significant bit of the state byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct LacpState(pub u8);
+
+impl LacpState {
+    /// Bit 0: active LACP (false = passive)
+    pub fn lacp_activity(&self) -> bool {
+        self.0 & 0x01 != 0
+    }
+
+    /// Bit 1: short timeout (false = long timeout)
+    pub fn lacp_timeout(&self) -> bool {
+        self.0 & 0x02 != 0
+    }
+
+    /// Bit 2: link is aggregatable (false = individual)
+    pub fn aggregation(&self) -> bool {
+        self.0 & 0x04 != 0
+    }
+
+    /// Bit 3: link is in sync with the aggregator
+    pub fn synchronization(&self) -> bool {
+        self.0 & 0x08 != 0
+    }
+
+    /// Bit 4: collecting incoming frames is enabled
+    pub fn collecting(&self) -> bool {
+        self.0 & 0x10 != 0
+    }
+
+    /// Bit 5: distributing outgoing frames is enabled
+    pub fn distributing(&self) -> bool {
+        self.0 & 0x20 != 0
+    }
+
+    /// Bit 6: partner information is administrative defaults
+    pub fn defaulted(&self) -> bool {
+        self.0 & 0x40 != 0
+    }
+
+    /// Bit 7: receive machine is in the EXPIRED state
+    pub fn expired(&self) -> bool {
+        self.0 & 0x80 != 0
+    }
+}
+
+/// Decoded dot3adAggPortState of a [`LagPortStats`] record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PortState {
+    /// dot3adAggPortActorAdminState (byte 0)
+    pub actor_admin: LacpState,
+    /// dot3adAggPortActorOperState (byte 1)
+    pub actor_oper: LacpState,
+    /// dot3adAggPortPartnerAdminState (byte 2)
+    pub partner_admin: LacpState,
+    /// dot3adAggPortPartnerOperState (byte 3)
+    pub partner_oper: LacpState,
+}
+
+impl From<[u8; 4]> for PortState {
+    fn from(bytes: [u8; 4]) -> Self {
+        Self {
+            actor_admin: LacpState(bytes[0]),
+            actor_oper: LacpState(bytes[1]),
+            partner_admin: LacpState(bytes[2]),
+            partner_oper: LacpState(bytes[3]),
+        }
+    }
+}
+
 /// Slow Path Counts - Format (0,8)
 ///
 /// Counts of packets processed via slow path (software) rather than fast path (hardware)
@@ -458,6 +625,7 @@ pub struct LagPortStats {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SlowPathCounts {
     /// Unknown reason
     pub unknown: u32,
@@ -507,6 +675,7 @@ pub struct SlowPathCounts {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct InfiniBandCounters {
     /// Total packets transmitted on all virtual lanes
     pub port_xmit_pkts: u64,
@@ -573,6 +742,7 @@ pub struct InfiniBandCounters {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Lane {
     /// 1-based index of lane within module, 0=unknown
     pub index: u32,
@@ -624,6 +794,7 @@ pub struct Lane {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct OpticalSfpQsfp {
     /// Module identifier (ifIndex of module or lowest ifIndex of ports sharing module)
     pub module_id: u32,
@@ -664,6 +835,7 @@ pub struct OpticalSfpQsfp {
 /// violating RFC 4506 XDR syntax requirements. The corrected version is shown above.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ProcessorCounters {
     /// 5 second average CPU utilization (0-100%) (spec: 5s_cpu)
     pub cpu_5s: u32,
@@ -699,6 +871,7 @@ pub struct ProcessorCounters {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct RadioUtilization {
     /// Elapsed time in milliseconds
     pub elapsed_time: u32,
@@ -759,6 +932,7 @@ pub struct RadioUtilization {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct QueueLength {
     /// Persistent index of queue within port
     pub queue_index: u32,
@@ -817,6 +991,7 @@ pub struct QueueLength {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct OpenFlowPort {
     /// Datapath ID
     pub datapath_id: u64,
@@ -841,6 +1016,7 @@ pub struct OpenFlowPort {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct OpenFlowPortName {
     /// Port name
     pub port_name: String,
@@ -871,6 +1047,7 @@ pub struct OpenFlowPortName {
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[repr(u32)]
 pub enum MachineType {
     Unknown = 0,
@@ -937,6 +1114,7 @@ impl From<u32> for MachineType {
 /// sFlow must be prepared to receive host_descr structures with unknown os_name values.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[repr(u32)]
 pub enum OsName {
     Unknown = 0,
@@ -997,6 +1175,7 @@ impl From<u32> for OsName {
 /// **ERRATUM:** UUID field changed from `opaque uuid<16>` to `opaque uuid[16]` (fixed array).
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct HostDescription {
     /// Hostname
     pub hostname: String,
@@ -1031,6 +1210,7 @@ pub struct HostDescription {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct HostAdapters {
     /// Adapters
     pub adapters: Vec<HostAdapter>,
@@ -1038,6 +1218,7 @@ pub struct HostAdapters {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct HostAdapter {
     /// Interface index
     pub if_index: u32,
@@ -1046,6 +1227,30 @@ pub struct HostAdapter {
     pub mac_addresses: Vec<crate::models::MacAddress>,
 }
 
+impl HostAdapters {
+    /// Associate interface counters with the MAC addresses of their adapter
+    ///
+    /// Returns one `(if_index, mac, counters)` entry per MAC address of each
+    /// adapter whose ifIndex matches a Generic Interface Counters (0,1)
+    /// record, in counters order. Counters without an adapter are left out.
+    pub fn associate<'a>(
+        &self,
+        counters: impl IntoIterator<Item = &'a GenericInterfaceCounters>,
+    ) -> Vec<(u32, crate::models::MacAddress, &'a GenericInterfaceCounters)> {
+        let mut associated = Vec::new();
+        for interface in counters {
+            for adapter in &self.adapters {
+                if adapter.if_index == interface.if_index {
+                    for mac in &adapter.mac_addresses {
+                        associated.push((interface.if_index, *mac, interface));
+                    }
+                }
+            }
+        }
+        associated
+    }
+}
+
 /// Host Parent - Format (0,2002)
 ///
 /// Containment hierarchy between logical and physical entities
@@ -1063,14 +1268,28 @@ pub struct HostAdapter {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct HostParent {
-    /// Container type (e.g., "docker", "lxc")
+    /// sFlowDataSource type of the parent (e.g., 2 = entPhysicalEntry)
     pub container_type: u32,
 
-    /// Container index
+    /// sFlowDataSource index of the parent
     pub container_index: u32,
 }
 
+impl HostParent {
+    /// Data source of the parent (containing) entity
+    ///
+    /// Matches the `source_id` of the counter samples the parent exports,
+    /// which links a virtual host to its physical host.
+    pub fn parent_data_source(&self) -> crate::models::DataSourceExpanded {
+        crate::models::DataSourceExpanded {
+            source_id_type: self.container_type,
+            source_id_index: self.container_index,
+        }
+    }
+}
+
 /// Host CPU - Format (0,2003)
 ///
 /// Physical server CPU statistics
@@ -1103,6 +1322,7 @@ pub struct HostParent {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct HostCpu {
     /// Load average (1 minute) - stored as hundredths (multiply by 100)
     pub load_one: u32,
@@ -1182,6 +1402,7 @@ pub struct HostCpu {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct HostMemory {
     /// Total memory in bytes
     pub mem_total: u64,
@@ -1241,6 +1462,7 @@ pub struct HostMemory {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct HostDiskIo {
     /// Total disk capacity in bytes
     pub disk_total: u64,
@@ -1293,6 +1515,7 @@ pub struct HostDiskIo {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct HostNetIo {
     /// Bytes received
     pub bytes_in: u64,
@@ -1353,6 +1576,7 @@ pub struct HostNetIo {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Mib2IpGroup {
     pub ip_forwarding: u32,
     pub ip_default_ttl: u32,
@@ -1379,6 +1603,11 @@ pub struct Mib2IpGroup {
 ///
 /// ICMP protocol statistics from MIB-II
 ///
+/// These are the IPv4 ICMP counters (RFC 1213 icmp group, no Router
+/// Advertisement or Neighbor Discovery counters). sflow_host_ip defines no
+/// separate ICMPv6 record, so ICMPv6 statistics are never reported here; a
+/// vendor ICMPv6 record would be kept as `CounterData::Unknown`.
+///
 /// # XDR Definition ([sFlow Host TCP/IP](https://sflow.org/sflow_host_ip.txt))
 ///
 /// ```text
@@ -1415,6 +1644,7 @@ pub struct Mib2IpGroup {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Mib2IcmpGroup {
     pub icmp_in_msgs: u32,
     pub icmp_in_errors: u32,
@@ -1473,6 +1703,7 @@ pub struct Mib2IcmpGroup {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Mib2TcpGroup {
     pub tcp_rto_algorithm: u32,
     pub tcp_rto_min: u32,
@@ -1513,6 +1744,7 @@ pub struct Mib2TcpGroup {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Mib2UdpGroup {
     pub udp_in_datagrams: u32,
     pub udp_no_ports: u32,
@@ -1543,6 +1775,7 @@ pub struct Mib2UdpGroup {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VirtualNode {
     /// Expected CPU frequency in MHz
     pub mhz: u32,
@@ -1581,6 +1814,7 @@ pub struct VirtualNode {
 /// **ERRATUM:** Comment reference corrected from `virtDomainInfo` to `virDomainInfo`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VirtualCpu {
     /// CPU state (0=running, 1=idle, 2=blocked)
     pub state: u32,
@@ -1612,6 +1846,7 @@ pub struct VirtualCpu {
 /// **ERRATUM:** Comment reference corrected from `virtDomainInfo` to `virDomainInfo`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VirtualMemory {
     /// Memory in bytes
     pub memory: u64,
@@ -1648,6 +1883,7 @@ pub struct VirtualMemory {
 /// from `virtDomainBlockInfo`/`virtDomainBlockStatsStruct` to `virDomainBlockInfo`/`virDomainBlockStatsStruct`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VirtualDiskIo {
     /// Capacity in bytes
     pub capacity: u64,
@@ -1701,6 +1937,7 @@ pub struct VirtualDiskIo {
 /// **ERRATUM:** Comment reference corrected from `virtDomainInterfaceStatsStruct` to `virDomainInterfaceStatsStruct`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VirtualNetIo {
     /// Bytes received
     pub rx_bytes: u64,
@@ -1746,6 +1983,7 @@ pub struct VirtualNetIo {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct JvmRuntime {
     /// JVM name
     pub vm_name: String,
@@ -1803,6 +2041,7 @@ pub struct JvmRuntime {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct JvmStatistics {
     /// Initial heap memory requested
     pub heap_initial: u64,
@@ -1914,6 +2153,7 @@ pub struct JvmStatistics {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MemcacheCountersDeprecated {
     /// Uptime in seconds
     pub uptime: u32,
@@ -2044,6 +2284,7 @@ pub struct MemcacheCountersDeprecated {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct HttpCounters {
     /// OPTIONS method count
     pub method_option_count: u32,
@@ -2118,6 +2359,7 @@ pub struct HttpCounters {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AppOperations {
     /// Application identifier
     pub application: String,
@@ -2179,6 +2421,7 @@ pub struct AppOperations {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AppResources {
     /// User time in milliseconds
     pub user_time: u32,
@@ -2252,6 +2495,7 @@ pub struct AppResources {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MemcacheCounters {
     /// Number of set commands
     pub cmd_set: u32,
@@ -2367,6 +2611,7 @@ pub struct MemcacheCounters {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AppWorkers {
     /// Number of active workers
     pub workers_active: u32,
@@ -2405,6 +2650,7 @@ pub struct AppWorkers {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct OvsDpStats {
     /// Number of flow table hits
     pub hits: u32,
@@ -2461,6 +2707,7 @@ pub struct OvsDpStats {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Energy {
     /// Voltage measured in millivolts (unknown = 4,294,976,295)
     pub voltage: u32,
@@ -2509,6 +2756,7 @@ pub struct Energy {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Temperature {
     /// Temperature reading from coolest thermometer in tenths of a degree Celsius
     pub minimum: i32,
@@ -2536,6 +2784,7 @@ pub struct Temperature {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Humidity {
     /// Relative humidity expressed as a percentage
     pub relative: i32,
@@ -2559,6 +2808,7 @@ pub struct Humidity {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Fans {
     /// Total number of fans
     pub total: u32,
@@ -2587,6 +2837,7 @@ pub struct Fans {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct BroadcomDeviceBuffers {
     /// Unicast buffers percentage utilization (in hundredths of a percent, e.g., 100 = 1%)
     pub uc_pc: i32,
@@ -2616,6 +2867,7 @@ pub struct BroadcomDeviceBuffers {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct BroadcomPortBuffers {
     /// Ingress unicast buffers percentage utilization (in hundredths of a percent)
     pub ingress_uc_pc: i32,
@@ -2687,6 +2939,7 @@ pub struct BroadcomPortBuffers {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct BroadcomTables {
     /// Number of host table entries
     pub host_entries: u32,
@@ -2836,6 +3089,7 @@ pub struct BroadcomTables {
 /// inconsistent with all other sFlow specifications. The corrected version is shown above.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct NvidiaGpu {
     /// Number of GPU devices
     pub device_count: u32,
@@ -8,24 +8,27 @@ use std::net::{Ipv4Addr, Ipv6Addr};
 /// Header protocol types for sampled headers
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum HeaderProtocol {
-    EthernetIso88023 = 1,
-    Iso88024TokenBus = 2,
-    Iso88025TokenRing = 3,
-    Fddi = 4,
-    FrameRelay = 5,
-    X25 = 6,
-    Ppp = 7,
-    Smds = 8,
-    Aal5 = 9,
-    Aal5Ip = 10,
-    Ipv4 = 11,
-    Ipv6 = 12,
-    Mpls = 13,
-    Pos = 14,
-    Ieee80211Mac = 15,
-    Ieee80211Ampdu = 16,
-    Ieee80211Amsdu = 17,
+    EthernetIso88023,
+    Iso88024TokenBus,
+    Iso88025TokenRing,
+    Fddi,
+    FrameRelay,
+    X25,
+    Ppp,
+    Smds,
+    Aal5,
+    Aal5Ip,
+    Ipv4,
+    Ipv6,
+    Mpls,
+    Pos,
+    Ieee80211Mac,
+    Ieee80211Ampdu,
+    Ieee80211Amsdu,
+    /// Header protocol not defined by the specification (raw value)
+    Unknown(u32),
 }
 
 impl HeaderProtocol {
@@ -52,6 +55,30 @@ impl HeaderProtocol {
             _ => None,
         }
     }
+
+    /// Convert HeaderProtocol enum to its u32 wire value
+    pub fn as_u32(&self) -> u32 {
+        match self {
+            HeaderProtocol::EthernetIso88023 => 1,
+            HeaderProtocol::Iso88024TokenBus => 2,
+            HeaderProtocol::Iso88025TokenRing => 3,
+            HeaderProtocol::Fddi => 4,
+            HeaderProtocol::FrameRelay => 5,
+            HeaderProtocol::X25 => 6,
+            HeaderProtocol::Ppp => 7,
+            HeaderProtocol::Smds => 8,
+            HeaderProtocol::Aal5 => 9,
+            HeaderProtocol::Aal5Ip => 10,
+            HeaderProtocol::Ipv4 => 11,
+            HeaderProtocol::Ipv6 => 12,
+            HeaderProtocol::Mpls => 13,
+            HeaderProtocol::Pos => 14,
+            HeaderProtocol::Ieee80211Mac => 15,
+            HeaderProtocol::Ieee80211Ampdu => 16,
+            HeaderProtocol::Ieee80211Amsdu => 17,
+            HeaderProtocol::Unknown(value) => *value,
+        }
+    }
 }
 
 impl std::fmt::Display for HeaderProtocol {
@@ -74,6 +101,75 @@ impl std::fmt::Display for HeaderProtocol {
             HeaderProtocol::Ieee80211Mac => write!(f, "IEEE 802.11 MAC"),
             HeaderProtocol::Ieee80211Ampdu => write!(f, "IEEE 802.11 A-MPDU"),
             HeaderProtocol::Ieee80211Amsdu => write!(f, "IEEE 802.11 A-MSDU"),
+            HeaderProtocol::Unknown(value) => write!(f, "Unknown ({})", value),
+        }
+    }
+}
+
+/// IP protocol numbers (IANA "Assigned Internet Protocol Numbers")
+///
+/// Used to name the raw `protocol` field of the sampled IP and socket records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum IpProtocol {
+    Icmp,
+    Tcp,
+    Udp,
+    Gre,
+    Esp,
+    Ah,
+    Icmpv6,
+    Sctp,
+    /// Protocol number without a named variant (raw value)
+    Unknown(u32),
+}
+
+impl IpProtocol {
+    /// Convert IpProtocol enum to its protocol number
+    pub fn as_u32(&self) -> u32 {
+        match self {
+            IpProtocol::Icmp => 1,
+            IpProtocol::Tcp => 6,
+            IpProtocol::Udp => 17,
+            IpProtocol::Gre => 47,
+            IpProtocol::Esp => 50,
+            IpProtocol::Ah => 51,
+            IpProtocol::Icmpv6 => 58,
+            IpProtocol::Sctp => 132,
+            IpProtocol::Unknown(value) => *value,
+        }
+    }
+}
+
+impl From<u32> for IpProtocol {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => IpProtocol::Icmp,
+            6 => IpProtocol::Tcp,
+            17 => IpProtocol::Udp,
+            47 => IpProtocol::Gre,
+            50 => IpProtocol::Esp,
+            51 => IpProtocol::Ah,
+            58 => IpProtocol::Icmpv6,
+            132 => IpProtocol::Sctp,
+            _ => IpProtocol::Unknown(value),
+        }
+    }
+}
+
+impl std::fmt::Display for IpProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IpProtocol::Icmp => write!(f, "ICMP"),
+            IpProtocol::Tcp => write!(f, "TCP"),
+            IpProtocol::Udp => write!(f, "UDP"),
+            IpProtocol::Gre => write!(f, "GRE"),
+            IpProtocol::Esp => write!(f, "ESP"),
+            IpProtocol::Ah => write!(f, "AH"),
+            IpProtocol::Icmpv6 => write!(f, "ICMPv6"),
+            IpProtocol::Sctp => write!(f, "SCTP"),
+            IpProtocol::Unknown(value) => write!(f, "Unknown ({})", value),
         }
     }
 }
@@ -97,6 +193,7 @@ impl std::fmt::Display for HeaderProtocol {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SampledHeader {
     /// Protocol of the sampled packet
     pub protocol: HeaderProtocol,
@@ -107,7 +204,8 @@ pub struct SampledHeader {
     /// Number of bytes stripped from the packet before sampling
     pub stripped: u32,
 
-    /// Raw header bytes
+    /// Raw header bytes, usually fewer than `frame_length`: only the first
+    /// bytes of the packet are captured
     pub header: Vec<u8>,
 }
 
@@ -132,6 +230,7 @@ pub struct SampledHeader {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SampledEthernet {
     /// Length of MAC packet in bytes
     pub length: u32,
@@ -169,6 +268,7 @@ pub struct SampledEthernet {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SampledIpv4 {
     /// Length of IP packet in bytes
     pub length: u32,
@@ -195,6 +295,23 @@ pub struct SampledIpv4 {
     pub tos: u32,
 }
 
+impl SampledIpv4 {
+    /// IP protocol of the sampled packet
+    pub fn ip_protocol(&self) -> IpProtocol {
+        IpProtocol::from(self.protocol)
+    }
+
+    /// Differentiated Services Code Point (upper 6 bits of the TOS byte)
+    pub fn dscp(&self) -> u8 {
+        dscp_value(self.tos)
+    }
+
+    /// Explicit Congestion Notification (lower 2 bits of the TOS byte)
+    pub fn ecn(&self) -> u8 {
+        ecn_value(self.tos)
+    }
+}
+
 /// Sampled IPv6 - Format (0,4)
 ///
 /// IPv6 packet header information
@@ -218,6 +335,7 @@ pub struct SampledIpv4 {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SampledIpv6 {
     /// Length of IP packet in bytes
     pub length: u32,
@@ -244,6 +362,33 @@ pub struct SampledIpv6 {
     pub priority: u32,
 }
 
+impl SampledIpv6 {
+    /// IP protocol of the sampled packet
+    pub fn ip_protocol(&self) -> IpProtocol {
+        IpProtocol::from(self.protocol)
+    }
+
+    /// Differentiated Services Code Point (upper 6 bits of the traffic class)
+    pub fn dscp(&self) -> u8 {
+        dscp_value(self.priority)
+    }
+
+    /// Explicit Congestion Notification (lower 2 bits of the traffic class)
+    pub fn ecn(&self) -> u8 {
+        ecn_value(self.priority)
+    }
+}
+
+/// DSCP from a TOS / traffic class byte held in the low 8 bits of `raw`
+fn dscp_value(raw: u32) -> u8 {
+    (raw as u8) >> 2
+}
+
+/// ECN from a TOS / traffic class byte held in the low 8 bits of `raw`
+fn ecn_value(raw: u32) -> u8 {
+    (raw as u8) & 0x03
+}
+
 /// Extended Switch Data - Format (0,1001)
 ///
 /// Layer 2 switching information
@@ -269,6 +414,7 @@ pub struct SampledIpv6 {
 /// **ERRATUM:** The specification was updated to clarify that 0xffffffff indicates unknown values.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedSwitch {
     /// Source VLAN ID
     /// **ERRATUM:** 0xffffffff if unknown
@@ -287,6 +433,69 @@ pub struct ExtendedSwitch {
     pub dst_priority: u32,
 }
 
+impl ExtendedSwitch {
+    /// Source VLAN ID, `None` if unknown or outside 0-4095
+    pub fn src_vlan_value(&self) -> Option<u16> {
+        vlan_value(self.src_vlan)
+    }
+
+    /// Source 802.1p priority, `None` if unknown or outside 0-7
+    pub fn src_priority_value(&self) -> Option<u8> {
+        priority_value(self.src_priority)
+    }
+
+    /// Destination VLAN ID, `None` if unknown or outside 0-4095
+    pub fn dst_vlan_value(&self) -> Option<u16> {
+        vlan_value(self.dst_vlan)
+    }
+
+    /// Destination 802.1p priority, `None` if unknown or outside 0-7
+    pub fn dst_priority_value(&self) -> Option<u8> {
+        priority_value(self.dst_priority)
+    }
+
+    /// Class of service name of the source priority, see [`cos_name`]
+    pub fn src_cos_name(&self) -> Option<&'static str> {
+        self.src_priority_value().map(cos_name)
+    }
+
+    /// Class of service name of the destination priority, see [`cos_name`]
+    pub fn dst_cos_name(&self) -> Option<&'static str> {
+        self.dst_priority_value().map(cos_name)
+    }
+}
+
+/// Traffic type of an 802.1p priority (IEEE 802.1Q Annex I)
+///
+/// Returns `"unknown"` for priorities outside 0-7.
+pub fn cos_name(priority: u8) -> &'static str {
+    match priority {
+        0 => "best effort",
+        1 => "background",
+        2 => "excellent effort",
+        3 => "critical applications",
+        4 => "video",
+        5 => "voice",
+        6 => "internetwork control",
+        7 => "network control",
+        _ => "unknown",
+    }
+}
+
+fn vlan_value(raw: u32) -> Option<u16> {
+    match raw {
+        0..=4095 => Some(raw as u16),
+        _ => None,
+    }
+}
+
+fn priority_value(raw: u32) -> Option<u8> {
+    match raw {
+        0..=7 => Some(raw as u8),
+        _ => None,
+    }
+}
+
 /// Extended Router Data - Format (0,1002)
 ///
 /// Layer 3 routing information
@@ -307,21 +516,36 @@ pub struct ExtendedSwitch {
 /// **ERRATUM:** The specification was clarified to specify "immediate" next hop router.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedRouter {
     /// IP address of immediate next hop router (spec: nexthop)
     /// **ERRATUM:** Clarified as "immediate" next hop router
     pub next_hop: crate::models::core::Address,
 
     /// Source subnet mask bits
+    ///
+    /// Prefix length of the route matching the source address reported by
+    /// the sampled IP or header record of the same flow sample.
     pub src_mask_len: u32,
 
     /// Destination subnet mask bits
+    ///
+    /// Prefix length of the route matching the destination address reported
+    /// by the sampled IP or header record of the same flow sample.
     pub dst_mask_len: u32,
 }
 
+impl ExtendedRouter {
+    /// Next hop IP address, or `None` for an unknown address type
+    pub fn next_hop_ip(&self) -> Option<std::net::IpAddr> {
+        self.next_hop.to_ip_addr()
+    }
+}
+
 /// AS Path Type
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[repr(u32)]
 pub enum AsPathType {
     AsSet = 1,
@@ -338,9 +562,19 @@ impl From<u32> for AsPathType {
     }
 }
 
+impl std::fmt::Display for AsPathType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AsPathType::AsSet => write!(f, "AS_SET"),
+            AsPathType::AsSequence => write!(f, "AS_SEQUENCE"),
+        }
+    }
+}
+
 /// AS Path Segment
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AsPathSegment {
     pub path_type: AsPathType,
     pub path_length: u32,
@@ -367,8 +601,12 @@ pub struct AsPathSegment {
 ///     unsigned int localpref;     /* LocalPref associated with this route */
 /// }
 /// ```
+///
+/// **Note:** Some agents append vendor-specific data after `localpref`. Those
+/// bytes are kept in `trailing` instead of being silently discarded.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedGateway {
     /// IP address of the border router (spec: nexthop)
     pub next_hop: crate::models::core::Address,
@@ -390,6 +628,71 @@ pub struct ExtendedGateway {
 
     /// Local preference (spec: localpref)
     pub local_pref: u32,
+
+    /// Bytes following `localpref` within the record (vendor extension, not in spec)
+    pub trailing: Vec<u8>,
+}
+
+/// AS number standing in for a 4-byte AS number towards 2-byte-only BGP
+/// speakers (RFC 6793)
+pub const AS_TRANS: u32 = 23456;
+
+/// Render an AS number in asplain notation, flagging [`AS_TRANS`]
+///
+/// ```
+/// use sflow_parser::models::record_flows::asn_to_string;
+///
+/// assert_eq!(asn_to_string(4200000000), "4200000000");
+/// assert_eq!(asn_to_string(23456), "23456 (AS_TRANS)");
+/// ```
+pub fn asn_to_string(asn: u32) -> String {
+    if asn == AS_TRANS {
+        format!("{} (AS_TRANS)", asn)
+    } else {
+        asn.to_string()
+    }
+}
+
+/// Render a standard BGP community as `asn:value` (RFC 1997 16:16 split)
+pub fn community_to_string(community: u32) -> String {
+    format!("{}:{}", community >> 16, community & 0xFFFF)
+}
+
+impl ExtendedGateway {
+    /// AS number of the router rendered with [`asn_to_string`]
+    pub fn as_number_string(&self) -> String {
+        asn_to_string(self.as_number)
+    }
+
+    /// Communities rendered with [`community_to_string`]
+    pub fn community_strings(&self) -> Vec<String> {
+        self.communities
+            .iter()
+            .map(|&community| community_to_string(community))
+            .collect()
+    }
+
+    /// Whether any AS number of the record needs 4 bytes
+    ///
+    /// Looks at the router, source and source peer AS numbers and at the
+    /// destination AS path.
+    pub fn has_32bit_asn(&self) -> bool {
+        self.asns().any(|asn| asn > u32::from(u16::MAX))
+    }
+
+    /// Whether [`AS_TRANS`] appears among the AS numbers of the record
+    ///
+    /// A 2-byte-only speaker replaced a 4-byte AS number somewhere, so
+    /// that part of the path does not identify the real AS.
+    pub fn has_as_trans(&self) -> bool {
+        self.asns().any(|asn| asn == AS_TRANS)
+    }
+
+    fn asns(&self) -> impl Iterator<Item = u32> + '_ {
+        [self.as_number, self.src_as, self.src_peer_as]
+            .into_iter()
+            .chain(self.dst_as_path.iter().flat_map(|s| s.path.iter().copied()))
+    }
 }
 
 /// Extended User Data - Format (0,1004)
@@ -411,6 +714,7 @@ pub struct ExtendedGateway {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedUser {
     /// Source character set (MIBEnum)
     pub src_charset: u32,
@@ -428,6 +732,7 @@ pub struct ExtendedUser {
 /// URL Direction
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[repr(u32)]
 pub enum UrlDirection {
     Source = 1,
@@ -462,6 +767,7 @@ impl From<u32> for UrlDirection {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedUrl {
     /// Direction (source or destination)
     pub direction: UrlDirection,
@@ -491,6 +797,7 @@ pub struct ExtendedUrl {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedMpls {
     /// Next hop address (spec: nexthop)
     pub next_hop: crate::models::core::Address,
@@ -502,6 +809,66 @@ pub struct ExtendedMpls {
     pub out_stack: Vec<u32>,
 }
 
+impl ExtendedMpls {
+    /// Next hop IP address, or `None` for an unknown address type
+    pub fn next_hop_ip(&self) -> Option<std::net::IpAddr> {
+        self.next_hop.to_ip_addr()
+    }
+
+    /// Top (first) entry of the input label stack
+    pub fn top_in_label(&self) -> Option<MplsLabel> {
+        self.in_stack.first().copied().map(MplsLabel::from_entry)
+    }
+
+    /// Top (first) entry of the output label stack
+    pub fn top_out_label(&self) -> Option<MplsLabel> {
+        self.out_stack.first().copied().map(MplsLabel::from_entry)
+    }
+}
+
+/// Decoded MPLS label stack entry (RFC 3032)
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |                Label                  | TC  |S|       TTL     |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct MplsLabel {
+    /// Label value (20 bits)
+    pub label: u32,
+    /// Traffic class, formerly EXP (3 bits)
+    pub traffic_class: u8,
+    /// Bottom of stack flag
+    pub bottom_of_stack: bool,
+    /// Time to live
+    pub ttl: u8,
+}
+
+impl MplsLabel {
+    /// Decode a 32-bit label stack entry
+    pub fn from_entry(entry: u32) -> Self {
+        Self {
+            label: entry >> 12,
+            traffic_class: ((entry >> 9) & 0x7) as u8,
+            bottom_of_stack: entry & 0x100 != 0,
+            ttl: (entry & 0xFF) as u8,
+        }
+    }
+
+    /// Encode back into a 32-bit label stack entry
+    pub fn to_entry(&self) -> u32 {
+        ((self.label & 0xF_FFFF) << 12)
+            | (u32::from(self.traffic_class & 0x7) << 9)
+            | (u32::from(self.bottom_of_stack) << 8)
+            | u32::from(self.ttl)
+    }
+}
+
 /// Extended NAT Data - Format (0,1007)
 ///
 /// Network Address Translation information
@@ -519,6 +886,7 @@ pub struct ExtendedMpls {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedNat {
     /// Source address type
     pub src_address: crate::models::core::Address,
@@ -545,6 +913,7 @@ pub struct ExtendedNat {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedMplsTunnel {
     /// Tunnel LSP name
     pub tunnel_lsp_name: String,
@@ -572,8 +941,12 @@ pub struct ExtendedMplsTunnel {
 ///     unsigned int vc_label_cos; /* VC Label COS value */
 /// }
 /// ```
+///
+/// The `vc_label_cos` word is split into `vc_label` (high 29 bits) and
+/// `vc_cos` (low 3 bits).
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedMplsVc {
     /// VC instance name
     pub vc_instance_name: String,
@@ -581,13 +954,20 @@ pub struct ExtendedMplsVc {
     /// VC ID
     pub vll_vc_id: u32,
 
-    /// VC label
+    /// VC label (high 29 bits of vc_label_cos)
     pub vc_label: u32,
 
-    /// VC COS
+    /// VC COS (low 3 bits of vc_label_cos)
     pub vc_cos: u32,
 }
 
+impl ExtendedMplsVc {
+    /// The packed vc_label_cos word as carried on the wire
+    pub fn vc_label_cos(&self) -> u32 {
+        (self.vc_label << 3) | (self.vc_cos & 0x7)
+    }
+}
+
 /// Extended MPLS FEC - Format (0,1010)
 ///
 /// MPLS Forwarding Equivalence Class information
@@ -605,6 +985,7 @@ pub struct ExtendedMplsVc {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedMplsFec {
     /// FEC address prefix
     pub fec_addr_prefix: crate::models::core::Address,
@@ -629,6 +1010,7 @@ pub struct ExtendedMplsFec {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedMplsLvpFec {
     /// FEC address prefix length
     pub mpls_fec_addr_prefix_length: u32,
@@ -650,6 +1032,7 @@ pub struct ExtendedMplsLvpFec {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedVlanTunnel {
     /// List of stripped 802.1Q TPID/TCI layers
     pub stack: Vec<u32>,
@@ -672,6 +1055,7 @@ pub struct ExtendedVlanTunnel {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Extended80211Payload {
     /// Cipher suite (OUI + Suite Type) (spec: ciphersuite)
     pub cipher_suite: u32,
@@ -680,6 +1064,106 @@ pub struct Extended80211Payload {
     pub data: Vec<u8>,
 }
 
+impl Extended80211Payload {
+    /// Decoded cipher suite
+    pub fn cipher_suite_decoded(&self) -> CipherSuite {
+        CipherSuite::from(self.cipher_suite)
+    }
+}
+
+/// IEEE 802.11 cipher suite selector
+///
+/// The selector is a 3-byte OUI followed by a suite type. Suites under the
+/// IEEE 802.11 OUI (00-0F-AC) are named, others are vendor-specific (e.g.
+/// 00-50-F2 for Microsoft WPA).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum CipherSuite {
+    UseGroupCipher,
+    Wep40,
+    Tkip,
+    Ccmp128,
+    Wep104,
+    BipCmac128,
+    GroupAddressedTrafficNotAllowed,
+    Gcmp128,
+    Gcmp256,
+    Ccmp256,
+    BipGmac128,
+    BipGmac256,
+    BipCmac256,
+    /// IEEE 802.11 suite type without a named variant
+    Ieee(u8),
+    /// Suite defined by a vendor OUI
+    Vendor {
+        oui: [u8; 3],
+        suite_type: u8,
+    },
+}
+
+impl CipherSuite {
+    /// OUI of the IEEE 802.11 cipher suites
+    pub const IEEE_OUI: [u8; 3] = [0x00, 0x0F, 0xAC];
+
+    /// Convert the cipher suite to its selector value
+    pub fn as_u32(&self) -> u32 {
+        let (oui, suite_type) = match *self {
+            CipherSuite::Vendor { oui, suite_type } => (oui, suite_type),
+            CipherSuite::Ieee(suite_type) => (Self::IEEE_OUI, suite_type),
+            named => (Self::IEEE_OUI, named.ieee_suite_type()),
+        };
+        u32::from_be_bytes([oui[0], oui[1], oui[2], suite_type])
+    }
+
+    /// Suite type of a named IEEE suite
+    fn ieee_suite_type(&self) -> u8 {
+        match self {
+            CipherSuite::UseGroupCipher => 0,
+            CipherSuite::Wep40 => 1,
+            CipherSuite::Tkip => 2,
+            CipherSuite::Ccmp128 => 4,
+            CipherSuite::Wep104 => 5,
+            CipherSuite::BipCmac128 => 6,
+            CipherSuite::GroupAddressedTrafficNotAllowed => 7,
+            CipherSuite::Gcmp128 => 8,
+            CipherSuite::Gcmp256 => 9,
+            CipherSuite::Ccmp256 => 10,
+            CipherSuite::BipGmac128 => 11,
+            CipherSuite::BipGmac256 => 12,
+            CipherSuite::BipCmac256 => 13,
+            CipherSuite::Ieee(suite_type) => *suite_type,
+            CipherSuite::Vendor { suite_type, .. } => *suite_type,
+        }
+    }
+}
+
+impl From<u32> for CipherSuite {
+    fn from(value: u32) -> Self {
+        let [a, b, c, suite_type] = value.to_be_bytes();
+        let oui = [a, b, c];
+        if oui != Self::IEEE_OUI {
+            return CipherSuite::Vendor { oui, suite_type };
+        }
+        match suite_type {
+            0 => CipherSuite::UseGroupCipher,
+            1 => CipherSuite::Wep40,
+            2 => CipherSuite::Tkip,
+            4 => CipherSuite::Ccmp128,
+            5 => CipherSuite::Wep104,
+            6 => CipherSuite::BipCmac128,
+            7 => CipherSuite::GroupAddressedTrafficNotAllowed,
+            8 => CipherSuite::Gcmp128,
+            9 => CipherSuite::Gcmp256,
+            10 => CipherSuite::Ccmp256,
+            11 => CipherSuite::BipGmac128,
+            12 => CipherSuite::BipGmac256,
+            13 => CipherSuite::BipCmac256,
+            other => CipherSuite::Ieee(other),
+        }
+    }
+}
+
 /// Extended 802.11 RX - Format (0,1014)
 ///
 /// 802.11 receive information
@@ -706,6 +1190,7 @@ pub struct Extended80211Payload {
 /// violating RFC 4506 XDR syntax requirements. The corrected version is shown above.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Extended80211Rx {
     /// SSID string (max 32 bytes)
     pub ssid: String,
@@ -728,7 +1213,12 @@ pub struct Extended80211Rx {
     /// Received channel power indicator (RCPI)
     pub rcpi: u32,
 
-    /// Packet duration in microseconds
+    /// Time the received packet occupied the RF medium, in microseconds
+    ///
+    /// Decoded on purpose: the missing semicolon in the specification makes
+    /// strict XDR readers see only the seven preceding fields, but the field
+    /// is part of the structure and host-sflow encodes it after `rcpi`.
+    /// See [`Extended80211Rx::packet_duration_us`] for a [`Duration`](std::time::Duration).
     pub packet_duration: u32,
 }
 
@@ -756,6 +1246,7 @@ pub struct Extended80211Rx {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Extended80211Tx {
     /// SSID string (max 32 bytes)
     pub ssid: String,
@@ -785,6 +1276,47 @@ pub struct Extended80211Tx {
     pub power: u32,
 }
 
+impl Extended80211Rx {
+    /// Speed in bits per second, the unit defined by the specification
+    pub fn speed_bps(&self) -> u64 {
+        self.speed
+    }
+
+    /// Speed in megabits per second
+    pub fn speed_mbps(&self) -> f64 {
+        self.speed as f64 / 1_000_000.0
+    }
+
+    /// Time the received packet occupied the RF medium
+    pub fn packet_duration_us(&self) -> std::time::Duration {
+        std::time::Duration::from_micros(u64::from(self.packet_duration))
+    }
+}
+
+impl Extended80211Tx {
+    /// Speed in bits per second, the unit defined by the specification
+    pub fn speed_bps(&self) -> u64 {
+        self.speed
+    }
+
+    /// Speed in megabits per second
+    pub fn speed_mbps(&self) -> f64 {
+        self.speed as f64 / 1_000_000.0
+    }
+
+    /// Number of retransmissions before the packet was sent
+    ///
+    /// Returns `None` when the number of transmissions is unknown (0).
+    pub fn retransmission_count(&self) -> Option<u32> {
+        self.transmissions.checked_sub(1)
+    }
+
+    /// Check if the packet was sent on the first attempt
+    pub fn was_successful_first_try(&self) -> bool {
+        self.transmissions == 1
+    }
+}
+
 /// PDU (Protocol Data Unit) in 802.11 aggregation
 ///
 /// # XDR Definition ([sFlow 802.11](https://sflow.org/sflow_80211.txt))
@@ -796,6 +1328,7 @@ pub struct Extended80211Tx {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Pdu {
     /// Flow records for this PDU
     pub flow_records: Vec<crate::models::FlowRecord>,
@@ -817,6 +1350,7 @@ pub struct Pdu {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Extended80211Aggregation {
     /// Array of PDUs in the aggregation
     pub pdus: Vec<Pdu>,
@@ -844,6 +1378,7 @@ pub struct Extended80211Aggregation {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedOpenFlowV1 {
     /// Flow cookie set by the OpenFlow controller
     pub flow_cookie: u64,
@@ -857,6 +1392,94 @@ pub struct ExtendedOpenFlowV1 {
     pub flow_actions: u32,
 }
 
+impl ExtendedOpenFlowV1 {
+    /// Decode the `flow_match` wildcards (OpenFlow 1.0 `ofp_flow_wildcards`)
+    pub fn decoded_match(&self) -> OpenFlowWildcards {
+        OpenFlowWildcards::from_bits(self.flow_match)
+    }
+}
+
+/// Wildcarded fields of an OpenFlow 1.0 match
+///
+/// A `true` field is wildcarded, i.e. not part of the flow key. The IP
+/// address fields give the number of wildcarded low-order bits, 32 or more
+/// meaning the whole address.
+///
+/// ```text
+/// enum ofp_flow_wildcards {
+///     OFPFW_IN_PORT  = 1 << 0,  /* Switch input port. */
+///     OFPFW_DL_VLAN  = 1 << 1,  /* VLAN id. */
+///     OFPFW_DL_SRC   = 1 << 2,  /* Ethernet source address. */
+///     OFPFW_DL_DST   = 1 << 3,  /* Ethernet destination address. */
+///     OFPFW_DL_TYPE  = 1 << 4,  /* Ethernet frame type. */
+///     OFPFW_NW_PROTO = 1 << 5,  /* IP protocol. */
+///     OFPFW_TP_SRC   = 1 << 6,  /* TCP/UDP source port. */
+///     OFPFW_TP_DST   = 1 << 7,  /* TCP/UDP destination port. */
+///     OFPFW_NW_SRC_SHIFT = 8,   /* IP source address wildcard bit count, 6 bits */
+///     OFPFW_NW_DST_SHIFT = 14,  /* IP destination address wildcard bit count, 6 bits */
+///     OFPFW_DL_VLAN_PCP = 1 << 20, /* VLAN priority. */
+///     OFPFW_NW_TOS = 1 << 21,   /* IP ToS (DSCP field, 6 bits). */
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpenFlowWildcards {
+    /// Switch input port
+    pub in_port: bool,
+    /// VLAN id
+    pub dl_vlan: bool,
+    /// Ethernet source address
+    pub dl_src: bool,
+    /// Ethernet destination address
+    pub dl_dst: bool,
+    /// Ethernet frame type
+    pub dl_type: bool,
+    /// IP protocol
+    pub nw_proto: bool,
+    /// TCP/UDP source port
+    pub tp_src: bool,
+    /// TCP/UDP destination port
+    pub tp_dst: bool,
+    /// Number of wildcarded low-order bits of the IP source address (0-63)
+    pub nw_src_bits: u8,
+    /// Number of wildcarded low-order bits of the IP destination address (0-63)
+    pub nw_dst_bits: u8,
+    /// VLAN priority
+    pub dl_vlan_pcp: bool,
+    /// IP ToS
+    pub nw_tos: bool,
+}
+
+impl OpenFlowWildcards {
+    /// Decode an `ofp_flow_wildcards` bit array
+    pub fn from_bits(bits: u32) -> Self {
+        let flag = |bit: u32| bits & (1 << bit) != 0;
+        Self {
+            in_port: flag(0),
+            dl_vlan: flag(1),
+            dl_src: flag(2),
+            dl_dst: flag(3),
+            dl_type: flag(4),
+            nw_proto: flag(5),
+            tp_src: flag(6),
+            tp_dst: flag(7),
+            nw_src_bits: ((bits >> 8) & 0x3F) as u8,
+            nw_dst_bits: ((bits >> 14) & 0x3F) as u8,
+            dl_vlan_pcp: flag(20),
+            nw_tos: flag(21),
+        }
+    }
+
+    /// Prefix length of the IP source address matched by the flow
+    pub fn nw_src_prefix_len(&self) -> u8 {
+        32 - self.nw_src_bits.min(32)
+    }
+
+    /// Prefix length of the IP destination address matched by the flow
+    pub fn nw_dst_prefix_len(&self) -> u8 {
+        32 - self.nw_dst_bits.min(32)
+    }
+}
+
 /// Extended Fiber Channel Routing Entry - Format (0,1018)
 ///
 /// Fiber Channel routing information
@@ -887,6 +1510,7 @@ pub struct ExtendedOpenFlowV1 {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedFc {
     /// Source FC address mask (number of bits)
     pub src_mask_len: u32,
@@ -932,6 +1556,7 @@ pub struct ExtendedFc {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedQueueLength {
     /// Persistent index within port of queue used to enqueue sampled packet
     pub queue_index: u32,
@@ -962,6 +1587,7 @@ pub struct ExtendedQueueLength {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedNatPort {
     /// Translated source port
     pub src_port: u32,
@@ -985,6 +1611,7 @@ pub struct ExtendedNatPort {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedL2TunnelEgress {
     /// Outer Ethernet header that will be added on egress
     pub header: SampledEthernet,
@@ -1005,6 +1632,7 @@ pub struct ExtendedL2TunnelEgress {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedL2TunnelIngress {
     /// Outer Ethernet header that was present on ingress
     pub header: SampledEthernet,
@@ -1025,6 +1653,7 @@ pub struct ExtendedL2TunnelIngress {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedIpv4TunnelEgress {
     /// Outer IPv4 header that will be added on egress
     pub header: SampledIpv4,
@@ -1045,6 +1674,7 @@ pub struct ExtendedIpv4TunnelEgress {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedIpv4TunnelIngress {
     /// Outer IPv4 header that was present on ingress
     pub header: SampledIpv4,
@@ -1065,6 +1695,7 @@ pub struct ExtendedIpv4TunnelIngress {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedIpv6TunnelEgress {
     /// Outer IPv6 header that will be added on egress
     pub header: SampledIpv6,
@@ -1085,6 +1716,7 @@ pub struct ExtendedIpv6TunnelEgress {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedIpv6TunnelIngress {
     /// Outer IPv6 header that was present on ingress
     pub header: SampledIpv6,
@@ -1105,6 +1737,7 @@ pub struct ExtendedIpv6TunnelIngress {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedDecapsulateEgress {
     /// Offset in bytes to the inner header within the sampled packet header
     pub inner_header_offset: u32,
@@ -1125,6 +1758,7 @@ pub struct ExtendedDecapsulateEgress {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedDecapsulateIngress {
     /// Offset in bytes to the inner header within the sampled packet header
     pub inner_header_offset: u32,
@@ -1145,6 +1779,7 @@ pub struct ExtendedDecapsulateIngress {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedVniEgress {
     /// Virtual Network Identifier
     pub vni: u32,
@@ -1168,6 +1803,7 @@ pub struct ExtendedVniEgress {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedVniIngress {
     /// Virtual Network Identifier
     pub vni: u32,
@@ -1204,6 +1840,7 @@ pub struct ExtendedVniIngress {
 /// The corrected version is shown above.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedInfiniBandLrh {
     /// Source virtual lane
     pub src_vl: u32,
@@ -1258,6 +1895,7 @@ pub struct ExtendedInfiniBandLrh {
 /// non-standard data type `ib_grh_data` instead of `flow_data`. The corrected version is shown above.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedInfiniBandGrh {
     /// Flow label
     pub flow_label: u32,
@@ -1273,6 +1911,38 @@ pub struct ExtendedInfiniBandGrh {
     pub length: u32,
 }
 
+impl ExtendedInfiniBandGrh {
+    /// Source GID as eight colon-separated groups of 4 hex digits
+    ///
+    /// This is the uncompressed form printed by InfiniBand tools, e.g.
+    /// `fe80:0000:0000:0000:0002:c903:00a1:b2c3`.
+    pub fn s_gid_string(&self) -> String {
+        gid_string(&self.s_gid)
+    }
+
+    /// Destination GID as eight colon-separated groups of 4 hex digits
+    pub fn d_gid_string(&self) -> String {
+        gid_string(&self.d_gid)
+    }
+
+    /// Source GID as an IPv6 address (subnet prefix followed by the port GUID)
+    pub fn s_gid_as_ipv6(&self) -> std::net::Ipv6Addr {
+        std::net::Ipv6Addr::from(self.s_gid)
+    }
+
+    /// Destination GID as an IPv6 address (subnet prefix followed by the port GUID)
+    pub fn d_gid_as_ipv6(&self) -> std::net::Ipv6Addr {
+        std::net::Ipv6Addr::from(self.d_gid)
+    }
+}
+
+fn gid_string(gid: &[u8; 16]) -> String {
+    gid.chunks(2)
+        .map(|group| format!("{:02x}{:02x}", group[0], group[1]))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
 /// Extended InfiniBand BTH - Format (0,1033)
 ///
 /// InfiniBand Base Transport Header information
@@ -1297,6 +1967,7 @@ pub struct ExtendedInfiniBandGrh {
 /// The corrected version is shown above.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedInfiniBandBth {
     /// Partition key
     pub pkey: u32,
@@ -1323,6 +1994,7 @@ pub struct ExtendedInfiniBandBth {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedVlanIn {
     /// List of ingress 802.1Q TPID/TCI layers
     /// Each TPID,TCI pair is represented as a single 32-bit integer
@@ -1347,6 +2019,7 @@ pub struct ExtendedVlanIn {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedVlanOut {
     /// List of egress 802.1Q TPID/TCI layers
     /// Each TPID,TCI pair is represented as a single 32-bit integer
@@ -1370,6 +2043,7 @@ pub struct ExtendedVlanOut {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedEgressQueue {
     /// Egress queue number selected for sampled packet
     pub queue: u32,
@@ -1393,6 +2067,7 @@ pub struct ExtendedEgressQueue {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedAcl {
     /// Access list number
     pub number: u32,
@@ -1420,6 +2095,7 @@ pub struct ExtendedAcl {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedFunction {
     /// Function symbol name
     pub symbol: String,
@@ -1441,11 +2117,25 @@ pub struct ExtendedFunction {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedTransit {
     /// Transit delay in nanoseconds (0xffffffff indicates value >= 0xffffffff)
     pub delay: u32,
 }
 
+impl ExtendedTransit {
+    /// Value of `delay` for delays of 0xffffffff ns (about 4.3s) or more
+    pub const DELAY_SATURATED: u32 = u32::MAX;
+
+    /// Transit delay as a `Duration`, `None` if saturated
+    pub fn delay_duration(&self) -> Option<std::time::Duration> {
+        match self.delay {
+            Self::DELAY_SATURATED => None,
+            delay => Some(std::time::Duration::from_nanos(delay as u64)),
+        }
+    }
+}
+
 /// Extended Queue - Format (0,1040)
 ///
 /// Queue depth for sampled packet traversing switch
@@ -1462,6 +2152,7 @@ pub struct ExtendedTransit {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedQueue {
     /// Queue depth in bytes
     pub depth: u32,
@@ -1485,6 +2176,7 @@ pub struct ExtendedQueue {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedHwTrap {
     /// Hardware trap group name (NET_DM_ATTR_HW_TRAP_GROUP_NAME)
     pub group: String,
@@ -1493,6 +2185,67 @@ pub struct ExtendedHwTrap {
     pub trap: String,
 }
 
+impl ExtendedHwTrap {
+    /// Category of the trap, from its devlink trap group
+    pub fn category(&self) -> TrapCategory {
+        TrapCategory::from_group(&self.group)
+    }
+
+    /// Check if the packet was dropped by the device
+    ///
+    /// Drop traps are reported in the devlink `*_drops` groups; exception
+    /// and control traps deliver the packet to the CPU instead.
+    pub fn is_drop_trap(&self) -> bool {
+        self.group.ends_with("_drops")
+    }
+}
+
+/// Category of a devlink hardware trap
+///
+/// Derived from the generic trap group names documented in the Linux
+/// kernel ([devlink-trap](https://www.kernel.org/doc/html/latest/networking/devlink/devlink-trap.html)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum TrapCategory {
+    /// Layer 2 drops (`l2_drops`)
+    L2,
+    /// Layer 3 drops and exceptions (`l3_drops`, `l3_exceptions`)
+    L3,
+    /// Buffer drops, e.g. tail or early drops (`buffer_drops`)
+    Buffer,
+    /// Tunnel drops (`tunnel_drops`)
+    Tunnel,
+    /// ACL drops and traps (`acl_drops`, `acl_sample`, `acl_trap`)
+    Acl,
+    /// Packets the device failed to parse (`parser_error_drops`)
+    ParserError,
+    /// Control plane packets trapped to the CPU (`stp`, `lacp`, `bgp`, ...)
+    Control,
+    /// Group not defined by the kernel documentation
+    Other,
+}
+
+impl TrapCategory {
+    /// Categorize a devlink trap group name
+    pub fn from_group(group: &str) -> Self {
+        match group {
+            "l2_drops" => TrapCategory::L2,
+            "l3_drops" | "l3_exceptions" => TrapCategory::L3,
+            "buffer_drops" => TrapCategory::Buffer,
+            "tunnel_drops" => TrapCategory::Tunnel,
+            "acl_drops" | "acl_sample" | "acl_trap" => TrapCategory::Acl,
+            "parser_error_drops" => TrapCategory::ParserError,
+            "stp" | "lacp" | "lldp" | "mc_snooping" | "dhcp" | "neigh_discovery" | "bfd"
+            | "ospf" | "bgp" | "vrrp" | "pim" | "uc_loopback" | "local_delivery"
+            | "external_delivery" | "ipv6" | "ptp_event" | "ptp_general" | "eapol" => {
+                TrapCategory::Control
+            }
+            _ => TrapCategory::Other,
+        }
+    }
+}
+
 /// Extended Linux Drop Reason - Format (0,1042)
 ///
 /// Linux drop_monitor reason information
@@ -1510,6 +2263,7 @@ pub struct ExtendedHwTrap {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedLinuxDropReason {
     /// Drop reason string (NET_DM_ATTR_REASON)
     pub reason: String,
@@ -1530,6 +2284,7 @@ pub struct ExtendedLinuxDropReason {
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[repr(u32)]
 pub enum TransactionStatus {
     Succeeded = 0,
@@ -1552,6 +2307,18 @@ impl From<u32> for TransactionStatus {
     }
 }
 
+impl std::fmt::Display for TransactionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransactionStatus::Succeeded => write!(f, "succeeded"),
+            TransactionStatus::GenericFailure => write!(f, "generic_failure"),
+            TransactionStatus::OutOfMemory => write!(f, "outofmemory"),
+            TransactionStatus::Timeout => write!(f, "timeout"),
+            TransactionStatus::NotPermitted => write!(f, "notpermitted"),
+        }
+    }
+}
+
 /// Service direction for transactions
 ///
 /// # XDR Definition ([sFlow Discussion](https://sflow.org/discussion/sflow-discussion/0282.html))
@@ -1564,6 +2331,7 @@ impl From<u32> for TransactionStatus {
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[repr(u32)]
 pub enum ServiceDirection {
     Client = 1,
@@ -1580,6 +2348,15 @@ impl From<u32> for ServiceDirection {
     }
 }
 
+impl std::fmt::Display for ServiceDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServiceDirection::Client => write!(f, "client"),
+            ServiceDirection::Server => write!(f, "server"),
+        }
+    }
+}
+
 /// Transaction - Format (0,2000)
 ///
 /// Generic application transaction record sampled upon completion
@@ -1602,6 +2379,7 @@ impl From<u32> for ServiceDirection {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Transaction {
     /// Was this transaction observed by the server or the client
     pub direction: ServiceDirection,
@@ -1642,6 +2420,7 @@ pub struct Transaction {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedNfsStorageTransaction {
     /// Canonical path to file or directory (UTF8 encoded)
     pub path: Vec<u8>,
@@ -1670,6 +2449,7 @@ pub struct ExtendedNfsStorageTransaction {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedScsiStorageTransaction {
     /// Logical Unit Number
     pub lun: u32,
@@ -1681,6 +2461,70 @@ pub struct ExtendedScsiStorageTransaction {
     pub status: u32,
 }
 
+impl ExtendedScsiStorageTransaction {
+    /// Value of `operation` when the operation is unknown (maxint)
+    pub const UNKNOWN_OPERATION: u32 = u32::MAX;
+
+    /// Name of the SCSI operation code (SPC/SBC), e.g. `"READ_10"`
+    ///
+    /// Returns `None` for [`UNKNOWN_OPERATION`](Self::UNKNOWN_OPERATION) and
+    /// for opcodes outside the common command set.
+    pub fn operation_name(&self) -> Option<&'static str> {
+        let name = match self.operation {
+            0x00 => "TEST_UNIT_READY",
+            0x03 => "REQUEST_SENSE",
+            0x08 => "READ_6",
+            0x0A => "WRITE_6",
+            0x12 => "INQUIRY",
+            0x15 => "MODE_SELECT_6",
+            0x1A => "MODE_SENSE_6",
+            0x1B => "START_STOP_UNIT",
+            0x1E => "PREVENT_ALLOW_MEDIUM_REMOVAL",
+            0x25 => "READ_CAPACITY_10",
+            0x28 => "READ_10",
+            0x2A => "WRITE_10",
+            0x2F => "VERIFY_10",
+            0x35 => "SYNCHRONIZE_CACHE_10",
+            0x41 => "WRITE_SAME_10",
+            0x42 => "UNMAP",
+            0x4D => "LOG_SENSE",
+            0x55 => "MODE_SELECT_10",
+            0x5A => "MODE_SENSE_10",
+            0x5E => "PERSISTENT_RESERVE_IN",
+            0x5F => "PERSISTENT_RESERVE_OUT",
+            0x88 => "READ_16",
+            0x8A => "WRITE_16",
+            0x8F => "VERIFY_16",
+            0x91 => "SYNCHRONIZE_CACHE_16",
+            0x93 => "WRITE_SAME_16",
+            0x9E => "SERVICE_ACTION_IN_16",
+            0xA0 => "REPORT_LUNS",
+            0xA8 => "READ_12",
+            0xAA => "WRITE_12",
+            _ => return None,
+        };
+        Some(name)
+    }
+
+    /// Name of the SCSI status code (SAM), e.g. `"CHECK_CONDITION"`
+    pub fn status_name(&self) -> Option<&'static str> {
+        let name = match self.status {
+            0x00 => "GOOD",
+            0x02 => "CHECK_CONDITION",
+            0x04 => "CONDITION_MET",
+            0x08 => "BUSY",
+            0x10 => "INTERMEDIATE",
+            0x14 => "INTERMEDIATE_CONDITION_MET",
+            0x18 => "RESERVATION_CONFLICT",
+            0x28 => "TASK_SET_FULL",
+            0x30 => "ACA_ACTIVE",
+            0x40 => "TASK_ABORTED",
+            _ => return None,
+        };
+        Some(name)
+    }
+}
+
 /// Extended HTTP Transaction - Format (0,2003)
 ///
 /// HTTP transaction details
@@ -1701,6 +2545,7 @@ pub struct ExtendedScsiStorageTransaction {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedHttpTransaction {
     /// The HTTP request-line (see RFC 2616)
     pub url: String,
@@ -1721,6 +2566,23 @@ pub struct ExtendedHttpTransaction {
     pub status: u32,
 }
 
+impl ExtendedHttpTransaction {
+    /// Status class of the response (e.g. 2 for 200, 4 for 404)
+    ///
+    /// Returns `None` if the status is outside the 100-599 range.
+    pub fn status_class(&self) -> Option<u16> {
+        match self.status {
+            100..=599 => Some((self.status / 100) as u16),
+            _ => None,
+        }
+    }
+
+    /// Check if the response status is 2xx
+    pub fn is_success(&self) -> bool {
+        self.status_class() == Some(2)
+    }
+}
+
 /// Extended Socket IPv4 - Format (0,2100)
 ///
 /// IPv4 socket information for application transactions
@@ -1741,6 +2603,7 @@ pub struct ExtendedHttpTransaction {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedSocketIpv4 {
     /// IP Protocol type (e.g., TCP = 6, UDP = 17)
     pub protocol: u32,
@@ -1758,6 +2621,13 @@ pub struct ExtendedSocketIpv4 {
     pub remote_port: u32,
 }
 
+impl ExtendedSocketIpv4 {
+    /// IP protocol of the socket
+    pub fn ip_protocol(&self) -> IpProtocol {
+        IpProtocol::from(self.protocol)
+    }
+}
+
 /// Extended Socket IPv6 - Format (0,2101)
 ///
 /// IPv6 socket information for application transactions
@@ -1778,6 +2648,7 @@ pub struct ExtendedSocketIpv4 {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedSocketIpv6 {
     /// IP Protocol type (e.g., TCP = 6, UDP = 17)
     pub protocol: u32,
@@ -1795,6 +2666,13 @@ pub struct ExtendedSocketIpv6 {
     pub remote_port: u32,
 }
 
+impl ExtendedSocketIpv6 {
+    /// IP protocol of the socket
+    pub fn ip_protocol(&self) -> IpProtocol {
+        IpProtocol::from(self.protocol)
+    }
+}
+
 /// Extended Proxy Socket IPv4 - Format (0,2102)
 ///
 /// IPv4 socket information for proxy connections
@@ -1810,6 +2688,7 @@ pub struct ExtendedSocketIpv6 {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedProxySocketIpv4 {
     /// Socket information
     pub socket: ExtendedSocketIpv4,
@@ -1830,6 +2709,7 @@ pub struct ExtendedProxySocketIpv4 {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedProxySocketIpv6 {
     /// Socket information
     pub socket: ExtendedSocketIpv6,
@@ -1848,6 +2728,7 @@ pub struct ExtendedProxySocketIpv6 {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AppContext {
     /// Application name (e.g., "payment", "mail.smtp", "db.oracle")
     pub application: String,
@@ -1864,6 +2745,7 @@ pub struct AppContext {
 /// # XDR Definition ([sFlow Application](https://sflow.org/sflow_application.txt))
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[repr(u32)]
 pub enum AppStatus {
     Success = 0,
@@ -1898,6 +2780,24 @@ impl From<u32> for AppStatus {
     }
 }
 
+impl std::fmt::Display for AppStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppStatus::Success => write!(f, "SUCCESS"),
+            AppStatus::Other => write!(f, "OTHER"),
+            AppStatus::Timeout => write!(f, "TIMEOUT"),
+            AppStatus::InternalError => write!(f, "INTERNAL_ERROR"),
+            AppStatus::BadRequest => write!(f, "BAD_REQUEST"),
+            AppStatus::Forbidden => write!(f, "FORBIDDEN"),
+            AppStatus::TooLarge => write!(f, "TOO_LARGE"),
+            AppStatus::NotImplemented => write!(f, "NOT_IMPLEMENTED"),
+            AppStatus::NotFound => write!(f, "NOT_FOUND"),
+            AppStatus::Unavailable => write!(f, "UNAVAILABLE"),
+            AppStatus::Unauthorized => write!(f, "UNAUTHORIZED"),
+        }
+    }
+}
+
 /// Memcache Protocol
 ///
 /// # XDR Definition ([sFlow Memcache](https://sflow.org/sflow_memcache.txt))
@@ -1911,6 +2811,7 @@ impl From<u32> for AppStatus {
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[repr(u32)]
 pub enum MemcacheProtocol {
     Other = 0,
@@ -1918,6 +2819,16 @@ pub enum MemcacheProtocol {
     Binary = 2,
 }
 
+impl std::fmt::Display for MemcacheProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MemcacheProtocol::Other => write!(f, "OTHER"),
+            MemcacheProtocol::Ascii => write!(f, "ASCII"),
+            MemcacheProtocol::Binary => write!(f, "BINARY"),
+        }
+    }
+}
+
 impl MemcacheProtocol {
     /// Convert from u32 value to MemcacheProtocol enum
     pub fn from_u32(value: u32) -> Self {
@@ -1956,6 +2867,7 @@ impl MemcacheProtocol {
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[repr(u32)]
 pub enum MemcacheCommand {
     Other = 0,
@@ -1977,6 +2889,30 @@ pub enum MemcacheCommand {
     Touch = 16,
 }
 
+impl std::fmt::Display for MemcacheCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MemcacheCommand::Other => write!(f, "OTHER"),
+            MemcacheCommand::Set => write!(f, "SET"),
+            MemcacheCommand::Add => write!(f, "ADD"),
+            MemcacheCommand::Replace => write!(f, "REPLACE"),
+            MemcacheCommand::Append => write!(f, "APPEND"),
+            MemcacheCommand::Prepend => write!(f, "PREPEND"),
+            MemcacheCommand::Cas => write!(f, "CAS"),
+            MemcacheCommand::Get => write!(f, "GET"),
+            MemcacheCommand::Gets => write!(f, "GETS"),
+            MemcacheCommand::Incr => write!(f, "INCR"),
+            MemcacheCommand::Decr => write!(f, "DECR"),
+            MemcacheCommand::Delete => write!(f, "DELETE"),
+            MemcacheCommand::Stats => write!(f, "STATS"),
+            MemcacheCommand::Flush => write!(f, "FLUSH"),
+            MemcacheCommand::Version => write!(f, "VERSION"),
+            MemcacheCommand::Quit => write!(f, "QUIT"),
+            MemcacheCommand::Touch => write!(f, "TOUCH"),
+        }
+    }
+}
+
 impl MemcacheCommand {
     /// Convert from u32 value to MemcacheCommand enum
     pub fn from_u32(value: u32) -> Self {
@@ -2022,6 +2958,7 @@ impl MemcacheCommand {
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[repr(u32)]
 pub enum MemcacheStatus {
     Unknown = 0,
@@ -2036,6 +2973,23 @@ pub enum MemcacheStatus {
     Deleted = 9,
 }
 
+impl std::fmt::Display for MemcacheStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MemcacheStatus::Unknown => write!(f, "UNKNOWN"),
+            MemcacheStatus::Ok => write!(f, "OK"),
+            MemcacheStatus::Error => write!(f, "ERROR"),
+            MemcacheStatus::ClientError => write!(f, "CLIENT_ERROR"),
+            MemcacheStatus::ServerError => write!(f, "SERVER_ERROR"),
+            MemcacheStatus::Stored => write!(f, "STORED"),
+            MemcacheStatus::NotStored => write!(f, "NOT_STORED"),
+            MemcacheStatus::Exists => write!(f, "EXISTS"),
+            MemcacheStatus::NotFound => write!(f, "NOT_FOUND"),
+            MemcacheStatus::Deleted => write!(f, "DELETED"),
+        }
+    }
+}
+
 impl MemcacheStatus {
     /// Convert from u32 value to MemcacheStatus enum
     pub fn from_u32(value: u32) -> Self {
@@ -2078,6 +3032,7 @@ impl MemcacheStatus {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MemcacheOperation {
     /// Protocol (ASCII or Binary)
     pub protocol: MemcacheProtocol,
@@ -2124,6 +3079,7 @@ pub struct MemcacheOperation {
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[repr(u32)]
 pub enum HttpMethod {
     Other = 0,
@@ -2199,6 +3155,7 @@ impl std::fmt::Display for HttpMethod {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct HttpRequestDeprecated {
     /// HTTP method
     pub method: HttpMethod,
@@ -2258,6 +3215,7 @@ pub struct HttpRequestDeprecated {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AppOperation {
     /// Operation context
     pub context: AppContext,
@@ -2294,6 +3252,7 @@ pub struct AppOperation {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AppParentContext {
     /// Parent operation context
     pub context: AppContext,
@@ -2319,6 +3278,7 @@ pub struct AppParentContext {
 /// which is inconsistent with XDR syntax conventions. The corrected version is shown above.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AppInitiator {
     /// Business level identifier (e.g., customer id, vendor id)
     pub actor: String,
@@ -2344,6 +3304,7 @@ pub struct AppInitiator {
 /// which is inconsistent with XDR syntax conventions. The corrected version is shown above.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AppTarget {
     /// Business level identifier (e.g., customer id, vendor id)
     pub actor: String,
@@ -2383,6 +3344,7 @@ pub struct AppTarget {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct HttpRequest {
     /// HTTP method
     pub method: HttpMethod,
@@ -2424,10 +3386,37 @@ pub struct HttpRequest {
     pub status: i32,
 }
 
+/// Normalize a deprecated HTTP request (0,2201) to the current form
+///
+/// The deprecated record has no protocol version, so `protocol` is 0
+/// (unknown).
+impl From<HttpRequestDeprecated> for HttpRequest {
+    fn from(request: HttpRequestDeprecated) -> Self {
+        Self {
+            method: request.method,
+            protocol: 0,
+            uri: request.uri,
+            host: request.host,
+            referer: request.referer,
+            useragent: request.useragent,
+            xff: request.xff,
+            authuser: request.authuser,
+            mime_type: request.mime_type,
+            req_bytes: request.req_bytes,
+            resp_bytes: request.resp_bytes,
+            duration_us: request.duration_us,
+            status: request.status,
+        }
+    }
+}
+
 /// Extended Proxy Request - Format (0,2207)
 ///
 /// Rewritten URI for proxy requests
 ///
+/// Agents only attach this record when the proxy rewrote the host or URI:
+/// a flow sample without it was forwarded unmodified.
+///
 /// # XDR Definition ([sFlow HTTP](https://sflow.org/sflow_http.txt))
 ///
 /// ```text
@@ -2441,6 +3430,7 @@ pub struct HttpRequest {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedProxyRequest {
     /// URI in request to downstream server
     pub uri: String,
@@ -2449,6 +3439,16 @@ pub struct ExtendedProxyRequest {
     pub host: String,
 }
 
+impl ExtendedProxyRequest {
+    /// Whether the record reports a rewrite (either field non-empty)
+    ///
+    /// Some agents emit the record with both fields empty, which carries no
+    /// more information than its absence.
+    pub fn is_modified(&self) -> bool {
+        !self.uri.is_empty() || !self.host.is_empty()
+    }
+}
+
 /// Extended Nav Timing - Format (0,2208)
 ///
 /// Navigation timing information from web browsers
@@ -2491,6 +3491,7 @@ pub struct ExtendedProxyRequest {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedNavTiming {
     /// Navigation type (PerformanceNavigation)
     pub nav_type: u32,
@@ -2543,12 +3544,23 @@ pub struct ExtendedNavTiming {
 /// Packet direction for TCP info
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum PacketDirection {
     Unknown = 0,
     Received = 1,
     Sent = 2,
 }
 
+impl std::fmt::Display for PacketDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PacketDirection::Unknown => write!(f, "unknown"),
+            PacketDirection::Received => write!(f, "received"),
+            PacketDirection::Sent => write!(f, "sent"),
+        }
+    }
+}
+
 impl PacketDirection {
     /// Convert from u32 value to PacketDirection enum
     pub fn from_u32(value: u32) -> Self {
@@ -2587,6 +3599,7 @@ impl PacketDirection {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedTcpInfo {
     /// Sampled packet direction
     pub dir: PacketDirection,
@@ -2633,6 +3646,7 @@ pub struct ExtendedTcpInfo {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedEntities {
     /// Data source associated with packet source
     pub src_ds: crate::models::core::DataSourceExpanded,
@@ -2655,6 +3669,7 @@ pub struct ExtendedEntities {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ExtendedBstEgressQueue {
     /// Egress queue number selected for sampled packet
     pub queue: u32,
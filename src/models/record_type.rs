@@ -0,0 +1,117 @@
+//! Typed lookup of flow and counter records
+//!
+//! [`FlowRecordType`] and [`CounterRecordType`] tie each record struct to its
+//! data format and to the [`FlowData`] / [`CounterData`] variant holding it,
+//! so samples can be searched by Rust type with
+//! [`FlowSample::records`](crate::models::FlowSample::records) and
+//! [`CountersSample::records`](crate::models::CountersSample::records).
+//!
+//! The implementations are generated from the record parser tables, so a
+//! format added to the parser is also searchable by type.
+
+use crate::models::core::{CounterData, DataFormat, FlowData};
+
+/// A flow record struct carried by a [`FlowData`] variant
+pub trait FlowRecordType: Sized + 'static {
+    /// Data format (enterprise, format) of the record
+    const DATA_FORMAT: DataFormat;
+
+    /// The record held by `data`, if it is of this type
+    fn from_flow_data(data: &FlowData) -> Option<&Self>;
+}
+
+/// A counter record struct carried by a [`CounterData`] variant
+pub trait CounterRecordType: Sized + 'static {
+    /// Data format (enterprise, format) of the record
+    const DATA_FORMAT: DataFormat;
+
+    /// The record held by `data`, if it is of this type
+    fn from_counter_data(data: &CounterData) -> Option<&Self>;
+}
+
+/// Implement [`FlowRecordType`] for each record struct, and the
+/// [`FlowData::data_format`] and [`FlowData::name`] lookups
+///
+/// Invoked by the flow record parser table, which lists every supported
+/// format once.
+macro_rules! flow_record_types {
+    ($($variant:ident($ty:ident) => ($enterprise:literal, $format:literal),)*) => {
+        $(
+            impl $crate::models::FlowRecordType for $crate::models::record_flows::$ty {
+                const DATA_FORMAT: $crate::models::DataFormat = $crate::models::DataFormat::new($enterprise, $format);
+
+                fn from_flow_data(data: &$crate::models::FlowData) -> Option<&Self> {
+                    match data {
+                        $crate::models::FlowData::$variant(record) => Some(record),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+
+        impl $crate::models::FlowData {
+            /// Data format (enterprise, format) of the record
+            ///
+            /// For an unknown record this is the format it was received with.
+            pub fn data_format(&self) -> $crate::models::DataFormat {
+                match self {
+                    $(Self::$variant(_) => $crate::models::DataFormat::new($enterprise, $format),)*
+                    Self::Unknown { format, .. } => *format,
+                }
+            }
+
+            /// Name of the variant holding the record, e.g. `"SampledIpv4"`
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant(_) => stringify!($variant),)*
+                    Self::Unknown { .. } => "Unknown",
+                }
+            }
+        }
+    };
+}
+
+/// Implement [`CounterRecordType`] for each record struct, and the
+/// [`CounterData::data_format`] and [`CounterData::name`] lookups
+///
+/// Invoked by the counter record parser table, which lists every supported
+/// format once.
+macro_rules! counter_record_types {
+    ($($variant:ident($ty:ident) => ($enterprise:literal, $format:literal),)*) => {
+        $(
+            impl $crate::models::CounterRecordType for $crate::models::record_counters::$ty {
+                const DATA_FORMAT: $crate::models::DataFormat = $crate::models::DataFormat::new($enterprise, $format);
+
+                fn from_counter_data(data: &$crate::models::CounterData) -> Option<&Self> {
+                    match data {
+                        $crate::models::CounterData::$variant(record) => Some(record),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+
+        impl $crate::models::CounterData {
+            /// Data format (enterprise, format) of the record
+            ///
+            /// For an unknown record this is the format it was received with.
+            pub fn data_format(&self) -> $crate::models::DataFormat {
+                match self {
+                    $(Self::$variant(_) => $crate::models::DataFormat::new($enterprise, $format),)*
+                    Self::Unknown { format, .. } => *format,
+                }
+            }
+
+            /// Name of the variant holding the record, e.g. `"GenericInterface"`
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant(_) => stringify!($variant),)*
+                    Self::Unknown { .. } => "Unknown",
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use counter_record_types;
+pub(crate) use flow_record_types;
@@ -3,11 +3,72 @@
 //! This module contains top-level parsing functions for sFlow datagrams and samples.
 
 use super::error::{ParseError, Result};
-use super::Parser;
+use super::{ParseOptions, ParseScratch, ParseWarning, Parser};
+use crate::models::core::inner_header_offset;
 use crate::models::*;
 use std::io::{self, Cursor, Read};
 
+/// Kind of records held by a sample
+pub(super) enum SampleRecords {
+    Flow,
+    Counter,
+}
+
 impl<R: Read> Parser<R> {
+    /// Read the fields of a sample preceding its records array
+    ///
+    /// Returns the kind of records that follow, or `None` for samples
+    /// without records (nothing is read then).
+    pub(super) fn skip_sample_fields(
+        &mut self,
+        sample_type: DataFormat,
+    ) -> Result<Option<SampleRecords>> {
+        if sample_type.enterprise() != 0 {
+            return Ok(None);
+        }
+        match sample_type.format() {
+            1 => {
+                self.read_u32()?; // sequence_number
+                self.parse_data_source()?;
+                for _ in 0..3 {
+                    self.read_u32()?; // sampling_rate, sample_pool, drops
+                }
+                self.parse_interface()?;
+                self.parse_interface()?;
+                Ok(Some(SampleRecords::Flow))
+            }
+            2 => {
+                self.read_u32()?; // sequence_number
+                self.parse_data_source()?;
+                Ok(Some(SampleRecords::Counter))
+            }
+            3 => {
+                self.read_u32()?; // sequence_number
+                self.parse_data_source_expanded()?;
+                for _ in 0..3 {
+                    self.read_u32()?; // sampling_rate, sample_pool, drops
+                }
+                self.parse_interface_expanded()?;
+                self.parse_interface_expanded()?;
+                Ok(Some(SampleRecords::Flow))
+            }
+            4 => {
+                self.read_u32()?; // sequence_number
+                self.parse_data_source_expanded()?;
+                Ok(Some(SampleRecords::Counter))
+            }
+            5 => {
+                self.read_u32()?; // sequence_number
+                self.parse_data_source_expanded()?;
+                for _ in 0..4 {
+                    self.read_u32()?; // drops, input, output, reason
+                }
+                Ok(Some(SampleRecords::Flow))
+            }
+            _ => Ok(None),
+        }
+    }
+
     /// Parse a compact flow sample
     pub(super) fn parse_flow_sample(&mut self) -> Result<FlowSample> {
         let sequence_number = self.read_u32()?;
@@ -26,6 +87,7 @@ impl<R: Read> Parser<R> {
         for _ in 0..num_records {
             flow_records.push(self.parse_flow_record()?);
         }
+        self.check_inner_header_offset(&flow_records);
 
         Ok(FlowSample {
             sequence_number,
@@ -39,6 +101,29 @@ impl<R: Read> Parser<R> {
         })
     }
 
+    /// Warn when a decapsulate record points past the sampled header
+    ///
+    /// The inner header offset is relative to the header bytes of the
+    /// Sampled Header (0,1) record; without one there is nothing to check.
+    fn check_inner_header_offset(&mut self, flow_records: &[FlowRecord]) {
+        let header_length = flow_records
+            .iter()
+            .find_map(|record| match &record.flow_data {
+                FlowData::SampledHeader(h) => Some(h.header.len() as u32),
+                _ => None,
+            });
+        if let (Some(offset), Some(header_length)) =
+            (inner_header_offset(flow_records), header_length)
+        {
+            if offset >= header_length {
+                self.warn(ParseWarning::InnerHeaderOutOfBounds {
+                    offset,
+                    header_length,
+                });
+            }
+        }
+    }
+
     /// Parse a compact counter sample
     pub(super) fn parse_counters_sample(&mut self) -> Result<CountersSample> {
         let sequence_number = self.read_u32()?;
@@ -78,6 +163,7 @@ impl<R: Read> Parser<R> {
         for _ in 0..num_records {
             flow_records.push(self.parse_flow_record()?);
         }
+        self.check_inner_header_offset(&flow_records);
 
         Ok(FlowSampleExpanded {
             sequence_number,
@@ -133,6 +219,7 @@ impl<R: Read> Parser<R> {
         for _ in 0..num_records {
             flow_records.push(self.parse_flow_record()?);
         }
+        self.check_inner_header_offset(&flow_records);
 
         Ok(DiscardedPacket {
             sequence_number,
@@ -147,51 +234,77 @@ impl<R: Read> Parser<R> {
 
     /// Parse sample data based on format
     fn parse_sample_data(&mut self, format: DataFormat, data: Vec<u8>) -> Result<SampleData> {
-        let mut cursor = Cursor::new(data.clone());
-        let mut parser = Parser::new(&mut cursor);
-
-        // Standard sFlow formats (enterprise = 0)
-        if format.enterprise() == 0 {
-            match format.format() {
-                1 => {
-                    let sample = parser.parse_flow_sample()?;
-                    Ok(SampleData::FlowSample(sample))
-                }
-                2 => {
-                    let sample = parser.parse_counters_sample()?;
-                    Ok(SampleData::CountersSample(sample))
-                }
-                3 => {
-                    let sample = parser.parse_flow_sample_expanded()?;
-                    Ok(SampleData::FlowSampleExpanded(sample))
-                }
-                4 => {
-                    let sample = parser.parse_counters_sample_expanded()?;
-                    Ok(SampleData::CountersSampleExpanded(sample))
-                }
-                5 => {
-                    let sample = parser.parse_discarded_packet()?;
-                    Ok(SampleData::DiscardedPacket(sample))
-                }
-                _ => Ok(SampleData::Unknown { format, data }),
-            }
-        } else if format.enterprise() == 4300 {
+        let length = data.len();
+        trace_span!("sample", format, length);
+
+        // Samples that are not decoded keep their bytes
+        match (format.enterprise(), format.format()) {
+            (0, 1..=5) => {}
             // sFlow-RT (InMon Corp) formats
-            match format.format() {
-                1002 => Ok(SampleData::RtMetric { format, data }),
-                1003 => Ok(SampleData::RtFlow { format, data }),
-                _ => Ok(SampleData::Unknown { format, data }),
+            (4300, 1002) => return Ok(SampleData::RtMetric { format, data }),
+            (4300, 1003) => return Ok(SampleData::RtFlow { format, data }),
+            _ => {
+                self.warn(ParseWarning::UnknownSampleFormat(format));
+                return Ok(SampleData::Unknown { format, data });
+            }
+        }
+
+        let mut parser = self.nested(Cursor::new(data.as_slice()));
+        let result = parser.decode_sample_data(format);
+        let reader = self.end_nested(parser);
+        let sample_data = result?;
+        self.check_consumed(format, length, reader.position())?;
+        Ok(sample_data)
+    }
+
+    /// Decode a standard sample (enterprise 0, formats 1 to 5) from this
+    /// parser's reader
+    fn decode_sample_data(&mut self, format: DataFormat) -> Result<SampleData> {
+        match format.format() {
+            1 => {
+                let sample = self.parse_flow_sample()?;
+                Ok(SampleData::FlowSample(sample))
+            }
+            2 => {
+                let sample = self.parse_counters_sample()?;
+                Ok(SampleData::CountersSample(sample))
+            }
+            3 => {
+                let sample = self.parse_flow_sample_expanded()?;
+                Ok(SampleData::FlowSampleExpanded(sample))
             }
-        } else {
-            // Other vendor-specific formats
-            Ok(SampleData::Unknown { format, data })
+            4 => {
+                let sample = self.parse_counters_sample_expanded()?;
+                Ok(SampleData::CountersSampleExpanded(sample))
+            }
+            5 => {
+                let sample = self.parse_discarded_packet()?;
+                Ok(SampleData::DiscardedPacket(sample))
+            }
+            _ => unreachable!("format {} is not a standard sample", format),
         }
     }
 
     /// Parse a sample record
     fn parse_sample_record(&mut self) -> Result<SampleRecord> {
         let sample_type = self.parse_data_format()?;
-        let sample_data_raw = self.read_opaque()?;
+
+        // Flow samples (compact or expanded) can be skipped by their length
+        if self.options.skip_flow_samples
+            && sample_type.enterprise() == 0
+            && matches!(sample_type.format(), 1 | 3)
+        {
+            let length = self.skip_opaque()?;
+            return Ok(SampleRecord {
+                sample_type,
+                sample_data: SampleData::Skipped {
+                    format: sample_type,
+                    length,
+                },
+            });
+        }
+
+        let sample_data_raw = self.read_envelope()?;
         let sample_data = self.parse_sample_data(sample_type, sample_data_raw)?;
 
         Ok(SampleRecord {
@@ -200,8 +313,13 @@ impl<R: Read> Parser<R> {
         })
     }
 
-    /// Parse an sFlow v5 datagram
-    pub fn parse_datagram(&mut self) -> Result<SFlowDatagram> {
+    /// Parse an sFlow v5 datagram header
+    ///
+    /// Only the header is read, the reader is left at the first sample.
+    pub fn parse_datagram_header(&mut self) -> Result<DatagramHeader> {
+        // A new datagram starts a new record budget
+        self.records = 0;
+
         // Parse version
         let version = self.read_u32()?;
         if version != 5 {
@@ -223,32 +341,254 @@ impl<R: Read> Parser<R> {
         // Parse uptime
         let uptime = self.read_u32()?;
 
-        // Parse samples array
+        // Parse samples array length
         let num_samples = self.read_u32()?;
-        // Limit capacity to prevent OOM attacks - allocate conservatively
-        let capacity = num_samples.min(1024) as usize;
-        let mut samples = Vec::with_capacity(capacity);
-        for _ in 0..num_samples {
-            samples.push(self.parse_sample_record()?);
-        }
 
-        Ok(SFlowDatagram {
+        Ok(DatagramHeader {
             version: DatagramVersion::Version5,
             agent_address,
             sub_agent_id,
             sequence_number,
             uptime,
+            num_samples,
+        })
+    }
+
+    /// Parse an sFlow v5 datagram
+    pub fn parse_datagram(&mut self) -> Result<SFlowDatagram> {
+        let header = self.parse_datagram_header()?;
+
+        // Parse samples array
+        // Limit capacity to prevent OOM attacks - allocate conservatively
+        let capacity = header.num_samples.min(1024) as usize;
+        let mut samples = Vec::with_capacity(capacity);
+        for _ in 0..header.num_samples {
+            samples.push(self.parse_sample_record()?);
+        }
+
+        Ok(SFlowDatagram {
+            version: header.version,
+            agent_address: header.agent_address,
+            sub_agent_id: header.sub_agent_id,
+            sequence_number: header.sequence_number,
+            uptime: header.uptime,
             samples,
         })
     }
 }
 
+/// Length of the datagram header with an unknown (empty) agent address
+const MIN_DATAGRAM_HEADER_LEN: usize = 24;
+
+/// Check that the datagram header and the declared sample lengths fit in the buffer
+///
+/// An input shorter than its header is reported as [`ParseError::TooShort`].
+/// The sample envelopes are then walked without decoding them, so a
+/// corrupted length is reported as [`ParseError::DatagramTruncated`] before
+/// any record is parsed, and a buffer ending cleanly after fewer samples than
+/// declared as [`ParseError::MissingSamples`]. The walk stops at the end of
+/// the buffer whatever the declared count. Other header errors are reported
+/// as by the parser. With `ParseOptions::strict_trailing_bytes`, bytes left
+/// after the last sample are an error.
+pub(super) fn check_lengths(data: &[u8], options: &ParseOptions) -> Result<()> {
+    // The header length depends on the agent address type, when present.
+    // An invalid address type is left for the parser to report.
+    let need = match data.get(4..8) {
+        None | Some([0, 0, 0, 0]) => MIN_DATAGRAM_HEADER_LEN,
+        Some([0, 0, 0, 1]) => MIN_DATAGRAM_HEADER_LEN + 4,
+        Some([0, 0, 0, 2]) => MIN_DATAGRAM_HEADER_LEN + 16,
+        Some(_) => 0,
+    };
+    if data.len() < need {
+        return Err(ParseError::TooShort {
+            need,
+            got: data.len(),
+        });
+    }
+
+    let mut parser = Parser::new(Cursor::new(data));
+    let header = parser.parse_datagram_header()?;
+
+    let available = data.len();
+    for present in 0..header.num_samples {
+        // Sample format and length
+        let offset = parser.reader.position() as usize;
+        if offset == available {
+            return Err(ParseError::MissingSamples {
+                declared: header.num_samples,
+                present,
+            });
+        }
+        if offset + 8 > available {
+            return Err(ParseError::DatagramTruncated {
+                expected: offset + 8,
+                available,
+            });
+        }
+        parser.read_u32()?;
+        let length = parser.read_u32()? as usize;
+
+        // The length is untrusted: past usize::MAX it cannot fit either
+        let end = length
+            .div_ceil(4)
+            .checked_mul(4)
+            .and_then(|padded| padded.checked_add(offset + 8));
+        let end = match end {
+            Some(end) if end <= available => end,
+            end => {
+                return Err(ParseError::DatagramTruncated {
+                    expected: end.unwrap_or(usize::MAX),
+                    available,
+                })
+            }
+        };
+        parser.reader.set_position(end as u64);
+    }
+
+    let end = parser.reader.position() as usize;
+    if options.strict_trailing_bytes && end < available {
+        return Err(ParseError::InvalidData(format!(
+            "{} trailing bytes after the last sample",
+            available - end
+        )));
+    }
+    Ok(())
+}
+
+/// Check whether a received datagram looks cut short, e.g. by a lost IP fragment
+///
+/// The header and the declared sample lengths are compared with the
+/// length of `data`, without decoding any record. Returns `true` when they
+/// describe more bytes than received, see [`ParseError::TooShort`],
+/// [`ParseError::DatagramTruncated`] and [`ParseError::MissingSamples`].
+/// Malformed datagrams that are not truncated return `false`.
+pub fn looks_truncated(data: &[u8]) -> bool {
+    matches!(
+        check_lengths(data, &ParseOptions::default()),
+        Err(ParseError::TooShort { .. }
+            | ParseError::DatagramTruncated { .. }
+            | ParseError::MissingSamples { .. })
+    )
+}
+
+/// Byte order suggested by the version word of a datagram
+///
+/// See [`diagnose_byte_order`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrderDiagnosis {
+    /// Version 5 in network byte order, as the specification requires
+    BigEndian,
+    /// Version 5 in little-endian order: the capture was likely written
+    /// with its 32-bit words byte-swapped
+    LittleEndian,
+    /// Not an sFlow v5 version word in either byte order (or fewer than 4 bytes)
+    Unrecognized,
+}
+
+impl std::fmt::Display for ByteOrderDiagnosis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ByteOrderDiagnosis::BigEndian => write!(f, "big-endian sFlow v5"),
+            ByteOrderDiagnosis::LittleEndian => write!(
+                f,
+                "little-endian version 5, try byte-swapping each 32-bit word"
+            ),
+            ByteOrderDiagnosis::Unrecognized => write!(f, "not an sFlow v5 version word"),
+        }
+    }
+}
+
+/// Guess the byte order of a datagram from its version word
+///
+/// sFlow is always big-endian; this read-only heuristic helps tell a capture
+/// written by a buggy exporter (version word `05 00 00 00`) from data that is
+/// not sFlow v5 at all. Nothing past the first 4 bytes is looked at.
+pub fn diagnose_byte_order(data: &[u8]) -> ByteOrderDiagnosis {
+    let Some(word) = data.first_chunk::<4>() else {
+        return ByteOrderDiagnosis::Unrecognized;
+    };
+    if u32::from_be_bytes(*word) == 5 {
+        ByteOrderDiagnosis::BigEndian
+    } else if u32::from_le_bytes(*word) == 5 {
+        ByteOrderDiagnosis::LittleEndian
+    } else {
+        ByteOrderDiagnosis::Unrecognized
+    }
+}
+
 /// Parse an sFlow v5 datagram from a byte slice
+///
+/// The header and the declared sample lengths are checked against the slice
+/// length before decoding, see [`ParseError::TooShort`],
+/// [`ParseError::DatagramTruncated`] and [`ParseError::MissingSamples`].
 pub fn parse_datagram(data: &[u8]) -> Result<SFlowDatagram> {
+    check_lengths(data, &ParseOptions::default())?;
     let mut parser = Parser::new(Cursor::new(data));
     parser.parse_datagram()
 }
 
+/// Parse an sFlow v5 datagram from a byte slice with the given options
+pub fn parse_datagram_with_options(data: &[u8], options: &ParseOptions) -> Result<SFlowDatagram> {
+    check_lengths(data, options)?;
+    let mut parser = Parser::with_options(Cursor::new(data), options.clone());
+    parser.parse_datagram()
+}
+
+/// Parse an sFlow v5 datagram from a byte slice, reusing the buffers of `scratch`
+///
+/// Parsing is identical to [`parse_datagram_with_options`]; the record
+/// buffers are taken from `scratch` and given back once the datagram is
+/// parsed, so a collector passing the same scratch for every datagram
+/// avoids reallocating them.
+pub fn parse_datagram_with_scratch(
+    data: &[u8],
+    options: &ParseOptions,
+    scratch: &mut ParseScratch,
+) -> Result<SFlowDatagram> {
+    check_lengths(data, options)?;
+    let mut parser = Parser::with_options(Cursor::new(data), options.clone());
+    parser.scratch = std::mem::take(&mut scratch.records);
+    let result = parser.parse_datagram();
+    scratch.records = parser.scratch;
+    result
+}
+
+/// Parse an sFlow v5 datagram, also returning the non-fatal issues found
+///
+/// Parsing is identical to [`parse_datagram_with_options`], the returned
+/// warnings describe data that was tolerated rather than rejected (unknown
+/// formats and enumerants, records longer than their decoded content).
+pub fn parse_datagram_verbose(
+    data: &[u8],
+    options: &ParseOptions,
+) -> Result<(SFlowDatagram, Vec<ParseWarning>)> {
+    check_lengths(data, options)?;
+    let mut parser = Parser::with_options(Cursor::new(data), options.clone());
+    let datagram = parser.parse_datagram()?;
+    Ok((datagram, parser.warnings))
+}
+
+/// Parse only the header of an sFlow v5 datagram
+///
+/// Reads the version, agent address, sub-agent id, sequence number, uptime
+/// and number of samples, without looking at the samples. Useful to route
+/// datagrams by agent before decoding them.
+pub fn parse_header(data: &[u8]) -> Result<DatagramHeader> {
+    Parser::new(Cursor::new(data)).parse_datagram_header()
+}
+
+/// Parse only the counter samples of an sFlow v5 datagram
+///
+/// Flow samples are skipped using their declared length and returned as
+/// `SampleData::Skipped`, avoiding the cost of decoding their records.
+pub fn parse_counters_only(data: &[u8]) -> Result<SFlowDatagram> {
+    let options = ParseOptions {
+        skip_flow_samples: true,
+        ..Default::default()
+    };
+    parse_datagram_with_options(data, &options)
+}
+
 /// Parse multiple sFlow v5 datagrams from a byte slice
 /// This is useful when multiple datagrams are concatenated (like in our test file)
 pub fn parse_datagrams(data: &[u8]) -> Result<Vec<SFlowDatagram>> {
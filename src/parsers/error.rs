@@ -1,5 +1,6 @@
 //! Parser error types
 
+use crate::models::DataFormat;
 use std::fmt;
 use std::io;
 
@@ -10,6 +11,47 @@ pub enum ParseError {
     Io(io::Error),
     /// Invalid data format
     InvalidData(String),
+    /// String field is not valid UTF-8
+    Utf8(std::string::FromUtf8Error),
+    /// Flow or counter record length exceeds `ParseOptions::max_record_bytes`
+    RecordTooLarge {
+        /// Format of the offending record
+        format: DataFormat,
+        /// Declared record length in bytes
+        length: u32,
+    },
+    /// Datagram holds more records than `ParseOptions::max_total_records`
+    TooManyRecords {
+        /// Maximum number of records per datagram
+        limit: usize,
+    },
+    /// Padding of an opaque or string field is not zero, with
+    /// `ParseOptions::verify_padding`
+    NonZeroPadding {
+        /// Length of the field before padding
+        length: usize,
+    },
+    /// Input shorter than the datagram header
+    TooShort {
+        /// Bytes needed for the datagram header
+        need: usize,
+        /// Bytes available in the input
+        got: usize,
+    },
+    /// Declared sample lengths run past the end of the datagram buffer
+    DatagramTruncated {
+        /// Bytes needed up to the end of the first sample that does not fit
+        expected: usize,
+        /// Bytes available in the buffer
+        available: usize,
+    },
+    /// Datagram buffer ends before the declared number of samples
+    MissingSamples {
+        /// Number of samples declared in the datagram header
+        declared: u32,
+        /// Number of complete samples present in the buffer
+        present: u32,
+    },
 }
 
 impl fmt::Display for ParseError {
@@ -17,11 +59,55 @@ impl fmt::Display for ParseError {
         match self {
             ParseError::Io(e) => write!(f, "I/O error: {}", e),
             ParseError::InvalidData(msg) => write!(f, "Invalid data: {}", msg),
+            ParseError::Utf8(e) => write!(f, "Invalid UTF-8 string: {}", e),
+            ParseError::RecordTooLarge { format, length } => write!(
+                f,
+                "Record too large: format ({},{}) declares {} bytes",
+                format.enterprise(),
+                format.format(),
+                length
+            ),
+            ParseError::TooManyRecords { limit } => write!(
+                f,
+                "Too many records: datagram exceeds the limit of {} records",
+                limit
+            ),
+            ParseError::NonZeroPadding { length } => write!(
+                f,
+                "Non-zero XDR padding after {} bytes of opaque data",
+                length
+            ),
+            ParseError::TooShort { need, got } => write!(
+                f,
+                "Input too short: datagram header needs {} bytes, got {}",
+                need, got
+            ),
+            ParseError::DatagramTruncated {
+                expected,
+                available,
+            } => write!(
+                f,
+                "Datagram truncated: samples need {} bytes, {} available",
+                expected, available
+            ),
+            ParseError::MissingSamples { declared, present } => write!(
+                f,
+                "Missing samples: datagram declares {} samples, only {} present",
+                declared, present
+            ),
         }
     }
 }
 
-impl std::error::Error for ParseError {}
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Io(e) => Some(e),
+            ParseError::Utf8(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
 impl From<io::Error> for ParseError {
     fn from(err: io::Error) -> Self {
@@ -29,9 +115,28 @@ impl From<io::Error> for ParseError {
     }
 }
 
+/// Convert to an I/O error, e.g. to use `?` in I/O code
+///
+/// I/O errors are returned unchanged, so running out of input stays
+/// `UnexpectedEof`. Truncated inputs are reported as `UnexpectedEof` too,
+/// all other errors as `InvalidData` with the error message.
+impl From<ParseError> for io::Error {
+    fn from(err: ParseError) -> Self {
+        match err {
+            ParseError::Io(err) => err,
+            ParseError::TooShort { .. }
+            | ParseError::DatagramTruncated { .. }
+            | ParseError::MissingSamples { .. } => {
+                io::Error::new(io::ErrorKind::UnexpectedEof, err.to_string())
+            }
+            err => io::Error::new(io::ErrorKind::InvalidData, err.to_string()),
+        }
+    }
+}
+
 impl From<std::string::FromUtf8Error> for ParseError {
     fn from(err: std::string::FromUtf8Error) -> Self {
-        ParseError::InvalidData(format!("Invalid UTF-8 string: {}", err))
+        ParseError::Utf8(err)
     }
 }
 
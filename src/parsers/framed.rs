@@ -0,0 +1,112 @@
+//! Length-prefixed datagram streams
+//!
+//! sFlow is carried over UDP, one datagram per packet. Some relay products
+//! forward it over TCP instead, writing each datagram prefixed with its
+//! length as a big-endian u32. This framing is not part of the sFlow
+//! specification; [`TcpFramedDecoder`] only exists to interoperate with
+//! such relays.
+
+use super::datagram::parse_datagram_with_options;
+use super::error::{ParseError, Result};
+use super::ParseOptions;
+use crate::models::SFlowDatagram;
+use std::io::{self, Read};
+
+/// Largest accepted frame: the maximum UDP payload an agent can send
+pub const MAX_FRAME_LEN: usize = 65_535;
+
+/// Iterator over the datagrams of a length-prefixed stream
+///
+/// Each frame is a big-endian u32 length followed by that many bytes of
+/// sFlow datagram. The stream may end cleanly between two frames, which ends
+/// the iteration. A datagram that fails to parse is reported and iteration
+/// goes on with the next frame; a truncated frame or a length above
+/// [`MAX_FRAME_LEN`] loses the framing and is the last item returned.
+///
+/// ```no_run
+/// use sflow_parser::parsers::TcpFramedDecoder;
+/// use std::net::TcpStream;
+///
+/// let stream = TcpStream::connect("relay.example.net:6343")?;
+/// for datagram in TcpFramedDecoder::new(stream) {
+///     println!("{}", datagram?);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct TcpFramedDecoder<R: Read> {
+    reader: R,
+    options: ParseOptions,
+    frame: Vec<u8>,
+    failed: bool,
+}
+
+impl<R: Read> TcpFramedDecoder<R> {
+    /// Create a decoder reading frames from `reader`
+    pub fn new(reader: R) -> Self {
+        Self::with_options(reader, ParseOptions::default())
+    }
+
+    /// Create a decoder parsing each datagram with the given options
+    pub fn with_options(reader: R, options: ParseOptions) -> Self {
+        Self {
+            reader,
+            options,
+            frame: Vec::new(),
+            failed: false,
+        }
+    }
+
+    /// Give back the underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Read the next frame into `self.frame`, `Ok(false)` at a clean end of stream
+    fn read_frame(&mut self) -> Result<bool> {
+        let mut prefix = [0u8; 4];
+        let mut read = 0;
+        while read < prefix.len() {
+            match self.reader.read(&mut prefix[read..]) {
+                Ok(0) if read == 0 => return Ok(false),
+                Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+                Ok(n) => read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let length = u32::from_be_bytes(prefix) as usize;
+        if length > MAX_FRAME_LEN {
+            return Err(ParseError::InvalidData(format!(
+                "Frame length {} exceeds maximum {}",
+                length, MAX_FRAME_LEN
+            )));
+        }
+        self.frame.clear();
+        (&mut self.reader)
+            .take(length as u64)
+            .read_to_end(&mut self.frame)?;
+        if self.frame.len() != length {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        Ok(true)
+    }
+}
+
+impl<R: Read> Iterator for TcpFramedDecoder<R> {
+    type Item = Result<SFlowDatagram>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        match self.read_frame() {
+            Ok(true) => Some(parse_datagram_with_options(&self.frame, &self.options)),
+            Ok(false) => None,
+            Err(e) => {
+                self.failed = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Lazy record iteration
+//!
+//! [`lazy_flow_records`] and [`lazy_counter_records`] walk the records of a
+//! single sample from its raw bytes, decoding each record only when the
+//! iterator reaches it. Tools that only look at the first records of large
+//! samples do not pay for decoding the rest.
+
+use super::datagram::SampleRecords;
+use super::error::{ParseError, Result};
+use super::Parser;
+use crate::models::*;
+use std::io::Cursor;
+
+/// Parser over the body of a sample
+type SampleParser<'a> = Parser<Cursor<&'a [u8]>>;
+
+/// Iterator decoding the flow records of a sample on demand
+///
+/// Yields `Err` once if a record fails to decode, then stops.
+pub struct LazyFlowRecords<'a> {
+    parser: SampleParser<'a>,
+    remaining: u32,
+}
+
+/// Iterator decoding the counter records of a sample on demand
+///
+/// Yields `Err` once if a record fails to decode, then stops.
+pub struct LazyCounterRecords<'a> {
+    parser: SampleParser<'a>,
+    remaining: u32,
+}
+
+/// Position a parser on the records array of a sample record
+///
+/// `sample` is a sample record as found in a datagram: sample type, length
+/// and body. Returns the kind of records and their declared count.
+fn open_sample(sample: &[u8]) -> Result<(SampleParser<'_>, SampleRecords, u32)> {
+    let mut header = Parser::new(Cursor::new(sample));
+    let sample_type = header.parse_data_format()?;
+    let length = header.read_u32()? as usize;
+    let body = sample
+        .get(8..8 + length)
+        .ok_or(ParseError::DatagramTruncated {
+            expected: 8 + length,
+            available: sample.len(),
+        })?;
+
+    let mut parser = Parser::new(Cursor::new(body));
+    let Some(records) = parser.skip_sample_fields(sample_type)? else {
+        return Err(ParseError::InvalidData(format!(
+            "Sample format ({},{}) holds no flow or counter records",
+            sample_type.enterprise(),
+            sample_type.format()
+        )));
+    };
+    let count = parser.read_u32()?;
+    Ok((parser, records, count))
+}
+
+/// Iterate lazily over the flow records of a flow or discarded packet sample
+///
+/// `sample` holds the sample record bytes: sample type, length and body.
+/// Only the sample fields are read up front; each record is decoded when
+/// the iterator reaches it.
+pub fn lazy_flow_records(sample: &[u8]) -> Result<LazyFlowRecords<'_>> {
+    match open_sample(sample)? {
+        (parser, SampleRecords::Flow, remaining) => Ok(LazyFlowRecords { parser, remaining }),
+        _ => Err(ParseError::InvalidData(
+            "Counter sample holds no flow records".to_string(),
+        )),
+    }
+}
+
+/// Iterate lazily over the counter records of a counter sample
+///
+/// `sample` holds the sample record bytes: sample type, length and body.
+/// Only the sample fields are read up front; each record is decoded when
+/// the iterator reaches it.
+pub fn lazy_counter_records(sample: &[u8]) -> Result<LazyCounterRecords<'_>> {
+    match open_sample(sample)? {
+        (parser, SampleRecords::Counter, remaining) => Ok(LazyCounterRecords { parser, remaining }),
+        _ => Err(ParseError::InvalidData(
+            "Flow sample holds no counter records".to_string(),
+        )),
+    }
+}
+
+impl Iterator for LazyFlowRecords<'_> {
+    type Item = Result<FlowRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let record = self.parser.parse_flow_record();
+        self.remaining = if record.is_ok() {
+            self.remaining - 1
+        } else {
+            0
+        };
+        Some(record)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining as usize))
+    }
+}
+
+impl Iterator for LazyCounterRecords<'_> {
+    type Item = Result<CounterRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let record = self.parser.parse_counter_record();
+        self.remaining = if record.is_ok() {
+            self.remaining - 1
+        } else {
+            0
+        };
+        Some(record)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining as usize))
+    }
+}
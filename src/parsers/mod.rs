@@ -3,28 +3,237 @@
 //! This module provides parsing functionality for sFlow v5 datagrams.
 //! All data is in network byte order (big-endian) as per XDR specification.
 
+/// Build a table of record parsers
+///
+/// Each `(enterprise, format) => Variant(Type) = parse_fn` entry maps a data
+/// format to the record variant holding a `Type` struct and to a function
+/// decoding the record body into it. Entries must be sorted by data format.
+///
+/// The same entries are passed to `$types` (`flow_record_types!` or
+/// `counter_record_types!` from [`crate::models::record_type`]) so the typed
+/// record lookup is generated from this table.
+macro_rules! record_parsers {
+    (
+        $(#[$attr:meta])*
+        $vis:vis const $name:ident: $data:ident => $types:ident {
+            $(($enterprise:literal, $format:literal) => $variant:ident($ty:ident) = $parse:ident,)*
+        }
+    ) => {
+        $(#[$attr])*
+        $vis const $name: &RecordParsers<$data> = &[$((
+            DataFormat::new($enterprise, $format),
+            stringify!($variant),
+            |parser| Ok($data::$variant(parser.$parse()?)),
+        ),)*];
+
+        $types! {
+            $($variant($ty) => ($enterprise, $format),)*
+        }
+    };
+}
+
+/// Enter a `tracing` span named `$name` until the end of the scope
+///
+/// The span carries the data format and byte length of the sample or record
+/// being decoded. Without the `trace` feature this expands to nothing.
+macro_rules! trace_span {
+    ($name:literal, $format:expr, $length:expr) => {
+        #[cfg(feature = "trace")]
+        let _span = tracing::trace_span!($name, format = %$format, length = $length).entered();
+    };
+}
+
 mod datagram;
 mod error;
+mod framed;
+mod lazy;
+mod options;
 mod parser_counters;
 mod parser_flows;
+mod scratch;
+mod visitor;
+mod warning;
 
 // Re-export public types
-pub use datagram::{parse_datagram, parse_datagrams};
+pub use datagram::{
+    diagnose_byte_order, looks_truncated, parse_counters_only, parse_datagram,
+    parse_datagram_verbose, parse_datagram_with_options, parse_datagram_with_scratch,
+    parse_datagrams, parse_header, ByteOrderDiagnosis,
+};
 pub use error::{ParseError, Result};
+pub use framed::{TcpFramedDecoder, MAX_FRAME_LEN};
+pub use lazy::{lazy_counter_records, lazy_flow_records, LazyCounterRecords, LazyFlowRecords};
+pub use options::{ParseOptions, UnknownRecordCallback};
+pub use scratch::ParseScratch;
+pub use visitor::{parse_datagram_visit, DatagramVisitor};
+pub use warning::ParseWarning;
 
 use crate::models::*;
-use std::io::Read;
+use std::io::{self, Cursor, Read};
 use std::net::{Ipv4Addr, Ipv6Addr};
 
+/// Upper bound on any length-prefixed allocation (100MB)
+const MAX_OPAQUE_SIZE: usize = ParseOptions::DEFAULT_MAX_OPAQUE_BYTES;
+
+/// Initial reservation for length-prefixed data (64KB, the largest UDP payload)
+const OPAQUE_CAPACITY_HINT: usize = 64 * 1024;
+
+/// Decoder of a flow or counter record body, run on a parser over the record bytes
+type RecordParser<T> = fn(&mut Parser<Cursor<Vec<u8>>>) -> Result<T>;
+
+/// Record parsers with their data format and variant name, sorted by data format
+type RecordParsers<T> = [(DataFormat, &'static str, RecordParser<T>)];
+
+/// Whether a parser table is sorted by data format without duplicates,
+/// as required by [`find_record_parser`]; checked at compile time
+const fn is_sorted_by_format<T>(table: &RecordParsers<T>) -> bool {
+    let mut index = 1;
+    while index < table.len() {
+        if table[index - 1].0 .0 >= table[index].0 .0 {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+/// Parser of the record with the given format, if it is supported
+fn find_record_parser<T>(table: &RecordParsers<T>, format: DataFormat) -> Option<RecordParser<T>> {
+    table
+        .binary_search_by_key(&format.0, |(entry, _, _)| entry.0)
+        .ok()
+        .map(|index| table[index].2)
+}
+
+/// Flow record formats decoded by the parser, with the name of their
+/// [`FlowData`] variant, sorted by data format
+///
+/// Formats missing from this list are returned as `FlowData::Unknown`.
+pub fn supported_flow_formats() -> impl Iterator<Item = (DataFormat, &'static str)> {
+    parser_flows::FLOW_PARSERS
+        .iter()
+        .map(|(format, name, _)| (*format, *name))
+}
+
+/// Counter record formats decoded by the parser, with the name of their
+/// [`CounterData`] variant, sorted by data format
+///
+/// Formats missing from this list are returned as `CounterData::Unknown`.
+pub fn supported_counter_formats() -> impl Iterator<Item = (DataFormat, &'static str)> {
+    parser_counters::COUNTER_PARSERS
+        .iter()
+        .map(|(format, name, _)| (*format, *name))
+}
+
 /// Parser for sFlow v5 datagrams
 pub struct Parser<R: Read> {
     reader: R,
+    options: ParseOptions,
+    warnings: Vec<ParseWarning>,
+    /// Spare record buffers, reused from record to record
+    scratch: Vec<Vec<u8>>,
+    /// Records decoded so far in the current datagram, nested ones included
+    records: usize,
 }
 
 impl<R: Read> Parser<R> {
     /// Create a new parser from a reader
     pub fn new(reader: R) -> Self {
-        Self { reader }
+        Self::with_options(reader, ParseOptions::default())
+    }
+
+    /// Create a new parser from a reader with the given options
+    pub fn with_options(reader: R, options: ParseOptions) -> Self {
+        Self {
+            reader,
+            options,
+            warnings: Vec::new(),
+            scratch: Vec::new(),
+            records: 0,
+        }
+    }
+
+    /// Record a non-fatal parsing issue
+    pub(crate) fn warn(&mut self, warning: ParseWarning) {
+        self.warnings.push(warning);
+    }
+
+    /// Nested parser over `reader`, e.g. the body of a sample or record
+    ///
+    /// The options and scratch buffers are lent to the nested parser, take
+    /// them back with [`end_nested`](Self::end_nested).
+    pub(crate) fn nested<N: Read>(&mut self, reader: N) -> Parser<N> {
+        Parser {
+            reader,
+            options: std::mem::take(&mut self.options),
+            warnings: Vec::new(),
+            scratch: std::mem::take(&mut self.scratch),
+            records: self.records,
+        }
+    }
+
+    /// Take back what [`nested`](Self::nested) lent, with the warnings and
+    /// record count of the nested parser, and return its reader
+    pub(crate) fn end_nested<N: Read>(&mut self, mut nested: Parser<N>) -> N {
+        self.options = nested.options;
+        self.scratch = nested.scratch;
+        self.records = nested.records;
+        self.warnings.append(&mut nested.warnings);
+        nested.reader
+    }
+
+    /// Nested parser over the body of a flow or counter record
+    ///
+    /// Agents disagree on whether the declared record length covers the XDR
+    /// padding of the last field. The padding was already skipped after the
+    /// body, so it is put back (as zeros) for the decoder: a record whose
+    /// length excludes it decodes the same as one that includes it. Returns
+    /// the parser and the declared length.
+    pub(crate) fn record_parser(&mut self, mut data: Vec<u8>) -> (Parser<Cursor<Vec<u8>>>, usize) {
+        let length = data.len();
+        data.resize(length.div_ceil(4) * 4, 0);
+        (self.nested(Cursor::new(data)), length)
+    }
+
+    /// Pass a record returned as `Unknown` to `ParseOptions::on_unknown`
+    pub(crate) fn report_unknown(&self, format: DataFormat, data: &[u8]) {
+        if let Some(callback) = &self.options.on_unknown {
+            callback.call(format, data);
+        }
+    }
+
+    /// Count a decoded record against `ParseOptions::max_total_records`
+    pub(crate) fn count_record(&mut self) -> Result<()> {
+        self.records += 1;
+        if self.records > self.options.max_total_records {
+            return Err(ParseError::TooManyRecords {
+                limit: self.options.max_total_records,
+            });
+        }
+        Ok(())
+    }
+
+    /// Warn if a decoder did not consume the whole declared length
+    ///
+    /// With `ParseOptions::strict_lengths` the mismatch is an error instead.
+    pub(crate) fn check_consumed(
+        &mut self,
+        format: DataFormat,
+        declared: usize,
+        consumed: u64,
+    ) -> Result<()> {
+        if consumed < declared as u64 {
+            let warning = ParseWarning::LengthMismatch {
+                format,
+                declared: declared as u32,
+                consumed: consumed as u32,
+            };
+            if self.options.strict_lengths {
+                return Err(ParseError::InvalidData(warning.to_string()));
+            }
+            self.warn(warning);
+        }
+        Ok(())
     }
 
     /// Read a u32 in network byte order (big-endian)
@@ -63,30 +272,121 @@ impl<R: Read> Parser<R> {
     }
 
     /// Read an opaque byte array (length-prefixed)
+    ///
+    /// The length is bounded by `ParseOptions::max_opaque_bytes`.
     pub(crate) fn read_opaque(&mut self) -> Result<Vec<u8>> {
+        self.read_opaque_bounded(self.options.max_opaque_bytes)
+    }
+
+    /// Read the opaque body of a sample envelope
+    ///
+    /// Samples are bounded by the fixed 100MB sanity limit rather than
+    /// `max_opaque_bytes`, which applies to the fields inside records.
+    pub(crate) fn read_envelope(&mut self) -> Result<Vec<u8>> {
+        self.read_opaque_bounded(MAX_OPAQUE_SIZE)
+    }
+
+    /// Read the opaque body of a flow or counter record
+    ///
+    /// The declared length is checked against `ParseOptions::max_record_bytes`
+    /// and, with `ParseOptions::strict_record_padding`, must be padded.
+    ///
+    /// The bytes are read into one of the parser's scratch buffers, which
+    /// decoded records hand back to be reused by the next record.
+    pub(crate) fn read_record_envelope(&mut self, format: DataFormat) -> Result<Vec<u8>> {
+        let length = self.read_u32()?;
+        if length as usize > self.options.max_record_bytes {
+            return Err(ParseError::RecordTooLarge { format, length });
+        }
+        if self.options.strict_record_padding && length % 4 != 0 {
+            return Err(ParseError::InvalidData(format!(
+                "Format ({},{}) declares {} bytes, excluding the XDR padding",
+                format.enterprise(),
+                format.format(),
+                length
+            )));
+        }
+        let mut data = self.scratch.pop().unwrap_or_default();
+        data.clear();
+        self.read_opaque_body_into(length as usize, &mut data)?;
+        Ok(data)
+    }
+
+    /// Read the element count of an array inside a flow or counter record
+    ///
+    /// `element_size` is the smallest encoding of one element in bytes. A
+    /// count whose elements could not fit in `ParseOptions::max_record_bytes`
+    /// is rejected before anything is reserved for it.
+    pub(crate) fn read_array_len(&mut self, element_size: usize) -> Result<u32> {
+        let count = self.read_u32()?;
+        let min_bytes = (count as usize).saturating_mul(element_size);
+        if min_bytes > self.options.max_record_bytes {
+            return Err(ParseError::InvalidData(format!(
+                "Array of {} elements exceeds the record limit of {} bytes",
+                count, self.options.max_record_bytes
+            )));
+        }
+        Ok(count)
+    }
+
+    fn read_opaque_bounded(&mut self, max_length: usize) -> Result<Vec<u8>> {
         let length = self.read_u32()? as usize;
 
-        // Sanity check: reject unreasonably large allocations (> 100MB)
+        // Sanity check: reject unreasonably large allocations
         // Valid sFlow packets are typically much smaller
-        const MAX_OPAQUE_SIZE: usize = 100 * 1024 * 1024; // 100MB
-        if length > MAX_OPAQUE_SIZE {
+        if length > max_length {
             return Err(ParseError::InvalidData(format!(
                 "Opaque data length {} exceeds maximum {}",
-                length, MAX_OPAQUE_SIZE
+                length, max_length
             )));
         }
 
-        let mut data = vec![0u8; length];
-        self.reader.read_exact(&mut data)?;
+        self.read_opaque_body(length)
+    }
+
+    fn read_opaque_body(&mut self, length: usize) -> Result<Vec<u8>> {
+        // Grow with the bytes actually present rather than trusting the
+        // declared length for the initial reservation
+        let mut data = Vec::with_capacity(length.min(OPAQUE_CAPACITY_HINT));
+        self.read_opaque_body_into(length, &mut data)?;
+        Ok(data)
+    }
+
+    /// Read an opaque body of `length` bytes and its padding into an empty buffer
+    fn read_opaque_body_into(&mut self, length: usize, data: &mut Vec<u8>) -> Result<()> {
+        (&mut self.reader).take(length as u64).read_to_end(data)?;
+        if data.len() != length {
+            return Err(ParseError::Io(io::Error::from(
+                io::ErrorKind::UnexpectedEof,
+            )));
+        }
 
         // XDR requires padding to 4-byte boundary
         let padding = (4 - (length % 4)) % 4;
         if padding > 0 {
-            let mut pad = vec![0u8; padding];
-            self.reader.read_exact(&mut pad)?;
+            let mut pad = [0u8; 3];
+            self.reader.read_exact(&mut pad[..padding])?;
+            if self.options.verify_padding && pad != [0; 3] {
+                return Err(ParseError::NonZeroPadding { length });
+            }
         }
 
-        Ok(data)
+        Ok(())
+    }
+
+    /// Skip an opaque byte array (length-prefixed) without allocating
+    ///
+    /// Returns the declared length.
+    pub(crate) fn skip_opaque(&mut self) -> Result<u32> {
+        let length = self.read_u32()?;
+        let padded = (length as u64).div_ceil(4) * 4;
+        let skipped = io::copy(&mut (&mut self.reader).take(padded), &mut io::sink())?;
+        if skipped != padded {
+            return Err(ParseError::Io(io::Error::from(
+                io::ErrorKind::UnexpectedEof,
+            )));
+        }
+        Ok(length)
     }
 
     /// Read a fixed-size byte array
@@ -0,0 +1,182 @@
+//! Parser options
+//!
+//! Options controlling how strictly the parser applies the sFlow v5 specification.
+
+use crate::models::DataFormat;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Callback receiving the format and bytes of each unknown record
+type UnknownRecordFn = dyn FnMut(&DataFormat, &[u8]) + Send;
+
+/// Shared handle to a [`ParseOptions::on_unknown`] callback
+///
+/// Clones of the options call the same closure. Two handles are equal when
+/// they share the closure.
+#[derive(Clone)]
+pub struct UnknownRecordCallback(Arc<Mutex<Box<UnknownRecordFn>>>);
+
+impl UnknownRecordCallback {
+    /// Wrap a callback
+    pub fn new(callback: Box<UnknownRecordFn>) -> Self {
+        Self(Arc::new(Mutex::new(callback)))
+    }
+
+    /// Invoke the callback
+    pub(crate) fn call(&self, format: DataFormat, data: &[u8]) {
+        let mut callback = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        callback(&format, data);
+    }
+}
+
+impl fmt::Debug for UnknownRecordCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("UnknownRecordCallback")
+    }
+}
+
+impl PartialEq for UnknownRecordCallback {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for UnknownRecordCallback {}
+
+/// Options for parsing sFlow v5 datagrams
+///
+/// The default options are lenient: values that the specification allows
+/// receivers to ignore (such as unknown enumerants) are preserved instead of
+/// failing the whole datagram.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Reject enumerated values that are not defined by the specification
+    /// (e.g. an unknown header protocol) instead of storing them as `Unknown`
+    pub strict_enums: bool,
+
+    /// Reject samples and records whose decoded content is shorter than
+    /// their declared length instead of reporting
+    /// [`ParseWarning::LengthMismatch`](super::ParseWarning::LengthMismatch)
+    pub strict_lengths: bool,
+
+    /// Reject opaque and string fields whose XDR padding bytes are not zero,
+    /// as RFC 4506 requires, returning
+    /// [`ParseError::NonZeroPadding`](super::ParseError::NonZeroPadding)
+    /// instead of discarding the padding unread
+    pub verify_padding: bool,
+
+    /// Reject datagrams holding bytes after the last declared sample,
+    /// returning [`ParseError::InvalidData`](super::ParseError::InvalidData),
+    /// instead of ignoring them
+    pub strict_trailing_bytes: bool,
+
+    /// Reject flow and counter records whose declared length is not a
+    /// multiple of 4, i.e. leaves out the XDR padding of the last field,
+    /// returning [`ParseError::InvalidData`](super::ParseError::InvalidData).
+    /// By default such records are decoded as if the padding was counted.
+    pub strict_record_padding: bool,
+
+    /// Maximum length accepted for variable-length opaque or string fields
+    /// inside records (e.g. MPLS tunnel and VC names), checked before
+    /// allocating. The spec leaves these fields unbounded. Also bounds the
+    /// sample bodies walked by [`parse_datagram_visit`](super::parse_datagram_visit).
+    pub max_opaque_bytes: usize,
+
+    /// Maximum length accepted for a single flow or counter record, checked
+    /// against the record's length field before reading it. Exceeding it
+    /// returns [`ParseError::RecordTooLarge`](super::ParseError::RecordTooLarge).
+    pub max_record_bytes: usize,
+
+    /// Maximum number of flow and counter records decoded in one datagram,
+    /// counting the records nested in other records (e.g. the PDUs of an
+    /// 802.11 aggregation). Exceeding it returns
+    /// [`ParseError::TooManyRecords`](super::ParseError::TooManyRecords).
+    pub max_total_records: usize,
+
+    /// Skip flow samples (compact and expanded) using their declared length
+    /// instead of decoding their records. Skipped samples are returned as
+    /// `SampleData::Skipped`.
+    pub skip_flow_samples: bool,
+
+    /// Keep flow and counter records that fail to decode as `Unknown`
+    /// (with their raw bytes) and continue with the next record, reporting
+    /// [`ParseWarning::MalformedRecord`](super::ParseWarning::MalformedRecord),
+    /// instead of failing the whole datagram. Records are skipped using their
+    /// declared length, so a corrupted length still fails the datagram.
+    pub recover_bad_records: bool,
+
+    /// Called with the format and bytes of every flow or counter record
+    /// returned as `Unknown`: records of unsupported formats, and records
+    /// that failed to decode with `recover_bad_records`. Set it with
+    /// [`ParseOptions::on_unknown`].
+    pub on_unknown: Option<UnknownRecordCallback>,
+}
+
+impl ParseOptions {
+    /// Default maximum opaque length (100MB)
+    pub const DEFAULT_MAX_OPAQUE_BYTES: usize = 100 * 1024 * 1024;
+
+    /// Default maximum flow or counter record length (65535 bytes)
+    pub const DEFAULT_MAX_RECORD_BYTES: usize = 65535;
+
+    /// Default maximum number of records per datagram (8192), one per 8
+    /// bytes (the smallest record) of a 64KB datagram
+    pub const DEFAULT_MAX_TOTAL_RECORDS: usize = 8192;
+
+    /// Create the default (lenient) parse options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create options enabling every strictness check
+    ///
+    /// Intended for conformance testing of agents: any deviation that the
+    /// default options tolerate becomes an error. Size limits keep their
+    /// default values.
+    pub fn strict() -> Self {
+        Self {
+            strict_enums: true,
+            strict_lengths: true,
+            verify_padding: true,
+            strict_trailing_bytes: true,
+            strict_record_padding: true,
+            ..Self::default()
+        }
+    }
+
+    /// Call `callback` for each unknown or undecodable record
+    ///
+    /// A lighter alternative to the warnings of
+    /// [`parse_datagram_verbose`](super::parse_datagram_verbose) when only
+    /// the records the parser could not decode are of interest:
+    ///
+    /// ```
+    /// use sflow_parser::parsers::ParseOptions;
+    ///
+    /// let options = ParseOptions::new().on_unknown(Box::new(|format, data| {
+    ///     eprintln!("unknown record {} ({} bytes)", format, data.len());
+    /// }));
+    /// ```
+    pub fn on_unknown(mut self, callback: Box<UnknownRecordFn>) -> Self {
+        self.on_unknown = Some(UnknownRecordCallback::new(callback));
+        self
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            strict_enums: false,
+            strict_lengths: false,
+            verify_padding: false,
+            strict_trailing_bytes: false,
+            strict_record_padding: false,
+            max_opaque_bytes: Self::DEFAULT_MAX_OPAQUE_BYTES,
+            max_record_bytes: Self::DEFAULT_MAX_RECORD_BYTES,
+            max_total_records: Self::DEFAULT_MAX_TOTAL_RECORDS,
+            skip_flow_samples: false,
+            recover_bad_records: false,
+            on_unknown: None,
+        }
+    }
+}
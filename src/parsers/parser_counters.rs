@@ -2,10 +2,11 @@
 //!
 //! This module contains all parsing functions for sFlow counter records.
 
-use super::error::Result;
-use super::Parser;
+use super::error::{ParseError, Result};
+use super::{find_record_parser, is_sorted_by_format, ParseWarning, Parser, RecordParsers};
+use crate::models::record_type::counter_record_types;
 use crate::models::*;
-use std::io::{Cursor, Read};
+use std::io::Read;
 
 impl<R: Read> Parser<R> {
     /// Parse Generic Interface Counters - Format (0,1)
@@ -170,13 +171,15 @@ impl<R: Read> Parser<R> {
     pub(super) fn parse_lag_port_stats(
         &mut self,
     ) -> Result<crate::models::record_counters::LagPortStats> {
-        // Read actor system ID (MAC address - 6 bytes)
+        // Read actor and partner system IDs: `opaque mac[6]` is padded to
+        // 8 bytes in XDR, matching the 56 byte record emitted by agents
         let mut actor_system_id = [0u8; 6];
         self.reader.read_exact(&mut actor_system_id)?;
+        self.read_fixed(2)?;
 
-        // Read partner operational system ID (MAC address - 6 bytes)
         let mut partner_oper_system_id = [0u8; 6];
         self.reader.read_exact(&mut partner_oper_system_id)?;
+        self.read_fixed(2)?;
 
         // Read attached aggregator ID
         let dot3ad_agg_port_attached_agg_id = self.read_u32()?;
@@ -265,7 +268,7 @@ impl<R: Read> Parser<R> {
         let module_temperature = self.read_i32()?;
 
         // Parse variable-length array of lanes
-        let num_lanes = self.read_u32()?;
+        let num_lanes = self.read_array_len(40)?;
         let capacity_lanes = num_lanes.min(1024) as usize;
         let mut lanes = Vec::with_capacity(capacity_lanes);
         for _ in 0..num_lanes {
@@ -369,13 +372,13 @@ impl<R: Read> Parser<R> {
     pub(super) fn parse_host_adapters(
         &mut self,
     ) -> Result<crate::models::record_counters::HostAdapters> {
-        let num_adapters = self.read_u32()?;
+        let num_adapters = self.read_array_len(8)?;
         let capacity_adapters = num_adapters.min(1024) as usize;
         let mut adapters = Vec::with_capacity(capacity_adapters);
 
         for _ in 0..num_adapters {
             let if_index = self.read_u32()?;
-            let num_macs = self.read_u32()?;
+            let num_macs = self.read_array_len(8)?;
             let capacity_macs = num_macs.min(1024) as usize;
             let mut mac_addresses = Vec::with_capacity(capacity_macs);
 
@@ -909,14 +912,14 @@ impl<R: Read> Parser<R> {
         let egress_mc_pc = self.read_i32()?;
 
         // Read variable-length arrays for egress queue percentages
-        let uc_count = self.read_u32()?;
+        let uc_count = self.read_array_len(4)?;
         let capacity_uc = uc_count.min(1024) as usize;
         let mut egress_queue_uc_pc = Vec::with_capacity(capacity_uc);
         for _ in 0..uc_count {
             egress_queue_uc_pc.push(self.read_i32()?);
         }
 
-        let mc_count = self.read_u32()?;
+        let mc_count = self.read_array_len(4)?;
         let capacity_mc = mc_count.min(1024) as usize;
         let mut egress_queue_mc_pc = Vec::with_capacity(capacity_mc);
         for _ in 0..mc_count {
@@ -999,109 +1002,54 @@ impl<R: Read> Parser<R> {
         format: DataFormat,
         data: Vec<u8>,
     ) -> Result<CounterData> {
-        let mut cursor = Cursor::new(data.clone());
-        let mut parser = Parser::new(&mut cursor);
-
-        // Standard sFlow formats (enterprise = 0)
-        if format.enterprise() == 0 {
-            match format.format() {
-                1 => Ok(CounterData::GenericInterface(
-                    parser.parse_generic_interface_counters()?,
-                )),
-                2 => Ok(CounterData::EthernetInterface(
-                    parser.parse_ethernet_interface_counters()?,
-                )),
-                3 => Ok(CounterData::TokenRing(parser.parse_token_ring_counters()?)),
-                4 => Ok(CounterData::Vg100Interface(
-                    parser.parse_vg100_interface_counters()?,
-                )),
-                5 => Ok(CounterData::Vlan(parser.parse_vlan_counters()?)),
-                6 => Ok(CounterData::Ieee80211(parser.parse_ieee80211_counters()?)),
-                7 => Ok(CounterData::LagPortStats(parser.parse_lag_port_stats()?)),
-                8 => Ok(CounterData::SlowPathCounts(
-                    parser.parse_slow_path_counts()?,
-                )),
-                9 => Ok(CounterData::InfiniBandCounters(
-                    parser.parse_infiniband_counters()?,
-                )),
-                10 => Ok(CounterData::OpticalSfpQsfp(
-                    parser.parse_optical_sfp_qsfp()?,
-                )),
-                1001 => Ok(CounterData::Processor(parser.parse_processor_counters()?)),
-                1002 => Ok(CounterData::RadioUtilization(
-                    parser.parse_radio_utilization()?,
-                )),
-                1003 => Ok(CounterData::QueueLength(parser.parse_queue_length()?)),
-                1004 => Ok(CounterData::OpenFlowPort(parser.parse_openflow_port()?)),
-                1005 => Ok(CounterData::OpenFlowPortName(
-                    parser.parse_openflow_port_name()?,
-                )),
-                2000 => Ok(CounterData::HostDescription(
-                    parser.parse_host_description()?,
-                )),
-                2001 => Ok(CounterData::HostAdapters(parser.parse_host_adapters()?)),
-                2002 => Ok(CounterData::HostParent(parser.parse_host_parent()?)),
-                2003 => Ok(CounterData::HostCpu(parser.parse_host_cpu()?)),
-                2004 => Ok(CounterData::HostMemory(parser.parse_host_memory()?)),
-                2005 => Ok(CounterData::HostDiskIo(parser.parse_host_disk_io()?)),
-                2006 => Ok(CounterData::HostNetIo(parser.parse_host_net_io()?)),
-                2007 => Ok(CounterData::Mib2IpGroup(parser.parse_mib2_ip_group()?)),
-                2008 => Ok(CounterData::Mib2IcmpGroup(parser.parse_mib2_icmp_group()?)),
-                2009 => Ok(CounterData::Mib2TcpGroup(parser.parse_mib2_tcp_group()?)),
-                2010 => Ok(CounterData::Mib2UdpGroup(parser.parse_mib2_udp_group()?)),
-                2100 => Ok(CounterData::VirtualNode(parser.parse_virtual_node()?)),
-                2101 => Ok(CounterData::VirtualCpu(parser.parse_virtual_cpu()?)),
-                2102 => Ok(CounterData::VirtualMemory(parser.parse_virtual_memory()?)),
-                2103 => Ok(CounterData::VirtualDiskIo(parser.parse_virtual_disk_io()?)),
-                2104 => Ok(CounterData::VirtualNetIo(parser.parse_virtual_net_io()?)),
-                2105 => Ok(CounterData::JvmRuntime(parser.parse_jvm_runtime()?)),
-                2106 => Ok(CounterData::JvmStatistics(parser.parse_jvm_statistics()?)),
-                // DEPRECATED
-                2200 => Ok(CounterData::MemcacheCountersDeprecated(
-                    parser.parse_memcache_counters_deprecated()?,
-                )),
-                2201 => Ok(CounterData::HttpCounters(parser.parse_http_counters()?)),
-                2202 => Ok(CounterData::AppOperations(parser.parse_app_operations()?)),
-                2203 => Ok(CounterData::AppResources(parser.parse_app_resources()?)),
-                2204 => Ok(CounterData::MemcacheCounters(
-                    parser.parse_memcache_counters()?,
-                )),
-                2206 => Ok(CounterData::AppWorkers(parser.parse_app_workers()?)),
-                2207 => Ok(CounterData::OvsDpStats(parser.parse_ovs_dp_stats()?)),
-                3000 => Ok(CounterData::Energy(parser.parse_energy()?)),
-                3001 => Ok(CounterData::Temperature(parser.parse_temperature()?)),
-                3002 => Ok(CounterData::Humidity(parser.parse_humidity()?)),
-                3003 => Ok(CounterData::Fans(parser.parse_fans()?)),
-                _ => Ok(CounterData::Unknown { format, data }),
+        trace_span!("counter_record", format, data.len());
+        self.count_record()?;
+        let Some(parse) = find_record_parser(COUNTER_PARSERS, format) else {
+            self.warn(ParseWarning::UnknownCounterFormat(format));
+            self.report_unknown(format, &data);
+            // Copy the bytes out to keep the scratch buffer for the next record
+            let unknown = CounterData::Unknown {
+                format,
+                data: data.clone(),
+            };
+            self.scratch.push(data);
+            return Ok(unknown);
+        };
+
+        let (mut parser, length) = self.record_parser(data);
+        let result = parse(&mut parser);
+        let reader = self.end_nested(parser);
+        let counter_data = match result {
+            // The record was read whole, so the datagram can go on without it
+            Err(e)
+                if self.options.recover_bad_records
+                    && !matches!(e, ParseError::TooManyRecords { .. }) =>
+            {
+                self.warn(ParseWarning::MalformedRecord {
+                    format,
+                    error: e.to_string(),
+                });
+                let data = reader.into_inner();
+                self.report_unknown(format, &data[..length]);
+                let unknown = CounterData::Unknown {
+                    format,
+                    data: data[..length].to_vec(),
+                };
+                self.scratch.push(data);
+                return Ok(unknown);
             }
-        } else if format.enterprise() == 4413 {
-            // Broadcom enterprise formats
-            match format.format() {
-                1 => Ok(CounterData::BroadcomDeviceBuffers(
-                    parser.parse_broadcom_device_buffers()?,
-                )),
-                2 => Ok(CounterData::BroadcomPortBuffers(
-                    parser.parse_broadcom_port_buffers()?,
-                )),
-                3 => Ok(CounterData::BroadcomTables(parser.parse_broadcom_tables()?)),
-                _ => Ok(CounterData::Unknown { format, data }),
-            }
-        } else if format.enterprise() == 5703 {
-            // NVIDIA enterprise formats
-            match format.format() {
-                1 => Ok(CounterData::NvidiaGpu(parser.parse_nvidia_gpu()?)),
-                _ => Ok(CounterData::Unknown { format, data }),
-            }
-        } else {
-            // Other vendor-specific formats
-            Ok(CounterData::Unknown { format, data })
-        }
+            result => result?,
+        };
+
+        self.check_consumed(format, length, reader.position())?;
+        self.scratch.push(reader.into_inner());
+        Ok(counter_data)
     }
 
     /// Parse a counter record
     pub(super) fn parse_counter_record(&mut self) -> Result<CounterRecord> {
         let counter_format = self.parse_data_format()?;
-        let counter_data_raw = self.read_opaque()?;
+        let counter_data_raw = self.read_record_envelope(counter_format)?;
         let counter_data = self.parse_counter_data(counter_format, counter_data_raw)?;
 
         Ok(CounterRecord {
@@ -1110,3 +1058,62 @@ impl<R: Read> Parser<R> {
         })
     }
 }
+
+record_parsers! {
+    /// Decoders of the supported counter record formats, sorted by data format
+    pub(super) const COUNTER_PARSERS: CounterData => counter_record_types {
+        (0, 1) => GenericInterface(GenericInterfaceCounters) = parse_generic_interface_counters,
+        (0, 2) => EthernetInterface(EthernetInterfaceCounters) = parse_ethernet_interface_counters,
+        (0, 3) => TokenRing(TokenRingCounters) = parse_token_ring_counters,
+        (0, 4) => Vg100Interface(Vg100InterfaceCounters) = parse_vg100_interface_counters,
+        (0, 5) => Vlan(VlanCounters) = parse_vlan_counters,
+        (0, 6) => Ieee80211(Ieee80211Counters) = parse_ieee80211_counters,
+        (0, 7) => LagPortStats(LagPortStats) = parse_lag_port_stats,
+        (0, 8) => SlowPathCounts(SlowPathCounts) = parse_slow_path_counts,
+        (0, 9) => InfiniBandCounters(InfiniBandCounters) = parse_infiniband_counters,
+        (0, 10) => OpticalSfpQsfp(OpticalSfpQsfp) = parse_optical_sfp_qsfp,
+        (0, 1001) => Processor(ProcessorCounters) = parse_processor_counters,
+        (0, 1002) => RadioUtilization(RadioUtilization) = parse_radio_utilization,
+        (0, 1003) => QueueLength(QueueLength) = parse_queue_length,
+        (0, 1004) => OpenFlowPort(OpenFlowPort) = parse_openflow_port,
+        (0, 1005) => OpenFlowPortName(OpenFlowPortName) = parse_openflow_port_name,
+        (0, 2000) => HostDescription(HostDescription) = parse_host_description,
+        (0, 2001) => HostAdapters(HostAdapters) = parse_host_adapters,
+        (0, 2002) => HostParent(HostParent) = parse_host_parent,
+        (0, 2003) => HostCpu(HostCpu) = parse_host_cpu,
+        (0, 2004) => HostMemory(HostMemory) = parse_host_memory,
+        (0, 2005) => HostDiskIo(HostDiskIo) = parse_host_disk_io,
+        (0, 2006) => HostNetIo(HostNetIo) = parse_host_net_io,
+        (0, 2007) => Mib2IpGroup(Mib2IpGroup) = parse_mib2_ip_group,
+        (0, 2008) => Mib2IcmpGroup(Mib2IcmpGroup) = parse_mib2_icmp_group,
+        (0, 2009) => Mib2TcpGroup(Mib2TcpGroup) = parse_mib2_tcp_group,
+        (0, 2010) => Mib2UdpGroup(Mib2UdpGroup) = parse_mib2_udp_group,
+        (0, 2100) => VirtualNode(VirtualNode) = parse_virtual_node,
+        (0, 2101) => VirtualCpu(VirtualCpu) = parse_virtual_cpu,
+        (0, 2102) => VirtualMemory(VirtualMemory) = parse_virtual_memory,
+        (0, 2103) => VirtualDiskIo(VirtualDiskIo) = parse_virtual_disk_io,
+        (0, 2104) => VirtualNetIo(VirtualNetIo) = parse_virtual_net_io,
+        (0, 2105) => JvmRuntime(JvmRuntime) = parse_jvm_runtime,
+        (0, 2106) => JvmStatistics(JvmStatistics) = parse_jvm_statistics,
+        // DEPRECATED
+        (0, 2200) => MemcacheCountersDeprecated(MemcacheCountersDeprecated) = parse_memcache_counters_deprecated,
+        (0, 2201) => HttpCounters(HttpCounters) = parse_http_counters,
+        (0, 2202) => AppOperations(AppOperations) = parse_app_operations,
+        (0, 2203) => AppResources(AppResources) = parse_app_resources,
+        (0, 2204) => MemcacheCounters(MemcacheCounters) = parse_memcache_counters,
+        (0, 2206) => AppWorkers(AppWorkers) = parse_app_workers,
+        (0, 2207) => OvsDpStats(OvsDpStats) = parse_ovs_dp_stats,
+        (0, 3000) => Energy(Energy) = parse_energy,
+        (0, 3001) => Temperature(Temperature) = parse_temperature,
+        (0, 3002) => Humidity(Humidity) = parse_humidity,
+        (0, 3003) => Fans(Fans) = parse_fans,
+        // Broadcom enterprise formats
+        (4413, 1) => BroadcomDeviceBuffers(BroadcomDeviceBuffers) = parse_broadcom_device_buffers,
+        (4413, 2) => BroadcomPortBuffers(BroadcomPortBuffers) = parse_broadcom_port_buffers,
+        (4413, 3) => BroadcomTables(BroadcomTables) = parse_broadcom_tables,
+        // NVIDIA enterprise formats
+        (5703, 1) => NvidiaGpu(NvidiaGpu) = parse_nvidia_gpu,
+    }
+}
+
+const _: () = assert!(is_sorted_by_format(COUNTER_PARSERS));
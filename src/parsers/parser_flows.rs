@@ -3,9 +3,10 @@
 //! This module contains all parsing functions for sFlow flow records.
 
 use super::error::{ParseError, Result};
-use super::Parser;
+use super::{find_record_parser, is_sorted_by_format, ParseWarning, Parser, RecordParsers};
+use crate::models::record_type::flow_record_types;
 use crate::models::*;
-use std::io::{Cursor, Read};
+use std::io::Read;
 use std::net::{Ipv4Addr, Ipv6Addr};
 
 impl<R: Read> Parser<R> {
@@ -14,12 +15,24 @@ impl<R: Read> Parser<R> {
         &mut self,
     ) -> Result<crate::models::record_flows::SampledHeader> {
         let protocol_value = self.read_u32()?;
-        let protocol = crate::models::record_flows::HeaderProtocol::from_u32(protocol_value)
-            .ok_or_else(|| {
-                ParseError::InvalidData(format!("Unknown header protocol: {}", protocol_value))
-            })?;
+        let protocol = match crate::models::record_flows::HeaderProtocol::from_u32(protocol_value) {
+            Some(protocol) => protocol,
+            None if self.options.strict_enums => {
+                return Err(ParseError::InvalidData(format!(
+                    "Unknown header protocol: {}",
+                    protocol_value
+                )))
+            }
+            // Receivers must tolerate unknown enumerants, keep the raw value
+            None => {
+                self.warn(ParseWarning::UnknownHeaderProtocol(protocol_value));
+                crate::models::record_flows::HeaderProtocol::Unknown(protocol_value)
+            }
+        };
         let frame_length = self.read_u32()?;
         let stripped = self.read_u32()?;
+        // opaque header<>: its length prefix is the number of captured bytes,
+        // there is no separate header_length field
         let header = self.read_opaque()?;
 
         Ok(crate::models::record_flows::SampledHeader {
@@ -155,12 +168,12 @@ impl<R: Read> Parser<R> {
         let src_peer_as = self.read_u32()?;
 
         // Parse AS path segments
-        let num_segments = self.read_u32()?;
+        let num_segments = self.read_array_len(8)?;
         let capacity_segments = num_segments.min(1024) as usize;
         let mut dst_as_path = Vec::with_capacity(capacity_segments);
         for _ in 0..num_segments {
             let path_type = self.read_u32()?.into();
-            let path_length = self.read_u32()?;
+            let path_length = self.read_array_len(4)?;
             let capacity_path = path_length.min(1024) as usize;
             let mut path = Vec::with_capacity(capacity_path);
             for _ in 0..path_length {
@@ -174,7 +187,7 @@ impl<R: Read> Parser<R> {
         }
 
         // Parse communities
-        let num_communities = self.read_u32()?;
+        let num_communities = self.read_array_len(4)?;
         let capacity_communities = num_communities.min(1024) as usize;
         let mut communities = Vec::with_capacity(capacity_communities);
         for _ in 0..num_communities {
@@ -183,6 +196,10 @@ impl<R: Read> Parser<R> {
 
         let local_pref = self.read_u32()?;
 
+        // Keep any vendor extension bytes left in the record
+        let mut trailing = Vec::new();
+        self.reader.read_to_end(&mut trailing)?;
+
         Ok(crate::models::record_flows::ExtendedGateway {
             next_hop,
             as_number,
@@ -191,6 +208,7 @@ impl<R: Read> Parser<R> {
             dst_as_path,
             communities,
             local_pref,
+            trailing,
         })
     }
 
@@ -232,14 +250,14 @@ impl<R: Read> Parser<R> {
     ) -> Result<crate::models::record_flows::ExtendedMpls> {
         let next_hop = self.parse_address()?;
 
-        let in_stack_len = self.read_u32()?;
+        let in_stack_len = self.read_array_len(4)?;
         let capacity_in = in_stack_len.min(1024) as usize;
         let mut in_stack = Vec::with_capacity(capacity_in);
         for _ in 0..in_stack_len {
             in_stack.push(self.read_u32()?);
         }
 
-        let out_stack_len = self.read_u32()?;
+        let out_stack_len = self.read_array_len(4)?;
         let capacity_out = out_stack_len.min(1024) as usize;
         let mut out_stack = Vec::with_capacity(capacity_out);
         for _ in 0..out_stack_len {
@@ -287,8 +305,10 @@ impl<R: Read> Parser<R> {
     ) -> Result<crate::models::record_flows::ExtendedMplsVc> {
         let vc_instance_name = self.read_string()?;
         let vll_vc_id = self.read_u32()?;
-        let vc_label = self.read_u32()?;
-        let vc_cos = self.read_u32()?;
+        // A single word on the wire: label in the high bits, COS in the low 3 bits
+        let vc_label_cos = self.read_u32()?;
+        let vc_label = vc_label_cos >> 3;
+        let vc_cos = vc_label_cos & 0x7;
 
         Ok(crate::models::record_flows::ExtendedMplsVc {
             vc_instance_name,
@@ -326,7 +346,7 @@ impl<R: Read> Parser<R> {
     pub(super) fn parse_extended_vlan_tunnel(
         &mut self,
     ) -> Result<crate::models::record_flows::ExtendedVlanTunnel> {
-        let num_vlans = self.read_u32()?;
+        let num_vlans = self.read_array_len(4)?;
         let capacity = num_vlans.min(1024) as usize;
         let mut stack = Vec::with_capacity(capacity);
         for _ in 0..num_vlans {
@@ -414,13 +434,14 @@ impl<R: Read> Parser<R> {
     pub(super) fn parse_extended_80211_aggregation(
         &mut self,
     ) -> Result<crate::models::record_flows::Extended80211Aggregation> {
-        let pdu_count = self.read_u32()?;
+        let pdu_count = self.read_array_len(4)?;
         let capacity = pdu_count.min(256) as usize; // Reasonable limit for PDUs
         let mut pdus = Vec::with_capacity(capacity);
 
         for _ in 0..pdu_count {
-            // Parse flow records for this PDU
-            let flow_record_count = self.read_u32()?;
+            // Each PDU is a flow_record array: an element count followed by
+            // records framed with their own data format and length
+            let flow_record_count = self.read_array_len(8)?;
             let flow_capacity = flow_record_count.min(64) as usize;
             let mut flow_records = Vec::with_capacity(flow_capacity);
 
@@ -639,7 +660,7 @@ impl<R: Read> Parser<R> {
     pub(super) fn parse_extended_vlan_in(
         &mut self,
     ) -> Result<crate::models::record_flows::ExtendedVlanIn> {
-        let num_vlans = self.read_u32()?;
+        let num_vlans = self.read_array_len(4)?;
         let capacity = num_vlans.min(1024) as usize;
         let mut stack = Vec::with_capacity(capacity);
         for _ in 0..num_vlans {
@@ -652,7 +673,7 @@ impl<R: Read> Parser<R> {
     pub(super) fn parse_extended_vlan_out(
         &mut self,
     ) -> Result<crate::models::record_flows::ExtendedVlanOut> {
-        let num_vlans = self.read_u32()?;
+        let num_vlans = self.read_array_len(4)?;
         let capacity = num_vlans.min(1024) as usize;
         let mut stack = Vec::with_capacity(capacity);
         for _ in 0..num_vlans {
@@ -1111,172 +1132,54 @@ impl<R: Read> Parser<R> {
         format: DataFormat,
         data: Vec<u8>,
     ) -> Result<FlowData> {
-        let mut cursor = Cursor::new(data.clone());
-        let mut parser = Parser::new(&mut cursor);
-
-        // Standard sFlow formats (enterprise = 0)
-        if format.enterprise() == 0 {
-            match format.format() {
-                1 => Ok(FlowData::SampledHeader(parser.parse_sampled_header()?)),
-                2 => Ok(FlowData::SampledEthernet(parser.parse_sampled_ethernet()?)),
-                3 => Ok(FlowData::SampledIpv4(parser.parse_sampled_ipv4()?)),
-                4 => Ok(FlowData::SampledIpv6(parser.parse_sampled_ipv6()?)),
-                1001 => Ok(FlowData::ExtendedSwitch(parser.parse_extended_switch()?)),
-                1002 => Ok(FlowData::ExtendedRouter(parser.parse_extended_router()?)),
-                1003 => Ok(FlowData::ExtendedGateway(parser.parse_extended_gateway()?)),
-                1004 => Ok(FlowData::ExtendedUser(parser.parse_extended_user()?)),
-                // Note: Format 1005 is deprecated but kept for backward compatibility
-                1005 => Ok(FlowData::ExtendedUrl(parser.parse_extended_url()?)),
-                1006 => Ok(FlowData::ExtendedMpls(parser.parse_extended_mpls()?)),
-                1007 => Ok(FlowData::ExtendedNat(parser.parse_extended_nat()?)),
-                1008 => Ok(FlowData::ExtendedMplsTunnel(
-                    parser.parse_extended_mpls_tunnel()?,
-                )),
-                1009 => Ok(FlowData::ExtendedMplsVc(parser.parse_extended_mpls_vc()?)),
-                1010 => Ok(FlowData::ExtendedMplsFec(parser.parse_extended_mpls_fec()?)),
-                1011 => Ok(FlowData::ExtendedMplsLvpFec(
-                    parser.parse_extended_mpls_lvp_fec()?,
-                )),
-                1012 => Ok(FlowData::ExtendedVlanTunnel(
-                    parser.parse_extended_vlan_tunnel()?,
-                )),
-                1013 => Ok(FlowData::Extended80211Payload(
-                    parser.parse_extended_80211_payload()?,
-                )),
-                1014 => Ok(FlowData::Extended80211Rx(parser.parse_extended_80211_rx()?)),
-                1015 => Ok(FlowData::Extended80211Tx(parser.parse_extended_80211_tx()?)),
-                1016 => Ok(FlowData::Extended80211Aggregation(
-                    parser.parse_extended_80211_aggregation()?,
-                )),
-                // DEPRECATED
-                1017 => Ok(FlowData::ExtendedOpenFlowV1(
-                    parser.parse_extended_openflow_v1()?,
-                )),
-                1018 => Ok(FlowData::ExtendedFc(parser.parse_extended_fc()?)),
-                1019 => Ok(FlowData::ExtendedQueueLength(
-                    parser.parse_extended_queue_length()?,
-                )),
-                1020 => Ok(FlowData::ExtendedNatPort(parser.parse_extended_nat_port()?)),
-                1021 => Ok(FlowData::ExtendedL2TunnelEgress(
-                    parser.parse_extended_l2_tunnel_egress()?,
-                )),
-                1022 => Ok(FlowData::ExtendedL2TunnelIngress(
-                    parser.parse_extended_l2_tunnel_ingress()?,
-                )),
-                1023 => Ok(FlowData::ExtendedIpv4TunnelEgress(
-                    parser.parse_extended_ipv4_tunnel_egress()?,
-                )),
-                1024 => Ok(FlowData::ExtendedIpv4TunnelIngress(
-                    parser.parse_extended_ipv4_tunnel_ingress()?,
-                )),
-                1025 => Ok(FlowData::ExtendedIpv6TunnelEgress(
-                    parser.parse_extended_ipv6_tunnel_egress()?,
-                )),
-                1026 => Ok(FlowData::ExtendedIpv6TunnelIngress(
-                    parser.parse_extended_ipv6_tunnel_ingress()?,
-                )),
-                1027 => Ok(FlowData::ExtendedDecapsulateEgress(
-                    parser.parse_extended_decapsulate_egress()?,
-                )),
-                1028 => Ok(FlowData::ExtendedDecapsulateIngress(
-                    parser.parse_extended_decapsulate_ingress()?,
-                )),
-                1029 => Ok(FlowData::ExtendedVniEgress(
-                    parser.parse_extended_vni_egress()?,
-                )),
-                1030 => Ok(FlowData::ExtendedVniIngress(
-                    parser.parse_extended_vni_ingress()?,
-                )),
-                1031 => Ok(FlowData::ExtendedInfiniBandLrh(
-                    parser.parse_extended_infiniband_lrh()?,
-                )),
-                1032 => Ok(FlowData::ExtendedInfiniBandGrh(
-                    parser.parse_extended_infiniband_grh()?,
-                )),
-                1033 => Ok(FlowData::ExtendedInfiniBandBth(
-                    parser.parse_extended_infiniband_bth()?,
-                )),
-                1034 => Ok(FlowData::ExtendedVlanIn(parser.parse_extended_vlan_in()?)),
-                1035 => Ok(FlowData::ExtendedVlanOut(parser.parse_extended_vlan_out()?)),
-                1036 => Ok(FlowData::ExtendedEgressQueue(
-                    parser.parse_extended_egress_queue()?,
-                )),
-                1037 => Ok(FlowData::ExtendedAcl(parser.parse_extended_acl()?)),
-                1038 => Ok(FlowData::ExtendedFunction(
-                    parser.parse_extended_function()?,
-                )),
-                1039 => Ok(FlowData::ExtendedTransit(parser.parse_extended_transit()?)),
-                1040 => Ok(FlowData::ExtendedQueue(parser.parse_extended_queue()?)),
-                1041 => Ok(FlowData::ExtendedHwTrap(parser.parse_extended_hw_trap()?)),
-                1042 => Ok(FlowData::ExtendedLinuxDropReason(
-                    parser.parse_extended_linux_drop_reason()?,
-                )),
-                2000 => Ok(FlowData::Transaction(parser.parse_transaction()?)),
-                2001 => Ok(FlowData::ExtendedNfsStorageTransaction(
-                    parser.parse_extended_nfs_storage_transaction()?,
-                )),
-                2002 => Ok(FlowData::ExtendedScsiStorageTransaction(
-                    parser.parse_extended_scsi_storage_transaction()?,
-                )),
-                2003 => Ok(FlowData::ExtendedHttpTransaction(
-                    parser.parse_extended_http_transaction()?,
-                )),
-                2100 => Ok(FlowData::ExtendedSocketIpv4(
-                    parser.parse_extended_socket_ipv4()?,
-                )),
-                2101 => Ok(FlowData::ExtendedSocketIpv6(
-                    parser.parse_extended_socket_ipv6()?,
-                )),
-                2102 => Ok(FlowData::ExtendedProxySocketIpv4(
-                    parser.parse_extended_proxy_socket_ipv4()?,
-                )),
-                2103 => Ok(FlowData::ExtendedProxySocketIpv6(
-                    parser.parse_extended_proxy_socket_ipv6()?,
-                )),
-                2200 => Ok(FlowData::MemcacheOperation(
-                    parser.parse_memcache_operation()?,
-                )),
-                // DEPRECATED
-                2201 => Ok(FlowData::HttpRequestDeprecated(
-                    parser.parse_http_request_deprecated()?,
-                )),
-                2202 => Ok(FlowData::AppOperation(parser.parse_app_operation()?)),
-                2203 => Ok(FlowData::AppParentContext(
-                    parser.parse_app_parent_context()?,
-                )),
-                2204 => Ok(FlowData::AppInitiator(parser.parse_app_initiator()?)),
-                2205 => Ok(FlowData::AppTarget(parser.parse_app_target()?)),
-                2206 => Ok(FlowData::HttpRequest(parser.parse_http_request()?)),
-                2207 => Ok(FlowData::ExtendedProxyRequest(
-                    parser.parse_extended_proxy_request()?,
-                )),
-                2208 => Ok(FlowData::ExtendedNavTiming(
-                    parser.parse_extended_nav_timing()?,
-                )),
-                2209 => Ok(FlowData::ExtendedTcpInfo(parser.parse_extended_tcp_info()?)),
-                2210 => Ok(FlowData::ExtendedEntities(
-                    parser.parse_extended_entities()?,
-                )),
-                _ => Ok(FlowData::Unknown { format, data }),
+        trace_span!("flow_record", format, data.len());
+        self.count_record()?;
+        let Some(parse) = find_record_parser(FLOW_PARSERS, format) else {
+            self.warn(ParseWarning::UnknownFlowFormat(format));
+            self.report_unknown(format, &data);
+            // Copy the bytes out to keep the scratch buffer for the next record
+            let unknown = FlowData::Unknown {
+                format,
+                data: data.clone(),
+            };
+            self.scratch.push(data);
+            return Ok(unknown);
+        };
+
+        let (mut parser, length) = self.record_parser(data);
+        let result = parse(&mut parser);
+        let reader = self.end_nested(parser);
+        let flow_data = match result {
+            // The record was read whole, so the datagram can go on without it
+            Err(e)
+                if self.options.recover_bad_records
+                    && !matches!(e, ParseError::TooManyRecords { .. }) =>
+            {
+                self.warn(ParseWarning::MalformedRecord {
+                    format,
+                    error: e.to_string(),
+                });
+                let data = reader.into_inner();
+                self.report_unknown(format, &data[..length]);
+                let unknown = FlowData::Unknown {
+                    format,
+                    data: data[..length].to_vec(),
+                };
+                self.scratch.push(data);
+                return Ok(unknown);
             }
-        } else if format.enterprise() == 4413 {
-            // Broadcom enterprise formats
-            match format.format() {
-                1 => Ok(FlowData::ExtendedBstEgressQueue(
-                    parser.parse_extended_bst_egress_queue()?,
-                )),
-                _ => Ok(FlowData::Unknown { format, data }),
-            }
-        } else {
-            // Vendor-specific format
-            Ok(FlowData::Unknown { format, data })
-        }
+            result => result?,
+        };
+
+        self.check_consumed(format, length, reader.position())?;
+        self.scratch.push(reader.into_inner());
+        Ok(flow_data)
     }
 
     /// Parse a flow record
     pub(super) fn parse_flow_record(&mut self) -> Result<FlowRecord> {
         let flow_format = self.parse_data_format()?;
-        let flow_data_raw = self.read_opaque()?;
+        let flow_data_raw = self.read_record_envelope(flow_format)?;
         let flow_data = self.parse_flow_data(flow_format, flow_data_raw)?;
 
         Ok(FlowRecord {
@@ -1285,3 +1188,81 @@ impl<R: Read> Parser<R> {
         })
     }
 }
+
+record_parsers! {
+    /// Decoders of the supported flow record formats, sorted by data format
+    pub(super) const FLOW_PARSERS: FlowData => flow_record_types {
+        (0, 1) => SampledHeader(SampledHeader) = parse_sampled_header,
+        (0, 2) => SampledEthernet(SampledEthernet) = parse_sampled_ethernet,
+        (0, 3) => SampledIpv4(SampledIpv4) = parse_sampled_ipv4,
+        (0, 4) => SampledIpv6(SampledIpv6) = parse_sampled_ipv6,
+        (0, 1001) => ExtendedSwitch(ExtendedSwitch) = parse_extended_switch,
+        (0, 1002) => ExtendedRouter(ExtendedRouter) = parse_extended_router,
+        (0, 1003) => ExtendedGateway(ExtendedGateway) = parse_extended_gateway,
+        (0, 1004) => ExtendedUser(ExtendedUser) = parse_extended_user,
+        // Format 1005 is deprecated but kept for backward compatibility
+        (0, 1005) => ExtendedUrl(ExtendedUrl) = parse_extended_url,
+        (0, 1006) => ExtendedMpls(ExtendedMpls) = parse_extended_mpls,
+        (0, 1007) => ExtendedNat(ExtendedNat) = parse_extended_nat,
+        (0, 1008) => ExtendedMplsTunnel(ExtendedMplsTunnel) = parse_extended_mpls_tunnel,
+        (0, 1009) => ExtendedMplsVc(ExtendedMplsVc) = parse_extended_mpls_vc,
+        (0, 1010) => ExtendedMplsFec(ExtendedMplsFec) = parse_extended_mpls_fec,
+        (0, 1011) => ExtendedMplsLvpFec(ExtendedMplsLvpFec) = parse_extended_mpls_lvp_fec,
+        (0, 1012) => ExtendedVlanTunnel(ExtendedVlanTunnel) = parse_extended_vlan_tunnel,
+        (0, 1013) => Extended80211Payload(Extended80211Payload) = parse_extended_80211_payload,
+        (0, 1014) => Extended80211Rx(Extended80211Rx) = parse_extended_80211_rx,
+        (0, 1015) => Extended80211Tx(Extended80211Tx) = parse_extended_80211_tx,
+        (0, 1016) => Extended80211Aggregation(Extended80211Aggregation) = parse_extended_80211_aggregation,
+        // DEPRECATED
+        (0, 1017) => ExtendedOpenFlowV1(ExtendedOpenFlowV1) = parse_extended_openflow_v1,
+        (0, 1018) => ExtendedFc(ExtendedFc) = parse_extended_fc,
+        (0, 1019) => ExtendedQueueLength(ExtendedQueueLength) = parse_extended_queue_length,
+        (0, 1020) => ExtendedNatPort(ExtendedNatPort) = parse_extended_nat_port,
+        (0, 1021) => ExtendedL2TunnelEgress(ExtendedL2TunnelEgress) = parse_extended_l2_tunnel_egress,
+        (0, 1022) => ExtendedL2TunnelIngress(ExtendedL2TunnelIngress) = parse_extended_l2_tunnel_ingress,
+        (0, 1023) => ExtendedIpv4TunnelEgress(ExtendedIpv4TunnelEgress) = parse_extended_ipv4_tunnel_egress,
+        (0, 1024) => ExtendedIpv4TunnelIngress(ExtendedIpv4TunnelIngress) = parse_extended_ipv4_tunnel_ingress,
+        (0, 1025) => ExtendedIpv6TunnelEgress(ExtendedIpv6TunnelEgress) = parse_extended_ipv6_tunnel_egress,
+        (0, 1026) => ExtendedIpv6TunnelIngress(ExtendedIpv6TunnelIngress) = parse_extended_ipv6_tunnel_ingress,
+        (0, 1027) => ExtendedDecapsulateEgress(ExtendedDecapsulateEgress) = parse_extended_decapsulate_egress,
+        (0, 1028) => ExtendedDecapsulateIngress(ExtendedDecapsulateIngress) = parse_extended_decapsulate_ingress,
+        (0, 1029) => ExtendedVniEgress(ExtendedVniEgress) = parse_extended_vni_egress,
+        (0, 1030) => ExtendedVniIngress(ExtendedVniIngress) = parse_extended_vni_ingress,
+        (0, 1031) => ExtendedInfiniBandLrh(ExtendedInfiniBandLrh) = parse_extended_infiniband_lrh,
+        (0, 1032) => ExtendedInfiniBandGrh(ExtendedInfiniBandGrh) = parse_extended_infiniband_grh,
+        (0, 1033) => ExtendedInfiniBandBth(ExtendedInfiniBandBth) = parse_extended_infiniband_bth,
+        (0, 1034) => ExtendedVlanIn(ExtendedVlanIn) = parse_extended_vlan_in,
+        (0, 1035) => ExtendedVlanOut(ExtendedVlanOut) = parse_extended_vlan_out,
+        (0, 1036) => ExtendedEgressQueue(ExtendedEgressQueue) = parse_extended_egress_queue,
+        (0, 1037) => ExtendedAcl(ExtendedAcl) = parse_extended_acl,
+        (0, 1038) => ExtendedFunction(ExtendedFunction) = parse_extended_function,
+        (0, 1039) => ExtendedTransit(ExtendedTransit) = parse_extended_transit,
+        (0, 1040) => ExtendedQueue(ExtendedQueue) = parse_extended_queue,
+        (0, 1041) => ExtendedHwTrap(ExtendedHwTrap) = parse_extended_hw_trap,
+        (0, 1042) => ExtendedLinuxDropReason(ExtendedLinuxDropReason) = parse_extended_linux_drop_reason,
+        (0, 2000) => Transaction(Transaction) = parse_transaction,
+        (0, 2001) => ExtendedNfsStorageTransaction(ExtendedNfsStorageTransaction) = parse_extended_nfs_storage_transaction,
+        (0, 2002) => ExtendedScsiStorageTransaction(ExtendedScsiStorageTransaction) = parse_extended_scsi_storage_transaction,
+        (0, 2003) => ExtendedHttpTransaction(ExtendedHttpTransaction) = parse_extended_http_transaction,
+        (0, 2100) => ExtendedSocketIpv4(ExtendedSocketIpv4) = parse_extended_socket_ipv4,
+        (0, 2101) => ExtendedSocketIpv6(ExtendedSocketIpv6) = parse_extended_socket_ipv6,
+        (0, 2102) => ExtendedProxySocketIpv4(ExtendedProxySocketIpv4) = parse_extended_proxy_socket_ipv4,
+        (0, 2103) => ExtendedProxySocketIpv6(ExtendedProxySocketIpv6) = parse_extended_proxy_socket_ipv6,
+        (0, 2200) => MemcacheOperation(MemcacheOperation) = parse_memcache_operation,
+        // DEPRECATED
+        (0, 2201) => HttpRequestDeprecated(HttpRequestDeprecated) = parse_http_request_deprecated,
+        (0, 2202) => AppOperation(AppOperation) = parse_app_operation,
+        (0, 2203) => AppParentContext(AppParentContext) = parse_app_parent_context,
+        (0, 2204) => AppInitiator(AppInitiator) = parse_app_initiator,
+        (0, 2205) => AppTarget(AppTarget) = parse_app_target,
+        (0, 2206) => HttpRequest(HttpRequest) = parse_http_request,
+        (0, 2207) => ExtendedProxyRequest(ExtendedProxyRequest) = parse_extended_proxy_request,
+        (0, 2208) => ExtendedNavTiming(ExtendedNavTiming) = parse_extended_nav_timing,
+        (0, 2209) => ExtendedTcpInfo(ExtendedTcpInfo) = parse_extended_tcp_info,
+        (0, 2210) => ExtendedEntities(ExtendedEntities) = parse_extended_entities,
+        // Broadcom enterprise formats
+        (4413, 1) => ExtendedBstEgressQueue(ExtendedBstEgressQueue) = parse_extended_bst_egress_queue,
+    }
+}
+
+const _: () = assert!(is_sorted_by_format(FLOW_PARSERS));
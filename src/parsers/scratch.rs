@@ -0,0 +1,33 @@
+//! Reusable parser buffers
+//!
+//! Flow and counter records are read into a buffer before being decoded.
+//! A parser reuses that buffer from one record to the next within a
+//! datagram; a [`ParseScratch`] carries it over to the next datagram.
+
+/// Buffers lent to the parser across datagrams
+///
+/// Pass the same scratch to every [`parse_datagram_with_scratch`] call of a
+/// collector loop to avoid allocating a record buffer per datagram. The
+/// parsed datagram never borrows from the scratch, and the buffer is
+/// cleared before each record, so reusing it across datagrams (or agents)
+/// cannot leak bytes from one record into another. A scratch is not
+/// shared between threads: use one per worker.
+///
+/// [`parse_datagram_with_scratch`]: super::parse_datagram_with_scratch
+#[derive(Debug, Clone, Default)]
+pub struct ParseScratch {
+    /// One buffer per level of record nesting
+    pub(super) records: Vec<Vec<u8>>,
+}
+
+impl ParseScratch {
+    /// Create an empty scratch, buffers grow on first use
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Capacity in bytes currently reserved by the scratch buffers
+    pub fn capacity(&self) -> usize {
+        self.records.iter().map(Vec::capacity).sum()
+    }
+}
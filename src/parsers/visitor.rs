@@ -0,0 +1,143 @@
+//! Record-level visitor API
+//!
+//! [`parse_datagram_visit`] walks a datagram and hands each decoded record to
+//! a [`DatagramVisitor`] instead of collecting them into an [`SFlowDatagram`].
+//! Records the visitor does not want are skipped by their declared length
+//! without being copied or decoded.
+
+use super::datagram::{check_lengths, SampleRecords};
+use super::error::{ParseError, Result};
+use super::{ParseOptions, Parser};
+use crate::models::*;
+use std::io::{self, Cursor, Read};
+
+/// Callbacks invoked while walking a datagram
+///
+/// All methods have empty default implementations, so a visitor only
+/// overrides what it needs. The `wants_*` methods are consulted before a
+/// record is read: returning `false` skips it without allocating.
+pub trait DatagramVisitor {
+    /// Called once with the datagram header, before any record
+    fn on_header(&mut self, _header: &DatagramHeader) {}
+
+    /// Whether the flow record with this format should be decoded
+    fn wants_flow_record(&mut self, _format: DataFormat) -> bool {
+        true
+    }
+
+    /// Called for each decoded flow record (flow and discarded packet samples)
+    fn on_flow_record(&mut self, _record: &FlowData) {}
+
+    /// Whether the counter record with this format should be decoded
+    fn wants_counter_record(&mut self, _format: DataFormat) -> bool {
+        true
+    }
+
+    /// Called for each decoded counter record
+    fn on_counter_record(&mut self, _record: &CounterData) {}
+}
+
+impl<R: Read> Parser<R> {
+    /// Walk an sFlow v5 datagram, calling the visitor for each record
+    pub fn visit_datagram<V: DatagramVisitor>(&mut self, visitor: &mut V) -> Result<()> {
+        let header = self.parse_datagram_header()?;
+        visitor.on_header(&header);
+
+        for _ in 0..header.num_samples {
+            self.visit_sample_record(visitor)?;
+        }
+        Ok(())
+    }
+
+    /// Walk a sample record within its declared length
+    fn visit_sample_record<V: DatagramVisitor>(&mut self, visitor: &mut V) -> Result<()> {
+        let sample_type = self.parse_data_format()?;
+        let length = self.read_u32()? as usize;
+        if length > self.options.max_opaque_bytes {
+            return Err(ParseError::InvalidData(format!(
+                "Opaque data length {} exceeds maximum {}",
+                length, self.options.max_opaque_bytes
+            )));
+        }
+
+        trace_span!("sample", sample_type, length);
+
+        // Records are read through a view limited to the padded sample body
+        let padded = (length as u64).div_ceil(4) * 4;
+        let mut sample =
+            Parser::with_options((&mut self.reader).take(padded), self.options.clone());
+        sample.records = self.records;
+
+        match sample.skip_sample_fields(sample_type)? {
+            Some(SampleRecords::Flow) => sample.visit_flow_records(visitor)?,
+            Some(SampleRecords::Counter) => sample.visit_counter_records(visitor)?,
+            None => {}
+        }
+        self.records = sample.records;
+
+        // Skip whatever the sample holds past its records (or all of it)
+        io::copy(&mut sample.reader, &mut io::sink())?;
+        if sample.reader.limit() != 0 {
+            return Err(ParseError::Io(io::Error::from(
+                io::ErrorKind::UnexpectedEof,
+            )));
+        }
+        Ok(())
+    }
+
+    /// Skip an unwanted record, bounded like a decoded one by
+    /// `ParseOptions::max_record_bytes`
+    fn skip_record(&mut self, format: DataFormat) -> Result<()> {
+        let length = self.skip_opaque()?;
+        if length as usize > self.options.max_record_bytes {
+            return Err(ParseError::RecordTooLarge { format, length });
+        }
+        Ok(())
+    }
+
+    fn visit_flow_records<V: DatagramVisitor>(&mut self, visitor: &mut V) -> Result<()> {
+        let num_records = self.read_u32()?;
+        for _ in 0..num_records {
+            let flow_format = self.parse_data_format()?;
+            if visitor.wants_flow_record(flow_format) {
+                let flow_data_raw = self.read_record_envelope(flow_format)?;
+                let flow_data = self.parse_flow_data(flow_format, flow_data_raw)?;
+                visitor.on_flow_record(&flow_data);
+            } else {
+                self.skip_record(flow_format)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn visit_counter_records<V: DatagramVisitor>(&mut self, visitor: &mut V) -> Result<()> {
+        let num_records = self.read_u32()?;
+        for _ in 0..num_records {
+            let counter_format = self.parse_data_format()?;
+            if visitor.wants_counter_record(counter_format) {
+                let counter_data_raw = self.read_record_envelope(counter_format)?;
+                let counter_data = self.parse_counter_data(counter_format, counter_data_raw)?;
+                visitor.on_counter_record(&counter_data);
+            } else {
+                self.skip_record(counter_format)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Walk an sFlow v5 datagram from a byte slice, calling the visitor for each record
+///
+/// Unlike [`super::parse_datagram_with_options`], no [`SFlowDatagram`] is
+/// built: each record is dropped once the visitor has seen it. The sample
+/// lengths are checked against the slice before any callback, and the
+/// limits of `options` apply to decoded and skipped records alike.
+pub fn parse_datagram_visit<V: DatagramVisitor>(
+    data: &[u8],
+    options: &ParseOptions,
+    visitor: &mut V,
+) -> Result<()> {
+    check_lengths(data, options)?;
+    let mut parser = Parser::with_options(Cursor::new(data), options.clone());
+    parser.visit_datagram(visitor)
+}
@@ -0,0 +1,102 @@
+//! Parser warning types
+
+use crate::models::DataFormat;
+use std::fmt;
+
+/// Non-fatal issue encountered while parsing
+///
+/// Warnings are collected by
+/// [`parse_datagram_verbose`](super::parse_datagram_verbose); the affected
+/// data is still returned (e.g. as an `Unknown` variant).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseWarning {
+    /// Sample format not recognized, kept as `SampleData::Unknown`
+    UnknownSampleFormat(DataFormat),
+    /// Flow record format not recognized, kept as `FlowData::Unknown`
+    UnknownFlowFormat(DataFormat),
+    /// Counter record format not recognized, kept as `CounterData::Unknown`
+    UnknownCounterFormat(DataFormat),
+    /// Header protocol of a sampled header not defined by the specification
+    UnknownHeaderProtocol(u32),
+    /// A sample or record was decoded without consuming all of its declared
+    /// length, the remaining bytes were skipped
+    LengthMismatch {
+        /// Format of the sample or record
+        format: DataFormat,
+        /// Declared length in bytes
+        declared: u32,
+        /// Bytes consumed by the decoder
+        consumed: u32,
+    },
+    /// A flow or counter record failed to decode and was kept as `Unknown`
+    /// (see [`ParseOptions::recover_bad_records`](super::ParseOptions::recover_bad_records))
+    MalformedRecord {
+        /// Format of the record
+        format: DataFormat,
+        /// Decoding error
+        error: String,
+    },
+    /// The inner header offset of an Extended Decapsulate record (0,1027 or
+    /// 0,1028) does not fall within the sampled header of the same sample
+    InnerHeaderOutOfBounds {
+        /// Declared offset of the inner header
+        offset: u32,
+        /// Number of header bytes captured in the sampled header
+        header_length: u32,
+    },
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseWarning::UnknownSampleFormat(format) => write!(
+                f,
+                "Unrecognized sample format ({},{})",
+                format.enterprise(),
+                format.format()
+            ),
+            ParseWarning::UnknownFlowFormat(format) => write!(
+                f,
+                "Unrecognized flow record format ({},{})",
+                format.enterprise(),
+                format.format()
+            ),
+            ParseWarning::UnknownCounterFormat(format) => write!(
+                f,
+                "Unrecognized counter record format ({},{})",
+                format.enterprise(),
+                format.format()
+            ),
+            ParseWarning::UnknownHeaderProtocol(protocol) => {
+                write!(f, "Unknown header protocol: {}", protocol)
+            }
+            ParseWarning::LengthMismatch {
+                format,
+                declared,
+                consumed,
+            } => write!(
+                f,
+                "Format ({},{}) declares {} bytes but {} were decoded",
+                format.enterprise(),
+                format.format(),
+                declared,
+                consumed
+            ),
+            ParseWarning::MalformedRecord { format, error } => write!(
+                f,
+                "Malformed record format ({},{}) kept as unknown: {}",
+                format.enterprise(),
+                format.format(),
+                error
+            ),
+            ParseWarning::InnerHeaderOutOfBounds {
+                offset,
+                header_length,
+            } => write!(
+                f,
+                "Inner header offset {} is beyond the {} sampled header bytes",
+                offset, header_length
+            ),
+        }
+    }
+}
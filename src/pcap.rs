@@ -0,0 +1,217 @@
+//! Replay of sFlow datagrams captured in pcap files
+//!
+//! [`parse_pcap`] reads a libpcap or pcapng capture with the
+//! [`pcap-file`](https://docs.rs/pcap-file) crate, keeps the UDP packets sent
+//! to the sFlow collector port and parses their payloads. Ethernet (with
+//! 802.1Q/802.1ad tags), raw IP and Linux cooked (SLL) link types are
+//! supported over IPv4 and IPv6.
+
+use crate::models::header::{read_u16, skip_ipv6_extensions};
+use crate::models::SFlowDatagram;
+use crate::parsers::{parse_datagram, ParseError, Result};
+use pcap_file::pcap::PcapReader;
+use pcap_file::pcapng::{Block, PcapNgReader};
+use pcap_file::{DataLink, PcapError};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+/// Default sFlow collector UDP port
+pub const DEFAULT_SFLOW_PORT: u16 = 6343;
+
+/// Magic number of a pcapng Section Header Block, the same in both byte orders
+const PCAPNG_MAGIC: [u8; 4] = [0x0A, 0x0D, 0x0D, 0x0A];
+
+/// Parse the sFlow datagrams of a pcap or pcapng file
+///
+/// UDP packets whose destination port is `port` (6343 if `None`) are parsed
+/// in capture order; every other packet is ignored. The file is read as
+/// the iterator advances. The file header is checked up front, including
+/// the link type of a classic pcap file; errors in individual datagrams (or
+/// a truncated packet record) are reported by the iterator. In a pcapng
+/// file, the packets of interfaces with an unsupported link type are
+/// ignored.
+pub fn parse_pcap(
+    path: &Path,
+    port: Option<u16>,
+) -> Result<impl Iterator<Item = Result<SFlowDatagram>>> {
+    let file = File::open(path).map_err(ParseError::Io)?;
+    Capture::new(BufReader::new(file), port.unwrap_or(DEFAULT_SFLOW_PORT))
+}
+
+/// Packet source of a capture file, by file format
+enum Source<R: io::Read> {
+    Pcap {
+        reader: PcapReader<R>,
+        link_type: DataLink,
+    },
+    PcapNg {
+        reader: PcapNgReader<R>,
+        /// Link type of each interface of the current section, by interface id
+        link_types: Vec<DataLink>,
+    },
+}
+
+/// Iterator over the sFlow datagrams of a capture stream
+struct Capture<R: io::Read> {
+    source: Source<R>,
+    port: u16,
+    failed: bool,
+}
+
+impl<R: BufRead> Capture<R> {
+    fn new(mut reader: R, port: u16) -> Result<Self> {
+        let header_error = |e: PcapError| match e {
+            PcapError::IoError(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                ParseError::InvalidData("pcap file shorter than its header".to_string())
+            }
+            e => pcap_error(e),
+        };
+
+        let source = if reader.fill_buf()?.starts_with(&PCAPNG_MAGIC) {
+            Source::PcapNg {
+                reader: PcapNgReader::new(reader).map_err(header_error)?,
+                link_types: Vec::new(),
+            }
+        } else {
+            let reader = PcapReader::new(reader).map_err(header_error)?;
+            let link_type = reader.header().datalink;
+            if !is_supported(link_type) {
+                return Err(ParseError::InvalidData(format!(
+                    "Unsupported pcap link type {}",
+                    u32::from(link_type)
+                )));
+            }
+            Source::Pcap { reader, link_type }
+        };
+
+        Ok(Self {
+            source,
+            port,
+            failed: false,
+        })
+    }
+
+    /// Payload of the next UDP packet sent to the port, parsed
+    ///
+    /// Returns `Ok(None)` at the end of the file, and an error if the
+    /// capture itself cannot be read.
+    fn next_datagram(&mut self) -> Result<Option<Result<SFlowDatagram>>> {
+        let port = self.port;
+        match &mut self.source {
+            Source::Pcap { reader, link_type } => {
+                while let Some(packet) = reader.next_packet() {
+                    let packet = packet.map_err(pcap_error)?;
+                    if let Some(payload) = udp_payload(*link_type, &packet.data, port) {
+                        return Ok(Some(parse_datagram(payload)));
+                    }
+                }
+            }
+            Source::PcapNg { reader, link_types } => {
+                while let Some(block) = reader.next_block() {
+                    let (interface_id, data) = match block.map_err(pcap_error)? {
+                        Block::SectionHeader(_) => {
+                            link_types.clear();
+                            continue;
+                        }
+                        Block::InterfaceDescription(interface) => {
+                            link_types.push(interface.linktype);
+                            continue;
+                        }
+                        Block::EnhancedPacket(packet) => (packet.interface_id, packet.data),
+                        // Simple packets are captured on the first interface
+                        Block::SimplePacket(packet) => (0, packet.data),
+                        _ => continue,
+                    };
+                    let Some(&link_type) = link_types.get(interface_id as usize) else {
+                        continue;
+                    };
+                    if let Some(payload) = udp_payload(link_type, &data, port) {
+                        return Ok(Some(parse_datagram(payload)));
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<R: BufRead> Iterator for Capture<R> {
+    type Item = Result<SFlowDatagram>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        // A datagram that fails to parse does not stop the capture, a
+        // damaged capture file does
+        self.next_datagram().unwrap_or_else(|e| {
+            self.failed = true;
+            Some(Err(e))
+        })
+    }
+}
+
+fn is_supported(link_type: DataLink) -> bool {
+    matches!(
+        link_type,
+        DataLink::ETHERNET | DataLink::RAW | DataLink::IPV4 | DataLink::IPV6 | DataLink::LINUX_SLL
+    )
+}
+
+fn pcap_error(e: PcapError) -> ParseError {
+    match e {
+        PcapError::IoError(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+            ParseError::InvalidData("Truncated pcap packet record".to_string())
+        }
+        PcapError::IoError(e) => ParseError::Io(e),
+        e => ParseError::InvalidData(format!("Invalid pcap file: {}", e)),
+    }
+}
+
+/// Extract the payload of a UDP packet sent to `port`
+fn udp_payload(link_type: DataLink, frame: &[u8], port: u16) -> Option<&[u8]> {
+    let ip = match link_type {
+        DataLink::ETHERNET => {
+            let mut offset = 12;
+            let mut ethertype = read_u16(frame, offset)?;
+            // 802.1Q and 802.1ad tags
+            while ethertype == 0x8100 || ethertype == 0x88A8 {
+                offset += 4;
+                ethertype = read_u16(frame, offset)?;
+            }
+            frame.get(offset + 2..)?
+        }
+        DataLink::LINUX_SLL => frame.get(16..)?,
+        DataLink::RAW | DataLink::IPV4 | DataLink::IPV6 => frame,
+        _ => return None,
+    };
+
+    let udp = match ip.first()? >> 4 {
+        4 => {
+            let header_len = ((ip[0] & 0x0F) as usize) * 4;
+            let flags_fragment = read_u16(ip, 6)?;
+            // Only unfragmented packets carry a whole datagram
+            if *ip.get(9)? != 17 || flags_fragment & 0x3FFF != 0 {
+                return None;
+            }
+            ip.get(header_len..)?
+        }
+        6 => {
+            // A first fragment stops before the UDP length, see below
+            let (next_header, transport) = skip_ipv6_extensions(*ip.get(6)?, ip.get(40..)?);
+            if next_header != 17 {
+                return None;
+            }
+            transport
+        }
+        _ => return None,
+    };
+
+    if read_u16(udp, 2)? != port {
+        return None;
+    }
+    // Missing bytes mean a truncated capture or a fragmented datagram
+    let length = read_u16(udp, 4)? as usize;
+    udp.get(8..length.max(8))
+}
@@ -0,0 +1,385 @@
+//! Human-readable rendering of datagrams
+//!
+//! [`pretty_print`] renders a datagram as an indented tree: the datagram
+//! header, one line per sample and one line per record. Address-bearing
+//! records (sampled IP, sampled header, sockets, router and gateway) show
+//! their endpoints, the common switching, drop and interface records their
+//! main fields, other records only their kind.
+//!
+//! With the `color` feature, record kinds, IP addresses and unknown or
+//! skipped data are highlighted with ANSI colors. [`pretty_print`] does so
+//! unless the `NO_COLOR` environment variable is set to a non-empty value
+//! (<https://no-color.org>); [`pretty_print_with_color`] takes the decision
+//! from the caller and [`pretty_print_plain`] never emits color codes.
+
+use crate::models::*;
+use std::fmt::{Display, Write};
+use std::net::IpAddr;
+
+/// Render a datagram, colored if the `color` feature is enabled and `NO_COLOR` is not set
+pub fn pretty_print(datagram: &SFlowDatagram) -> String {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    pretty_print_with_color(datagram, !no_color)
+}
+
+/// Render a datagram, colored if `color` is set and the `color` feature is enabled
+pub fn pretty_print_with_color(datagram: &SFlowDatagram, color: bool) -> String {
+    render(
+        datagram,
+        Painter {
+            color: color && cfg!(feature = "color"),
+        },
+    )
+}
+
+/// Render a datagram without color codes
+pub fn pretty_print_plain(datagram: &SFlowDatagram) -> String {
+    pretty_print_with_color(datagram, false)
+}
+
+/// Highlighted elements of the output
+#[derive(Clone, Copy)]
+enum Style {
+    /// Sample and record kinds
+    Kind,
+    /// IP addresses
+    Address,
+    /// Unknown, skipped or undecoded data
+    Unknown,
+}
+
+impl Style {
+    /// Render `text` in the style
+    #[cfg(feature = "color")]
+    fn apply(self, text: impl Display) -> String {
+        use owo_colors::OwoColorize;
+
+        let style = match self {
+            Style::Kind => owo_colors::Style::new().bold().cyan(),
+            Style::Address => owo_colors::Style::new().green(),
+            Style::Unknown => owo_colors::Style::new().bold().red(),
+        };
+        text.style(style).to_string()
+    }
+
+    /// Render `text` in the style; without the `color` feature, as is
+    #[cfg(not(feature = "color"))]
+    fn apply(self, text: impl Display) -> String {
+        text.to_string()
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Painter {
+    color: bool,
+}
+
+impl Painter {
+    fn paint(self, style: Style, text: impl Display) -> String {
+        if self.color {
+            style.apply(text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn endpoint(self, ip: IpAddr, port: u32) -> String {
+        match ip {
+            IpAddr::V4(_) => format!("{}:{}", self.paint(Style::Address, ip), port),
+            IpAddr::V6(_) => format!("[{}]:{}", self.paint(Style::Address, ip), port),
+        }
+    }
+}
+
+fn render(datagram: &SFlowDatagram, p: Painter) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "sFlow v5 agent={} sub_agent_id={} seq={} uptime={}ms samples={}",
+        p.paint(Style::Address, &datagram.agent_address),
+        datagram.sub_agent_id,
+        datagram.sequence_number,
+        datagram.uptime,
+        datagram.samples.len()
+    );
+
+    for sample in &datagram.samples {
+        render_sample(&mut out, p, &sample.sample_data);
+    }
+    out
+}
+
+fn render_sample(out: &mut String, p: Painter, sample: &SampleData) {
+    match sample {
+        SampleData::FlowSample(s) => {
+            let _ = writeln!(
+                out,
+                "  {} seq={} source={} rate={} pool={} drops={} input={} output={}",
+                p.paint(Style::Kind, "FlowSample"),
+                s.sequence_number,
+                s.source_id,
+                s.sampling_rate,
+                s.sample_pool,
+                s.drops,
+                interface(&s.input),
+                interface(&s.output)
+            );
+            render_flow_records(out, p, &s.flow_records);
+        }
+        SampleData::FlowSampleExpanded(s) => {
+            let _ = writeln!(
+                out,
+                "  {} seq={} source={}:{} rate={} pool={} drops={} input={} output={}",
+                p.paint(Style::Kind, "FlowSampleExpanded"),
+                s.sequence_number,
+                s.source_id.source_id_type,
+                s.source_id.source_id_index,
+                s.sampling_rate,
+                s.sample_pool,
+                s.drops,
+                s.input.value,
+                s.output.value
+            );
+            render_flow_records(out, p, &s.flow_records);
+        }
+        SampleData::DiscardedPacket(s) => {
+            let _ = writeln!(
+                out,
+                "  {} seq={} source={}:{} drops={} input={} output={} reason={}",
+                p.paint(Style::Kind, "DiscardedPacket"),
+                s.sequence_number,
+                s.source_id.source_id_type,
+                s.source_id.source_id_index,
+                s.drops,
+                s.input_ifindex,
+                s.output_ifindex,
+                s.reason
+            );
+            render_flow_records(out, p, &s.flow_records);
+        }
+        SampleData::CountersSample(s) => {
+            let _ = writeln!(
+                out,
+                "  {} seq={} source={}",
+                p.paint(Style::Kind, "CountersSample"),
+                s.sequence_number,
+                s.source_id
+            );
+            render_counter_records(out, p, &s.counters);
+        }
+        SampleData::CountersSampleExpanded(s) => {
+            let _ = writeln!(
+                out,
+                "  {} seq={} source={}:{}",
+                p.paint(Style::Kind, "CountersSampleExpanded"),
+                s.sequence_number,
+                s.source_id.source_id_type,
+                s.source_id.source_id_index
+            );
+            render_counter_records(out, p, &s.counters);
+        }
+        SampleData::RtMetric { format, data } => {
+            let _ = writeln!(
+                out,
+                "  {} {} {} bytes",
+                p.paint(Style::Kind, "RtMetric"),
+                format,
+                data.len()
+            );
+        }
+        SampleData::RtFlow { format, data } => {
+            let _ = writeln!(
+                out,
+                "  {} {} {} bytes",
+                p.paint(Style::Kind, "RtFlow"),
+                format,
+                data.len()
+            );
+        }
+        SampleData::Skipped { format, length } => {
+            let _ = writeln!(
+                out,
+                "  {} {} {} bytes",
+                p.paint(Style::Unknown, "Skipped"),
+                format,
+                length
+            );
+        }
+        SampleData::Unknown { format, data } => {
+            let _ = writeln!(
+                out,
+                "  {} {} {} bytes",
+                p.paint(Style::Unknown, "Unknown"),
+                format,
+                data.len()
+            );
+        }
+    }
+}
+
+fn render_flow_records(out: &mut String, p: Painter, records: &[FlowRecord]) {
+    for record in records {
+        let data = &record.flow_data;
+        let line = match data {
+            FlowData::Unknown { data, .. } => {
+                format!(
+                    "{} {} bytes",
+                    p.paint(Style::Unknown, "Unknown"),
+                    data.len()
+                )
+            }
+            _ => with_summary(
+                p.paint(Style::Kind, data.name()),
+                flow_endpoints(p, data).or_else(|| flow_summary(data)),
+            ),
+        };
+        let _ = writeln!(out, "    {} {}", record.flow_format, line);
+    }
+}
+
+fn render_counter_records(out: &mut String, p: Painter, records: &[CounterRecord]) {
+    for record in records {
+        let data = &record.counter_data;
+        let line = match data {
+            CounterData::Unknown { data, .. } => {
+                format!(
+                    "{} {} bytes",
+                    p.paint(Style::Unknown, "Unknown"),
+                    data.len()
+                )
+            }
+            _ => with_summary(p.paint(Style::Kind, data.name()), counter_summary(data)),
+        };
+        let _ = writeln!(out, "    {} {}", record.counter_format, line);
+    }
+}
+
+fn with_summary(kind: String, summary: Option<String>) -> String {
+    match summary {
+        Some(summary) => format!("{} {}", kind, summary),
+        None => kind,
+    }
+}
+
+/// Main fields of the common records without endpoints
+fn flow_summary(data: &FlowData) -> Option<String> {
+    let summary = match data {
+        FlowData::SampledEthernet(eth) => format!(
+            "{} -> {} eth_type=0x{:04x} length={}",
+            eth.src_mac, eth.dst_mac, eth.eth_type, eth.length
+        ),
+        FlowData::ExtendedSwitch(switch) => format!(
+            "src_vlan={} src_priority={} dst_vlan={} dst_priority={}",
+            switch.src_vlan, switch.src_priority, switch.dst_vlan, switch.dst_priority
+        ),
+        FlowData::ExtendedNat(nat) => format!("{} -> {}", nat.src_address, nat.dst_address),
+        FlowData::ExtendedNatPort(nat) => format!("{} -> {}", nat.src_port, nat.dst_port),
+        FlowData::ExtendedVniEgress(vni) => format!("vni={}", vni.vni),
+        FlowData::ExtendedVniIngress(vni) => format!("vni={}", vni.vni),
+        FlowData::ExtendedEgressQueue(queue) => format!("queue={}", queue.queue),
+        FlowData::ExtendedAcl(acl) => format!(
+            "number={} name={} direction={}",
+            acl.number, acl.name, acl.direction
+        ),
+        FlowData::ExtendedFunction(function) => format!("symbol={}", function.symbol),
+        FlowData::ExtendedHwTrap(trap) => format!("group={} trap={}", trap.group, trap.trap),
+        FlowData::ExtendedLinuxDropReason(drop) => format!("reason={}", drop.reason),
+        _ => return None,
+    };
+    Some(summary)
+}
+
+/// Main fields of the common counter records
+fn counter_summary(data: &CounterData) -> Option<String> {
+    let summary = match data {
+        CounterData::GenericInterface(c) => format!(
+            "if_index={} speed={} status={} in_octets={} out_octets={} in_errors={} out_errors={}",
+            c.if_index,
+            c.if_speed,
+            c.if_status,
+            c.if_in_octets,
+            c.if_out_octets,
+            c.if_in_errors,
+            c.if_out_errors
+        ),
+        CounterData::Processor(c) => format!(
+            "cpu_5s={} cpu_1m={} cpu_5m={} total_memory={} free_memory={}",
+            c.cpu_5s, c.cpu_1m, c.cpu_5m, c.total_memory, c.free_memory
+        ),
+        _ => return None,
+    };
+    Some(summary)
+}
+
+/// Summary of the records that identify endpoints
+fn flow_endpoints(p: Painter, data: &FlowData) -> Option<String> {
+    let summary = match data {
+        FlowData::SampledIpv4(ip) => format!(
+            "{} -> {} {}",
+            p.endpoint(IpAddr::V4(ip.src_ip), ip.src_port),
+            p.endpoint(IpAddr::V4(ip.dst_ip), ip.dst_port),
+            ip.ip_protocol()
+        ),
+        FlowData::SampledIpv6(ip) => format!(
+            "{} -> {} {}",
+            p.endpoint(IpAddr::V6(ip.src_ip), ip.src_port),
+            p.endpoint(IpAddr::V6(ip.dst_ip), ip.dst_port),
+            ip.ip_protocol()
+        ),
+        FlowData::SampledHeader(header) => {
+            let mut summary = format!(
+                "{} frame_length={} captured={}",
+                header.protocol,
+                header.frame_length,
+                header.header.len()
+            );
+            if let Some(decoded) = header.decode() {
+                let _ = write!(
+                    summary,
+                    " {} -> {} {}",
+                    p.paint(Style::Address, decoded.src_ip),
+                    p.paint(Style::Address, decoded.dst_ip),
+                    decoded.protocol
+                );
+            }
+            summary
+        }
+        FlowData::ExtendedSocketIpv4(socket) => format!(
+            "{} -> {} {}",
+            p.endpoint(IpAddr::V4(socket.local_ip), socket.local_port),
+            p.endpoint(IpAddr::V4(socket.remote_ip), socket.remote_port),
+            socket.ip_protocol()
+        ),
+        FlowData::ExtendedSocketIpv6(socket) => format!(
+            "{} -> {} {}",
+            p.endpoint(IpAddr::V6(socket.local_ip), socket.local_port),
+            p.endpoint(IpAddr::V6(socket.remote_ip), socket.remote_port),
+            socket.ip_protocol()
+        ),
+        FlowData::ExtendedRouter(router) => format!(
+            "next_hop={} src_mask={} dst_mask={}",
+            p.paint(Style::Address, &router.next_hop),
+            router.src_mask_len,
+            router.dst_mask_len
+        ),
+        FlowData::ExtendedGateway(gateway) => format!(
+            "next_hop={} as={} src_as={} src_peer_as={}",
+            p.paint(Style::Address, &gateway.next_hop),
+            gateway.as_number,
+            gateway.src_as,
+            gateway.src_peer_as
+        ),
+        _ => return None,
+    };
+    Some(summary)
+}
+
+fn interface(interface: &Interface) -> String {
+    if interface.is_discarded() {
+        format!("discarded({})", interface.value())
+    } else if interface.is_multiple() {
+        format!("multiple({})", interface.value())
+    } else {
+        interface.value().to_string()
+    }
+}
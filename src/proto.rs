@@ -0,0 +1,244 @@
+//! Protobuf representation of the sFlow records
+//!
+//! Available with the `prost` feature. The messages are hand-written prost
+//! types matching `proto/sflow.proto`, so they can be encoded with
+//! [`prost::Message`] and decoded by any protobuf implementation using that
+//! schema. IP addresses are encoded as their raw network-order bytes.
+//!
+//! Only a subset of records is mapped for now. Converting an unmapped
+//! [`FlowData`] or [`CounterData`] variant yields a record with `record`
+//! set to `None`.
+
+use crate::models::record_counters::GenericInterfaceCounters as GenericInterfaceModel;
+use crate::models::record_flows::{
+    SampledHeader as SampledHeaderModel, SampledIpv4 as SampledIpv4Model,
+    SampledIpv6 as SampledIpv6Model,
+};
+use crate::models::{CounterData, FlowData};
+
+/// Sampled Header - Format (0,1)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SampledHeader {
+    #[prost(uint32, tag = "1")]
+    pub protocol: u32,
+    #[prost(uint32, tag = "2")]
+    pub frame_length: u32,
+    #[prost(uint32, tag = "3")]
+    pub stripped: u32,
+    #[prost(bytes = "vec", tag = "4")]
+    pub header: Vec<u8>,
+}
+
+/// Sampled IPv4 - Format (0,3)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SampledIpv4 {
+    #[prost(uint32, tag = "1")]
+    pub length: u32,
+    #[prost(uint32, tag = "2")]
+    pub protocol: u32,
+    #[prost(bytes = "vec", tag = "3")]
+    pub src_ip: Vec<u8>,
+    #[prost(bytes = "vec", tag = "4")]
+    pub dst_ip: Vec<u8>,
+    #[prost(uint32, tag = "5")]
+    pub src_port: u32,
+    #[prost(uint32, tag = "6")]
+    pub dst_port: u32,
+    #[prost(uint32, tag = "7")]
+    pub tcp_flags: u32,
+    #[prost(uint32, tag = "8")]
+    pub tos: u32,
+}
+
+/// Sampled IPv6 - Format (0,4)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SampledIpv6 {
+    #[prost(uint32, tag = "1")]
+    pub length: u32,
+    #[prost(uint32, tag = "2")]
+    pub protocol: u32,
+    #[prost(bytes = "vec", tag = "3")]
+    pub src_ip: Vec<u8>,
+    #[prost(bytes = "vec", tag = "4")]
+    pub dst_ip: Vec<u8>,
+    #[prost(uint32, tag = "5")]
+    pub src_port: u32,
+    #[prost(uint32, tag = "6")]
+    pub dst_port: u32,
+    #[prost(uint32, tag = "7")]
+    pub tcp_flags: u32,
+    #[prost(uint32, tag = "8")]
+    pub priority: u32,
+}
+
+/// Flow record
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct FlowRecord {
+    #[prost(oneof = "flow_record::Record", tags = "1, 3, 4")]
+    pub record: Option<flow_record::Record>,
+}
+
+/// Nested types for [`FlowRecord`]
+pub mod flow_record {
+    /// Flow record payload
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Record {
+        #[prost(message, tag = "1")]
+        SampledHeader(super::SampledHeader),
+        #[prost(message, tag = "3")]
+        SampledIpv4(super::SampledIpv4),
+        #[prost(message, tag = "4")]
+        SampledIpv6(super::SampledIpv6),
+    }
+}
+
+/// Generic Interface Counters - Format (0,1)
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GenericInterfaceCounters {
+    #[prost(uint32, tag = "1")]
+    pub if_index: u32,
+    #[prost(uint32, tag = "2")]
+    pub if_type: u32,
+    #[prost(uint64, tag = "3")]
+    pub if_speed: u64,
+    #[prost(uint32, tag = "4")]
+    pub if_direction: u32,
+    #[prost(uint32, tag = "5")]
+    pub if_status: u32,
+    #[prost(uint64, tag = "6")]
+    pub if_in_octets: u64,
+    #[prost(uint32, tag = "7")]
+    pub if_in_ucast_pkts: u32,
+    #[prost(uint32, tag = "8")]
+    pub if_in_multicast_pkts: u32,
+    #[prost(uint32, tag = "9")]
+    pub if_in_broadcast_pkts: u32,
+    #[prost(uint32, tag = "10")]
+    pub if_in_discards: u32,
+    #[prost(uint32, tag = "11")]
+    pub if_in_errors: u32,
+    #[prost(uint32, tag = "12")]
+    pub if_in_unknown_protos: u32,
+    #[prost(uint64, tag = "13")]
+    pub if_out_octets: u64,
+    #[prost(uint32, tag = "14")]
+    pub if_out_ucast_pkts: u32,
+    #[prost(uint32, tag = "15")]
+    pub if_out_multicast_pkts: u32,
+    #[prost(uint32, tag = "16")]
+    pub if_out_broadcast_pkts: u32,
+    #[prost(uint32, tag = "17")]
+    pub if_out_discards: u32,
+    #[prost(uint32, tag = "18")]
+    pub if_out_errors: u32,
+    #[prost(uint32, tag = "19")]
+    pub if_promiscuous_mode: u32,
+}
+
+/// Counter record
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CounterRecord {
+    #[prost(oneof = "counter_record::Record", tags = "1")]
+    pub record: Option<counter_record::Record>,
+}
+
+/// Nested types for [`CounterRecord`]
+pub mod counter_record {
+    /// Counter record payload
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Record {
+        #[prost(message, tag = "1")]
+        GenericInterface(super::GenericInterfaceCounters),
+    }
+}
+
+impl From<&SampledHeaderModel> for SampledHeader {
+    fn from(h: &SampledHeaderModel) -> Self {
+        Self {
+            protocol: h.protocol.as_u32(),
+            frame_length: h.frame_length,
+            stripped: h.stripped,
+            header: h.header.clone(),
+        }
+    }
+}
+
+impl From<&SampledIpv4Model> for SampledIpv4 {
+    fn from(s: &SampledIpv4Model) -> Self {
+        Self {
+            length: s.length,
+            protocol: s.protocol,
+            src_ip: s.src_ip.octets().to_vec(),
+            dst_ip: s.dst_ip.octets().to_vec(),
+            src_port: s.src_port,
+            dst_port: s.dst_port,
+            tcp_flags: s.tcp_flags,
+            tos: s.tos,
+        }
+    }
+}
+
+impl From<&SampledIpv6Model> for SampledIpv6 {
+    fn from(s: &SampledIpv6Model) -> Self {
+        Self {
+            length: s.length,
+            protocol: s.protocol,
+            src_ip: s.src_ip.octets().to_vec(),
+            dst_ip: s.dst_ip.octets().to_vec(),
+            src_port: s.src_port,
+            dst_port: s.dst_port,
+            tcp_flags: s.tcp_flags,
+            priority: s.priority,
+        }
+    }
+}
+
+impl From<&FlowData> for FlowRecord {
+    fn from(data: &FlowData) -> Self {
+        let record = match data {
+            FlowData::SampledHeader(h) => Some(flow_record::Record::SampledHeader(h.into())),
+            FlowData::SampledIpv4(s) => Some(flow_record::Record::SampledIpv4(s.into())),
+            FlowData::SampledIpv6(s) => Some(flow_record::Record::SampledIpv6(s.into())),
+            _ => None,
+        };
+        Self { record }
+    }
+}
+
+impl From<&GenericInterfaceModel> for GenericInterfaceCounters {
+    fn from(c: &GenericInterfaceModel) -> Self {
+        Self {
+            if_index: c.if_index,
+            if_type: c.if_type,
+            if_speed: c.if_speed,
+            if_direction: c.if_direction,
+            if_status: c.if_status,
+            if_in_octets: c.if_in_octets,
+            if_in_ucast_pkts: c.if_in_ucast_pkts,
+            if_in_multicast_pkts: c.if_in_multicast_pkts,
+            if_in_broadcast_pkts: c.if_in_broadcast_pkts,
+            if_in_discards: c.if_in_discards,
+            if_in_errors: c.if_in_errors,
+            if_in_unknown_protos: c.if_in_unknown_protos,
+            if_out_octets: c.if_out_octets,
+            if_out_ucast_pkts: c.if_out_ucast_pkts,
+            if_out_multicast_pkts: c.if_out_multicast_pkts,
+            if_out_broadcast_pkts: c.if_out_broadcast_pkts,
+            if_out_discards: c.if_out_discards,
+            if_out_errors: c.if_out_errors,
+            if_promiscuous_mode: c.if_promiscuous_mode,
+        }
+    }
+}
+
+impl From<&CounterData> for CounterRecord {
+    fn from(data: &CounterData) -> Self {
+        let record = match data {
+            CounterData::GenericInterface(c) => {
+                Some(counter_record::Record::GenericInterface(c.into()))
+            }
+            _ => None,
+        };
+        Self { record }
+    }
+}
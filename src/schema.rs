@@ -0,0 +1,14 @@
+//! JSON Schema export for the sFlow model types
+//!
+//! Available with the `schemars` feature. The schema describes the JSON
+//! produced by serializing the models with the `serde` feature.
+
+use crate::models::SFlowDatagram;
+
+/// Generate the JSON Schema of an [`SFlowDatagram`]
+///
+/// All nested model types (samples, flow and counter records) are emitted
+/// as definitions under `$defs`.
+pub fn sflow_json_schema() -> serde_json::Value {
+    schemars::schema_for!(SFlowDatagram).to_value()
+}
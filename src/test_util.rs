@@ -0,0 +1,252 @@
+//! Test helpers to encode records into sFlow v5 XDR bytes
+//!
+//! Available in the crate's own tests and with the `test-util` feature.
+//! Writing record byte arrays by hand is error-prone, these helpers build
+//! them from the model types so tests can describe records structurally:
+//!
+//! ```
+//! use sflow_parser::models::record_flows::SampledIpv4;
+//! use sflow_parser::models::DataFormat;
+//! use sflow_parser::test_util::*;
+//! use std::net::Ipv4Addr;
+//!
+//! let sampled_ipv4 = SampledIpv4 {
+//!     length: 1500,
+//!     protocol: 6,
+//!     src_ip: Ipv4Addr::new(192, 0, 2, 10),
+//!     dst_ip: Ipv4Addr::new(198, 51, 100, 20),
+//!     src_port: 49152,
+//!     dst_port: 443,
+//!     tcp_flags: 0x18,
+//!     tos: 0,
+//! };
+//! let record = encode_sampled_ipv4(&sampled_ipv4);
+//! let data = flow_sample_datagram(&[(DataFormat::new(0, 3), record)]);
+//! let datagram = sflow_parser::parse_datagram(&data)?;
+//! assert_eq!(datagram.samples.len(), 1);
+//! # Ok::<(), sflow_parser::ParseError>(())
+//! ```
+//!
+//! Datagrams are written with the crate encoder (see [`encode_datagram`]),
+//! so fixtures and encoded models share one XDR writer.
+
+use crate::encode::{self, Sink};
+use crate::models::record_counters::GenericInterfaceCounters;
+use crate::models::record_flows::{
+    ExtendedRouter, ExtendedSwitch, ExtendedUser, SampledEthernet, SampledHeader, SampledIpv4,
+    SampledIpv6,
+};
+use crate::models::{
+    Address, CounterData, CounterRecord, CountersSample, CountersSampleExpanded, DataFormat,
+    DataSource, DataSourceExpanded, FlowData, FlowRecord, FlowSample, Interface, SFlowDatagram,
+    SampleData, SampleRecord,
+};
+
+pub use crate::encode::{
+    encode_counter_data, encode_counters_sample, encode_counters_sample_expanded, encode_datagram,
+    encode_flow_data, encode_flow_sample, encode_flow_sample_expanded, encode_sample_data,
+    reencode,
+};
+
+/// Encode Sampled Header - Format (0,1)
+pub fn encode_sampled_header(record: &SampledHeader) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(16 + record.header.len() + 3);
+    encode::put_sampled_header(&mut buf, record);
+    buf
+}
+
+/// Encode Sampled Ethernet - Format (0,2)
+pub fn encode_sampled_ethernet(record: &SampledEthernet) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(20);
+    encode::put_sampled_ethernet(&mut buf, record);
+    buf
+}
+
+/// Encode Sampled IPv4 - Format (0,3)
+pub fn encode_sampled_ipv4(record: &SampledIpv4) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(32);
+    encode::put_sampled_ipv4(&mut buf, record);
+    buf
+}
+
+/// Encode Sampled IPv6 - Format (0,4)
+pub fn encode_sampled_ipv6(record: &SampledIpv6) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(56);
+    encode::put_sampled_ipv6(&mut buf, record);
+    buf
+}
+
+/// Encode Extended Switch - Format (0,1001)
+pub fn encode_extended_switch(record: &ExtendedSwitch) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(16);
+    encode::put_extended_switch(&mut buf, record);
+    buf
+}
+
+/// Encode Extended Router - Format (0,1002)
+pub fn encode_extended_router(record: &ExtendedRouter) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(28);
+    encode::put_extended_router(&mut buf, record);
+    buf
+}
+
+/// Encode Extended User - Format (0,1004)
+pub fn encode_extended_user(record: &ExtendedUser) -> Vec<u8> {
+    let mut buf = Vec::new();
+    encode::put_extended_user(&mut buf, record);
+    buf
+}
+
+/// Encode Generic Interface Counters - Format (0,1)
+pub fn encode_generic_interface(record: &GenericInterfaceCounters) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(88);
+    encode::put_generic_interface_counters(&mut buf, record);
+    buf
+}
+
+/// Wrap record data in a flow or counter record header (format + length)
+pub fn encode_record(format: DataFormat, data: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(8 + data.len() + 3);
+    buf.put_u32(format.0);
+    buf.put_opaque(data);
+    buf
+}
+
+/// Builder of counter samples, e.g. to emulate an agent in tests
+///
+/// The sequence number and source default to 0. The format of each record
+/// is taken from its data.
+///
+/// ```
+/// use sflow_parser::models::record_counters::OpenFlowPort;
+/// use sflow_parser::models::{CounterData, DataSource};
+/// use sflow_parser::test_util::*;
+///
+/// let port = OpenFlowPort {
+///     datapath_id: 1,
+///     port_no: 3,
+/// };
+/// let sample = CounterSampleBuilder::new()
+///     .sequence(7)
+///     .source(DataSource::new(0, 3))
+///     .add_counter(CounterData::OpenFlowPort(port))
+///     .build();
+/// let data = counters_sample_datagram(&sample);
+/// let datagram = sflow_parser::parse_datagram(&data)?;
+/// assert_eq!(datagram.samples.len(), 1);
+/// # Ok::<(), sflow_parser::ParseError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct CounterSampleBuilder {
+    sequence_number: u32,
+    source_id: DataSource,
+    counters: Vec<CounterRecord>,
+}
+
+impl CounterSampleBuilder {
+    /// Create a builder for an empty counter sample
+    pub fn new() -> Self {
+        Self {
+            sequence_number: 0,
+            source_id: DataSource(0),
+            counters: Vec::new(),
+        }
+    }
+
+    /// Set the sample sequence number
+    pub fn sequence(mut self, sequence_number: u32) -> Self {
+        self.sequence_number = sequence_number;
+        self
+    }
+
+    /// Set the data source the counters belong to
+    pub fn source(mut self, source_id: DataSource) -> Self {
+        self.source_id = source_id;
+        self
+    }
+
+    /// Append a counter record
+    pub fn add_counter(mut self, counter_data: CounterData) -> Self {
+        self.counters.push(CounterRecord {
+            counter_format: counter_data.data_format(),
+            counter_data,
+        });
+        self
+    }
+
+    /// Build a compact counter sample
+    pub fn build(self) -> CountersSample {
+        CountersSample {
+            sequence_number: self.sequence_number,
+            source_id: self.source_id,
+            counters: self.counters,
+        }
+    }
+
+    /// Build an expanded counter sample
+    pub fn build_expanded(self) -> CountersSampleExpanded {
+        CountersSampleExpanded {
+            sequence_number: self.sequence_number,
+            source_id: DataSourceExpanded::from(self.source_id),
+            counters: self.counters,
+        }
+    }
+}
+
+impl Default for CounterSampleBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Encode a datagram from agent 192.0.2.1 holding one sample
+fn single_sample_datagram(format: u32, sample_data: SampleData) -> Vec<u8> {
+    let mut datagram = SFlowDatagram::new(Address::IPv4([192, 0, 2, 1].into()), 0, 1, 1000);
+    datagram.samples.push(SampleRecord {
+        sample_type: DataFormat::new(0, format),
+        sample_data,
+    });
+    encode_datagram(&datagram).expect("test samples are encodable")
+}
+
+/// Build a datagram holding a single compact counter sample
+///
+/// The agent is 192.0.2.1, as for [`flow_sample_datagram`].
+pub fn counters_sample_datagram(sample: &CountersSample) -> Vec<u8> {
+    single_sample_datagram(2, SampleData::CountersSample(sample.clone()))
+}
+
+/// Build a datagram holding a single expanded counter sample
+pub fn counters_sample_expanded_datagram(sample: &CountersSampleExpanded) -> Vec<u8> {
+    single_sample_datagram(4, SampleData::CountersSampleExpanded(sample.clone()))
+}
+
+/// Build a datagram holding a single compact flow sample with the given
+/// (format, record data) flow records
+///
+/// The agent is 192.0.2.1, the sample uses source ID 1, sampling rate
+/// 1024 and input/output interfaces 1 and 2.
+pub fn flow_sample_datagram(records: &[(DataFormat, Vec<u8>)]) -> Vec<u8> {
+    // Record data is written back as is, like the data of an unknown record
+    let flow_records = records
+        .iter()
+        .map(|(format, data)| FlowRecord {
+            flow_format: *format,
+            flow_data: FlowData::Unknown {
+                format: *format,
+                data: data.clone(),
+            },
+        })
+        .collect();
+    let sample = FlowSample {
+        sequence_number: 1,
+        source_id: DataSource(1),
+        sampling_rate: 1024,
+        sample_pool: 1024,
+        drops: 0,
+        input: Interface(1),
+        output: Interface(2),
+        flow_records,
+    };
+    single_sample_datagram(1, SampleData::FlowSample(sample))
+}
@@ -0,0 +1,56 @@
+//! Fixtures shared by the integration tests
+//!
+//! Each test crate includes this module and uses part of it.
+
+#![allow(dead_code)]
+
+use sflow_parser::encode::encode_datagram;
+use sflow_parser::models::record_flows::{ExtendedSwitch, ExtendedVniIngress};
+use sflow_parser::models::*;
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+
+/// Datagram from agent 192.168.1.1 with one flow sample holding an
+/// extended_vni_ingress (0,1030) and an extended_switch (0,1001) record
+pub fn flow_datagram() -> Vec<u8> {
+    let records = [
+        FlowData::ExtendedVniIngress(ExtendedVniIngress { vni: 5000 }),
+        FlowData::ExtendedSwitch(ExtendedSwitch {
+            src_vlan: 10,
+            src_priority: 0,
+            dst_vlan: 20,
+            dst_priority: 0,
+        }),
+    ];
+    let sample = FlowSample {
+        sequence_number: 1,
+        source_id: DataSource(1),
+        sampling_rate: 1024,
+        sample_pool: 100,
+        drops: 0,
+        input: Interface(1),
+        output: Interface(2),
+        flow_records: records
+            .into_iter()
+            .map(|flow_data| FlowRecord {
+                flow_format: flow_data.data_format(),
+                flow_data,
+            })
+            .collect(),
+    };
+
+    let agent = Address::IPv4(Ipv4Addr::new(192, 168, 1, 1));
+    let mut datagram = SFlowDatagram::new(agent, 0, 1, 100);
+    datagram.samples.push(SampleRecord {
+        sample_type: DataFormat::new(0, 1),
+        sample_data: SampleData::FlowSample(sample),
+    });
+    encode_datagram(&datagram).unwrap()
+}
+
+/// Write `data` to a file of the temporary directory, unique to the process
+pub fn write_temp(name: &str, data: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("sflow-parser-{}-{}", std::process::id(), name));
+    std::fs::write(&path, data).unwrap();
+    path
+}
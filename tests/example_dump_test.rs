@@ -0,0 +1,33 @@
+//! Tests for the dump example
+//!
+//! These tests run the example's logic on a crafted file, so the example
+//! keeps compiling against the public API.
+
+#[allow(dead_code)]
+#[path = "../examples/dump.rs"]
+mod dump;
+
+mod common;
+
+use common::{flow_datagram, write_temp};
+
+#[test]
+fn test_dump_concatenated_datagrams() {
+    let mut data = flow_datagram();
+    data.extend(flow_datagram());
+    let path = write_temp("dump.bin", &data);
+
+    let mut out = Vec::new();
+    let count = dump::dump(&path, &mut out).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(count, 2);
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(text.matches("192.168.1.1").count(), 2);
+}
+
+#[test]
+fn test_dump_missing_file() {
+    let path = std::env::temp_dir().join("sflow-parser-missing-dump.bin");
+    assert!(dump::dump(&path, &mut Vec::new()).is_err());
+}
@@ -0,0 +1,57 @@
+//! Tests for the listener metrics
+//!
+//! These tests verify that the listener reports its counters through the
+//! metrics facade when the metrics feature is enabled.
+
+#![cfg(feature = "metrics")]
+
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+use sflow_parser::listener::SFlowListener;
+use std::collections::HashMap;
+
+mod common;
+
+use common::flow_datagram;
+
+/// Counter values of a snapshot, summed over all label sets
+///
+/// Taking a snapshot resets the debugging recorder, so take it once per test.
+fn counter_values(recorder: &DebuggingRecorder) -> HashMap<String, u64> {
+    let mut values = HashMap::new();
+    for (key, _, _, value) in recorder.snapshotter().snapshot().into_vec() {
+        if let DebugValue::Counter(value) = value {
+            *values.entry(key.key().name().to_string()).or_default() += value;
+        }
+    }
+    values
+}
+
+#[test]
+fn test_listener_metrics_datagram_received() {
+    let recorder = DebuggingRecorder::new();
+    let mut listener = SFlowListener::bind("127.0.0.1:0").unwrap();
+
+    metrics::with_local_recorder(&recorder, || {
+        listener.process(&flow_datagram()).unwrap();
+    });
+
+    let values = counter_values(&recorder);
+    assert_eq!(values["sflow_datagrams_received"], 1);
+    assert_eq!(values["sflow_samples_total"], 1);
+    assert_eq!(values["sflow_flow_records_total"], 2);
+    assert!(!values.contains_key("sflow_parse_errors"));
+}
+
+#[test]
+fn test_listener_metrics_parse_error() {
+    let recorder = DebuggingRecorder::new();
+    let mut listener = SFlowListener::bind("127.0.0.1:0").unwrap();
+
+    metrics::with_local_recorder(&recorder, || {
+        assert!(listener.process(&[0x00, 0x00, 0x00, 0x04]).is_err());
+    });
+
+    let values = counter_values(&recorder);
+    assert_eq!(values["sflow_datagrams_received"], 1);
+    assert_eq!(values["sflow_parse_errors"], 1);
+}
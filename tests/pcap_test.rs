@@ -0,0 +1,223 @@
+//! Tests for reading sFlow datagrams from pcap files
+//!
+//! These tests verify that UDP payloads are extracted from captured frames
+//! of pcap and pcapng files and parsed, and that packets on other ports are
+//! ignored.
+
+#![cfg(feature = "pcap")]
+
+use sflow_parser::pcap::parse_pcap;
+use sflow_parser::{parse_datagram, parse_datagrams};
+use std::path::PathBuf;
+
+mod common;
+
+use common::write_temp;
+
+/// Minimal sFlow datagram: IPv4 agent 192.0.2.1 without samples
+fn sflow_payload() -> Vec<u8> {
+    let mut data = Vec::new();
+    for value in [5u32, 1] {
+        data.extend_from_slice(&value.to_be_bytes()); // version, address type
+    }
+    data.extend_from_slice(&[192, 0, 2, 1]); // agent address
+    for value in [0u32, 42, 1000, 0] {
+        data.extend_from_slice(&value.to_be_bytes()); // sub agent, sequence, uptime, samples
+    }
+    data
+}
+
+/// Ethernet/IPv4/UDP frame carrying `payload` to `dst_port`
+fn udp_frame(dst_port: u16, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::new();
+    frame.extend_from_slice(&[0x00, 0x11, 0x22, 0x33, 0x44, 0x55]); // dst mac
+    frame.extend_from_slice(&[0x00, 0x66, 0x77, 0x88, 0x99, 0xAA]); // src mac
+    frame.extend_from_slice(&[0x08, 0x00]); // IPv4
+
+    let total_length = (20 + 8 + payload.len()) as u16;
+    frame.extend_from_slice(&[0x45, 0x00]);
+    frame.extend_from_slice(&total_length.to_be_bytes());
+    frame.extend_from_slice(&[0x00, 0x00, 0x40, 0x00, 0x40, 0x11, 0x00, 0x00]);
+    frame.extend_from_slice(&[192, 0, 2, 1, 192, 0, 2, 2]);
+
+    frame.extend_from_slice(&50000u16.to_be_bytes());
+    frame.extend_from_slice(&dst_port.to_be_bytes());
+    frame.extend_from_slice(&((8 + payload.len()) as u16).to_be_bytes());
+    frame.extend_from_slice(&[0x00, 0x00]); // checksum
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Ethernet/IPv6/UDP frame carrying `payload` to port 6343, behind a
+/// hop-by-hop options extension header
+fn udp6_frame(payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::new();
+    frame.extend_from_slice(&[0x00, 0x11, 0x22, 0x33, 0x44, 0x55]); // dst mac
+    frame.extend_from_slice(&[0x00, 0x66, 0x77, 0x88, 0x99, 0xAA]); // src mac
+    frame.extend_from_slice(&[0x86, 0xDD]); // IPv6
+
+    let payload_length = (8 + 8 + payload.len()) as u16;
+    frame.extend_from_slice(&[0x60, 0x00, 0x00, 0x00]);
+    frame.extend_from_slice(&payload_length.to_be_bytes());
+    frame.extend_from_slice(&[0x00, 0x40]); // next header: hop-by-hop, hop limit
+    frame.extend_from_slice(&[0x20, 0x01, 0x0D, 0xB8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    frame.extend_from_slice(&[0x20, 0x01, 0x0D, 0xB8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2]);
+    // Hop-by-hop options: next header UDP, 8 bytes, PadN
+    frame.extend_from_slice(&[17, 0, 1, 4, 0, 0, 0, 0]);
+
+    frame.extend_from_slice(&50000u16.to_be_bytes());
+    frame.extend_from_slice(&6343u16.to_be_bytes());
+    frame.extend_from_slice(&((8 + payload.len()) as u16).to_be_bytes());
+    frame.extend_from_slice(&[0x00, 0x00]); // checksum
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Little-endian microsecond pcap file with Ethernet link type
+fn pcap_file(frames: &[Vec<u8>]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&0xA1B2C3D4u32.to_le_bytes());
+    data.extend_from_slice(&2u16.to_le_bytes());
+    data.extend_from_slice(&4u16.to_le_bytes());
+    data.extend_from_slice(&[0; 8]); // thiszone, sigfigs
+    data.extend_from_slice(&65535u32.to_le_bytes());
+    data.extend_from_slice(&1u32.to_le_bytes()); // LINKTYPE_ETHERNET
+    for frame in frames {
+        data.extend_from_slice(&[0; 8]); // timestamp
+        data.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+        data.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+        data.extend_from_slice(frame);
+    }
+    data
+}
+
+#[test]
+fn test_parse_pcap_synthetic() {
+    let payload = sflow_payload();
+    let data = pcap_file(&[udp_frame(53, &[0xAA; 12]), udp_frame(6343, &payload)]);
+    let path = write_temp("synthetic.pcap", &data);
+
+    let datagrams: Vec<_> = parse_pcap(&path, None).unwrap().collect();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(datagrams.len(), 1);
+    let datagram = datagrams[0].as_ref().unwrap();
+    assert_eq!(*datagram, parse_datagram(&payload).unwrap());
+    assert_eq!(datagram.sequence_number, 42);
+}
+
+#[test]
+fn test_parse_pcap_custom_port() {
+    let data = pcap_file(&[udp_frame(9999, &sflow_payload())]);
+    let path = write_temp("custom-port.pcap", &data);
+
+    assert_eq!(parse_pcap(&path, None).unwrap().count(), 0);
+    assert_eq!(parse_pcap(&path, Some(9999)).unwrap().count(), 1);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_parse_pcap_fixture() {
+    let path = PathBuf::from("tests/data/sflow.pcap");
+    let datagrams: Vec<_> = parse_pcap(&path, None)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    // sflow.bin holds the payload extracted from the same capture
+    let payload = std::fs::read("tests/data/sflow.bin").unwrap();
+    assert_eq!(datagrams, parse_datagrams(&payload).unwrap());
+}
+
+#[test]
+fn test_parse_pcap_rejects_non_pcap() {
+    let path = write_temp("not-a.pcap", &[0u8; 32]);
+    assert!(parse_pcap(&path, None).is_err());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_parse_pcap_rejects_unsupported_link_type() {
+    let mut data = pcap_file(&[udp_frame(6343, &sflow_payload())]);
+    data[20..24].copy_from_slice(&105u32.to_le_bytes()); // LINKTYPE_IEEE802_11
+    let path = write_temp("wifi.pcap", &data);
+    let err = parse_pcap(&path, None).err().unwrap();
+    assert!(err.to_string().contains("link type 105"), "{}", err);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_parse_pcap_truncated_record() {
+    let mut data = pcap_file(&[
+        udp_frame(6343, &sflow_payload()),
+        udp_frame(6343, &sflow_payload()),
+    ]);
+    data.truncate(data.len() - 10);
+    let path = write_temp("truncated.pcap", &data);
+    let results: Vec<_> = parse_pcap(&path, None).unwrap().collect();
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(results[1]
+        .as_ref()
+        .unwrap_err()
+        .to_string()
+        .contains("Truncated"));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_parse_pcap_ipv6_extension_header() {
+    let payload = sflow_payload();
+    let data = pcap_file(&[udp6_frame(&payload)]);
+    let path = write_temp("ipv6.pcap", &data);
+
+    let datagrams: Vec<_> = parse_pcap(&path, None).unwrap().collect();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(datagrams.len(), 1);
+    assert_eq!(
+        *datagrams[0].as_ref().unwrap(),
+        parse_datagram(&payload).unwrap()
+    );
+}
+
+#[test]
+fn test_parse_pcapng() {
+    use pcap_file::pcapng::blocks::enhanced_packet::EnhancedPacketBlock;
+    use pcap_file::pcapng::blocks::interface_description::InterfaceDescriptionBlock;
+    use pcap_file::pcapng::PcapNgWriter;
+    use pcap_file::DataLink;
+    use std::borrow::Cow;
+    use std::time::Duration;
+
+    let payload = sflow_payload();
+    let mut writer = PcapNgWriter::new(Vec::new()).unwrap();
+    writer
+        .write_pcapng_block(InterfaceDescriptionBlock {
+            linktype: DataLink::ETHERNET,
+            snaplen: 65535,
+            options: vec![],
+        })
+        .unwrap();
+    for frame in [udp_frame(53, &[0xAA; 12]), udp_frame(6343, &payload)] {
+        writer
+            .write_pcapng_block(EnhancedPacketBlock {
+                interface_id: 0,
+                timestamp: Duration::ZERO,
+                original_len: frame.len() as u32,
+                data: Cow::Owned(frame),
+                options: vec![],
+            })
+            .unwrap();
+    }
+    let path = write_temp("synthetic.pcapng", &writer.into_inner());
+
+    let datagrams: Vec<_> = parse_pcap(&path, None).unwrap().collect();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(datagrams.len(), 1);
+    assert_eq!(
+        *datagrams[0].as_ref().unwrap(),
+        parse_datagram(&payload).unwrap()
+    );
+}
@@ -0,0 +1,137 @@
+//! Tests for the datagram pretty-printer
+//!
+//! These tests verify the rendered tree and that color codes are only emitted
+//! with the color feature and when requested.
+
+#![cfg(feature = "pretty")]
+
+use sflow_parser::models::record_flows::{ExtendedSwitch, SampledIpv4};
+use sflow_parser::models::*;
+use sflow_parser::pretty::{pretty_print, pretty_print_plain, pretty_print_with_color};
+use std::net::Ipv4Addr;
+
+fn datagram() -> SFlowDatagram {
+    let mut datagram = SFlowDatagram::new(Address::IPv4(Ipv4Addr::new(192, 0, 2, 1)), 0, 7, 1000);
+    datagram.samples.push(SampleRecord {
+        sample_type: DataFormat::new(0, 1),
+        sample_data: SampleData::FlowSample(FlowSample {
+            sequence_number: 1,
+            source_id: DataSource::new(0, 3),
+            sampling_rate: 1024,
+            sample_pool: 4096,
+            drops: 0,
+            input: Interface(3),
+            output: Interface(4),
+            flow_records: vec![
+                FlowRecord {
+                    flow_format: DataFormat::new(0, 3),
+                    flow_data: FlowData::SampledIpv4(SampledIpv4 {
+                        length: 64,
+                        protocol: 6,
+                        src_ip: Ipv4Addr::new(10, 0, 0, 1),
+                        dst_ip: Ipv4Addr::new(10, 0, 0, 2),
+                        src_port: 40000,
+                        dst_port: 443,
+                        tcp_flags: 0x18,
+                        tos: 0,
+                    }),
+                },
+                FlowRecord {
+                    flow_format: DataFormat::new(9999, 1),
+                    flow_data: FlowData::Unknown {
+                        format: DataFormat::new(9999, 1),
+                        data: vec![0; 8],
+                    },
+                },
+            ],
+        }),
+    });
+    datagram
+}
+
+#[test]
+fn test_pretty_print_plain() {
+    let text = pretty_print_plain(&datagram());
+    let lines: Vec<&str> = text.lines().collect();
+
+    assert!(!text.contains('\x1b'));
+    assert_eq!(
+        lines[0],
+        "sFlow v5 agent=192.0.2.1 sub_agent_id=0 seq=7 uptime=1000ms samples=1"
+    );
+    assert_eq!(
+        lines[1],
+        "  FlowSample seq=1 source=0:3 rate=1024 pool=4096 drops=0 input=3 output=4"
+    );
+    assert_eq!(
+        lines[2],
+        "    0:3 SampledIpv4 10.0.0.1:40000 -> 10.0.0.2:443 TCP"
+    );
+    assert_eq!(lines[3], "    9999:1 Unknown 8 bytes");
+}
+
+#[test]
+fn test_pretty_print_with_color() {
+    let datagram = datagram();
+
+    let text = pretty_print_with_color(&datagram, false);
+    assert_eq!(text, pretty_print_plain(&datagram));
+
+    let text = pretty_print_with_color(&datagram, true);
+    if cfg!(feature = "color") {
+        assert!(text.contains("\x1b[32m10.0.0.1\x1b[0m"));
+        assert!(text.contains("\x1b[31;1mUnknown\x1b[0m"));
+    } else {
+        assert_eq!(text, pretty_print_plain(&datagram));
+    }
+}
+
+#[test]
+fn test_pretty_print_record_fields() {
+    let mut datagram = SFlowDatagram::new(Address::Unknown, 0, 1, 0);
+    datagram.samples.push(SampleRecord {
+        sample_type: DataFormat::new(0, 5),
+        sample_data: SampleData::DiscardedPacket(DiscardedPacket {
+            sequence_number: 2,
+            source_id: DataSourceExpanded {
+                source_id_type: 0,
+                source_id_index: 3,
+            },
+            drops: 0,
+            input_ifindex: 3,
+            output_ifindex: 0,
+            reason: DropReason::BlackholeRoute,
+            flow_records: vec![FlowRecord {
+                flow_format: DataFormat::new(0, 1001),
+                flow_data: FlowData::ExtendedSwitch(ExtendedSwitch {
+                    src_vlan: 10,
+                    src_priority: 0,
+                    dst_vlan: 20,
+                    dst_priority: 1,
+                }),
+            }],
+        }),
+    });
+
+    let text = pretty_print_plain(&datagram);
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(
+        lines[1],
+        "  DiscardedPacket seq=2 source=0:3 drops=0 input=3 output=0 reason=blackhole_route"
+    );
+    assert_eq!(
+        lines[2],
+        "    0:1001 ExtendedSwitch src_vlan=10 src_priority=0 dst_vlan=20 dst_priority=1"
+    );
+}
+
+#[test]
+fn test_pretty_print_no_color() {
+    // Only this test reads the environment, the others pass the color decision
+    std::env::set_var("NO_COLOR", "1");
+    let text = pretty_print(&datagram());
+    std::env::remove_var("NO_COLOR");
+
+    assert!(!text.contains("\x1b["));
+    assert_eq!(text, pretty_print_plain(&datagram()));
+}
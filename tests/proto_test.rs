@@ -0,0 +1,92 @@
+//! Tests for the protobuf conversions
+//!
+//! These tests verify that records converted with the prost feature survive
+//! an encode/decode round trip.
+
+#![cfg(feature = "prost")]
+
+use prost::Message;
+use sflow_parser::models::record_counters::GenericInterfaceCounters;
+use sflow_parser::models::record_flows::SampledIpv4;
+use sflow_parser::models::{CounterData, FlowData};
+use sflow_parser::proto::{counter_record, flow_record, CounterRecord, FlowRecord};
+use std::net::Ipv4Addr;
+
+#[test]
+fn test_sampled_ipv4_round_trip() {
+    let flow = FlowData::SampledIpv4(SampledIpv4 {
+        length: 1500,
+        protocol: 6,
+        src_ip: Ipv4Addr::new(192, 0, 2, 1),
+        dst_ip: Ipv4Addr::new(198, 51, 100, 7),
+        src_port: 49152,
+        dst_port: 443,
+        tcp_flags: 0x18,
+        tos: 0,
+    });
+
+    let bytes = FlowRecord::from(&flow).encode_to_vec();
+    let decoded = FlowRecord::decode(bytes.as_slice()).unwrap();
+
+    let Some(flow_record::Record::SampledIpv4(ipv4)) = decoded.record else {
+        panic!("Expected SampledIpv4");
+    };
+    let src: [u8; 4] = ipv4.src_ip.as_slice().try_into().unwrap();
+    let dst: [u8; 4] = ipv4.dst_ip.as_slice().try_into().unwrap();
+    assert_eq!(Ipv4Addr::from(src), Ipv4Addr::new(192, 0, 2, 1));
+    assert_eq!(Ipv4Addr::from(dst), Ipv4Addr::new(198, 51, 100, 7));
+    assert_eq!(ipv4.src_port, 49152);
+    assert_eq!(ipv4.dst_port, 443);
+    assert_eq!(ipv4.protocol, 6);
+    assert_eq!(ipv4.length, 1500);
+    assert_eq!(ipv4.tcp_flags, 0x18);
+}
+
+#[test]
+fn test_generic_interface_round_trip() {
+    let counters = CounterData::GenericInterface(GenericInterfaceCounters {
+        if_index: 3,
+        if_type: 6,
+        if_speed: 10_000_000_000,
+        if_direction: 1,
+        if_status: 3,
+        if_in_octets: 1 << 40,
+        if_in_ucast_pkts: 1000,
+        if_in_multicast_pkts: 10,
+        if_in_broadcast_pkts: 1,
+        if_in_discards: 0,
+        if_in_errors: 2,
+        if_in_unknown_protos: 0,
+        if_out_octets: 1 << 33,
+        if_out_ucast_pkts: 900,
+        if_out_multicast_pkts: 9,
+        if_out_broadcast_pkts: 0,
+        if_out_discards: 1,
+        if_out_errors: 0,
+        if_promiscuous_mode: 0,
+    });
+
+    let bytes = CounterRecord::from(&counters).encode_to_vec();
+    let decoded = CounterRecord::decode(bytes.as_slice()).unwrap();
+
+    let Some(counter_record::Record::GenericInterface(generic)) = decoded.record else {
+        panic!("Expected GenericInterface");
+    };
+    assert_eq!(generic.if_index, 3);
+    assert_eq!(generic.if_speed, 10_000_000_000);
+    assert_eq!(generic.if_in_octets, 1 << 40);
+    assert_eq!(generic.if_out_octets, 1 << 33);
+    assert_eq!(generic.if_in_errors, 2);
+}
+
+#[test]
+fn test_unmapped_record_has_no_payload() {
+    let flow = FlowData::ExtendedUser(sflow_parser::models::record_flows::ExtendedUser {
+        src_charset: 106,
+        src_user: "alice".to_string(),
+        dst_charset: 106,
+        dst_user: "bob".to_string(),
+    });
+
+    assert_eq!(FlowRecord::from(&flow).record, None);
+}
@@ -0,0 +1,35 @@
+//! Tests for the JSON Schema export
+//!
+//! These tests verify that the schema generated with the schemars feature
+//! describes the sFlow data structures.
+
+#![cfg(feature = "schemars")]
+
+use sflow_parser::sflow_json_schema;
+
+#[test]
+fn test_schema_contains_record_definitions() {
+    let schema = sflow_json_schema();
+
+    assert_eq!(schema["title"], "SFlowDatagram");
+    let defs = schema["$defs"].as_object().expect("schema has definitions");
+    assert!(defs.contains_key("FlowData"));
+    assert!(defs.contains_key("CounterData"));
+    assert!(defs.contains_key("SampleData"));
+}
+
+#[test]
+fn test_schema_datagram_properties() {
+    let schema = sflow_json_schema();
+
+    let properties = schema["properties"].as_object().unwrap();
+    for field in [
+        "agent_address",
+        "sub_agent_id",
+        "sequence_number",
+        "uptime",
+        "samples",
+    ] {
+        assert!(properties.contains_key(field), "missing property {}", field);
+    }
+}
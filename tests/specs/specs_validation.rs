@@ -889,6 +889,15 @@ fn is_intentional_deviation(xdr_fields: &[XdrField], rust_fields: &[FieldMetadat
         return true;
     }
 
+    // ExtendedGateway (0,1003) - keep vendor extension bytes after localpref
+    if xdr_fields.len() == 7
+        && rust_fields.len() == 8
+        && rust_fields.iter().any(|f| f.name == "local_pref")
+        && rust_fields.last().is_some_and(|f| f.name == "trailing")
+    {
+        return true;
+    }
+
     // ExtendedMplsFec (0,1010) - use Address type instead of string/mask
     if xdr_fields.len() == 2
         && rust_fields.len() == 2
@@ -900,7 +909,8 @@ fn is_intentional_deviation(xdr_fields: &[XdrField], rust_fields: &[FieldMetadat
         return true;
     }
 
-    // Extended80211Rx (0,1014) - add packet_duration field
+    // Extended80211Rx (0,1014) - packet_duration is hidden from the XDR parser
+    // by the missing semicolon in the spec, but is decoded (host-sflow sends it)
     if xdr_fields.len() == 7
         && rust_fields.len() == 8
         && rust_fields.iter().any(|f| f.name == "packet_duration")
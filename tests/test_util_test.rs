@@ -0,0 +1,358 @@
+//! Tests for the test-util record encoders
+//!
+//! These tests build each basic flow record structurally, encode it and
+//! verify that parsing the bytes yields the same record.
+
+#![cfg(feature = "test-util")]
+
+use sflow_parser::models::record_flows::*;
+use sflow_parser::models::*;
+use sflow_parser::parse_datagram;
+use sflow_parser::test_util::*;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Encode a single record into a datagram, parse it back and return the flow data
+fn round_trip(format: DataFormat, record: Vec<u8>) -> FlowData {
+    let data = flow_sample_datagram(&[(format, record)]);
+    let datagram = parse_datagram(&data).unwrap();
+    match datagram.samples.into_iter().next().unwrap().sample_data {
+        SampleData::FlowSample(sample) => {
+            let record = sample.flow_records.into_iter().next().unwrap();
+            assert_eq!(record.flow_format, format);
+            record.flow_data
+        }
+        _ => panic!("Expected FlowSample"),
+    }
+}
+
+#[test]
+fn test_encode_sampled_header() {
+    let header = SampledHeader {
+        protocol: HeaderProtocol::EthernetIso88023,
+        frame_length: 1518,
+        stripped: 4,
+        header: (0..14).collect(), // not a multiple of 4, exercises padding
+    };
+    let flow = round_trip(DataFormat::new(0, 1), encode_sampled_header(&header));
+    assert_eq!(flow, FlowData::SampledHeader(header));
+}
+
+#[test]
+fn test_encode_sampled_ethernet() {
+    let ethernet = SampledEthernet {
+        length: 1518,
+        src_mac: MacAddress::new([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]),
+        dst_mac: MacAddress::new([0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB]),
+        eth_type: 0x0800,
+    };
+    let flow = round_trip(DataFormat::new(0, 2), encode_sampled_ethernet(&ethernet));
+    assert_eq!(flow, FlowData::SampledEthernet(ethernet));
+}
+
+#[test]
+fn test_encode_sampled_ipv4() {
+    let ipv4 = SampledIpv4 {
+        length: 1500,
+        protocol: 6,
+        src_ip: Ipv4Addr::new(192, 168, 1, 100),
+        dst_ip: Ipv4Addr::new(10, 0, 0, 1),
+        src_port: 49152,
+        dst_port: 443,
+        tcp_flags: 0x18,
+        tos: 0x10,
+    };
+    let flow = round_trip(DataFormat::new(0, 3), encode_sampled_ipv4(&ipv4));
+    assert_eq!(flow, FlowData::SampledIpv4(ipv4));
+}
+
+#[test]
+fn test_encode_sampled_ipv6() {
+    let ipv6 = SampledIpv6 {
+        length: 1280,
+        protocol: 17,
+        src_ip: "2001:db8::1".parse().unwrap(),
+        dst_ip: Ipv6Addr::LOCALHOST,
+        src_port: 5353,
+        dst_port: 53,
+        tcp_flags: 0,
+        priority: 3,
+    };
+    let flow = round_trip(DataFormat::new(0, 4), encode_sampled_ipv6(&ipv6));
+    assert_eq!(flow, FlowData::SampledIpv6(ipv6));
+}
+
+#[test]
+fn test_encode_extended_switch() {
+    let switch = ExtendedSwitch {
+        src_vlan: 100,
+        src_priority: 3,
+        dst_vlan: 200,
+        dst_priority: 0xFFFFFFFF,
+    };
+    let flow = round_trip(DataFormat::new(0, 1001), encode_extended_switch(&switch));
+    assert_eq!(flow, FlowData::ExtendedSwitch(switch));
+}
+
+#[test]
+fn test_encode_extended_router() {
+    for next_hop in [
+        Address::Unknown,
+        Address::IPv4(Ipv4Addr::new(192, 168, 1, 1)),
+        Address::IPv6("fe80::1".parse().unwrap()),
+    ] {
+        let router = ExtendedRouter {
+            next_hop,
+            src_mask_len: 24,
+            dst_mask_len: 16,
+        };
+        let flow = round_trip(DataFormat::new(0, 1002), encode_extended_router(&router));
+        assert_eq!(flow, FlowData::ExtendedRouter(router));
+    }
+}
+
+#[test]
+fn test_encode_extended_user() {
+    let user = ExtendedUser {
+        src_charset: 106,
+        src_user: "alice".to_string(),
+        dst_charset: 106,
+        dst_user: "bob".to_string(),
+    };
+    let flow = round_trip(DataFormat::new(0, 1004), encode_extended_user(&user));
+    assert_eq!(flow, FlowData::ExtendedUser(user));
+}
+
+#[test]
+fn test_flow_sample_datagram_multiple_records() {
+    let ipv4 = SampledIpv4 {
+        length: 64,
+        protocol: 17,
+        src_ip: Ipv4Addr::new(10, 0, 0, 1),
+        dst_ip: Ipv4Addr::new(10, 0, 0, 2),
+        src_port: 1234,
+        dst_port: 53,
+        tcp_flags: 0,
+        tos: 0,
+    };
+    let switch = ExtendedSwitch {
+        src_vlan: 10,
+        src_priority: 0,
+        dst_vlan: 20,
+        dst_priority: 0,
+    };
+    let data = flow_sample_datagram(&[
+        (DataFormat::new(0, 3), encode_sampled_ipv4(&ipv4)),
+        (DataFormat::new(0, 1001), encode_extended_switch(&switch)),
+    ]);
+
+    let datagram = parse_datagram(&data).unwrap();
+    assert_eq!(
+        datagram.agent_address,
+        Address::IPv4(Ipv4Addr::new(192, 0, 2, 1))
+    );
+    match &datagram.samples[0].sample_data {
+        SampleData::FlowSample(sample) => {
+            assert_eq!(sample.flow_records.len(), 2);
+            assert_eq!(
+                sample.flow_records[0].flow_data,
+                FlowData::SampledIpv4(ipv4)
+            );
+            assert_eq!(
+                sample.flow_records[1].flow_data,
+                FlowData::ExtendedSwitch(switch)
+            );
+        }
+        _ => panic!("Expected FlowSample"),
+    }
+}
+
+#[test]
+fn test_counter_sample_builder_round_trip() {
+    let counters = sflow_parser::models::record_counters::GenericInterfaceCounters {
+        if_index: 3,
+        if_type: 6,
+        if_speed: 10_000_000_000,
+        if_direction: 1,
+        if_status: 3,
+        if_in_octets: 0x1_0000_0001,
+        if_in_ucast_pkts: 1000,
+        if_in_multicast_pkts: 10,
+        if_in_broadcast_pkts: 5,
+        if_in_discards: 1,
+        if_in_errors: 2,
+        if_in_unknown_protos: 0,
+        if_out_octets: 0x2_0000_0002,
+        if_out_ucast_pkts: 2000,
+        if_out_multicast_pkts: 20,
+        if_out_broadcast_pkts: 6,
+        if_out_discards: 3,
+        if_out_errors: 4,
+        if_promiscuous_mode: 0,
+    };
+    let builder = CounterSampleBuilder::new()
+        .sequence(42)
+        .source(DataSource::new(0, 3))
+        .add_counter(CounterData::GenericInterface(counters));
+
+    let sample = builder.clone().build();
+    assert_eq!(sample.counters[0].counter_format, DataFormat::new(0, 1));
+    let datagram = parse_datagram(&counters_sample_datagram(&sample)).unwrap();
+    assert_eq!(
+        datagram.samples[0].sample_data,
+        SampleData::CountersSample(sample)
+    );
+
+    let sample = builder.build_expanded();
+    assert_eq!(sample.source_id.source_id_index, 3);
+    let datagram = parse_datagram(&counters_sample_expanded_datagram(&sample)).unwrap();
+    assert_eq!(
+        datagram.samples[0].sample_data,
+        SampleData::CountersSampleExpanded(sample)
+    );
+}
+
+#[test]
+fn test_encoded_len_matches_encoding() {
+    let header = SampledHeader {
+        protocol: HeaderProtocol::EthernetIso88023,
+        frame_length: 1518,
+        stripped: 4,
+        header: (0..14).collect(),
+    };
+    let user = ExtendedUser {
+        src_charset: 106,
+        src_user: "alice".to_string(),
+        dst_charset: 106,
+        dst_user: "bob".to_string(),
+    };
+    let data = flow_sample_datagram(&[
+        (DataFormat::new(0, 1), encode_sampled_header(&header)),
+        (DataFormat::new(0, 1004), encode_extended_user(&user)),
+        (DataFormat::new(9999, 1), vec![1, 2, 3]),
+    ]);
+    let mut datagram = parse_datagram(&data).unwrap();
+    assert_eq!(datagram.encoded_len(), data.len());
+    assert_eq!(encode_datagram(&datagram).unwrap(), data);
+
+    // IPv6 agent with a second, counters sample
+    datagram.agent_address = Address::IPv6(Ipv6Addr::LOCALHOST);
+    let counters = CounterSampleBuilder::new()
+        .sequence(2)
+        .add_counter(CounterData::Unknown {
+            format: DataFormat::new(9999, 2),
+            data: vec![0; 6],
+        })
+        .build();
+    datagram.samples.push(SampleRecord {
+        sample_type: DataFormat::new(0, 2),
+        sample_data: SampleData::CountersSample(counters),
+    });
+
+    let data = encode_datagram(&datagram).unwrap();
+    assert_eq!(datagram.encoded_len(), data.len());
+    assert_eq!(parse_datagram(&data).unwrap(), datagram);
+}
+
+#[test]
+fn test_reencode_fixes_sample_length() {
+    let switch = ExtendedSwitch {
+        src_vlan: 10,
+        src_priority: 0,
+        dst_vlan: 20,
+        dst_priority: 0,
+    };
+    let data = flow_sample_datagram(&[(DataFormat::new(0, 1001), encode_extended_switch(&switch))]);
+
+    // Sample length (bytes 32..36) 4 bytes too long, covering trailing junk
+    let mut bad = data.clone();
+    let length = u32::from_be_bytes(bad[32..36].try_into().unwrap());
+    bad[32..36].copy_from_slice(&(length + 4).to_be_bytes());
+    bad.extend_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+
+    let fixed = reencode(&bad).unwrap();
+    assert_eq!(
+        u32::from_be_bytes(fixed[32..36].try_into().unwrap()),
+        length
+    );
+    assert_eq!(fixed, data);
+    assert_eq!(parse_datagram(&bad).unwrap().normalize().unwrap(), data);
+}
+
+#[test]
+fn test_encode_counter_records_round_trip() {
+    use sflow_parser::models::record_counters::*;
+
+    let counters = [
+        CounterData::LagPortStats(LagPortStats {
+            dot3ad_agg_port_actor_system_id: MacAddress::new([0, 1, 2, 3, 4, 5]),
+            dot3ad_agg_port_partner_oper_system_id: MacAddress::new([6, 7, 8, 9, 10, 11]),
+            dot3ad_agg_port_attached_agg_id: 7,
+            dot3ad_agg_port_state: [1, 2, 3, 4],
+            dot3ad_agg_port_stats_lacpd_us_rx: 1,
+            dot3ad_agg_port_stats_marker_pdus_rx: 2,
+            dot3ad_agg_port_stats_marker_response_pdus_rx: 3,
+            dot3ad_agg_port_stats_unknown_rx: 4,
+            dot3ad_agg_port_stats_illegal_rx: 5,
+            dot3ad_agg_port_stats_lacpd_us_tx: 6,
+            dot3ad_agg_port_stats_marker_pdus_tx: 7,
+            dot3ad_agg_port_stats_marker_response_pdus_tx: 8,
+        }),
+        CounterData::OpticalSfpQsfp(OpticalSfpQsfp {
+            module_id: 1,
+            module_num_lanes: 1,
+            module_supply_voltage: 3300,
+            module_temperature: -5,
+            lanes: vec![Lane {
+                index: 1,
+                tx_bias_current: 2,
+                tx_power: 3,
+                tx_power_min: 4,
+                tx_power_max: 5,
+                tx_wavelength: 6,
+                rx_power: 7,
+                rx_power_min: 8,
+                rx_power_max: 9,
+                rx_wavelength: 10,
+            }],
+        }),
+        CounterData::Processor(ProcessorCounters {
+            cpu_5s: 1,
+            cpu_1m: 2,
+            cpu_5m: 3,
+            total_memory: 1 << 34,
+            free_memory: 1 << 33,
+        }),
+        CounterData::HostDescription(HostDescription {
+            hostname: "host1".to_string(),
+            uuid: [0xAB; 16],
+            machine_type: MachineType::X86_64,
+            os_name: OsName::Linux,
+            os_release: "6.1".to_string(),
+        }),
+        CounterData::HostAdapters(HostAdapters {
+            adapters: vec![HostAdapter {
+                if_index: 2,
+                mac_addresses: vec![MacAddress::new([0, 0x11, 0x22, 0x33, 0x44, 0x55])],
+            }],
+        }),
+        CounterData::BroadcomPortBuffers(BroadcomPortBuffers {
+            ingress_uc_pc: 1,
+            ingress_mc_pc: -1,
+            egress_uc_pc: 2,
+            egress_mc_pc: -2,
+            egress_queue_uc_pc: vec![3, 4],
+            egress_queue_mc_pc: vec![-3],
+        }),
+    ];
+
+    let mut builder = CounterSampleBuilder::new().sequence(1);
+    for counter in counters {
+        builder = builder.add_counter(counter);
+    }
+    let sample = builder.build();
+    let datagram = parse_datagram(&counters_sample_datagram(&sample)).unwrap();
+    assert_eq!(
+        datagram.samples[0].sample_data,
+        SampleData::CountersSample(sample)
+    );
+}
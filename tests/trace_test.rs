@@ -0,0 +1,75 @@
+//! Tests for the parser tracing spans
+//!
+//! These tests verify that the parser emits a span per sample and per
+//! record when the trace feature is enabled.
+
+#![cfg(feature = "trace")]
+
+use sflow_parser::parsers::parse_datagram;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+mod common;
+
+use common::flow_datagram;
+
+/// Subscriber recording the name and fields of every new span
+#[derive(Default)]
+struct SpanRecorder {
+    spans: Arc<Mutex<Vec<String>>>,
+    next_id: AtomicU64,
+}
+
+struct FieldWriter<'a>(&'a mut String);
+
+impl Visit for FieldWriter<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        write!(self.0, " {}={:?}", field.name(), value).unwrap();
+    }
+}
+
+impl Subscriber for SpanRecorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut line = span.metadata().name().to_string();
+        span.record(&mut FieldWriter(&mut line));
+        self.spans.lock().unwrap().push(line);
+        Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+#[test]
+fn test_spans_per_sample_and_record() {
+    let recorder = SpanRecorder::default();
+    let spans = recorder.spans.clone();
+
+    tracing::subscriber::with_default(recorder, || {
+        parse_datagram(&flow_datagram()).unwrap();
+    });
+
+    assert_eq!(
+        *spans.lock().unwrap(),
+        vec![
+            "sample format=0:1 length=68",
+            "flow_record format=0:1030 length=4",
+            "flow_record format=0:1001 length=16",
+        ]
+    );
+}
@@ -0,0 +1,490 @@
+//! Tests for the analysis helpers over parsed data
+
+use sflow_parser::analysis::*;
+use sflow_parser::models::record_counters::*;
+use sflow_parser::models::*;
+use std::net::Ipv4Addr;
+
+fn agent() -> Address {
+    Address::IPv4(Ipv4Addr::new(192, 168, 1, 1))
+}
+
+fn interface_counters(in_octets: u64, out_octets: u64, in_ucast: u32) -> GenericInterfaceCounters {
+    GenericInterfaceCounters {
+        if_index: 7,
+        if_type: 6,
+        if_speed: 1_000_000_000,
+        if_direction: 1,
+        if_status: 3,
+        if_in_octets: in_octets,
+        if_in_ucast_pkts: in_ucast,
+        if_in_multicast_pkts: 0,
+        if_in_broadcast_pkts: 0,
+        if_in_discards: 0,
+        if_in_errors: 0,
+        if_in_unknown_protos: 0,
+        if_out_octets: out_octets,
+        if_out_ucast_pkts: 0,
+        if_out_multicast_pkts: 0,
+        if_out_broadcast_pkts: 0,
+        if_out_discards: 0,
+        if_out_errors: 0,
+        if_promiscuous_mode: 0,
+    }
+}
+
+fn counters_sample(sequence_number: u32, counters: GenericInterfaceCounters) -> CountersSample {
+    CountersSample {
+        sequence_number,
+        source_id: DataSource::new(0, 7),
+        counters: vec![CounterRecord {
+            counter_format: DataFormat::new(0, 1),
+            counter_data: CounterData::GenericInterface(counters),
+        }],
+    }
+}
+
+#[test]
+fn test_counter_rates_octets() {
+    let mut calculator = CounterRateCalculator::new();
+
+    // First sample only initializes the series
+    let first = counters_sample(1, interface_counters(1_000_000, 500_000, 1_000));
+    assert!(calculator.update(&agent(), 0, 10_000, &first).is_empty());
+    assert_eq!(calculator.len(), 1);
+
+    // 10 seconds later
+    let second = counters_sample(2, interface_counters(11_000_000, 1_500_000, 3_000));
+    let rates = calculator.update(&agent(), 0, 20_000, &second);
+
+    assert_eq!(rates.len(), 1);
+    assert_eq!(rates[0].if_index, 7);
+    assert_eq!(rates[0].interval_secs, 10.0);
+    assert_eq!(rates[0].in_octets_per_sec, 1_000_000.0);
+    assert_eq!(rates[0].out_octets_per_sec, 100_000.0);
+    assert_eq!(rates[0].in_packets_per_sec, 200.0);
+}
+
+#[test]
+fn test_counter_rates_counter32_wraparound() {
+    let mut calculator = CounterRateCalculator::new();
+
+    let first = counters_sample(1, interface_counters(0, 0, u32::MAX - 99));
+    calculator.update(&agent(), 0, 0, &first);
+
+    // The 32-bit packet counter wrapped: 100 packets up to u32::MAX, then 900 more
+    let second = counters_sample(2, interface_counters(0, 0, 900));
+    let rates = calculator.update(&agent(), 0, 10_000, &second);
+
+    assert_eq!(rates[0].in_packets_per_sec, 100.0);
+}
+
+#[test]
+fn test_counter_rates_series_are_keyed_by_agent() {
+    let mut calculator = CounterRateCalculator::new();
+    let other_agent = Address::IPv4(Ipv4Addr::new(10, 0, 0, 1));
+
+    let sample = counters_sample(1, interface_counters(0, 0, 0));
+    calculator.update(&agent(), 0, 0, &sample);
+    assert!(calculator
+        .update(&other_agent, 0, 1_000, &sample)
+        .is_empty());
+    assert_eq!(calculator.len(), 2);
+}
+
+#[test]
+fn test_counter_rates_series_are_keyed_by_sub_agent() {
+    let mut calculator = CounterRateCalculator::new();
+
+    // Two sub-agents reporting the same data source do not share a baseline
+    let first = counters_sample(1, interface_counters(1_000, 0, 0));
+    let other = counters_sample(1, interface_counters(900_000, 0, 0));
+    calculator.update(&agent(), 0, 0, &first);
+    assert!(calculator.update(&agent(), 1, 5_000, &other).is_empty());
+    assert_eq!(calculator.len(), 2);
+
+    let second = counters_sample(2, interface_counters(11_000, 0, 0));
+    let rates = calculator.update(&agent(), 0, 10_000, &second);
+    assert_eq!(rates[0].in_octets_per_sec, 1_000.0);
+}
+
+#[test]
+fn test_counter_rates_uptime_wraparound() {
+    let mut calculator = CounterRateCalculator::new();
+
+    // sysUpTime wraps after 2^32 ms: 4 seconds before, 6 seconds after
+    let first = counters_sample(1, interface_counters(0, 0, 0));
+    calculator.update(&agent(), 0, u32::MAX - 3_999, &first);
+    let second = counters_sample(2, interface_counters(10_000, 0, 0));
+    let rates = calculator.update(&agent(), 0, 6_000, &second);
+
+    assert_eq!(rates.len(), 1);
+    assert_eq!(rates[0].interval_secs, 10.0);
+    assert_eq!(rates[0].in_octets_per_sec, 1_000.0);
+}
+
+#[test]
+fn test_generic_interface_counter_widths() {
+    assert_eq!(
+        GenericInterfaceCounters::counter_width("if_in_ucast_pkts"),
+        Some(CounterWidth::Counter32)
+    );
+    assert_eq!(
+        GenericInterfaceCounters::counter_width("if_in_octets"),
+        Some(CounterWidth::Counter64)
+    );
+    assert_eq!(GenericInterfaceCounters::counter_width("if_speed"), None);
+    assert_eq!(GenericInterfaceCounters::counter_widths().len(), 13);
+
+    // Same readings, different modulus
+    assert_eq!(CounterWidth::Counter32.delta(u32::MAX as u64 - 9, 10), 20);
+    assert_eq!(
+        CounterWidth::Counter64.delta(u32::MAX as u64 - 9, 10),
+        u64::MAX - u32::MAX as u64 + 20
+    );
+}
+
+#[test]
+fn test_counter_rates_counter64_octets_cross_32_bits() {
+    let mut calculator = CounterRateCalculator::new();
+
+    // A 64-bit octet counter crossing 2^32 is not a wraparound
+    let first = counters_sample(1, interface_counters(u32::MAX as u64 - 999, 0, 0));
+    calculator.update(&agent(), 0, 0, &first);
+    let second = counters_sample(2, interface_counters(u32::MAX as u64 + 9_001, 0, 0));
+    let rates = calculator.update(&agent(), 0, 10_000, &second);
+
+    assert_eq!(rates[0].in_octets_per_sec, 1_000.0);
+}
+
+#[test]
+fn test_counter_rates_uptime_decrease_resets_baseline() {
+    let mut calculator = CounterRateCalculator::new();
+
+    let first = counters_sample(1, interface_counters(50_000_000, 0, 10_000));
+    calculator.update(&agent(), 0, 600_000, &first);
+
+    // The agent restarted: lower uptime and counters back near zero
+    let restarted = counters_sample(1, interface_counters(1_000, 0, 10));
+    assert!(calculator.update(&agent(), 0, 5_000, &restarted).is_empty());
+
+    // Rates resume from the post-restart baseline
+    let next = counters_sample(2, interface_counters(11_000, 0, 1_010));
+    let rates = calculator.update(&agent(), 0, 15_000, &next);
+    assert_eq!(rates.len(), 1);
+    assert_eq!(rates[0].in_octets_per_sec, 1_000.0);
+    assert_eq!(rates[0].in_packets_per_sec, 100.0);
+}
+
+#[test]
+fn test_counter_rates_update_datagram_expanded() {
+    let mut calculator = CounterRateCalculator::new();
+    let compact = counters_sample(1, interface_counters(0, 0, 0));
+    let expanded = CountersSampleExpanded {
+        sequence_number: 2,
+        source_id: DataSourceExpanded {
+            source_id_type: 0,
+            source_id_index: 7,
+        },
+        counters: counters_sample(2, interface_counters(10_000, 0, 0)).counters,
+    };
+    let datagram = |uptime, sample_data| SFlowDatagram {
+        version: DatagramVersion::Version5,
+        agent_address: agent(),
+        sub_agent_id: 0,
+        sequence_number: 1,
+        uptime,
+        samples: vec![SampleRecord {
+            sample_type: DataFormat::new(0, 2),
+            sample_data,
+        }],
+    };
+
+    // A compact and an expanded sample of the same source share a series
+    let first = datagram(0, SampleData::CountersSample(compact));
+    assert!(calculator.update_datagram(&first).is_empty());
+    let second = datagram(10_000, SampleData::CountersSampleExpanded(expanded));
+    let rates = calculator.update_datagram(&second);
+    assert_eq!(rates.len(), 1);
+    assert_eq!(rates[0].in_octets_per_sec, 1_000.0);
+    assert_eq!(calculator.len(), 1);
+}
+
+#[test]
+fn test_counter_store_keeps_newest() {
+    let mut store = CounterStore::new();
+    let source = DataSource::new(0, 7);
+
+    assert!(store.update(
+        &agent(),
+        0,
+        1_000,
+        &counters_sample(10, interface_counters(100, 0, 0))
+    ));
+    assert!(store.update(
+        &agent(),
+        0,
+        2_000,
+        &counters_sample(11, interface_counters(200, 0, 0))
+    ));
+    assert_eq!(store.len(), 1);
+
+    let latest = store.latest(&agent(), 0, source).unwrap();
+    assert_eq!(latest.sequence_number, 11);
+    assert!(matches!(
+        &latest.counters[0].counter_data,
+        CounterData::GenericInterface(c) if c.if_in_octets == 200
+    ));
+
+    // An older (reordered) sample does not replace the newer one
+    assert!(!store.update(
+        &agent(),
+        0,
+        1_000,
+        &counters_sample(10, interface_counters(100, 0, 0))
+    ));
+    assert_eq!(
+        store.latest(&agent(), 0, source).unwrap().sequence_number,
+        11
+    );
+
+    let other_agent = Address::IPv4(Ipv4Addr::new(10, 0, 0, 1));
+    assert!(store.latest(&other_agent, 0, source).is_none());
+    assert!(store.latest(&agent(), 1, source).is_none());
+}
+
+#[test]
+fn test_counter_store_sequence_wraparound() {
+    let mut store = CounterStore::new();
+
+    store.update(
+        &agent(),
+        0,
+        1_000,
+        &counters_sample(u32::MAX, interface_counters(0, 0, 0)),
+    );
+    assert!(store.update(
+        &agent(),
+        0,
+        2_000,
+        &counters_sample(2, interface_counters(0, 0, 0))
+    ));
+    assert_eq!(
+        store
+            .latest(&agent(), 0, DataSource::new(0, 7))
+            .unwrap()
+            .sequence_number,
+        2
+    );
+}
+
+#[test]
+fn test_counter_store_agent_restart() {
+    let mut store = CounterStore::new();
+
+    store.update(
+        &agent(),
+        0,
+        3_600_000,
+        &counters_sample(500, interface_counters(0, 0, 0)),
+    );
+
+    // The agent rebooted: uptime and sequence numbers start over
+    assert!(store.update(
+        &agent(),
+        0,
+        5_000,
+        &counters_sample(1, interface_counters(0, 0, 0))
+    ));
+    assert_eq!(
+        store
+            .latest(&agent(), 0, DataSource::new(0, 7))
+            .unwrap()
+            .sequence_number,
+        1
+    );
+}
+
+#[test]
+fn test_counter_store_keyed_by_sub_agent_and_expanded() {
+    let mut store = CounterStore::new();
+    let sample = counters_sample(1, interface_counters(0, 0, 0));
+    let datagram = |sub_agent_id, sample_data| SFlowDatagram {
+        version: DatagramVersion::Version5,
+        agent_address: agent(),
+        sub_agent_id,
+        sequence_number: 1,
+        uptime: 1_000,
+        samples: vec![SampleRecord {
+            sample_type: DataFormat::new(0, 4),
+            sample_data,
+        }],
+    };
+
+    // Same source id from two sub-agents: two entries
+    assert_eq!(
+        store.update_datagram(&datagram(0, SampleData::CountersSample(sample.clone()))),
+        1
+    );
+    let expanded = CountersSampleExpanded::from(sample);
+    assert_eq!(
+        store.update_datagram(&datagram(1, SampleData::CountersSampleExpanded(expanded))),
+        1
+    );
+    assert_eq!(store.len(), 2);
+    assert!(store.latest(&agent(), 1, DataSource::new(0, 7)).is_some());
+}
+
+#[test]
+fn test_interface_octet_aggregator() {
+    use super::comprehensive::helpers::{
+        build_counter_sample_with_records, create_datagram_header, generic_interface_record_data,
+    };
+    use sflow_parser::parsers::{parse_datagram_visit, ParseOptions};
+
+    let datagram = |in_octets: u64, out_octets: u64| {
+        let mut record = generic_interface_record_data(7);
+        record[24..32].copy_from_slice(&in_octets.to_be_bytes());
+        record[56..64].copy_from_slice(&out_octets.to_be_bytes());
+        let mut data = create_datagram_header(1);
+        data.extend_from_slice(&build_counter_sample_with_records(&[
+            (0x0001, record),
+            (0x0001, generic_interface_record_data(8)),
+        ]));
+        data
+    };
+
+    let options = ParseOptions::default();
+    let mut octets = InterfaceOctetAggregator::new();
+    parse_datagram_visit(&datagram(1_000, 2_000), &options, &mut octets).unwrap();
+    parse_datagram_visit(&datagram(5_000, 9_000), &options, &mut octets).unwrap();
+
+    assert_eq!(octets.len(), 2);
+    assert_eq!(
+        octets.get(&agent(), 7),
+        Some(InterfaceOctets {
+            in_octets: 5_000,
+            out_octets: 9_000,
+        })
+    );
+    assert_eq!(
+        octets.get(&agent(), 8),
+        Some(InterfaceOctets {
+            in_octets: 10_000,
+            out_octets: 20_000,
+        })
+    );
+    assert_eq!(
+        octets.get(&Address::IPv4(Ipv4Addr::new(10, 0, 0, 1)), 7),
+        None
+    );
+}
+
+#[test]
+fn test_coverage_report() {
+    let unknown = |enterprise, format| CounterRecord {
+        counter_format: DataFormat::new(enterprise, format),
+        counter_data: CounterData::Unknown {
+            format: DataFormat::new(enterprise, format),
+            data: vec![0; 4],
+        },
+    };
+    let datagram = |records: Vec<CounterRecord>| {
+        let mut sample = counters_sample(1, interface_counters(0, 0, 0));
+        sample.counters.extend(records);
+        let mut datagram = SFlowDatagram::new(agent(), 0, 1, 100);
+        datagram.samples.push(SampleRecord {
+            sample_type: DataFormat::new(0, 2),
+            sample_data: SampleData::CountersSample(sample),
+        });
+        datagram
+    };
+
+    let mut report = CoverageReport::new();
+    report.observe(&datagram(vec![unknown(0, 4000), unknown(4413, 7)]));
+    report.observe(&datagram(vec![unknown(4413, 7)]));
+    report.observe(&datagram(vec![unknown(4413, 7)]));
+
+    assert_eq!(
+        report.unknown_formats(),
+        vec![((4413, 7), 3), ((0, 4000), 1)]
+    );
+    assert_eq!(report.total_unknown(), 4);
+}
+
+#[test]
+fn test_coverage_report_kinds_and_nested_records() {
+    use sflow_parser::models::record_flows::{Extended80211Aggregation, Pdu};
+
+    let unknown_flow = |format| FlowRecord {
+        flow_format: DataFormat::new(0, format),
+        flow_data: FlowData::Unknown {
+            format: DataFormat::new(0, format),
+            data: Vec::new(),
+        },
+    };
+    let aggregation = FlowRecord {
+        flow_format: DataFormat::new(0, 1016),
+        flow_data: FlowData::Extended80211Aggregation(Extended80211Aggregation {
+            pdus: vec![
+                Pdu {
+                    flow_records: vec![unknown_flow(4000)],
+                },
+                Pdu {
+                    flow_records: vec![unknown_flow(4000)],
+                },
+            ],
+        }),
+    };
+    let flows = FlowSample {
+        sequence_number: 1,
+        source_id: DataSource::new(0, 7),
+        sampling_rate: 1,
+        sample_pool: 1,
+        drops: 0,
+        input: Interface(1),
+        output: Interface(2),
+        flow_records: vec![unknown_flow(4000), aggregation],
+    };
+    let mut counters = counters_sample(1, interface_counters(0, 0, 0));
+    counters.counters.push(CounterRecord {
+        counter_format: DataFormat::new(0, 4000),
+        counter_data: CounterData::Unknown {
+            format: DataFormat::new(0, 4000),
+            data: Vec::new(),
+        },
+    });
+
+    let mut datagram = SFlowDatagram::new(agent(), 0, 1, 100);
+    for (format, sample_data) in [
+        (1, SampleData::FlowSample(flows)),
+        (2, SampleData::CountersSample(counters)),
+        (
+            4000,
+            SampleData::Unknown {
+                format: DataFormat::new(0, 4000),
+                data: Vec::new(),
+            },
+        ),
+    ] {
+        datagram.samples.push(SampleRecord {
+            sample_type: DataFormat::new(0, format),
+            sample_data,
+        });
+    }
+
+    let mut report = CoverageReport::new();
+    report.observe(&datagram);
+
+    // Same (enterprise, format) pair, counted apart per kind
+    assert_eq!(report.unknown_formats(), vec![((0, 4000), 5)]);
+    assert_eq!(
+        report.unknown_formats_by_kind(),
+        vec![
+            ((FormatKind::Flow, 0, 4000), 3),
+            ((FormatKind::Sample, 0, 4000), 1),
+            ((FormatKind::Counter, 0, 4000), 1),
+        ]
+    );
+    assert_eq!(report.total_unknown(), 5);
+}
@@ -321,18 +321,18 @@ fn test_counter_0_6_ieee80211_counters() {
 
 #[test]
 fn test_counter_0_7_lag_port_stats() {
-    // LAG Port Stats: 2 MAC (6 bytes each) + 1 u32 + 4 bytes state + 8 u32 = 52 bytes
+    // LAG Port Stats: 2 MAC (8 bytes each, XDR padded) + 1 u32 + 4 bytes state + 8 u32 = 56 bytes
     let record_data = [
-        // Actor system ID (MAC address)
-        0x00, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E,
-        // Partner operational system ID (MAC address)
-        0x00, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, // Attached aggregator ID
+        // Actor system ID (MAC address + 2 bytes padding)
+        0x00, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E, 0x00, 0x00,
+        // Partner operational system ID (MAC address + 2 bytes padding)
+        0x00, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0x00, 0x00, // Attached aggregator ID
         0x00, 0x00, 0x00, 0x01, // dot3ad_agg_port_attached_agg_id = 1
         // Port state (4 bytes)
-        0x01, // byte 0: dot3adAggPortActorAdminState
-        0x02, // byte 1: dot3adAggPortActorOperState
-        0x03, // byte 2: dot3adAggPortPartnerAdminState
-        0x04, // byte 3: dot3adAggPortPartnerOperState
+        0x3D, // byte 0: dot3adAggPortActorAdminState
+        0x3F, // byte 1: dot3adAggPortActorOperState
+        0x05, // byte 2: dot3adAggPortPartnerAdminState
+        0x7C, // byte 3: dot3adAggPortPartnerOperState
         // Statistics
         0x00, 0x00, 0x03, 0xE8, // dot3ad_agg_port_stats_lacpd_us_rx = 1000
         0x00, 0x00, 0x00, 0x0A, // dot3ad_agg_port_stats_marker_pdus_rx = 10
@@ -367,7 +367,15 @@ fn test_counter_0_7_lag_port_stats() {
                     // Verify aggregator ID
                     assert_eq!(lag.dot3ad_agg_port_attached_agg_id, 1);
                     // Verify port state
-                    assert_eq!(lag.dot3ad_agg_port_state, [0x01, 0x02, 0x03, 0x04]);
+                    assert_eq!(lag.dot3ad_agg_port_state, [0x3D, 0x3F, 0x05, 0x7C]);
+                    assert_eq!(
+                        lag.actor_system_id(),
+                        MacAddress::new([0x00, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E])
+                    );
+                    assert_eq!(
+                        lag.partner_system_id(),
+                        MacAddress::new([0x00, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE])
+                    );
                     // Verify statistics
                     assert_eq!(lag.dot3ad_agg_port_stats_lacpd_us_rx, 1000);
                     assert_eq!(lag.dot3ad_agg_port_stats_marker_pdus_rx, 10);
@@ -860,6 +868,39 @@ fn test_counter_0_2002_host_parent() {
     }
 }
 
+#[test]
+fn test_counter_0_2002_host_parent_data_source() {
+    // Virtual host whose parent is the physical host entPhysicalEntry 1
+    let record_data = [
+        0x00, 0x00, 0x00, 0x02, // container_type = 2 (entPhysicalEntry)
+        0x00, 0x00, 0x00, 0x01, // container_index = 1
+    ];
+
+    let data = build_counter_sample_test(0x07D2, &record_data); // record type = 2002
+
+    let datagram = parse_datagram(&data).unwrap();
+    match &datagram.samples[0].sample_data {
+        SampleData::CountersSample(counters) => match &counters.counters[0].counter_data {
+            CounterData::HostParent(parent) => {
+                let source = parent.parent_data_source();
+                assert_eq!(
+                    source,
+                    DataSourceExpanded {
+                        source_id_type: 2,
+                        source_id_index: 1,
+                    }
+                );
+                // Matches the compact source_id exported by the physical host
+                let physical = DataSource::new(2, 1);
+                assert_eq!(source.source_id_type, physical.source_type() as u32);
+                assert_eq!(source.source_id_index, physical.index());
+            }
+            _ => panic!("Expected HostParent"),
+        },
+        _ => panic!("Expected CountersSample"),
+    }
+}
+
 #[test]
 fn test_counter_0_2003_host_cpu_counters() {
     // Host CPU counters: 8 u32 + 7 u64 = 68 bytes
@@ -2278,3 +2319,30 @@ fn test_counter_5703_1_nvidia_gpu() {
         _ => panic!("Expected CountersSample"),
     }
 }
+
+#[test]
+fn test_counters_sample_records_by_type() {
+    let data = {
+        let mut data = create_datagram_header(1);
+        data.extend_from_slice(&build_counter_sample_with_records(&[
+            (0x0001, generic_interface_record_data(7)),
+            (0xFFFF, vec![0xAA; 8]),
+            (0x0001, generic_interface_record_data(9)),
+        ]));
+        data
+    };
+
+    let datagram = parse_datagram(&data).unwrap();
+    match &datagram.samples[0].sample_data {
+        SampleData::CountersSample(sample) => {
+            let indexes: Vec<u32> = sample
+                .records::<GenericInterfaceCounters>()
+                .map(|iface| iface.if_index)
+                .collect();
+            assert_eq!(indexes, vec![7, 9]);
+            assert_eq!(sample.records::<HostCpu>().count(), 0);
+            assert_eq!(GenericInterfaceCounters::DATA_FORMAT, DataFormat::new(0, 1));
+        }
+        _ => panic!("Expected CountersSample"),
+    }
+}
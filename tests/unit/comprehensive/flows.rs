@@ -29,13 +29,13 @@
 
 use super::helpers::*;
 use sflow_parser::models::{AppStatus, HttpMethod};
-use sflow_parser::parsers::parse_datagram;
+use sflow_parser::parsers::{parse_datagram, parse_datagram_verbose, ParseOptions};
 
 // ===== Enterprise 0: Sampled Records (Formats 1-4) =====
 
 #[test]
 fn test_flow_0_1_sampled_header() {
-    // Sampled header: protocol(4) + frame_length(4) + stripped(4) + header_len(4) + header(14) + padding(2) = 32 bytes
+    // Sampled header: protocol(4) + frame_length(4) + stripped(4) + header<> length prefix(4) + header(14) + padding(2) = 32 bytes
     let record_data = [
         0x00, 0x00, 0x00, 0x01, // protocol = Ethernet
         0x00, 0x00, 0x05, 0xDC, // frame length = 1500
@@ -72,6 +72,46 @@ fn test_flow_0_1_sampled_header() {
     }
 }
 
+#[test]
+fn test_flow_0_1_sampled_header_partial_capture() {
+    // 37 of 1500 bytes captured: the opaque length prefix is the captured length,
+    // followed by 3 bytes of padding before the next record
+    let switch_data = [
+        0x00, 0x00, 0x00, 0x0A, // src_vlan = 10
+        0x00, 0x00, 0x00, 0x00, // src_priority
+        0x00, 0x00, 0x00, 0x14, // dst_vlan = 20
+        0x00, 0x00, 0x00, 0x00, // dst_priority
+    ];
+    let sample = build_flow_sample_with_records(&[
+        (0x0001, sampled_header_record_data(37)),
+        (0x03E9, switch_data.to_vec()),
+    ]);
+    let mut data = create_datagram_header(1);
+    data.extend_from_slice(&sample);
+
+    let (datagram, warnings) = parse_datagram_verbose(&data, &ParseOptions::default()).unwrap();
+    assert!(warnings.is_empty(), "{:?}", warnings);
+
+    match &datagram.samples[0].sample_data {
+        SampleData::FlowSample(flow) => {
+            match &flow.flow_records[0].flow_data {
+                FlowData::SampledHeader(header) => {
+                    assert_eq!(header.frame_length, 1500);
+                    assert_eq!(header.stripped, 4);
+                    assert_eq!(header.header.len(), 37);
+                    assert_eq!(header.header, (0..37).collect::<Vec<u8>>());
+                }
+                _ => panic!("Expected SampledHeader"),
+            }
+            match &flow.flow_records[1].flow_data {
+                FlowData::ExtendedSwitch(switch) => assert_eq!(switch.dst_vlan, 20),
+                _ => panic!("Expected ExtendedSwitch"),
+            }
+        }
+        _ => panic!("Expected FlowSample"),
+    }
+}
+
 #[test]
 fn test_flow_0_2_sampled_ethernet() {
     // Sampled Ethernet data: length(4) + src_mac(6) + dst_mac(6) + eth_type(4) = 20 bytes
@@ -290,6 +330,7 @@ fn test_flow_0_1003_extended_gateway() {
                     assert_eq!(gw.dst_as_path[0].path[0], 65003);
                     assert_eq!(gw.communities.len(), 2);
                     assert_eq!(gw.local_pref, 100);
+                    assert!(gw.trailing.is_empty());
                 }
                 _ => panic!("Expected ExtendedGateway"),
             }
@@ -298,6 +339,44 @@ fn test_flow_0_1003_extended_gateway() {
     }
 }
 
+#[test]
+fn test_flow_0_1003_extended_gateway_vendor_trailing_bytes() {
+    // Canonical extended_gateway followed by 8 vendor-specific bytes
+    let record_data = [
+        0x00, 0x00, 0x00, 0x01, // next_hop address type = IPv4
+        0x0A, 0x00, 0x00, 0x01, // next_hop = 10.0.0.1
+        0x00, 0x00, 0xFD, 0xE8, // as_number = 65000
+        0x00, 0x00, 0xFD, 0xE9, // src_as = 65001
+        0x00, 0x00, 0xFD, 0xEA, // src_peer_as = 65002
+        0x00, 0x00, 0x00, 0x00, // num_segments = 0
+        0x00, 0x00, 0x00, 0x01, // num_communities = 1
+        0xFD, 0xE8, 0x00, 0x64, // community 65000:100
+        0x00, 0x00, 0x00, 0xC8, // local_pref = 200
+        0xCA, 0xFE, 0xBA, 0xBE, // vendor extension
+        0x00, 0x00, 0x00, 0x07, // vendor extension
+    ];
+
+    let data = build_flow_sample_test(0x03EB, &record_data); // record type = 1003
+
+    let datagram = parse_datagram(&data).unwrap();
+    match &datagram.samples[0].sample_data {
+        SampleData::FlowSample(flow) => match &flow.flow_records[0].flow_data {
+            FlowData::ExtendedGateway(gw) => {
+                assert_eq!(gw.as_number, 65000);
+                assert!(gw.dst_as_path.is_empty());
+                assert_eq!(gw.communities, vec![0xFDE80064]);
+                assert_eq!(gw.local_pref, 200);
+                assert_eq!(
+                    gw.trailing,
+                    vec![0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x00, 0x00, 0x07]
+                );
+            }
+            _ => panic!("Expected ExtendedGateway"),
+        },
+        _ => panic!("Expected FlowSample"),
+    }
+}
+
 #[test]
 fn test_flow_0_1004_extended_user() {
     // Extended User data: src_charset(4) + src_user_len(4) + "alice"(5) + padding(3) +
@@ -372,6 +451,54 @@ fn test_flow_0_1005_extended_url() {
     }
 }
 
+#[test]
+fn test_flow_record_length_excluding_padding() {
+    // Extended URL whose declared length leaves out the padding of the host
+    let url_record = vec![
+        0x00, 0x00, 0x00, 0x02, // direction = 2 (destination)
+        0x00, 0x00, 0x00, 0x04, // url length = 4
+        b'/', b'a', b'p', b'i', // "/api"
+        0x00, 0x00, 0x00, 0x02, // host length = 2
+        b'n', b'x', 0x00, 0x00, // "nx" + padding
+    ];
+    let switch_record = vec![
+        0x00, 0x00, 0x00, 0x0A, // src_vlan = 10
+        0x00, 0x00, 0x00, 0x00, // src_priority = 0
+        0x00, 0x00, 0x00, 0x14, // dst_vlan = 20
+        0x00, 0x00, 0x00, 0x00, // dst_priority = 0
+    ];
+    let mut data = create_datagram_header(1);
+    data.extend_from_slice(&build_flow_sample_with_records(&[
+        (0x03ED, url_record),    // record type = 1005
+        (0x03E9, switch_record), // record type = 1001
+    ]));
+    // Declared length = 18 instead of 20, the padding bytes stay on the wire
+    data[72..76].copy_from_slice(&u32_bytes(18));
+
+    let (datagram, warnings) = parse_datagram_verbose(&data, &ParseOptions::default()).unwrap();
+    assert!(warnings.is_empty(), "{:?}", warnings);
+    match &datagram.samples[0].sample_data {
+        SampleData::FlowSample(flow) => {
+            assert_eq!(flow.flow_records.len(), 2);
+            match &flow.flow_records[0].flow_data {
+                FlowData::ExtendedUrl(url) => {
+                    assert_eq!(url.url, "/api");
+                    assert_eq!(url.host, "nx");
+                }
+                other => panic!("Expected ExtendedUrl, got {:?}", other),
+            }
+            match &flow.flow_records[1].flow_data {
+                FlowData::ExtendedSwitch(switch) => {
+                    assert_eq!(switch.src_vlan, 10);
+                    assert_eq!(switch.dst_vlan, 20);
+                }
+                other => panic!("Expected ExtendedSwitch, got {:?}", other),
+            }
+        }
+        _ => panic!("Expected FlowSample"),
+    }
+}
+
 #[test]
 fn test_flow_0_1006_extended_mpls() {
     // Extended MPLS data: next_hop_type(4) + next_hop(4 for IPv4) +
@@ -449,6 +576,91 @@ fn test_flow_0_1007_extended_nat() {
     }
 }
 
+#[test]
+fn test_flow_0_1007_extended_nat_ipv6() {
+    // Extended NAT data: 2 * (addr_type(4) + IPv6 addr(16)) = 40 bytes
+    let record_data = [
+        0x00, 0x00, 0x00, 0x02, // src_address type = IPv6
+        0x20, 0x01, 0x0D, 0xB8, 0x00, 0x00, 0x00, 0x00, // src_address = 2001:db8::1
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, //
+        0x00, 0x00, 0x00, 0x02, // dst_address type = IPv6
+        0x20, 0x01, 0x0D, 0xB8, 0x00, 0x01, 0x00, 0x00, // dst_address = 2001:db8:1::ff
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, //
+    ];
+
+    let data = build_flow_sample_test(0x03EF, &record_data); // record type = 1007
+
+    let datagram = parse_datagram(&data).unwrap();
+    match &datagram.samples[0].sample_data {
+        SampleData::FlowSample(flow) => match &flow.flow_records[0].flow_data {
+            FlowData::ExtendedNat(nat) => {
+                assert_eq!(
+                    nat.src_address,
+                    Address::IPv6("2001:db8::1".parse().unwrap())
+                );
+                assert_eq!(
+                    nat.dst_address,
+                    Address::IPv6("2001:db8:1::ff".parse().unwrap())
+                );
+            }
+            _ => panic!("Expected ExtendedNat"),
+        },
+        _ => panic!("Expected FlowSample"),
+    }
+}
+
+#[test]
+fn test_flow_0_1007_extended_nat_mixed_families() {
+    // IPv4 source and IPv6 destination, followed by a NAT port record to
+    // check the record boundary stays aligned
+    let nat = vec![
+        0x00, 0x00, 0x00, 0x01, // src_address type = IPv4
+        0xC0, 0xA8, 0x01, 0x64, // src_address = 192.168.1.100
+        0x00, 0x00, 0x00, 0x02, // dst_address type = IPv6
+        0x20, 0x01, 0x0D, 0xB8, 0x00, 0x00, 0x00, 0x00, // dst_address = 2001:db8::1
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, //
+    ];
+    let nat_port = vec![
+        0x00, 0x00, 0x1F, 0x90, // src_port = 8080
+        0x00, 0x00, 0x00, 0x50, // dst_port = 80
+    ];
+
+    let mut data = create_datagram_header(1);
+    data.extend(build_flow_sample_with_records(&[
+        (0x03EF, nat),      // record type = 1007
+        (0x03FC, nat_port), // record type = 1020
+    ]));
+
+    let (datagram, warnings) = parse_datagram_verbose(&data, &ParseOptions::default()).unwrap();
+    assert!(warnings.is_empty());
+    match &datagram.samples[0].sample_data {
+        SampleData::FlowSample(flow) => {
+            assert_eq!(flow.flow_records.len(), 2);
+            match &flow.flow_records[0].flow_data {
+                FlowData::ExtendedNat(nat) => {
+                    assert_eq!(
+                        nat.src_address,
+                        Address::IPv4("192.168.1.100".parse().unwrap())
+                    );
+                    assert_eq!(
+                        nat.dst_address,
+                        Address::IPv6("2001:db8::1".parse().unwrap())
+                    );
+                }
+                _ => panic!("Expected ExtendedNat"),
+            }
+            match &flow.flow_records[1].flow_data {
+                FlowData::ExtendedNatPort(nat_port) => {
+                    assert_eq!(nat_port.src_port, 8080);
+                    assert_eq!(nat_port.dst_port, 80);
+                }
+                _ => panic!("Expected ExtendedNatPort"),
+            }
+        }
+        _ => panic!("Expected FlowSample"),
+    }
+}
+
 #[test]
 fn test_flow_0_1008_extended_mpls_tunnel() {
     // Extended MPLS Tunnel data: tunnel_lsp_name_len(4) + "mpls0"(5) + padding(3) + tunnel_id(4) + tunnel_cos(4) = 20 bytes
@@ -481,15 +693,72 @@ fn test_flow_0_1008_extended_mpls_tunnel() {
     }
 }
 
+#[test]
+fn test_flow_0_1008_extended_mpls_tunnel_aligned_name() {
+    // An 8-byte name needs no padding: tunnel_id must follow the name directly
+    let record_data = [
+        0x00, 0x00, 0x00, 0x08, // tunnel_lsp_name length = 8
+        b'l', b's', b'p', b'-', b'c', b'o', b'r', b'e', // "lsp-core"
+        0x00, 0x00, 0x00, 0x2A, // tunnel_id = 42
+        0x00, 0x00, 0x00, 0x05, // tunnel_cos = 5
+    ];
+
+    let data = build_flow_sample_test(0x03F0, &record_data); // record type = 1008
+
+    let datagram = parse_datagram(&data).unwrap();
+    match &datagram.samples[0].sample_data {
+        SampleData::FlowSample(flow) => match &flow.flow_records[0].flow_data {
+            FlowData::ExtendedMplsTunnel(tunnel) => {
+                assert_eq!(tunnel.tunnel_lsp_name, "lsp-core");
+                assert_eq!(tunnel.tunnel_id, 42);
+                assert_eq!(tunnel.tunnel_cos, 5);
+            }
+            _ => panic!("Expected ExtendedMplsTunnel"),
+        },
+        _ => panic!("Expected FlowSample"),
+    }
+}
+
+#[test]
+fn test_flow_0_1008_extended_mpls_tunnel_name_too_long() {
+    use sflow_parser::parsers::{parse_datagram_with_options, ParseOptions};
+
+    // Absurd name length is rejected before any allocation
+    let record_data = [
+        0xFF, 0xFF, 0xFF, 0xF0, // tunnel_lsp_name length = 4294967280
+        0x00, 0x00, 0x00, 0x2A, // tunnel_id = 42
+        0x00, 0x00, 0x00, 0x05, // tunnel_cos = 5
+    ];
+    let data = build_flow_sample_test(0x03F0, &record_data); // record type = 1008
+    let err = parse_datagram(&data).unwrap_err();
+    assert!(err.to_string().contains("exceeds maximum"));
+
+    // The bound is configurable through max_opaque_bytes
+    let record_data = [
+        0x00, 0x00, 0x00, 0x08, // tunnel_lsp_name length = 8
+        b'l', b's', b'p', b'-', b'c', b'o', b'r', b'e', // "lsp-core"
+        0x00, 0x00, 0x00, 0x2A, // tunnel_id = 42
+        0x00, 0x00, 0x00, 0x05, // tunnel_cos = 5
+    ];
+    let data = build_flow_sample_test(0x03F0, &record_data);
+    let options = ParseOptions {
+        max_opaque_bytes: 4,
+        ..Default::default()
+    };
+    let err = parse_datagram_with_options(&data, &options).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Opaque data length 8 exceeds maximum 4"));
+}
+
 #[test]
 fn test_flow_0_1009_extended_mpls_vc() {
-    // Extended MPLS VC data: vc_name_len(4) + "vc100"(5) + padding(3) + vll_vc_id(4) + vc_label(4) + vc_cos(4) = 24 bytes
+    // Extended MPLS VC data: vc_name_len(4) + "vc100"(5) + padding(3) + vll_vc_id(4) + vc_label_cos(4) = 20 bytes
     let record_data = [
         0x00, 0x00, 0x00, 0x05, // vc_instance_name length = 5
         b'v', b'c', b'1', b'0', b'0', 0x00, 0x00, 0x00, // "vc100" + padding
         0x00, 0x00, 0x00, 0x64, // vll_vc_id = 100
-        0x00, 0x00, 0x03, 0xE8, // vc_label = 1000
-        0x00, 0x00, 0x00, 0x03, // vc_cos = 3
+        0x00, 0x00, 0x1F, 0x43, // vc_label_cos = (1000 << 3) | 3
     ];
 
     let data = build_flow_sample_test(0x03F1, &record_data); // record type = 1009
@@ -510,6 +779,7 @@ fn test_flow_0_1009_extended_mpls_vc() {
                     assert_eq!(vc.vll_vc_id, 100);
                     assert_eq!(vc.vc_label, 1000);
                     assert_eq!(vc.vc_cos, 3);
+                    assert_eq!(vc.vc_label_cos(), 0x1F43);
                 }
                 _ => panic!("Expected ExtendedMplsVc"),
             }
@@ -613,13 +883,12 @@ fn test_flow_0_1012_extended_vlan_tunnel() {
 
 #[test]
 fn test_flow_0_1013_extended_80211_payload() {
-    // Extended 802.11 Payload: cipher_suite(4) + data_length(4) + data(8 padded to 12) = 20 bytes
+    // Extended 802.11 Payload: cipher_suite(4) + data_length(4) + data(8, already aligned) = 16 bytes
     let record_data = [
         0x00, 0x00, 0x00, 0x04, // cipher_suite = 4 (CCMP/AES)
         0x00, 0x00, 0x00, 0x08, // data length = 8 bytes
         0x01, 0x02, 0x03, 0x04, // data bytes
         0x05, 0x06, 0x07, 0x08, // data bytes
-        0x00, 0x00, 0x00, 0x00, // padding to 4-byte boundary
     ];
 
     let data = build_flow_sample_test(0x03F5, &record_data); // record type = 1013
@@ -645,6 +914,46 @@ fn test_flow_0_1013_extended_80211_payload() {
     }
 }
 
+#[test]
+fn test_flow_0_1013_extended_80211_payload_padding() {
+    // The spec only defines {ciphersuite, data<>}: a 5-byte payload is padded to 8 bytes
+    // and the following record must still be aligned
+    let payload_record = vec![
+        0x00, 0x0F, 0xAC, 0x04, // cipher_suite = 00-0F-AC:4 (CCMP)
+        0x00, 0x00, 0x00, 0x05, // data length = 5 bytes
+        0xDE, 0xAD, 0xBE, 0xEF, // data bytes
+        0x42, 0x00, 0x00, 0x00, // data byte + 3 bytes XDR padding
+    ];
+    let vni_record = vec![
+        0x00, 0x00, 0x00, 0x2A, // vni = 42
+    ];
+
+    let mut data = create_datagram_header(1);
+    data.extend_from_slice(&build_flow_sample_with_records(&[
+        (0x03F5, payload_record), // record type = 1013
+        (0x0406, vni_record),     // record type = 1030
+    ]));
+
+    let datagram = parse_datagram(&data).unwrap();
+    match &datagram.samples[0].sample_data {
+        SampleData::FlowSample(flow) => {
+            assert_eq!(flow.flow_records.len(), 2);
+            match &flow.flow_records[0].flow_data {
+                FlowData::Extended80211Payload(wifi) => {
+                    assert_eq!(wifi.cipher_suite, 0x000FAC04);
+                    assert_eq!(wifi.data, vec![0xDE, 0xAD, 0xBE, 0xEF, 0x42]);
+                }
+                _ => panic!("Expected Extended80211Payload"),
+            }
+            match &flow.flow_records[1].flow_data {
+                FlowData::ExtendedVniIngress(vni) => assert_eq!(vni.vni, 42),
+                _ => panic!("Expected ExtendedVniIngress"),
+            }
+        }
+        _ => panic!("Expected FlowSample"),
+    }
+}
+
 #[test]
 fn test_flow_0_1014_extended_80211_rx() {
     // Extended 802.11 RX: ssid_len(4) + "TestNet"(7) + padding(1) + bssid(6) + padding(2) +
@@ -655,7 +964,7 @@ fn test_flow_0_1014_extended_80211_rx() {
         0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x00, 0x00, // bssid (6 bytes) + padding (2 bytes)
         0x00, 0x00, 0x00, 0x04, // version = 4 (802.11n)
         0x00, 0x00, 0x00, 0x24, // channel = 36
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xE8, // speed = 1000 (u64)
+        0x00, 0x00, 0x00, 0x00, 0x3B, 0x9A, 0xCA, 0x00, // speed = 1000 Mbps in bps (u64)
         0x00, 0x00, 0x00, 0xB4, // rsni = 180
         0x00, 0x00, 0x00, 0x5A, // rcpi = 90
         0x00, 0x00, 0x03, 0xE8, // packet_duration = 1000 microseconds
@@ -682,7 +991,8 @@ fn test_flow_0_1014_extended_80211_rx() {
                     );
                     assert_eq!(rx.version, 4);
                     assert_eq!(rx.channel, 36);
-                    assert_eq!(rx.speed, 1000);
+                    assert_eq!(rx.speed, 1_000_000_000);
+                    assert_eq!(rx.speed_mbps(), 1000.0);
                     assert_eq!(rx.rsni, 180);
                     assert_eq!(rx.rcpi, 90);
                     assert_eq!(rx.packet_duration, 1000);
@@ -708,7 +1018,7 @@ fn test_flow_0_1015_extended_80211_tx() {
         0x00, 0x00, 0x00, 0x64, // packet_duration = 100
         0x00, 0x00, 0x00, 0x32, // retrans_duration = 50
         0x00, 0x00, 0x00, 0x06, // channel = 6
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0xB0, // speed = 1200 Mbps (u64)
+        0x00, 0x00, 0x00, 0x00, 0x47, 0x86, 0x8C, 0x00, // speed = 1200 Mbps in bps (u64)
         0x00, 0x00, 0x00, 0x14, // power = 20 dBm
     ];
 
@@ -731,7 +1041,8 @@ fn test_flow_0_1015_extended_80211_tx() {
                     assert_eq!(tx.version, 3);
                     assert_eq!(tx.transmissions, 2);
                     assert_eq!(tx.channel, 6);
-                    assert_eq!(tx.speed, 1200);
+                    assert_eq!(tx.speed, 1_200_000_000);
+                    assert_eq!(tx.speed_mbps(), 1200.0);
                     assert_eq!(tx.power, 20);
                 }
                 _ => panic!("Expected Extended80211Tx"),
@@ -773,6 +1084,117 @@ fn test_flow_0_1016_extended_80211_aggregation() {
     }
 }
 
+#[test]
+fn test_flow_0_1016_extended_80211_aggregation_nested_record() {
+    // One PDU holding a SampledHeader flow record framed as format(4) + length(4) + data
+    let header_data = sampled_header_record_data(14);
+    let mut record_data = Vec::new();
+    record_data.extend_from_slice(&u32_bytes(1)); // pdu_count = 1
+    record_data.extend_from_slice(&u32_bytes(1)); // PDU 1: flow_record_count = 1
+    record_data.extend_from_slice(&u32_bytes(0x0001)); // format = (0,1) sampled header
+    record_data.extend_from_slice(&u32_bytes(header_data.len() as u32)); // record length
+    record_data.extend_from_slice(&header_data);
+
+    let data = build_flow_sample_test(0x03F8, &record_data); // record type = 1016
+
+    let datagram = parse_datagram(&data).unwrap();
+    match &datagram.samples[0].sample_data {
+        SampleData::FlowSample(flow) => match &flow.flow_records[0].flow_data {
+            FlowData::Extended80211Aggregation(agg) => {
+                assert_eq!(agg.pdus.len(), 1);
+                assert_eq!(agg.pdus[0].flow_records.len(), 1);
+                let record = &agg.pdus[0].flow_records[0];
+                assert_eq!(record.flow_format, DataFormat::new(0, 1));
+                match &record.flow_data {
+                    FlowData::SampledHeader(header) => {
+                        assert_eq!(header.frame_length, 1500);
+                        assert_eq!(header.header.len(), 14);
+                    }
+                    _ => panic!("Expected SampledHeader"),
+                }
+            }
+            _ => panic!("Expected Extended80211Aggregation"),
+        },
+        _ => panic!("Expected FlowSample"),
+    }
+}
+
+#[test]
+fn test_flow_0_1016_extended_80211_aggregation_record_budget() {
+    use sflow_parser::parsers::{parse_datagram_with_options, ParseError};
+
+    // 4 PDUs of 4 SampledHeader records: 16 nested records plus the aggregation
+    let header_data = sampled_header_record_data(14);
+    let mut record_data = Vec::new();
+    record_data.extend_from_slice(&u32_bytes(4)); // pdu_count = 4
+    for _ in 0..4 {
+        record_data.extend_from_slice(&u32_bytes(4)); // flow_record_count = 4
+        for _ in 0..4 {
+            record_data.extend_from_slice(&u32_bytes(0x0001)); // format = (0,1)
+            record_data.extend_from_slice(&u32_bytes(header_data.len() as u32));
+            record_data.extend_from_slice(&header_data);
+        }
+    }
+    let data = build_flow_sample_test(0x03F8, &record_data); // record type = 1016
+
+    let mut options = ParseOptions {
+        max_total_records: 16,
+        ..Default::default()
+    };
+    let err = parse_datagram_with_options(&data, &options).unwrap_err();
+    assert!(matches!(err, ParseError::TooManyRecords { limit: 16 }));
+    assert_eq!(
+        err.to_string(),
+        "Too many records: datagram exceeds the limit of 16 records"
+    );
+
+    // The budget is not a malformed record to recover from
+    options.recover_bad_records = true;
+    let err = parse_datagram_with_options(&data, &options).unwrap_err();
+    assert!(matches!(err, ParseError::TooManyRecords { limit: 16 }));
+
+    options.max_total_records = 17;
+    assert!(parse_datagram_with_options(&data, &options).is_ok());
+}
+
+#[test]
+fn test_record_budget_spans_samples() {
+    use sflow_parser::parsers::{
+        parse_datagram_visit, parse_datagram_with_options, DatagramVisitor, ParseError,
+    };
+
+    // Two flow samples of 3 extended switch records each
+    let switch = [10u32, 0, 20, 0]
+        .iter()
+        .flat_map(|v| u32_bytes(*v))
+        .collect::<Vec<u8>>();
+    let sample = build_flow_sample_with_records(&[
+        (1001, switch.clone()),
+        (1001, switch.clone()),
+        (1001, switch),
+    ]);
+    let mut data = create_datagram_header(2);
+    data.extend_from_slice(&sample);
+    data.extend_from_slice(&sample);
+
+    let mut options = ParseOptions {
+        max_total_records: 5,
+        ..Default::default()
+    };
+    let err = parse_datagram_with_options(&data, &options).unwrap_err();
+    assert!(matches!(err, ParseError::TooManyRecords { limit: 5 }));
+
+    // The visitor counts against the same budget
+    struct Ignore;
+    impl DatagramVisitor for Ignore {}
+    let err = parse_datagram_visit(&data, &options, &mut Ignore).unwrap_err();
+    assert!(matches!(err, ParseError::TooManyRecords { limit: 5 }));
+
+    options.max_total_records = 6;
+    assert!(parse_datagram_with_options(&data, &options).is_ok());
+    assert!(parse_datagram_visit(&data, &options, &mut Ignore).is_ok());
+}
+
 #[test]
 fn test_flow_0_1017_extended_openflow_v1() {
     // Extended OpenFlow v1: flow_cookie(8) + flow_match(4) + flow_actions(4) = 16 bytes
@@ -1156,6 +1578,44 @@ fn test_flow_0_1028_extended_decapsulate_ingress() {
     }
 }
 
+#[test]
+fn test_flow_0_1027_inner_header_offset_bounds() {
+    use sflow_parser::parsers::ParseWarning;
+
+    let datagram_with_offset = |offset: u32| {
+        let mut data = create_datagram_header(1);
+        data.extend_from_slice(&build_flow_sample_with_records(&[
+            (0x0001, sampled_header_record_data(32)), // 32 header bytes
+            (0x0403, u32_bytes(offset).to_vec()),     // record type = 1027
+        ]));
+        data
+    };
+
+    // Offset within the captured header
+    let (datagram, warnings) =
+        parse_datagram_verbose(&datagram_with_offset(14), &ParseOptions::default()).unwrap();
+    assert!(warnings.is_empty(), "{:?}", warnings);
+    match &datagram.samples[0].sample_data {
+        SampleData::FlowSample(flow) => assert_eq!(flow.inner_header_offset(), Some(14)),
+        _ => panic!("Expected FlowSample"),
+    }
+
+    // Offset past the captured header: decoded, but reported
+    let (datagram, warnings) =
+        parse_datagram_verbose(&datagram_with_offset(40), &ParseOptions::default()).unwrap();
+    assert_eq!(
+        warnings,
+        vec![ParseWarning::InnerHeaderOutOfBounds {
+            offset: 40,
+            header_length: 32,
+        }]
+    );
+    match &datagram.samples[0].sample_data {
+        SampleData::FlowSample(flow) => assert_eq!(flow.inner_header_offset(), Some(40)),
+        _ => panic!("Expected FlowSample"),
+    }
+}
+
 #[test]
 fn test_flow_0_1029_extended_vni_egress() {
     // Extended VNI Egress: vni(4)
@@ -1776,6 +2236,42 @@ fn test_flow_0_2003_extended_http_transaction() {
     }
 }
 
+#[test]
+fn test_flow_0_2003_extended_http_transaction_empty_strings() {
+    let mut record_data = Vec::new();
+
+    // url = "/missing"
+    record_data.extend(encode_string("/missing"));
+
+    // host, referer, useragent and user are empty (zero length, no padding)
+    for _ in 0..4 {
+        record_data.extend(&[0x00, 0x00, 0x00, 0x00]);
+    }
+
+    // status = 404
+    record_data.extend(&[0x00, 0x00, 0x01, 0x94]);
+
+    let data = build_flow_sample_test(0x07D3, &record_data); // record type = 2003
+
+    let datagram = parse_datagram(&data).unwrap();
+    match &datagram.samples[0].sample_data {
+        SampleData::FlowSample(flow) => match &flow.flow_records[0].flow_data {
+            FlowData::ExtendedHttpTransaction(http) => {
+                assert_eq!(http.url, "/missing");
+                assert_eq!(http.host, "");
+                assert_eq!(http.referer, "");
+                assert_eq!(http.user_agent, "");
+                assert_eq!(http.user, "");
+                assert_eq!(http.status, 404);
+                assert_eq!(http.status_class(), Some(4));
+                assert!(!http.is_success());
+            }
+            _ => panic!("Expected ExtendedHttpTransaction"),
+        },
+        _ => panic!("Expected FlowSample"),
+    }
+}
+
 // ===== Enterprise 0: Application/Socket Records (Formats 2100-2207) =====
 
 #[test]
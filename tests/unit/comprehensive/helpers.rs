@@ -3,6 +3,9 @@
 //! Helper functions for building test data for comprehensive parser tests.
 //! These helpers create binary sFlow v5 datagrams for testing.
 //!
+//! The fixture builders at the end of this file are also used by the
+//! benchmarks (`benches/parser_benchmark.rs`), so keep them self-contained.
+//!
 //! Reference: https://sflow.org/sflow_version_5.txt
 
 #[allow(unused_imports)]
@@ -122,3 +125,117 @@ pub(crate) fn create_datagram_header(num_samples: u32) -> Vec<u8> {
 
     header
 }
+
+/// Build a flow sample with several flow records
+/// Each record is given as (record type, record data)
+pub(crate) fn build_flow_sample_with_records(records: &[(u32, Vec<u8>)]) -> Vec<u8> {
+    let lengths: Vec<usize> = records.iter().map(|(_, data)| data.len()).collect();
+    let sample_length = calculate_flow_sample_length(&lengths);
+
+    let mut data = Vec::with_capacity(8 + sample_length as usize);
+    data.extend_from_slice(&[0x00, 0x00, 0x00, 0x01]); // sample type = flow sample
+    data.extend_from_slice(&u32_bytes(sample_length));
+    data.extend_from_slice(&[
+        0x00, 0x00, 0x00, 0x01, // sequence number
+        0x00, 0x00, 0x00, 0x01, // source ID
+        0x00, 0x00, 0x04, 0x00, // sampling rate = 1024
+        0x00, 0x00, 0x00, 0x64, // sample pool = 100
+        0x00, 0x00, 0x00, 0x00, // drops = 0
+        0x00, 0x00, 0x00, 0x01, // input interface = 1
+        0x00, 0x00, 0x00, 0x02, // output interface = 2
+    ]);
+    data.extend_from_slice(&u32_bytes(records.len() as u32)); // number of flow records
+
+    for (record_type, record_data) in records {
+        data.extend_from_slice(&u32_bytes(*record_type));
+        data.extend_from_slice(&u32_bytes(record_data.len() as u32));
+        data.extend_from_slice(record_data);
+    }
+
+    data
+}
+
+/// Build a counter sample with several counter records
+/// Each record is given as (record type, record data)
+pub(crate) fn build_counter_sample_with_records(records: &[(u32, Vec<u8>)]) -> Vec<u8> {
+    // Counter sample header = 12 bytes, each record header = 8 bytes
+    let sample_length: usize = 12 + records.iter().map(|(_, d)| 8 + d.len()).sum::<usize>();
+
+    let mut data = Vec::with_capacity(8 + sample_length);
+    data.extend_from_slice(&[0x00, 0x00, 0x00, 0x02]); // sample type = counter sample
+    data.extend_from_slice(&u32_bytes(sample_length as u32));
+    data.extend_from_slice(&[
+        0x00, 0x00, 0x00, 0x01, // sequence number
+        0x00, 0x00, 0x00, 0x01, // source ID
+    ]);
+    data.extend_from_slice(&u32_bytes(records.len() as u32)); // number of counter records
+
+    for (record_type, record_data) in records {
+        data.extend_from_slice(&u32_bytes(*record_type));
+        data.extend_from_slice(&u32_bytes(record_data.len() as u32));
+        data.extend_from_slice(record_data);
+    }
+
+    data
+}
+
+/// Generic interface counters record data (0,1) for the given interface index
+pub(crate) fn generic_interface_record_data(if_index: u32) -> Vec<u8> {
+    let mut data = Vec::with_capacity(88);
+    data.extend_from_slice(&u32_bytes(if_index)); // if_index
+    data.extend_from_slice(&u32_bytes(6)); // if_type = ethernetCsmacd
+    data.extend_from_slice(&1_000_000_000u64.to_be_bytes()); // if_speed = 1 Gbps
+    data.extend_from_slice(&u32_bytes(1)); // if_direction = full-duplex
+    data.extend_from_slice(&u32_bytes(3)); // if_status = up
+    data.extend_from_slice(&10_000u64.to_be_bytes()); // if_in_octets
+    for value in [100, 10, 5, 0, 0, 0] {
+        data.extend_from_slice(&u32_bytes(value)); // if_in_* counters
+    }
+    data.extend_from_slice(&20_000u64.to_be_bytes()); // if_out_octets
+    for value in [200, 20, 10, 0, 0, 0] {
+        data.extend_from_slice(&u32_bytes(value)); // if_out_* counters, if_promiscuous_mode
+    }
+    data
+}
+
+/// Sampled header record data (0,1) carrying `header_length` bytes of Ethernet header
+pub(crate) fn sampled_header_record_data(header_length: usize) -> Vec<u8> {
+    let mut data = Vec::with_capacity(16 + header_length + 3);
+    data.extend_from_slice(&u32_bytes(1)); // protocol = Ethernet
+    data.extend_from_slice(&u32_bytes(1500)); // frame_length
+    data.extend_from_slice(&u32_bytes(4)); // stripped
+    data.extend_from_slice(&u32_bytes(header_length as u32)); // header length
+    data.extend((0..header_length).map(|i| i as u8)); // header bytes
+    data.resize(data.len() + (4 - header_length % 4) % 4, 0); // XDR padding
+    data
+}
+
+/// Fixture: datagram with one counter sample holding `count` generic interface records
+pub(crate) fn counter_datagram_fixture(count: u32) -> Vec<u8> {
+    let records: Vec<(u32, Vec<u8>)> = (0..count)
+        .map(|i| (0x0001, generic_interface_record_data(i + 1)))
+        .collect();
+    let mut data = create_datagram_header(1);
+    data.extend_from_slice(&build_counter_sample_with_records(&records));
+    data
+}
+
+/// Fixture: datagram with one flow sample holding `count` sampled header records
+pub(crate) fn sampled_header_datagram_fixture(count: u32, header_length: usize) -> Vec<u8> {
+    let records: Vec<(u32, Vec<u8>)> = (0..count)
+        .map(|_| (0x0001, sampled_header_record_data(header_length)))
+        .collect();
+    let mut data = create_datagram_header(1);
+    data.extend_from_slice(&build_flow_sample_with_records(&records));
+    data
+}
+
+/// Fixture: datagram with one flow sample holding `count` records of an unknown vendor format
+pub(crate) fn unknown_records_datagram_fixture(count: u32) -> Vec<u8> {
+    // enterprise = 9999, format = 1
+    let record_type = (9999 << 12) | 1;
+    let records: Vec<(u32, Vec<u8>)> = (0..count).map(|_| (record_type, vec![0xAB; 64])).collect();
+    let mut data = create_datagram_header(1);
+    data.extend_from_slice(&build_flow_sample_with_records(&records));
+    data
+}
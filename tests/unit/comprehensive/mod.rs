@@ -7,5 +7,5 @@
 
 pub mod counters;
 pub mod flows;
-mod helpers;
+pub(crate) mod helpers;
 pub mod samples;
@@ -24,7 +24,13 @@
 //! Tests are organized by sample type and functionality, not by (enterprise, format).
 
 use super::helpers::*;
-use sflow_parser::parsers::parse_datagram;
+use sflow_parser::parsers::{
+    lazy_counter_records, lazy_flow_records, parse_counters_only, parse_datagram,
+    parse_datagram_visit, parse_datagram_with_options, parse_datagram_with_scratch, parse_header,
+    supported_counter_formats, supported_flow_formats, DatagramVisitor, ParseError, ParseOptions,
+    ParseScratch, Parser, TcpFramedDecoder,
+};
+use std::io::Cursor;
 
 #[test]
 fn test_parse_expanded_flow_sample() {
@@ -198,6 +204,202 @@ fn test_parse_unknown_flow_record() {
     }
 }
 
+#[test]
+fn test_parse_header_without_samples() {
+    // Header declares 3 samples but none follow
+    let data = create_datagram_header(3);
+
+    let header = parse_header(&data).unwrap();
+    assert_eq!(header.version, DatagramVersion::Version5);
+    assert_eq!(
+        header.agent_address,
+        Address::IPv4("192.168.1.1".parse().unwrap())
+    );
+    assert_eq!(header.sub_agent_id, 0);
+    assert_eq!(header.sequence_number, 1);
+    assert_eq!(header.uptime, 100);
+    assert_eq!(header.num_samples, 3);
+    assert!(parse_datagram(&data).is_err());
+
+    // Only the 28 header bytes are consumed
+    let mut data = data;
+    data.extend_from_slice(&[0xFF; 16]);
+    let mut cursor = Cursor::new(&data[..]);
+    Parser::new(&mut cursor).parse_datagram_header().unwrap();
+    assert_eq!(cursor.position(), 28);
+}
+
+#[test]
+fn test_parse_with_scratch() {
+    let options = ParseOptions::default();
+    let mut flows = create_datagram_header(1);
+    flows.extend(build_flow_sample_with_records(&[
+        (0x0003, vec![0; 32]),       // sampled IPv4
+        (0x03E9, vec![0; 16]),       // extended switch
+        (0x0270F005, vec![0xAA; 4]), // unknown record
+    ]));
+    let counters = counter_datagram_fixture(3);
+
+    let mut scratch = ParseScratch::new();
+    for data in [&flows, &counters, &flows] {
+        assert_eq!(
+            parse_datagram_with_scratch(data, &options, &mut scratch).unwrap(),
+            parse_datagram_with_options(data, &options).unwrap()
+        );
+    }
+    assert!(scratch.capacity() >= 88);
+
+    // A failed datagram leaves the scratch usable
+    assert!(parse_datagram_with_scratch(&counters[..40], &options, &mut scratch).is_err());
+    assert_eq!(
+        parse_datagram_with_scratch(&counters, &options, &mut scratch).unwrap(),
+        parse_datagram(&counters).unwrap()
+    );
+}
+
+#[test]
+fn test_tcp_framed_decoder() {
+    let first = counter_datagram_fixture(1);
+    let second = counter_datagram_fixture(2);
+    let mut stream = Vec::new();
+    for datagram in [&first, &second] {
+        stream.extend_from_slice(&u32_bytes(datagram.len() as u32)); // frame length
+        stream.extend_from_slice(datagram);
+    }
+
+    let datagrams: Vec<_> = TcpFramedDecoder::new(Cursor::new(&stream))
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(datagrams.len(), 2);
+    assert_eq!(datagrams[0], parse_datagram(&first).unwrap());
+    assert_eq!(datagrams[1], parse_datagram(&second).unwrap());
+
+    // A frame cut short ends the stream with an error
+    let mut decoder = TcpFramedDecoder::new(Cursor::new(&stream[..stream.len() - 1]));
+    assert!(decoder.next().unwrap().is_ok());
+    assert!(matches!(decoder.next(), Some(Err(ParseError::Io(_)))));
+    assert!(decoder.next().is_none());
+}
+
+#[test]
+fn test_lazy_flow_records() {
+    let mut ipv4 = vec![0; 32];
+    ipv4[7] = 6; // protocol = TCP
+    ipv4[23] = 80; // dst port
+    let bad_router = vec![
+        0x00, 0x00, 0x00, 0x09, // next hop address type = 9 (invalid)
+        0x00, 0x00, 0x00, 0x00, //
+    ];
+    let sample = build_flow_sample_with_records(&[
+        (0x0003, ipv4),       // sampled IPv4
+        (0x03EA, bad_router), // extended router
+    ]);
+
+    // Decoding the whole sample fails on the second record
+    let mut data = create_datagram_header(1);
+    data.extend_from_slice(&sample);
+    assert!(parse_datagram(&data).is_err());
+
+    let mut records = lazy_flow_records(&sample).unwrap();
+    assert_eq!(records.size_hint(), (0, Some(2)));
+    let first = records.next().unwrap().unwrap();
+    match first.flow_data {
+        FlowData::SampledIpv4(ip) => assert_eq!(ip.dst_port, 80),
+        _ => panic!("Expected SampledIpv4"),
+    }
+    assert!(records.next().unwrap().is_err());
+    assert!(records.next().is_none());
+
+    assert!(lazy_counter_records(&sample).is_err());
+    let counters = build_counter_sample_with_records(&[(1, generic_interface_record_data(7))]);
+    let records: Vec<_> = lazy_counter_records(&counters)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].counter_format, DataFormat::new(0, 1));
+}
+
+/// Name of the enum variant in a record's debug output
+fn variant_name(debug: &str) -> &str {
+    debug.split(['(', ' ']).next().unwrap()
+}
+
+#[test]
+fn test_supported_flow_formats() {
+    let formats: Vec<_> = supported_flow_formats().collect();
+    assert!(formats.windows(2).all(|pair| pair[0].0 .0 < pair[1].0 .0));
+
+    for (format, name) in formats {
+        let data = build_flow_sample_test(format.0, &[0; 512]);
+        let datagram = parse_datagram(&data).unwrap();
+        let SampleData::FlowSample(flow) = &datagram.samples[0].sample_data else {
+            panic!("Expected FlowSample");
+        };
+        let flow_data = &flow.flow_records[0].flow_data;
+        assert_eq!(flow_data.data_format(), format, "{name}");
+        assert_eq!(variant_name(&format!("{flow_data:?}")), name);
+    }
+}
+
+#[test]
+fn test_supported_counter_formats() {
+    let formats: Vec<_> = supported_counter_formats().collect();
+    assert!(formats.windows(2).all(|pair| pair[0].0 .0 < pair[1].0 .0));
+
+    for (format, name) in formats {
+        let data = build_counter_sample_test(format.0, &[0; 512]);
+        let datagram = parse_datagram(&data).unwrap();
+        let SampleData::CountersSample(counters) = &datagram.samples[0].sample_data else {
+            panic!("Expected CountersSample");
+        };
+        let counter_data = &counters.counters[0].counter_data;
+        assert_eq!(counter_data.data_format(), format, "{name}");
+        assert_eq!(variant_name(&format!("{counter_data:?}")), name);
+    }
+}
+
+#[test]
+fn test_parse_unknown_enterprise_flow_record() {
+    let mut data = create_datagram_header(1);
+    data.extend_from_slice(&[
+        0x00, 0x00, 0x00, 0x01, // sample type = flow sample
+        0x00, 0x00, 0x00, 0x2C, // sample length = 44 bytes
+        0x00, 0x00, 0x00, 0x01, // sequence number
+        0x00, 0x00, 0x00, 0x01, // source id
+        0x00, 0x00, 0x00, 0x64, // sampling rate
+        0x00, 0x00, 0x00, 0x64, // sample pool
+        0x00, 0x00, 0x00, 0x00, // drops
+        0x00, 0x00, 0x00, 0x01, // input interface
+        0x00, 0x00, 0x00, 0x02, // output interface
+        0x00, 0x00, 0x00, 0x01, // number of flow records = 1
+        0x02, 0x70, 0xF0, 0x05, // record type = enterprise 9999, format 5
+        0x00, 0x00, 0x00, 0x04, // record length = 4 bytes
+        0xAA, 0xBB, 0xCC, 0xDD, // record data
+    ]);
+
+    let datagram = parse_datagram(&data).unwrap();
+    match &datagram.samples[0].sample_data {
+        SampleData::FlowSample(flow) => {
+            let record = &flow.flow_records[0];
+            assert_eq!(record.flow_format, DataFormat::new(9999, 5));
+            assert_eq!(
+                record.flow_data.unknown_enterprise_format(),
+                Some((9999, 5))
+            );
+            match &record.flow_data {
+                FlowData::Unknown { format, data } => {
+                    assert_eq!(format.enterprise(), 9999);
+                    assert_eq!(format.format(), 5);
+                    assert_eq!(data, &[0xAA, 0xBB, 0xCC, 0xDD]);
+                }
+                _ => panic!("Expected Unknown flow record"),
+            }
+        }
+        _ => panic!("Expected FlowSample"),
+    }
+}
+
 #[test]
 fn test_parse_unknown_counter_record() {
     let mut data = create_datagram_header(1);
@@ -404,3 +606,237 @@ fn test_parse_rtflow_sample() {
         _ => panic!("Expected RtFlow"),
     }
 }
+
+#[test]
+fn test_parse_counter_datagram_fixture() {
+    let data = counter_datagram_fixture(50);
+    let datagram = parse_datagram(&data).unwrap();
+
+    match &datagram.samples[0].sample_data {
+        SampleData::CountersSample(sample) => {
+            assert_eq!(sample.counters.len(), 50);
+            match &sample.counters[49].counter_data {
+                CounterData::GenericInterface(iface) => {
+                    assert_eq!(iface.if_index, 50);
+                    assert_eq!(iface.if_in_octets, 10_000);
+                    assert_eq!(iface.if_out_octets, 20_000);
+                }
+                _ => panic!("Expected GenericInterface"),
+            }
+        }
+        _ => panic!("Expected CountersSample"),
+    }
+}
+
+#[test]
+fn test_parse_sampled_header_datagram_fixture() {
+    let data = sampled_header_datagram_fixture(50, 128);
+    let datagram = parse_datagram(&data).unwrap();
+
+    match &datagram.samples[0].sample_data {
+        SampleData::FlowSample(sample) => {
+            assert_eq!(sample.flow_records.len(), 50);
+            for record in &sample.flow_records {
+                match &record.flow_data {
+                    FlowData::SampledHeader(header) => assert_eq!(header.header.len(), 128),
+                    _ => panic!("Expected SampledHeader"),
+                }
+            }
+        }
+        _ => panic!("Expected FlowSample"),
+    }
+}
+
+#[test]
+fn test_parse_unknown_records_datagram_fixture() {
+    let data = unknown_records_datagram_fixture(50);
+    let datagram = parse_datagram(&data).unwrap();
+
+    match &datagram.samples[0].sample_data {
+        SampleData::FlowSample(sample) => {
+            assert_eq!(sample.flow_records.len(), 50);
+            assert!(sample
+                .flow_records
+                .iter()
+                .all(|r| matches!(r.flow_data, FlowData::Unknown { .. })));
+        }
+        _ => panic!("Expected FlowSample"),
+    }
+}
+
+#[test]
+fn test_parse_counters_only_skips_flow_samples() {
+    let flow_sample = build_flow_sample_with_records(&[(0x0001, sampled_header_record_data(64))]);
+    let counter_sample =
+        build_counter_sample_with_records(&[(0x0001, generic_interface_record_data(7))]);
+
+    let mut data = create_datagram_header(3);
+    data.extend_from_slice(&flow_sample);
+    data.extend_from_slice(&counter_sample);
+    data.extend_from_slice(&flow_sample);
+
+    let datagram = parse_counters_only(&data).unwrap();
+    assert_eq!(datagram.samples.len(), 3);
+
+    for index in [0, 2] {
+        match &datagram.samples[index].sample_data {
+            SampleData::Skipped { format, length } => {
+                assert_eq!(*format, DataFormat::new(0, 1));
+                assert_eq!(*length as usize, flow_sample.len() - 8);
+            }
+            _ => panic!("Expected Skipped flow sample"),
+        }
+    }
+
+    // Counter samples are decoded exactly as with a full parse
+    let full = parse_datagram(&data).unwrap();
+    assert_eq!(datagram.samples[1], full.samples[1]);
+    match &datagram.samples[1].sample_data {
+        SampleData::CountersSample(sample) => match &sample.counters[0].counter_data {
+            CounterData::GenericInterface(iface) => assert_eq!(iface.if_index, 7),
+            _ => panic!("Expected GenericInterface"),
+        },
+        _ => panic!("Expected CountersSample"),
+    }
+}
+
+#[test]
+fn test_parse_counters_only_truncated_flow_sample() {
+    let flow_sample = build_flow_sample_with_records(&[(0x0001, sampled_header_record_data(64))]);
+    let mut data = create_datagram_header(1);
+    data.extend_from_slice(&flow_sample[..flow_sample.len() - 4]);
+
+    assert!(parse_counters_only(&data).is_err());
+}
+
+/// Visitor counting SampledIpv4 records and skipping every other flow record
+#[derive(Default)]
+struct SampledIpv4Counter {
+    header: Option<DatagramHeader>,
+    skipped: usize,
+    count: usize,
+    counters: usize,
+}
+
+impl DatagramVisitor for SampledIpv4Counter {
+    fn on_header(&mut self, header: &DatagramHeader) {
+        self.header = Some(header.clone());
+    }
+
+    fn wants_flow_record(&mut self, format: DataFormat) -> bool {
+        let wanted = format == DataFormat::new(0, 3);
+        if !wanted {
+            self.skipped += 1;
+        }
+        wanted
+    }
+
+    fn on_flow_record(&mut self, record: &FlowData) {
+        assert!(matches!(record, FlowData::SampledIpv4(_)));
+        self.count += 1;
+    }
+
+    fn wants_counter_record(&mut self, _format: DataFormat) -> bool {
+        false
+    }
+
+    fn on_counter_record(&mut self, _record: &CounterData) {
+        self.counters += 1;
+    }
+}
+
+fn sampled_ipv4_record_data() -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&u32_bytes(64)); // length
+    data.extend_from_slice(&u32_bytes(6)); // protocol = TCP
+    data.extend_from_slice(&[10, 0, 0, 1]); // src_ip
+    data.extend_from_slice(&[10, 0, 0, 2]); // dst_ip
+    data.extend_from_slice(&u32_bytes(12345)); // src_port
+    data.extend_from_slice(&u32_bytes(443)); // dst_port
+    data.extend_from_slice(&u32_bytes(0x02)); // tcp_flags
+    data.extend_from_slice(&u32_bytes(0)); // tos
+    data
+}
+
+#[test]
+fn test_parse_datagram_visit_counts_sampled_ipv4() {
+    let flow_sample = build_flow_sample_with_records(&[
+        (0x0001, sampled_header_record_data(64)),
+        (0x0003, sampled_ipv4_record_data()),
+        (0xFFFF, vec![0xAA; 8]),
+        (0x0003, sampled_ipv4_record_data()),
+    ]);
+    let counter_sample =
+        build_counter_sample_with_records(&[(0x0001, generic_interface_record_data(7))]);
+
+    let mut data = create_datagram_header(3);
+    data.extend_from_slice(&flow_sample);
+    data.extend_from_slice(&counter_sample);
+    data.extend_from_slice(&flow_sample);
+
+    let mut visitor = SampledIpv4Counter::default();
+    parse_datagram_visit(&data, &ParseOptions::default(), &mut visitor).unwrap();
+
+    assert_eq!(visitor.header.unwrap().num_samples, 3);
+    assert_eq!(visitor.count, 4);
+    assert_eq!(visitor.skipped, 4);
+    assert_eq!(visitor.counters, 0);
+}
+
+#[test]
+fn test_parse_datagram_visit_truncated_sample() {
+    let flow_sample = build_flow_sample_with_records(&[(0x0003, sampled_ipv4_record_data())]);
+    let mut data = create_datagram_header(1);
+    data.extend_from_slice(&flow_sample[..flow_sample.len() - 4]);
+
+    // Reported before any callback, as by parse_datagram
+    let mut visitor = SampledIpv4Counter::default();
+    let err = parse_datagram_visit(&data, &ParseOptions::default(), &mut visitor).unwrap_err();
+    assert!(
+        matches!(err, ParseError::DatagramTruncated { .. }),
+        "{}",
+        err
+    );
+    assert!(visitor.header.is_none());
+}
+
+#[test]
+fn test_parse_ipv6_agent_address() {
+    let data = [
+        0x00, 0x00, 0x00, 0x05, // version = 5
+        0x00, 0x00, 0x00, 0x02, // agent address type = IPv6
+        0x20, 0x01, 0x0D, 0xB8, // 2001:db8::1
+        0x00, 0x00, 0x00, 0x00, //
+        0x00, 0x00, 0x00, 0x00, //
+        0x00, 0x00, 0x00, 0x01, //
+        0x00, 0x00, 0x00, 0x03, // sub-agent ID = 3
+        0x00, 0x00, 0x00, 0x2A, // sequence number = 42
+        0x00, 0x00, 0x03, 0xE8, // uptime = 1000ms
+        0x00, 0x00, 0x00, 0x00, // number of samples = 0
+    ];
+
+    let datagram = parse_datagram(&data).unwrap();
+    let expected: std::net::Ipv6Addr = "2001:db8::1".parse().unwrap();
+    assert_eq!(datagram.agent_ip(), Some(std::net::IpAddr::V6(expected)));
+    assert_eq!(
+        datagram.to_string(),
+        "sFlow v5 agent=2001:db8::1 sub_agent_id=3 seq=42 uptime=1000ms samples=0"
+    );
+}
+
+#[test]
+fn test_parse_unknown_agent_address() {
+    let data = [
+        0x00, 0x00, 0x00, 0x05, // version = 5
+        0x00, 0x00, 0x00, 0x00, // agent address type = Unknown (no address bytes)
+        0x00, 0x00, 0x00, 0x00, // sub-agent ID = 0
+        0x00, 0x00, 0x00, 0x01, // sequence number = 1
+        0x00, 0x00, 0x00, 0x64, // uptime = 100ms
+        0x00, 0x00, 0x00, 0x00, // number of samples = 0
+    ];
+
+    let datagram = parse_datagram(&data).unwrap();
+    assert_eq!(datagram.agent_address, Address::Unknown);
+    assert_eq!(datagram.agent_ip(), None);
+    assert!(datagram.to_string().starts_with("sFlow v5 agent=0.0.0.0 "));
+}
@@ -67,6 +67,72 @@ fn test_data_source_max_index() {
     assert_eq!(source.index(), 0xFFFFFF);
 }
 
+#[test]
+fn test_data_source_as_map_key() {
+    use std::collections::{BTreeMap, HashMap};
+
+    let mut octets: HashMap<DataSource, u64> = HashMap::new();
+    for (source, value) in [
+        (DataSource::new(0, 3), 1000),
+        (DataSource::new(0, 7), 500),
+        (DataSource::new(0, 3), 250),
+    ] {
+        *octets.entry(source).or_default() += value;
+    }
+    assert_eq!(octets.len(), 2);
+    assert_eq!(octets[&DataSource::new(0, 3)], 1250);
+
+    // Ordered by source type, then index
+    let sources: BTreeMap<DataSource, ()> = [
+        (DataSource::new(2, 1), ()),
+        (DataSource::new(0, 9), ()),
+        (DataSource::new(0, 3), ()),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(
+        sources.keys().copied().collect::<Vec<_>>(),
+        vec![
+            DataSource::new(0, 3),
+            DataSource::new(0, 9),
+            DataSource::new(2, 1)
+        ]
+    );
+}
+
+#[test]
+fn test_data_format_display_round_trip() {
+    for format in [
+        DataFormat::new(0, 3),
+        DataFormat::new(4413, 1),
+        DataFormat::new(0xFFFFF, 0xFFF),
+    ] {
+        let text = format.to_string();
+        assert_eq!(text.parse::<DataFormat>().unwrap(), format);
+    }
+    assert_eq!(DataFormat::new(0, 3).to_string(), "0:3");
+    assert_eq!(DataFormat::new(4413, 1).to_string(), "4413:1");
+}
+
+#[test]
+fn test_data_format_from_str_rejects_out_of_range() {
+    assert!("0:4096".parse::<DataFormat>().is_err());
+    assert!("1048576:1".parse::<DataFormat>().is_err());
+    assert!("0".parse::<DataFormat>().is_err());
+    assert!("0:x".parse::<DataFormat>().is_err());
+}
+
+#[test]
+fn test_data_source_display_round_trip() {
+    for source in [DataSource::new(0, 17), DataSource::new(2, 0xFFFFFF)] {
+        let text = source.to_string();
+        assert_eq!(text.parse::<DataSource>().unwrap(), source);
+    }
+    assert_eq!(DataSource::new(0, 17).to_string(), "0:17");
+    assert!("256:1".parse::<DataSource>().is_err());
+    assert!("0:16777216".parse::<DataSource>().is_err());
+}
+
 #[test]
 fn test_interface_single() {
     // Single interface with ifIndex 42
@@ -140,6 +206,43 @@ fn test_address_types() {
     }
 }
 
+#[test]
+fn test_address_to_ip_addr() {
+    use std::net::IpAddr;
+
+    let v4 = Address::IPv4(Ipv4Addr::new(10, 0, 0, 1));
+    assert_eq!(
+        v4.to_ip_addr(),
+        Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)))
+    );
+    assert_eq!(v4.to_string(), "10.0.0.1");
+
+    let v6_addr = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1);
+    let v6 = Address::IPv6(v6_addr);
+    assert_eq!(v6.to_ip_addr(), Some(IpAddr::V6(v6_addr)));
+    assert_eq!(v6.to_string(), "2001:db8::1");
+
+    assert_eq!(Address::Unknown.to_ip_addr(), None);
+}
+
+#[test]
+fn test_address_canonical_string() {
+    // sflowtool prints unknown agent addresses as 0.0.0.0
+    assert_eq!(Address::Unknown.to_canonical_string(), "0.0.0.0");
+    assert_eq!(Address::Unknown.to_string(), "0.0.0.0");
+
+    let v4 = Address::IPv4(Ipv4Addr::new(192, 168, 1, 1));
+    assert_eq!(v4.to_canonical_string(), "192.168.1.1");
+
+    // IPv6 uses the compressed form
+    let v6 = Address::IPv6(Ipv6Addr::new(
+        0xfe80, 0, 0, 0, 0x0202, 0xb3ff, 0xfe1e, 0x8329,
+    ));
+    assert_eq!(v6.to_canonical_string(), "fe80::202:b3ff:fe1e:8329");
+    let v6 = Address::IPv6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 1, 0, 0, 1));
+    assert_eq!(v6.to_canonical_string(), "2001:db8::1:0:0:1");
+}
+
 #[test]
 fn test_datagram_version() {
     assert_eq!(DatagramVersion::Version5 as u32, 5);
@@ -165,6 +268,42 @@ fn test_sflow_datagram_creation() {
     assert_eq!(datagram.samples.len(), 0);
 }
 
+#[test]
+fn test_uptime_duration_and_delta() {
+    use std::time::Duration;
+
+    let datagram = SFlowDatagram::new(Address::IPv4(Ipv4Addr::new(10, 0, 0, 1)), 0, 1, 90_500);
+    assert_eq!(datagram.uptime_duration(), Duration::from_millis(90_500));
+
+    assert_eq!(uptime_delta(1_000, 31_000), Duration::from_secs(30));
+    assert_eq!(uptime_delta(5_000, 5_000), Duration::ZERO);
+
+    // 0x100 before the wrap plus 0x100 after it
+    assert_eq!(
+        uptime_delta(0xFFFF_FF00, 0x0000_0100),
+        Duration::from_millis(0x200)
+    );
+}
+
+#[test]
+fn test_datagram_key() {
+    use std::net::IpAddr;
+
+    let datagram = SFlowDatagram::new(Address::IPv4(Ipv4Addr::new(10, 0, 0, 1)), 2, 42, 1000);
+    assert_eq!(
+        datagram.datagram_key(),
+        (IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), 2)
+    );
+    assert_eq!(datagram.sub_agent_id, 2);
+    assert_eq!(datagram.sequence_number, 42);
+
+    let unknown = SFlowDatagram::new(Address::Unknown, 0, 1, 0);
+    assert_eq!(
+        unknown.datagram_key(),
+        (IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0)
+    );
+}
+
 #[test]
 fn test_expanded_structures() {
     let source = DataSourceExpanded {
@@ -0,0 +1,170 @@
+//! Tests for the datagram encoder
+
+use sflow_parser::encode::{encode_datagram, encode_flow_data, EncodeError};
+use sflow_parser::models::record_flows::*;
+use sflow_parser::models::*;
+use sflow_parser::parse_datagram;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Build a datagram from agent 192.0.2.1 with the given (format, sample) pairs
+fn datagram(samples: Vec<(u32, SampleData)>) -> SFlowDatagram {
+    let mut datagram = SFlowDatagram::new(Address::IPv4(Ipv4Addr::new(192, 0, 2, 1)), 0, 1, 1000);
+    for (format, sample_data) in samples {
+        datagram.samples.push(SampleRecord {
+            sample_type: DataFormat::new(0, format),
+            sample_data,
+        });
+    }
+    datagram
+}
+
+fn records(data: Vec<FlowData>) -> Vec<FlowRecord> {
+    data.into_iter()
+        .map(|flow_data| FlowRecord {
+            flow_format: flow_data.data_format(),
+            flow_data,
+        })
+        .collect()
+}
+
+#[test]
+fn test_encode_fixture_round_trip() {
+    let data = std::fs::read("tests/data/sflow.bin").unwrap();
+    let datagram = parse_datagram(&data).unwrap();
+    assert_eq!(encode_datagram(&datagram).unwrap(), data);
+}
+
+#[test]
+fn test_encode_discarded_packet_round_trip() {
+    let ipv4 = SampledIpv4 {
+        length: 64,
+        protocol: 6,
+        src_ip: Ipv4Addr::new(10, 0, 0, 1),
+        dst_ip: Ipv4Addr::new(10, 0, 0, 2),
+        src_port: 1234,
+        dst_port: 80,
+        tcp_flags: 0x02,
+        tos: 0,
+    };
+    let flow_records = records(vec![
+        FlowData::ExtendedGateway(ExtendedGateway {
+            next_hop: Address::IPv6(Ipv6Addr::LOCALHOST),
+            as_number: 65000,
+            src_as: 65001,
+            src_peer_as: 65002,
+            dst_as_path: vec![AsPathSegment {
+                path_type: AsPathType::AsSequence,
+                path_length: 2,
+                path: vec![65003, 65004],
+            }],
+            communities: vec![0xFDE8_0001],
+            local_pref: 100,
+            trailing: Vec::new(),
+        }),
+        FlowData::ExtendedMplsVc(ExtendedMplsVc {
+            vc_instance_name: "vc1".to_string(),
+            vll_vc_id: 7,
+            vc_label: 1000,
+            vc_cos: 5,
+        }),
+        FlowData::Extended80211Rx(Extended80211Rx {
+            ssid: "lab".to_string(),
+            bssid: MacAddress::new([0, 1, 2, 3, 4, 5]),
+            version: 4,
+            channel: 36,
+            speed: 1_000_000_000,
+            rsni: 20,
+            rcpi: 30,
+            packet_duration: 40,
+        }),
+        FlowData::Extended80211Aggregation(Extended80211Aggregation {
+            pdus: vec![Pdu {
+                flow_records: records(vec![FlowData::SampledIpv4(ipv4.clone())]),
+            }],
+        }),
+        FlowData::ExtendedSocketIpv4(ExtendedSocketIpv4 {
+            protocol: 6,
+            local_ip: Ipv4Addr::new(10, 0, 0, 1),
+            remote_ip: Ipv4Addr::new(10, 0, 0, 2),
+            local_port: 1234,
+            remote_port: 80,
+        }),
+        FlowData::ExtendedLinuxDropReason(ExtendedLinuxDropReason {
+            reason: "NETFILTER_DROP".to_string(),
+        }),
+    ]);
+    let sample = SampleData::DiscardedPacket(DiscardedPacket {
+        sequence_number: 3,
+        source_id: DataSourceExpanded {
+            source_id_type: 0,
+            source_id_index: 7,
+        },
+        drops: 1,
+        input_ifindex: 7,
+        output_ifindex: 0,
+        reason: DropReason::Acl,
+        flow_records,
+    });
+    let datagram = datagram(vec![(5, sample)]);
+
+    let data = encode_datagram(&datagram).unwrap();
+    assert_eq!(parse_datagram(&data).unwrap(), datagram);
+}
+
+#[test]
+fn test_encode_flow_data_mpls_vc_label_cos() {
+    let vc = FlowData::ExtendedMplsVc(ExtendedMplsVc {
+        vc_instance_name: String::new(),
+        vll_vc_id: 0,
+        vc_label: 1,
+        vc_cos: 3,
+    });
+    // Empty name (4 bytes), VC ID, then label and COS in a single word
+    assert_eq!(encode_flow_data(&vc)[8..], [0, 0, 0, 0x0B]);
+}
+
+#[test]
+fn test_encode_skipped_sample_fails() {
+    let format = DataFormat::new(0, 1);
+    let datagram = datagram(vec![(1, SampleData::Skipped { format, length: 48 })]);
+    assert_eq!(
+        encode_datagram(&datagram),
+        Err(EncodeError::SkippedSample { format })
+    );
+    assert!(datagram.normalize().is_err());
+}
+
+#[test]
+fn test_encoded_len() {
+    let data = std::fs::read("tests/data/sflow.bin").unwrap();
+    let mut datagram = parse_datagram(&data).unwrap();
+    assert_eq!(datagram.encoded_len(), data.len());
+
+    // Skipped samples count with their declared length and header
+    datagram.samples.push(SampleRecord {
+        sample_type: DataFormat::new(0, 1),
+        sample_data: SampleData::Skipped {
+            format: DataFormat::new(0, 1),
+            length: 46,
+        },
+    });
+    assert_eq!(datagram.encoded_len(), data.len() + 8 + 48);
+}
+
+#[test]
+fn test_datagram_looks_truncated() {
+    let data = std::fs::read("tests/data/sflow.bin").unwrap();
+    let mut datagram = parse_datagram(&data).unwrap();
+    assert!(!datagram.looks_truncated(data.len()));
+
+    // A sample declaring more bytes than were received
+    datagram.samples.push(SampleRecord {
+        sample_type: DataFormat::new(0, 1),
+        sample_data: SampleData::Skipped {
+            format: DataFormat::new(0, 1),
+            length: 1400,
+        },
+    });
+    assert!(datagram.looks_truncated(data.len() + 200));
+    assert!(!datagram.looks_truncated(data.len() + 8 + 1400));
+}
@@ -0,0 +1,798 @@
+//! Tests for the FlowSample convenience accessors
+
+use sflow_parser::models::record_flows::*;
+use sflow_parser::models::*;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Build a flow sample holding the given flow records
+fn flow_sample(records: Vec<(DataFormat, FlowData)>) -> FlowSample {
+    FlowSample {
+        sequence_number: 1,
+        source_id: DataSource::new(0, 1),
+        sampling_rate: 1024,
+        sample_pool: 1024,
+        drops: 0,
+        input: Interface(1),
+        output: Interface(2),
+        flow_records: records
+            .into_iter()
+            .map(|(flow_format, flow_data)| FlowRecord {
+                flow_format,
+                flow_data,
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn test_vni_ingress_only() {
+    let sample = flow_sample(vec![(
+        DataFormat::new(0, 1030),
+        FlowData::ExtendedVniIngress(ExtendedVniIngress { vni: 5000 }),
+    )]);
+    assert_eq!(sample.vni(), Some((Some(5000), None)));
+}
+
+#[test]
+fn test_vni_ingress_and_egress() {
+    let sample = flow_sample(vec![
+        (
+            DataFormat::new(0, 1029),
+            FlowData::ExtendedVniEgress(ExtendedVniEgress { vni: 200 }),
+        ),
+        (
+            DataFormat::new(0, 1030),
+            FlowData::ExtendedVniIngress(ExtendedVniIngress { vni: 100 }),
+        ),
+    ]);
+    assert_eq!(sample.vni(), Some((Some(100), Some(200))));
+}
+
+#[test]
+fn test_vni_absent() {
+    let sample = flow_sample(vec![]);
+    assert_eq!(sample.vni(), None);
+}
+
+fn sampled_ipv4(src_ip: Ipv4Addr, dst_ip: Ipv4Addr) -> (DataFormat, FlowData) {
+    (
+        DataFormat::new(0, 3),
+        FlowData::SampledIpv4(SampledIpv4 {
+            length: 64,
+            protocol: 6,
+            src_ip,
+            dst_ip,
+            src_port: 40000,
+            dst_port: 80,
+            tcp_flags: 0,
+            tos: 0,
+        }),
+    )
+}
+
+fn extended_router(
+    next_hop: Address,
+    src_mask_len: u32,
+    dst_mask_len: u32,
+) -> (DataFormat, FlowData) {
+    (
+        DataFormat::new(0, 1002),
+        FlowData::ExtendedRouter(ExtendedRouter {
+            next_hop,
+            src_mask_len,
+            dst_mask_len,
+        }),
+    )
+}
+
+#[test]
+fn test_routed_prefixes_ipv4() {
+    let sample = flow_sample(vec![
+        sampled_ipv4(
+            Ipv4Addr::new(192, 168, 1, 100),
+            Ipv4Addr::new(10, 20, 30, 40),
+        ),
+        extended_router(Address::IPv4(Ipv4Addr::new(192, 168, 1, 1)), 24, 16),
+    ]);
+
+    let (src, dst) = sample.routed_prefixes().unwrap();
+    assert_eq!(src.addr, IpAddr::V4(Ipv4Addr::new(192, 168, 1, 0)));
+    assert_eq!(src.len, 24);
+    assert_eq!(src.to_string(), "192.168.1.0/24");
+    assert_eq!(dst.to_string(), "10.20.0.0/16");
+}
+
+#[test]
+fn test_routed_prefixes_ipv6() {
+    let sample = flow_sample(vec![
+        (
+            DataFormat::new(0, 4),
+            FlowData::SampledIpv6(SampledIpv6 {
+                length: 80,
+                protocol: 17,
+                src_ip: "2001:db8:1:2::10".parse().unwrap(),
+                dst_ip: "2001:db8:ffff::1".parse().unwrap(),
+                src_port: 53,
+                dst_port: 53,
+                tcp_flags: 0,
+                priority: 0,
+            }),
+        ),
+        extended_router(Address::IPv6(Ipv6Addr::LOCALHOST), 48, 128),
+    ]);
+
+    let (src, dst) = sample.routed_prefixes().unwrap();
+    assert_eq!(src.to_string(), "2001:db8:1::/48");
+    assert_eq!(dst.to_string(), "2001:db8:ffff::1/128");
+}
+
+#[test]
+fn test_routed_prefixes_missing_or_invalid() {
+    let ipv4 = sampled_ipv4(Ipv4Addr::new(192, 168, 1, 100), Ipv4Addr::new(10, 0, 0, 1));
+    let router = extended_router(Address::Unknown, 24, 24);
+
+    assert_eq!(flow_sample(vec![ipv4.clone()]).routed_prefixes(), None);
+    assert_eq!(flow_sample(vec![router.clone()]).routed_prefixes(), None);
+
+    // A /33 does not fit an IPv4 address
+    let bad_router = extended_router(Address::Unknown, 33, 24);
+    assert_eq!(flow_sample(vec![ipv4, bad_router]).routed_prefixes(), None);
+}
+
+/// Ethernet frame carrying an IPv4/TCP packet 192.168.1.100:40000 -> 10.20.30.40:80
+fn ethernet_ipv4_tcp_header() -> SampledHeader {
+    let mut header = vec![
+        0x00, 0x11, 0x22, 0x33, 0x44, 0x55, // dst MAC
+        0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, // src MAC
+        0x08, 0x00, // EtherType = IPv4
+        0x45, 0x00, 0x00, 0x28, // version/IHL, TOS, total length
+        0x00, 0x00, 0x00, 0x00, // identification, flags/fragment offset
+        0x40, 0x06, 0x00, 0x00, // TTL, protocol = TCP, checksum
+        192, 168, 1, 100, // src
+        10, 20, 30, 40, // dst
+        0x9C, 0x40, 0x00, 0x50, // src port = 40000, dst port = 80
+    ];
+    header.extend_from_slice(&[0; 16]); // rest of the TCP header
+    SampledHeader {
+        protocol: HeaderProtocol::EthernetIso88023,
+        frame_length: 64,
+        stripped: 4,
+        header,
+    }
+}
+
+#[test]
+fn test_canonical_five_tuple_sources_agree() {
+    let expected = FiveTuple {
+        src_ip: IpAddr::V4(Ipv4Addr::new(192, 168, 1, 100)),
+        dst_ip: IpAddr::V4(Ipv4Addr::new(10, 20, 30, 40)),
+        protocol: IpProtocol::Tcp,
+        src_port: 40000,
+        dst_port: 80,
+    };
+
+    let from_ipv4 = flow_sample(vec![sampled_ipv4(
+        Ipv4Addr::new(192, 168, 1, 100),
+        Ipv4Addr::new(10, 20, 30, 40),
+    )]);
+    assert_eq!(from_ipv4.canonical_five_tuple(), Some(expected));
+
+    let from_header = flow_sample(vec![(
+        DataFormat::new(0, 1),
+        FlowData::SampledHeader(ethernet_ipv4_tcp_header()),
+    )]);
+    assert_eq!(from_header.canonical_five_tuple(), Some(expected));
+
+    let from_socket = flow_sample(vec![(
+        DataFormat::new(0, 2100),
+        FlowData::ExtendedSocketIpv4(ExtendedSocketIpv4 {
+            protocol: 6,
+            local_ip: Ipv4Addr::new(192, 168, 1, 100),
+            remote_ip: Ipv4Addr::new(10, 20, 30, 40),
+            local_port: 40000,
+            remote_port: 80,
+        }),
+    )]);
+    assert_eq!(from_socket.canonical_five_tuple(), Some(expected));
+}
+
+#[test]
+fn test_canonical_five_tuple_priority() {
+    // Explicit Sampled IPv4 wins over the sampled header
+    let sample = flow_sample(vec![
+        (
+            DataFormat::new(0, 1),
+            FlowData::SampledHeader(ethernet_ipv4_tcp_header()),
+        ),
+        sampled_ipv4(Ipv4Addr::new(172, 16, 0, 1), Ipv4Addr::new(172, 16, 0, 2)),
+    ]);
+    let tuple = sample.canonical_five_tuple().unwrap();
+    assert_eq!(tuple.src_ip, IpAddr::V4(Ipv4Addr::new(172, 16, 0, 1)));
+
+    // Undecodable headers fall through to the next source
+    let mut header = ethernet_ipv4_tcp_header();
+    header.header.truncate(20);
+    let sample = flow_sample(vec![(
+        DataFormat::new(0, 1),
+        FlowData::SampledHeader(header),
+    )]);
+    assert_eq!(sample.canonical_five_tuple(), None);
+}
+
+fn extended_switch(src_vlan: u32, dst_vlan: u32) -> (DataFormat, FlowData) {
+    (
+        DataFormat::new(0, 1001),
+        FlowData::ExtendedSwitch(ExtendedSwitch {
+            src_vlan,
+            src_priority: 0,
+            dst_vlan,
+            dst_priority: 0,
+        }),
+    )
+}
+
+#[test]
+fn test_records_by_type() {
+    let sample = flow_sample(vec![
+        sampled_ipv4(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2)),
+        extended_switch(10, 20),
+        sampled_ipv4(Ipv4Addr::new(10, 0, 0, 3), Ipv4Addr::new(10, 0, 0, 4)),
+        extended_switch(30, 40),
+    ]);
+
+    let sources: Vec<Ipv4Addr> = sample
+        .records::<SampledIpv4>()
+        .map(|ip| ip.src_ip)
+        .collect();
+    assert_eq!(
+        sources,
+        vec![Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 3)]
+    );
+
+    let vlans: Vec<(u32, u32)> = sample
+        .records::<ExtendedSwitch>()
+        .map(|switch| (switch.src_vlan, switch.dst_vlan))
+        .collect();
+    assert_eq!(vlans, vec![(10, 20), (30, 40)]);
+
+    assert_eq!(sample.records::<ExtendedRouter>().count(), 0);
+    assert_eq!(SampledIpv4::DATA_FORMAT, DataFormat::new(0, 3));
+    assert_eq!(ExtendedSwitch::DATA_FORMAT, DataFormat::new(0, 1001));
+}
+
+fn transaction(duration: u32) -> (DataFormat, FlowData) {
+    (
+        DataFormat::new(0, 2000),
+        FlowData::Transaction(Transaction {
+            direction: ServiceDirection::Server,
+            wait: 250,
+            duration,
+            status: TransactionStatus::Succeeded,
+            bytes_received: 512,
+            bytes_sent: 4096,
+        }),
+    )
+}
+
+#[test]
+fn test_transaction_view_http() {
+    let sample = flow_sample(vec![
+        (
+            DataFormat::new(0, 2003),
+            FlowData::ExtendedHttpTransaction(ExtendedHttpTransaction {
+                url: "GET /index.html HTTP/1.1".to_string(),
+                host: "example.com".to_string(),
+                referer: String::new(),
+                user_agent: "curl/8.0".to_string(),
+                user: String::new(),
+                status: 404,
+            }),
+        ),
+        transaction(1500),
+    ]);
+
+    let view = sample.transaction().unwrap();
+    assert!(matches!(view, TransactionView::Http(_, http) if http.host == "example.com"));
+    assert_eq!(view.duration(), std::time::Duration::from_micros(1500));
+    assert_eq!(view.wait(), std::time::Duration::from_micros(250));
+    assert_eq!(view.http_status(), Some(404));
+    assert_eq!(view.transaction().bytes_sent, 4096);
+}
+
+#[test]
+fn test_transaction_view_generic_and_absent() {
+    let generic = flow_sample(vec![transaction(10)]);
+    let view = generic.transaction().unwrap();
+    assert!(matches!(view, TransactionView::Generic(_)));
+    assert_eq!(view.http_status(), None);
+
+    // Detail records alone do not make a transaction
+    let detail_only = flow_sample(vec![(
+        DataFormat::new(0, 2002),
+        FlowData::ExtendedScsiStorageTransaction(ExtendedScsiStorageTransaction {
+            lun: 1,
+            operation: 0x28,
+            status: 0,
+        }),
+    )]);
+    assert_eq!(detail_only.transaction(), None);
+}
+
+/// Version-independent extraction through SampledIp
+fn sampled_five_tuple(sample: &FlowSample) -> Option<(IpAddr, IpAddr, IpProtocol, u32, u32)> {
+    let ip = sample.sampled_ip()?;
+    Some((
+        ip.src_ip(),
+        ip.dst_ip(),
+        ip.protocol(),
+        ip.src_port(),
+        ip.dst_port(),
+    ))
+}
+
+#[test]
+fn test_sampled_ip_both_versions() {
+    let v4 = flow_sample(vec![
+        extended_switch(10, 20),
+        sampled_ipv4(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2)),
+    ]);
+    assert_eq!(
+        sampled_five_tuple(&v4),
+        Some((
+            IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+            IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)),
+            IpProtocol::Tcp,
+            40000,
+            80
+        ))
+    );
+
+    let v6 = flow_sample(vec![(
+        DataFormat::new(0, 4),
+        FlowData::SampledIpv6(SampledIpv6 {
+            length: 80,
+            protocol: 17,
+            src_ip: "2001:db8::1".parse().unwrap(),
+            dst_ip: "2001:db8::2".parse().unwrap(),
+            src_port: 5353,
+            dst_port: 53,
+            tcp_flags: 0,
+            priority: 0,
+        }),
+    )]);
+    assert!(matches!(v6.sampled_ip(), Some(SampledIp::V6(_))));
+    assert_eq!(
+        sampled_five_tuple(&v6),
+        Some((
+            "2001:db8::1".parse().unwrap(),
+            "2001:db8::2".parse().unwrap(),
+            IpProtocol::Udp,
+            5353,
+            53
+        ))
+    );
+
+    assert_eq!(flow_sample(vec![extended_switch(1, 2)]).sampled_ip(), None);
+}
+
+#[test]
+fn test_sample_common_views() {
+    let compact = flow_sample(vec![extended_switch(10, 20)]);
+    let expanded = FlowSampleExpanded {
+        sequence_number: compact.sequence_number,
+        source_id: DataSourceExpanded {
+            source_id_type: 0,
+            source_id_index: 1,
+        },
+        sampling_rate: compact.sampling_rate,
+        sample_pool: compact.sample_pool,
+        drops: compact.drops,
+        input: InterfaceExpanded {
+            format: 0,
+            value: 1,
+        },
+        output: InterfaceExpanded {
+            format: 0,
+            value: 2,
+        },
+        flow_records: compact.flow_records.clone(),
+    };
+
+    let compact = SampleData::FlowSample(compact);
+    let expanded = SampleData::FlowSampleExpanded(expanded);
+    let view = compact.as_flow().unwrap();
+    assert_eq!(view, expanded.as_flow().unwrap());
+    assert_eq!(view.sampling_rate, 1024);
+    assert_eq!(view.output.value, 2);
+    assert_eq!(view.flow_records.len(), 1);
+    assert!(compact.as_counters().is_none());
+
+    // Counters samples normalize the same way
+    let counters = vec![CounterRecord {
+        counter_format: DataFormat::new(0, 1001),
+        counter_data: CounterData::Processor(record_counters::ProcessorCounters {
+            cpu_5s: 10,
+            cpu_1m: 20,
+            cpu_5m: 30,
+            total_memory: 1024,
+            free_memory: 512,
+        }),
+    }];
+    let compact = SampleData::CountersSample(CountersSample {
+        sequence_number: 7,
+        source_id: DataSource::new(0, 3),
+        counters: counters.clone(),
+    });
+    let expanded = SampleData::CountersSampleExpanded(CountersSampleExpanded {
+        sequence_number: 7,
+        source_id: DataSourceExpanded {
+            source_id_type: 0,
+            source_id_index: 3,
+        },
+        counters,
+    });
+    assert_eq!(compact.as_counters(), expanded.as_counters());
+    assert!(expanded.as_flow().is_none());
+
+    // The compact "internal" interface maps to its expanded value
+    assert_eq!(
+        InterfaceExpanded::from(Interface(0x3FFFFFFF)),
+        InterfaceExpanded {
+            format: 0,
+            value: 0xFFFFFFFF
+        }
+    );
+}
+
+#[test]
+fn test_egress_queue() {
+    let broadcom = (
+        DataFormat::new(4413, 1),
+        FlowData::ExtendedBstEgressQueue(ExtendedBstEgressQueue { queue: 5 }),
+    );
+    let standard = (
+        DataFormat::new(0, 1036),
+        FlowData::ExtendedEgressQueue(ExtendedEgressQueue { queue: 2 }),
+    );
+
+    // Broadcom only
+    let sample = flow_sample(vec![extended_switch(1, 2), broadcom.clone()]);
+    assert_eq!(sample.egress_queue(), Some(5));
+
+    // The standard record wins regardless of order
+    let sample = flow_sample(vec![broadcom, standard]);
+    assert_eq!(sample.egress_queue(), Some(2));
+
+    assert_eq!(flow_sample(vec![]).egress_queue(), None);
+}
+
+#[test]
+fn test_discard_explanation() {
+    let acl = (
+        DataFormat::new(0, 1037),
+        FlowData::ExtendedAcl(ExtendedAcl {
+            number: 101,
+            name: "deny-telnet".to_string(),
+            direction: 1,
+        }),
+    );
+    let hw_trap = |group: &str, trap: &str| {
+        (
+            DataFormat::new(0, 1041),
+            FlowData::ExtendedHwTrap(ExtendedHwTrap {
+                group: group.to_string(),
+                trap: trap.to_string(),
+            }),
+        )
+    };
+
+    // Forwarded packet: not a discard, even with drop records
+    let sample = flow_sample(vec![acl.clone()]);
+    assert!(!sample.is_discard());
+    assert_eq!(sample.discard_explanation(), None);
+
+    let mut sample = flow_sample(vec![
+        sampled_ipv4(Ipv4Addr::LOCALHOST, Ipv4Addr::LOCALHOST),
+        acl,
+    ]);
+    sample.output = Interface(0x40000000);
+    assert!(sample.is_discard());
+    assert_eq!(sample.discard_explanation(), Some(DropReason::Acl));
+
+    sample.flow_records.pop();
+    assert_eq!(sample.discard_explanation(), None);
+
+    let (format, flow_data) = hw_trap("l3_drops", "blackhole_route");
+    sample.flow_records.push(FlowRecord {
+        flow_format: format,
+        flow_data,
+    });
+    assert_eq!(
+        sample.discard_explanation(),
+        Some(DropReason::BlackholeRoute)
+    );
+
+    let (format, flow_data) = hw_trap("buffer_drops", "vendor_specific_drop");
+    sample.flow_records[1] = FlowRecord {
+        flow_format: format,
+        flow_data,
+    };
+    assert_eq!(
+        sample.discard_explanation(),
+        Some(DropReason::UnknownBuffer)
+    );
+}
+
+#[test]
+fn test_discard_explanation_linux_drop_reason() {
+    let mut sample = flow_sample(vec![
+        sampled_ipv4(Ipv4Addr::LOCALHOST, Ipv4Addr::LOCALHOST),
+        (
+            DataFormat::new(0, 1042),
+            FlowData::ExtendedLinuxDropReason(ExtendedLinuxDropReason {
+                reason: "NETFILTER_DROP".to_string(),
+            }),
+        ),
+    ]);
+    sample.output = Interface(0x40000000);
+
+    // No sFlow drop reason matches, the Linux reason is exposed as is
+    assert_eq!(sample.discard_explanation(), Some(DropReason::Unknown));
+    assert_eq!(sample.linux_drop_reason(), Some("NETFILTER_DROP"));
+    assert_eq!(sample.drop_function(), None);
+
+    sample.flow_records.push(FlowRecord {
+        flow_format: DataFormat::new(0, 1038),
+        flow_data: FlowData::ExtendedFunction(ExtendedFunction {
+            symbol: "nf_hook_slow".to_string(),
+        }),
+    });
+    assert_eq!(sample.drop_function(), Some("nf_hook_slow"));
+}
+
+#[test]
+fn test_all_ip_addresses() {
+    let src = Ipv4Addr::new(192, 168, 1, 100);
+    let dst = Ipv4Addr::new(10, 20, 30, 40);
+    let next_hop = Ipv4Addr::new(192, 168, 1, 1);
+    let sample = flow_sample(vec![
+        sampled_ipv4(src, dst),
+        extended_router(Address::IPv4(next_hop), 24, 16),
+        extended_switch(10, 20),
+    ]);
+
+    // The agent is also the next hop, it is listed once
+    let mut datagram = SFlowDatagram::new(Address::IPv4(next_hop), 0, 1, 100);
+    datagram.samples.push(SampleRecord {
+        sample_type: DataFormat::new(0, 1),
+        sample_data: SampleData::FlowSample(sample),
+    });
+
+    let ips = datagram.all_ip_addresses();
+    assert_eq!(
+        ips,
+        vec![IpAddr::V4(next_hop), IpAddr::V4(src), IpAddr::V4(dst)]
+    );
+
+    datagram.agent_address = Address::Unknown;
+    let ips = datagram.all_ip_addresses();
+    assert_eq!(ips.len(), 3);
+    assert!(ips.contains(&IpAddr::V4(next_hop)));
+}
+
+#[test]
+fn test_all_ip_addresses_sampled_header() {
+    // Ethernet frame carrying IPv4 172.16.0.1 -> 172.16.0.2 (UDP)
+    let mut header = vec![
+        0x00, 0x11, 0x22, 0x33, 0x44, 0x55, // dst MAC
+        0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, // src MAC
+        0x08, 0x00, // EtherType = IPv4
+    ];
+    header.extend_from_slice(&[
+        0x45, 0x00, 0x00, 0x1C, // version/IHL, TOS, total length = 28
+        0x00, 0x01, 0x00, 0x00, // identification, flags/fragment offset
+        0x40, 0x11, 0x00, 0x00, // TTL = 64, protocol = UDP, checksum
+        0xAC, 0x10, 0x00, 0x01, // src = 172.16.0.1
+        0xAC, 0x10, 0x00, 0x02, // dst = 172.16.0.2
+    ]);
+    let sampled_header = (
+        DataFormat::new(0, 1),
+        FlowData::SampledHeader(SampledHeader {
+            protocol: HeaderProtocol::EthernetIso88023,
+            frame_length: header.len() as u32,
+            stripped: 4,
+            header,
+        }),
+    );
+
+    let mut datagram = SFlowDatagram::new(Address::Unknown, 0, 1, 100);
+    datagram.samples.push(SampleRecord {
+        sample_type: DataFormat::new(0, 1),
+        sample_data: SampleData::FlowSample(flow_sample(vec![sampled_header])),
+    });
+
+    assert_eq!(
+        datagram.all_ip_addresses(),
+        vec![
+            IpAddr::V4(Ipv4Addr::new(172, 16, 0, 1)),
+            IpAddr::V4(Ipv4Addr::new(172, 16, 0, 2)),
+        ]
+    );
+}
+
+#[test]
+fn test_drop_samples() {
+    let linux_drop = (
+        DataFormat::new(0, 1042),
+        FlowData::ExtendedLinuxDropReason(ExtendedLinuxDropReason {
+            reason: "NETFILTER_DROP".to_string(),
+        }),
+    );
+    let forwarded = flow_sample(vec![sampled_ipv4(
+        Ipv4Addr::new(10, 0, 0, 1),
+        Ipv4Addr::new(10, 0, 0, 2),
+    )]);
+    let dropped = flow_sample(vec![
+        sampled_ipv4(Ipv4Addr::new(10, 0, 0, 3), Ipv4Addr::new(10, 0, 0, 4)),
+        linux_drop,
+    ]);
+
+    let mut datagram = SFlowDatagram::new(Address::IPv4(Ipv4Addr::new(192, 168, 1, 1)), 0, 1, 100);
+    for sample in [forwarded, dropped.clone()] {
+        datagram.samples.push(SampleRecord {
+            sample_type: DataFormat::new(0, 1),
+            sample_data: SampleData::FlowSample(sample),
+        });
+    }
+
+    let drops: Vec<_> = datagram.drop_samples().collect();
+    assert_eq!(drops.len(), 1);
+    assert_eq!(drops[0].sample_data, SampleData::FlowSample(dropped));
+
+    // A discarded output interface alone marks a drop as well
+    let mut discarded = flow_sample(vec![extended_switch(10, 20)]);
+    discarded.output = Interface(0x40000000);
+    assert!(SampleData::FlowSample(discarded).is_drop_sample());
+
+    // So does any drop record, an ACL included
+    let acl = flow_sample(vec![(
+        DataFormat::new(0, 1037),
+        FlowData::ExtendedAcl(ExtendedAcl {
+            number: 101,
+            name: "deny-telnet".to_string(),
+            direction: 1,
+        }),
+    )]);
+    assert!(SampleData::FlowSample(acl).is_drop_sample());
+}
+
+#[test]
+fn test_group_by_source() {
+    let port_1 = flow_sample(vec![extended_switch(10, 20)]);
+    let mut port_2 = flow_sample(vec![extended_switch(30, 40)]);
+    port_2.source_id = DataSource::new(0, 2);
+    // Expanded encoding of the same source as port_1
+    let counters = CountersSampleExpanded {
+        sequence_number: 5,
+        source_id: DataSourceExpanded {
+            source_id_type: 0,
+            source_id_index: 1,
+        },
+        counters: Vec::new(),
+    };
+
+    let mut datagram = SFlowDatagram::new(Address::IPv4(Ipv4Addr::new(192, 168, 1, 1)), 0, 1, 100);
+    for (sample_type, sample_data) in [
+        (
+            DataFormat::new(0, 1),
+            SampleData::FlowSample(port_1.clone()),
+        ),
+        (DataFormat::new(0, 1), SampleData::FlowSample(port_2)),
+        (
+            DataFormat::new(0, 4),
+            SampleData::CountersSampleExpanded(counters.clone()),
+        ),
+    ] {
+        datagram.samples.push(SampleRecord {
+            sample_type,
+            sample_data,
+        });
+    }
+
+    let groups = datagram.group_by_source();
+    assert_eq!(groups.len(), 2);
+    let port_1_samples = &groups[&DataSourceKey::from(DataSource::new(0, 1))];
+    assert_eq!(port_1_samples.len(), 2);
+    assert_eq!(
+        port_1_samples[0].sample_data,
+        SampleData::FlowSample(port_1)
+    );
+    assert_eq!(
+        port_1_samples[1].sample_data,
+        SampleData::CountersSampleExpanded(counters)
+    );
+    assert_eq!(groups[&DataSourceKey::from(DataSource::new(0, 2))].len(), 1);
+}
+
+#[test]
+fn test_port_translation() {
+    let nat_port = |src_port, dst_port| {
+        (
+            DataFormat::new(0, 1020),
+            FlowData::ExtendedNatPort(ExtendedNatPort { src_port, dst_port }),
+        )
+    };
+    let sampled = sampled_ipv4(Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2));
+
+    // Source port 40000 rewritten, destination port 80 unchanged
+    let sample = flow_sample(vec![sampled.clone(), nat_port(61000, 80)]);
+    let translation = sample.port_translation().unwrap();
+    assert_eq!(
+        translation,
+        PortTranslation {
+            src_port: 40000,
+            translated_src_port: 61000,
+            dst_port: 80,
+            translated_dst_port: 80,
+        }
+    );
+    assert!(translation.src_translated());
+    assert!(!translation.dst_translated());
+
+    // Both records are needed
+    assert_eq!(flow_sample(vec![sampled]).port_translation(), None);
+    assert_eq!(
+        flow_sample(vec![nat_port(61000, 80)]).port_translation(),
+        None
+    );
+}
+
+#[test]
+fn test_transit_info() {
+    use std::time::Duration;
+
+    let transit = |delay| {
+        (
+            DataFormat::new(0, 1039),
+            FlowData::ExtendedTransit(ExtendedTransit { delay }),
+        )
+    };
+    let queue = (
+        DataFormat::new(0, 1040),
+        FlowData::ExtendedQueue(ExtendedQueue { depth: 65536 }),
+    );
+
+    let sample = flow_sample(vec![transit(1_500_000), queue.clone()]);
+    assert_eq!(
+        sample.transit_info(),
+        Some(TransitInfo {
+            delay: Some(TransitDelay::Measured(Duration::from_micros(1500))),
+            queue_depth: Some(65536),
+        })
+    );
+
+    // A saturated delay is not a measurement
+    let sample = flow_sample(vec![transit(ExtendedTransit::DELAY_SATURATED), queue]);
+    let info = sample.transit_info().unwrap();
+    assert_eq!(info.delay, Some(TransitDelay::Saturated));
+    assert_eq!(info.delay.unwrap().duration(), None);
+    assert_eq!(info.queue_depth, Some(65536));
+
+    // A queue record alone does not report a delay
+    let queue_only = flow_sample(vec![(
+        DataFormat::new(0, 1040),
+        FlowData::ExtendedQueue(ExtendedQueue { depth: 10 }),
+    )]);
+    assert_eq!(
+        queue_only.transit_info(),
+        Some(TransitInfo {
+            delay: None,
+            queue_depth: Some(10),
+        })
+    );
+
+    assert_eq!(
+        flow_sample(vec![extended_switch(10, 20)]).transit_info(),
+        None
+    );
+}
@@ -0,0 +1,242 @@
+//! Tests for SampledHeader::decode
+
+use sflow_parser::models::record_flows::{HeaderProtocol, IpProtocol, MplsLabel, SampledHeader};
+use sflow_parser::models::MacAddress;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// IPv4/TCP packet 10.0.0.1:49152 -> 10.0.0.2:443 with SYN+ACK flags
+fn ipv4_tcp_packet() -> Vec<u8> {
+    vec![
+        0x45, 0x00, 0x00, 0x28, // version/IHL, TOS, total length = 40
+        0x00, 0x01, 0x00, 0x00, // identification, flags/fragment offset
+        0x40, 0x06, 0x00, 0x00, // TTL = 64, protocol = TCP, checksum
+        0x0A, 0x00, 0x00, 0x01, // src = 10.0.0.1
+        0x0A, 0x00, 0x00, 0x02, // dst = 10.0.0.2
+        0xC0, 0x00, 0x01, 0xBB, // src port = 49152, dst port = 443
+        0x00, 0x00, 0x00, 0x00, // sequence number
+        0x00, 0x00, 0x00, 0x00, // acknowledgment number
+        0x50, 0x12, 0xFF, 0xFF, // data offset, flags = SYN+ACK, window
+        0x00, 0x00, 0x00, 0x00, // checksum, urgent pointer
+    ]
+}
+
+fn sampled_header(protocol: HeaderProtocol, header: Vec<u8>) -> SampledHeader {
+    SampledHeader {
+        protocol,
+        frame_length: header.len() as u32,
+        stripped: 0,
+        header,
+    }
+}
+
+fn assert_inner_ipv4_tcp(header: &SampledHeader) {
+    let decoded = header.decode().expect("header decodes");
+    assert_eq!(decoded.src_ip, IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+    assert_eq!(decoded.dst_ip, IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)));
+    assert_eq!(decoded.protocol, IpProtocol::Tcp);
+    assert_eq!(decoded.src_port, Some(49152));
+    assert_eq!(decoded.dst_port, Some(443));
+    assert_eq!(decoded.tcp_flags, Some(0x12));
+}
+
+#[test]
+fn test_decode_ethernet_vlan_ipv4() {
+    let mut frame = vec![
+        0x00, 0x11, 0x22, 0x33, 0x44, 0x55, // dst MAC
+        0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, // src MAC
+        0x81, 0x00, 0x00, 0x64, // 802.1Q tag, VLAN 100
+        0x08, 0x00, // EtherType = IPv4
+    ];
+    frame.extend(ipv4_tcp_packet());
+    let header = sampled_header(HeaderProtocol::EthernetIso88023, frame);
+
+    assert_inner_ipv4_tcp(&header);
+    let decoded = header.decode().unwrap();
+    assert_eq!(
+        decoded.dst_mac,
+        Some(MacAddress::new([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]))
+    );
+    assert_eq!(
+        decoded.src_mac,
+        Some(MacAddress::new([0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB]))
+    );
+    assert_eq!(decoded.vlan, Some(100));
+}
+
+#[test]
+fn test_decode_ppp_ipv4() {
+    // HDLC-like framing: address 0xFF, control 0x03, protocol 0x0021 (IPv4)
+    let mut frame = vec![0xFF, 0x03, 0x00, 0x21];
+    frame.extend(ipv4_tcp_packet());
+    let header = sampled_header(HeaderProtocol::Ppp, frame);
+
+    assert_inner_ipv4_tcp(&header);
+    assert_eq!(header.decode().unwrap().src_mac, None);
+
+    // Address/control and protocol field compression
+    let mut frame = vec![0x21];
+    frame.extend(ipv4_tcp_packet());
+    assert_inner_ipv4_tcp(&sampled_header(HeaderProtocol::Ppp, frame));
+}
+
+#[test]
+fn test_decode_frame_relay_ipv4() {
+    // Q.922 address (DLCI 16), control 0x03 (UI), NLPID 0xCC (IPv4)
+    let mut frame = vec![0x04, 0x01, 0x03, 0xCC];
+    frame.extend(ipv4_tcp_packet());
+    assert_inner_ipv4_tcp(&sampled_header(HeaderProtocol::FrameRelay, frame));
+
+    // Cisco encapsulation: EtherType directly after the address
+    let mut frame = vec![0x04, 0x01, 0x08, 0x00];
+    frame.extend(ipv4_tcp_packet());
+    assert_inner_ipv4_tcp(&sampled_header(HeaderProtocol::FrameRelay, frame));
+}
+
+#[test]
+fn test_decode_mpls_ipv4() {
+    let mut frame = vec![
+        0x00, 0x11, 0x22, 0x33, 0x44, 0x55, // dst MAC
+        0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, // src MAC
+        0x88, 0x47, // EtherType = MPLS unicast
+        0x00, 0x3E, 0x80, 0x3F, // label 1000, TC 0, TTL 63
+        0x00, 0x07, 0xD3, 0x3F, // label 125, TC 1, bottom of stack, TTL 63
+    ];
+    frame.extend(ipv4_tcp_packet());
+    let header = sampled_header(HeaderProtocol::EthernetIso88023, frame);
+
+    assert_inner_ipv4_tcp(&header);
+    let decoded = header.decode().unwrap();
+    assert_eq!(
+        decoded.mpls_labels,
+        vec![
+            MplsLabel {
+                label: 1000,
+                traffic_class: 0,
+                bottom_of_stack: false,
+                ttl: 63,
+            },
+            MplsLabel {
+                label: 125,
+                traffic_class: 1,
+                bottom_of_stack: true,
+                ttl: 63,
+            },
+        ]
+    );
+
+    // Bare label stack
+    let mut frame = vec![0x00, 0x07, 0xD1, 0x40];
+    frame.extend(ipv4_tcp_packet());
+    let header = sampled_header(HeaderProtocol::Mpls, frame);
+    assert_inner_ipv4_tcp(&header);
+    assert_eq!(header.decode().unwrap().mpls_labels[0].label, 125);
+
+    // Stack cut before the bottom label
+    let frame = vec![0x00, 0x3E, 0x80, 0x3F];
+    assert!(sampled_header(HeaderProtocol::Mpls, frame)
+        .decode()
+        .is_none());
+}
+
+#[test]
+fn test_decode_ipv4_with_options() {
+    let packet = vec![
+        0x46, 0x00, 0x00, 0x24, // version/IHL = 6 (24 bytes), TOS, total length = 36
+        0x00, 0x01, 0x00, 0x00, // identification, flags/fragment offset
+        0x40, 0x11, 0x00, 0x00, // TTL = 64, protocol = UDP, checksum
+        0x0A, 0x00, 0x00, 0x01, // src = 10.0.0.1
+        0x0A, 0x00, 0x00, 0x02, // dst = 10.0.0.2
+        0x94, 0x04, 0x00, 0x00, // router alert option
+        0x30, 0x39, 0x00, 0x35, // src port = 12345, dst port = 53
+        0x00, 0x08, 0x00, 0x00, // length, checksum
+    ];
+
+    let decoded = sampled_header(HeaderProtocol::Ipv4, packet)
+        .decode()
+        .unwrap();
+    assert_eq!(decoded.protocol, IpProtocol::Udp);
+    assert_eq!(decoded.src_port, Some(12345));
+    assert_eq!(decoded.dst_port, Some(53));
+}
+
+#[test]
+fn test_decode_ipv4_non_first_fragment() {
+    let mut packet = ipv4_tcp_packet();
+    packet[6..8].copy_from_slice(&[0x20, 0xB9]); // more fragments, offset = 185 (1480 bytes)
+    let decoded = sampled_header(HeaderProtocol::Ipv4, packet)
+        .decode()
+        .unwrap();
+    assert_eq!(decoded.src_ip, IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+    assert_eq!(decoded.protocol, IpProtocol::Tcp);
+    assert_eq!(decoded.src_port, None);
+    assert_eq!(decoded.dst_port, None);
+    assert_eq!(decoded.tcp_flags, None);
+
+    // First fragment, only the more fragments flag set
+    let mut packet = ipv4_tcp_packet();
+    packet[6] = 0x20;
+    assert_inner_ipv4_tcp(&sampled_header(HeaderProtocol::Ipv4, packet));
+}
+
+#[test]
+fn test_decode_ipv6_with_hop_by_hop() {
+    let mut packet = vec![
+        0x60, 0x00, 0x00, 0x00, // version = 6, traffic class, flow label
+        0x00, 0x1C, 0x00, 0x40, // payload length = 28, next header = hop-by-hop, hop limit
+    ];
+    packet.extend_from_slice(&"2001:db8::1".parse::<Ipv6Addr>().unwrap().octets());
+    packet.extend_from_slice(&"2001:db8::2".parse::<Ipv6Addr>().unwrap().octets());
+    packet.extend_from_slice(&[
+        0x06, 0x00, 0x05, 0x02, // next header = TCP, length = 0 (8 bytes), router alert
+        0x00, 0x00, 0x01, 0x00, // router alert value, PadN
+        0xC0, 0x00, 0x01, 0xBB, // src port = 49152, dst port = 443
+        0x00, 0x00, 0x00, 0x00, // sequence number
+        0x00, 0x00, 0x00, 0x00, // acknowledgment number
+        0x50, 0x02, 0xFF, 0xFF, // data offset, flags = SYN, window
+    ]);
+
+    let decoded = sampled_header(HeaderProtocol::Ipv6, packet.clone())
+        .decode()
+        .unwrap();
+    assert_eq!(decoded.src_ip, IpAddr::V6("2001:db8::1".parse().unwrap()));
+    assert_eq!(decoded.protocol, IpProtocol::Tcp);
+    assert_eq!(decoded.src_port, Some(49152));
+    assert_eq!(decoded.dst_port, Some(443));
+    assert_eq!(decoded.tcp_flags, Some(0x02));
+
+    // Non-first fragment: no transport header to read
+    packet[6] = 44; // next header = fragment
+    packet[40..48].copy_from_slice(&[0x06, 0x00, 0x00, 0xB8, 0x00, 0x00, 0x00, 0x01]);
+    let decoded = sampled_header(HeaderProtocol::Ipv6, packet)
+        .decode()
+        .unwrap();
+    assert_eq!(decoded.protocol, IpProtocol::Tcp);
+    assert_eq!(decoded.src_port, None);
+}
+
+#[test]
+fn test_decode_unsupported_or_truncated() {
+    // Non-IP PPP payload (LCP)
+    let frame = vec![0xFF, 0x03, 0xC0, 0x21, 0x01, 0x01, 0x00, 0x04];
+    assert!(sampled_header(HeaderProtocol::Ppp, frame)
+        .decode()
+        .is_none());
+
+    // Truncated inside the IP header
+    let frame = ipv4_tcp_packet()[..12].to_vec();
+    assert!(sampled_header(HeaderProtocol::Ipv4, frame)
+        .decode()
+        .is_none());
+
+    // Truncated before the transport header: IP fields only
+    let frame = ipv4_tcp_packet()[..20].to_vec();
+    let decoded = sampled_header(HeaderProtocol::Ipv4, frame)
+        .decode()
+        .unwrap();
+    assert_eq!(decoded.protocol, IpProtocol::Tcp);
+    assert_eq!(decoded.src_port, None);
+
+    // Link types without a decoder
+    let header = sampled_header(HeaderProtocol::X25, ipv4_tcp_packet());
+    assert!(header.decode().is_none());
+}
@@ -0,0 +1,53 @@
+//! Tests for the UDP listener
+
+use super::comprehensive::helpers::*;
+use sflow_parser::listener::{ListenerStats, SFlowListener};
+use std::net::UdpSocket;
+use std::time::Duration;
+
+#[test]
+fn test_listener_recv_loopback() {
+    let mut listener = SFlowListener::bind("127.0.0.1:0").unwrap();
+    listener
+        .socket()
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let data = counter_datagram_fixture(2);
+    sender
+        .send_to(&data, listener.local_addr().unwrap())
+        .unwrap();
+
+    let (peer, result) = listener.recv().unwrap();
+    assert_eq!(peer, sender.local_addr().unwrap());
+    let datagram = result.unwrap();
+    assert_eq!(datagram.samples.len(), 1);
+    assert_eq!(datagram.sequence_number, 1);
+}
+
+#[test]
+fn test_listener_stats() {
+    let mut listener = SFlowListener::bind("127.0.0.1:0").unwrap();
+    assert_eq!(listener.stats(), ListenerStats::default());
+
+    let first = counter_datagram_fixture(2);
+    let mut second = create_datagram_header(2);
+    second.extend(build_counter_sample_with_records(&[(
+        1,
+        generic_interface_record_data(1),
+    )]));
+    second.extend(build_counter_sample_with_records(&[(
+        1,
+        generic_interface_record_data(1),
+    )]));
+    listener.process(&first).unwrap();
+    listener.process(&second).unwrap();
+    assert!(listener.process(&first[..20]).is_err());
+
+    let stats = listener.stats();
+    assert_eq!(stats.datagrams, 3);
+    assert_eq!(stats.bytes, (first.len() + second.len() + 20) as u64);
+    assert_eq!(stats.parse_errors, 1);
+    assert_eq!(stats.samples, 3);
+}
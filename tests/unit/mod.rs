@@ -3,9 +3,14 @@
 //! This module contains unit tests split into logical groups
 //! matching the source code structure.
 
+mod analysis;
 mod comprehensive;
 mod core_types;
+mod encode;
 mod enums;
+mod flow_sample_helpers;
+mod header_decode;
+mod listener;
 mod parser_counters;
 mod parser_error_handling;
 mod parser_flows;
@@ -3,7 +3,11 @@
 //! These tests validate that the parser correctly handles invalid input
 //! and returns appropriate errors.
 
-use sflow_parser::parsers::parse_datagram;
+use sflow_parser::parsers::{
+    diagnose_byte_order, looks_truncated, parse_datagram, parse_datagram_verbose,
+    parse_datagram_with_options, ByteOrderDiagnosis, ParseError, ParseOptions, ParseWarning,
+    Parser,
+};
 
 #[test]
 fn test_invalid_version() {
@@ -73,12 +77,102 @@ fn test_truncated_sample() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_sample_length_max_is_truncation() {
+    let data = vec![
+        0x00, 0x00, 0x00, 0x05, // version = 5
+        0x00, 0x00, 0x00, 0x01, // agent address type = IPv4
+        0xC0, 0xA8, 0x01, 0x01, // agent address = 192.168.1.1
+        0x00, 0x00, 0x00, 0x00, // sub agent ID
+        0x00, 0x00, 0x00, 0x01, // sequence number
+        0x00, 0x00, 0x00, 0x64, // uptime
+        0x00, 0x00, 0x00, 0x01, // number of samples = 1
+        0x00, 0x00, 0x00, 0x02, // sample type = counters sample
+        0xFF, 0xFF, 0xFF, 0xFF, // sample length = u32::MAX
+    ];
+
+    // Rounding the length up to 4 bytes must not overflow
+    assert!(matches!(
+        parse_datagram(&data).unwrap_err(),
+        ParseError::DatagramTruncated { available: 36, .. }
+    ));
+}
+
+#[test]
+fn test_sample_length_exceeds_datagram() {
+    let mut data = vec![
+        0x00, 0x00, 0x00, 0x05, // version = 5
+        0x00, 0x00, 0x00, 0x01, // agent address type = IPv4
+        0xC0, 0xA8, 0x01, 0x01, // agent address = 192.168.1.1
+        0x00, 0x00, 0x00, 0x00, // sub agent ID
+        0x00, 0x00, 0x00, 0x01, // sequence number
+        0x00, 0x00, 0x00, 0x64, // uptime
+        0x00, 0x00, 0x00, 0x01, // number of samples = 1
+        0x00, 0x00, 0x00, 0x02, // sample type = counters sample
+        0x00, 0x00, 0x03, 0xE8, // sample length = 1000
+    ];
+    data.extend_from_slice(&[0x00; 24]); // only 24 bytes of sample data
+
+    // The slice is checked before decoding
+    match parse_datagram(&data).unwrap_err() {
+        ParseError::DatagramTruncated {
+            expected,
+            available,
+        } => {
+            assert_eq!(expected, 28 + 8 + 1000);
+            assert_eq!(available, 60);
+        }
+        err => panic!("Expected DatagramTruncated, got {:?}", err),
+    }
+
+    // Readers of unknown length fail when the data runs out
+    let err = Parser::new(std::io::Cursor::new(&data))
+        .parse_datagram()
+        .unwrap_err();
+    assert!(matches!(err, ParseError::Io(_)));
+}
+
 #[test]
 fn test_invalid_utf8_string() {
-    // This test would require crafting a sample with an invalid UTF-8 string
-    // in a field that expects a string (like hostname in HostDescription)
-    // For now, we document that this error path exists
-    // The parser uses String::from_utf8 which will return an error for invalid UTF-8
+    use super::comprehensive::helpers::build_counter_sample_test;
+    use std::error::Error;
+
+    // Host description (0,2000) whose hostname is not valid UTF-8
+    let mut record_data = vec![
+        0x00, 0x00, 0x00, 0x04, // hostname length = 4
+        0x68, 0x6F, 0xFF, 0xFE, // "ho" followed by invalid bytes
+    ];
+    record_data.extend_from_slice(&[0x00; 16]); // uuid
+    record_data.extend_from_slice(&[0x00, 0x00, 0x00, 0x02]); // machine_type = x86_64
+    record_data.extend_from_slice(&[0x00, 0x00, 0x00, 0x02]); // os_name = linux
+    record_data.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]); // os_release length = 0
+    let data = build_counter_sample_test(0x07D0, &record_data);
+
+    let err = parse_datagram(&data).unwrap_err();
+    assert!(matches!(err, ParseError::Utf8(_)), "{}", err);
+    let source = err.source().expect("UTF-8 error has a source");
+    let utf8 = source
+        .downcast_ref::<std::string::FromUtf8Error>()
+        .expect("source is the FromUtf8Error");
+    assert_eq!(utf8.as_bytes(), b"ho\xFF\xFE");
+
+    // Through a boxed error, the parse error itself can be recovered
+    let boxed: Box<dyn Error> = Box::new(err);
+    assert!(matches!(
+        boxed.downcast_ref::<ParseError>(),
+        Some(ParseError::Utf8(_))
+    ));
+
+    // Errors without an underlying cause have no source
+    assert!(parse_datagram(&[]).unwrap_err().source().is_none());
+}
+
+#[test]
+fn test_result_alias_at_crate_root() {
+    fn parse(data: &[u8]) -> sflow_parser::Result<u32> {
+        Ok(sflow_parser::parse_datagram(data)?.sequence_number)
+    }
+    assert!(matches!(parse(&[]), Err(ParseError::TooShort { .. })));
 }
 
 #[test]
@@ -88,6 +182,138 @@ fn test_empty_data() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_input_shorter_than_header() {
+    assert!(matches!(
+        parse_datagram(&[]).unwrap_err(),
+        ParseError::TooShort { need: 24, got: 0 }
+    ));
+
+    // Just the version word
+    let err = parse_datagram(&[0x00, 0x00, 0x00, 0x05]).unwrap_err();
+    assert!(matches!(err, ParseError::TooShort { need: 24, got: 4 }));
+    assert_eq!(
+        err.to_string(),
+        "Input too short: datagram header needs 24 bytes, got 4"
+    );
+
+    // An IPv6 agent address makes the header 40 bytes long
+    let data = [
+        0x00, 0x00, 0x00, 0x05, // version = 5
+        0x00, 0x00, 0x00, 0x02, // agent address type = IPv6
+        0x20, 0x01, 0x0D, 0xB8, // start of the agent address
+    ];
+    assert!(matches!(
+        parse_datagram(&data).unwrap_err(),
+        ParseError::TooShort { need: 40, got: 12 }
+    ));
+}
+
+#[test]
+fn test_fewer_samples_than_declared() {
+    let mut data = vec![
+        0x00, 0x00, 0x00, 0x05, // version = 5
+        0x00, 0x00, 0x00, 0x01, // agent address type = IPv4
+        0xC0, 0xA8, 0x01, 0x01, // agent address = 192.168.1.1
+        0x00, 0x00, 0x00, 0x00, // sub agent ID
+        0x00, 0x00, 0x00, 0x01, // sequence number
+        0x00, 0x00, 0x00, 0x64, // uptime
+        0x00, 0x00, 0x03, 0xE8, // number of samples = 1000
+        0x00, 0x00, 0x00, 0x02, // sample type = counters sample
+        0x00, 0x00, 0x00, 0x0C, // sample length = 12
+        0x00, 0x00, 0x00, 0x01, // sequence number
+        0x00, 0x00, 0x00, 0x01, // source ID
+        0x00, 0x00, 0x00, 0x00, // number of records = 0
+    ];
+
+    let err = parse_datagram(&data).unwrap_err();
+    assert!(matches!(
+        err,
+        ParseError::MissingSamples {
+            declared: 1000,
+            present: 1
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "Missing samples: datagram declares 1000 samples, only 1 present"
+    );
+
+    // The walk is bounded by the buffer, not by the declared count
+    data[24..28].copy_from_slice(&u32::MAX.to_be_bytes());
+    assert!(matches!(
+        parse_datagram(&data).unwrap_err(),
+        ParseError::MissingSamples {
+            declared: u32::MAX,
+            present: 1
+        }
+    ));
+}
+
+#[test]
+fn test_diagnose_byte_order() {
+    let header = [
+        0x00, 0x00, 0x00, 0x05, // version = 5
+        0x00, 0x00, 0x00, 0x01, // agent address type = IPv4
+    ];
+    assert_eq!(diagnose_byte_order(&header), ByteOrderDiagnosis::BigEndian);
+
+    // Each 32-bit word written little-endian
+    let swapped = [0x05, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00];
+    assert_eq!(
+        diagnose_byte_order(&swapped),
+        ByteOrderDiagnosis::LittleEndian
+    );
+    assert!(ByteOrderDiagnosis::LittleEndian
+        .to_string()
+        .contains("byte-swapping"));
+
+    // Garbage, version 4 and input shorter than a word
+    assert_eq!(
+        diagnose_byte_order(&[0xDE, 0xAD, 0xBE, 0xEF]),
+        ByteOrderDiagnosis::Unrecognized
+    );
+    assert_eq!(
+        diagnose_byte_order(&[0x00, 0x00, 0x00, 0x04]),
+        ByteOrderDiagnosis::Unrecognized
+    );
+    assert_eq!(
+        diagnose_byte_order(&[0x00, 0x00, 0x00]),
+        ByteOrderDiagnosis::Unrecognized
+    );
+}
+
+#[test]
+fn test_looks_truncated() {
+    let data = vec![
+        0x00, 0x00, 0x00, 0x05, // version = 5
+        0x00, 0x00, 0x00, 0x01, // agent address type = IPv4
+        0xC0, 0xA8, 0x01, 0x01, // agent address = 192.168.1.1
+        0x00, 0x00, 0x00, 0x00, // sub agent ID
+        0x00, 0x00, 0x00, 0x01, // sequence number
+        0x00, 0x00, 0x00, 0x64, // uptime
+        0x00, 0x00, 0x00, 0x01, // number of samples = 1
+        0x00, 0x00, 0x00, 0x02, // sample type = counters sample
+        0x00, 0x00, 0x00, 0x0C, // sample length = 12
+        0x00, 0x00, 0x00, 0x01, // sequence number
+        0x00, 0x00, 0x00, 0x01, // source ID
+        0x00, 0x00, 0x00, 0x00, // number of records = 0
+    ];
+    assert!(!looks_truncated(&data));
+
+    // Lost the end of the sample
+    assert!(looks_truncated(&data[..40]));
+    // Lost a whole sample
+    let mut missing = data.clone();
+    missing[27] = 2;
+    assert!(looks_truncated(&missing));
+
+    // Malformed but complete
+    let mut invalid = data.clone();
+    invalid[3] = 4;
+    assert!(!looks_truncated(&invalid));
+}
+
 #[test]
 fn test_minimal_valid_datagram() {
     // Minimal valid datagram with no samples
@@ -225,6 +451,77 @@ fn test_unknown_header_protocol() {
     }
 }
 
+/// Build a datagram with a single sampled_header flow record using the given protocol
+fn build_sampled_header_datagram(protocol: u32) -> Vec<u8> {
+    let mut data = vec![
+        0x00, 0x00, 0x00, 0x05, // version = 5
+        0x00, 0x00, 0x00, 0x01, // agent address type = IPv4
+        0xC0, 0xA8, 0x01, 0x01, // agent address = 192.168.1.1
+        0x00, 0x00, 0x00, 0x00, // sub agent ID
+        0x00, 0x00, 0x00, 0x01, // sequence number
+        0x00, 0x00, 0x00, 0x64, // uptime = 100ms
+        0x00, 0x00, 0x00, 0x01, // number of samples = 1
+        0x00, 0x00, 0x00, 0x01, // sample type = flow sample
+        0x00, 0x00, 0x00, 0x3C, // sample length = 60 (32 + 8 + 20)
+        0x00, 0x00, 0x00, 0x01, // sequence number
+        0x00, 0x00, 0x00, 0x01, // source ID
+        0x00, 0x00, 0x03, 0xE8, // sampling rate = 1000
+        0x00, 0x00, 0x00, 0x64, // sample pool = 100
+        0x00, 0x00, 0x00, 0x00, // drops = 0
+        0x00, 0x00, 0x00, 0x01, // input interface = 1
+        0x00, 0x00, 0x00, 0x02, // output interface = 2
+        0x00, 0x00, 0x00, 0x01, // number of flow records = 1
+        0x00, 0x00, 0x00, 0x01, // flow record type = sampled_header (0,1)
+        0x00, 0x00, 0x00, 0x14, // flow data length = 20 bytes
+    ];
+    data.extend_from_slice(&protocol.to_be_bytes());
+    data.extend_from_slice(&[
+        0x00, 0x00, 0x00, 0x40, // frame_length = 64
+        0x00, 0x00, 0x00, 0x00, // stripped = 0
+        0x00, 0x00, 0x00, 0x04, // header length = 4
+        0xAA, 0xBB, 0xCC, 0xDD, // header bytes
+    ]);
+    data
+}
+
+#[test]
+fn test_unknown_header_protocol_preserved() {
+    use sflow_parser::models::record_flows::HeaderProtocol;
+    use sflow_parser::models::{FlowData, SampleData};
+
+    // Receivers must tolerate unknown enumerants: protocol 99 is kept as Unknown
+    let data = build_sampled_header_datagram(99);
+    let datagram = parse_datagram(&data).unwrap();
+
+    let SampleData::FlowSample(sample) = &datagram.samples[0].sample_data else {
+        panic!("Expected FlowSample");
+    };
+    let FlowData::SampledHeader(header) = &sample.flow_records[0].flow_data else {
+        panic!("Expected SampledHeader");
+    };
+    assert_eq!(header.protocol, HeaderProtocol::Unknown(99));
+    assert_eq!(header.protocol.as_u32(), 99);
+    assert_eq!(header.protocol.to_string(), "Unknown (99)");
+    assert_eq!(header.frame_length, 64);
+    assert_eq!(header.header, vec![0xAA, 0xBB, 0xCC, 0xDD]);
+}
+
+#[test]
+fn test_unknown_header_protocol_strict() {
+    let data = build_sampled_header_datagram(99);
+    let options = ParseOptions {
+        strict_enums: true,
+        ..Default::default()
+    };
+
+    let err = parse_datagram_with_options(&data, &options).unwrap_err();
+    assert!(err.to_string().contains("Unknown header protocol: 99"));
+
+    // Known protocols are still accepted in strict mode
+    let data = build_sampled_header_datagram(1);
+    assert!(parse_datagram_with_options(&data, &options).is_ok());
+}
+
 #[test]
 fn test_valid_header_protocol_values() {
     // Test that all valid header protocol values (1-17) are accepted
@@ -297,3 +594,405 @@ fn test_valid_header_protocol_values() {
         );
     }
 }
+
+#[test]
+fn test_flow_record_too_large() {
+    use sflow_parser::models::DataFormat;
+
+    // A flow record claiming 1MB inside a tiny datagram
+    let data = [
+        0x00, 0x00, 0x00, 0x05, // version = 5
+        0x00, 0x00, 0x00, 0x01, // agent address type = IPv4
+        0xC0, 0xA8, 0x01, 0x01, // agent address = 192.168.1.1
+        0x00, 0x00, 0x00, 0x00, // sub agent ID
+        0x00, 0x00, 0x00, 0x01, // sequence number
+        0x00, 0x00, 0x00, 0x64, // uptime = 100ms
+        0x00, 0x00, 0x00, 0x01, // number of samples = 1
+        0x00, 0x00, 0x00, 0x01, // sample type = flow sample
+        0x00, 0x00, 0x00, 0x28, // sample length = 40 (32 + 8)
+        0x00, 0x00, 0x00, 0x01, // sequence number
+        0x00, 0x00, 0x00, 0x01, // source ID
+        0x00, 0x00, 0x03, 0xE8, // sampling rate = 1000
+        0x00, 0x00, 0x00, 0x64, // sample pool = 100
+        0x00, 0x00, 0x00, 0x00, // drops = 0
+        0x00, 0x00, 0x00, 0x01, // input interface = 1
+        0x00, 0x00, 0x00, 0x02, // output interface = 2
+        0x00, 0x00, 0x00, 0x01, // number of flow records = 1
+        0x01, 0x13, 0xD0, 0x05, // flow record type = (4413,5)
+        0x00, 0x10, 0x00, 0x00, // flow data length = 1MB
+    ];
+
+    let err = parse_datagram(&data).unwrap_err();
+    match &err {
+        ParseError::RecordTooLarge { format, length } => {
+            assert_eq!(*format, DataFormat::new(4413, 5));
+            assert_eq!(*length, 0x0010_0000);
+        }
+        other => panic!("Expected RecordTooLarge, got {:?}", other),
+    }
+    assert_eq!(
+        err.to_string(),
+        "Record too large: format (4413,5) declares 1048576 bytes"
+    );
+
+    // Raising the cap defers to the usual truncation error
+    let options = ParseOptions {
+        max_record_bytes: 2 * 1024 * 1024,
+        ..Default::default()
+    };
+    let err = parse_datagram_with_options(&data, &options).unwrap_err();
+    assert!(!matches!(err, ParseError::RecordTooLarge { .. }));
+}
+
+#[test]
+fn test_verbose_unknown_flow_format_warning() {
+    use sflow_parser::models::{DataFormat, FlowData, SampleData};
+
+    let mut data = build_sampled_header_datagram(1);
+    // Turn the sampled_header record into a vendor format (9999,1)
+    data[68..72].copy_from_slice(&((9999u32 << 12) | 1).to_be_bytes());
+
+    let (datagram, warnings) = parse_datagram_verbose(&data, &ParseOptions::default()).unwrap();
+
+    let SampleData::FlowSample(sample) = &datagram.samples[0].sample_data else {
+        panic!("Expected FlowSample");
+    };
+    assert!(matches!(
+        sample.flow_records[0].flow_data,
+        FlowData::Unknown { .. }
+    ));
+    assert_eq!(
+        warnings,
+        vec![ParseWarning::UnknownFlowFormat(DataFormat::new(9999, 1))]
+    );
+    assert_eq!(
+        warnings[0].to_string(),
+        "Unrecognized flow record format (9999,1)"
+    );
+
+    // The plain entry point returns the same datagram
+    assert_eq!(parse_datagram(&data).unwrap(), datagram);
+}
+
+#[test]
+fn test_on_unknown_callback() {
+    use sflow_parser::models::{DataFormat, FlowData, SampleData};
+    use std::sync::{Arc, Mutex};
+
+    let mut data = build_sampled_header_datagram(1);
+    // Turn the sampled_header record into a vendor format (9999,1)
+    data[68..72].copy_from_slice(&((9999u32 << 12) | 1).to_be_bytes());
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = seen.clone();
+    let options = ParseOptions::default().on_unknown(Box::new(move |format, bytes| {
+        sink.lock().unwrap().push((*format, bytes.to_vec()));
+    }));
+    let datagram = parse_datagram_with_options(&data, &options).unwrap();
+
+    let SampleData::FlowSample(sample) = &datagram.samples[0].sample_data else {
+        panic!("Expected FlowSample");
+    };
+    let FlowData::Unknown { data: record, .. } = &sample.flow_records[0].flow_data else {
+        panic!("Expected Unknown");
+    };
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![(DataFormat::new(9999, 1), record.clone())]
+    );
+
+    // Known records do not reach the callback
+    seen.lock().unwrap().clear();
+    parse_datagram_with_options(&build_sampled_header_datagram(1), &options).unwrap();
+    assert!(seen.lock().unwrap().is_empty());
+
+    // Clones of the options share the callback
+    assert_eq!(options.clone(), options);
+}
+
+#[test]
+fn test_verbose_header_protocol_and_length_warnings() {
+    use sflow_parser::models::DataFormat;
+
+    let (_, warnings) =
+        parse_datagram_verbose(&build_sampled_header_datagram(1), &ParseOptions::default())
+            .unwrap();
+    assert!(warnings.is_empty());
+
+    let (_, warnings) =
+        parse_datagram_verbose(&build_sampled_header_datagram(99), &ParseOptions::default())
+            .unwrap();
+    assert_eq!(warnings, vec![ParseWarning::UnknownHeaderProtocol(99)]);
+
+    // Declare 4 extra bytes in the flow record (and its sample) that the decoder ignores
+    let mut data = build_sampled_header_datagram(1);
+    data[32..36].copy_from_slice(&0x40u32.to_be_bytes()); // sample length = 64
+    data[72..76].copy_from_slice(&24u32.to_be_bytes()); // flow data length = 24
+    data.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+
+    let (_, warnings) = parse_datagram_verbose(&data, &ParseOptions::default()).unwrap();
+    assert_eq!(
+        warnings,
+        vec![ParseWarning::LengthMismatch {
+            format: DataFormat::new(0, 1),
+            declared: 24,
+            consumed: 20,
+        }]
+    );
+}
+
+#[test]
+fn test_strict_profile() {
+    let strict = ParseOptions::strict();
+    assert!(strict.strict_enums);
+    assert!(strict.strict_lengths);
+    assert!(strict.verify_padding);
+    assert!(strict.strict_trailing_bytes);
+    assert!(strict.strict_record_padding);
+    assert_eq!(
+        strict.max_record_bytes,
+        ParseOptions::default().max_record_bytes
+    );
+
+    // A conforming datagram parses identically under both profiles
+    let data = build_sampled_header_datagram(1);
+    assert_eq!(
+        parse_datagram_with_options(&data, &strict).unwrap(),
+        parse_datagram(&data).unwrap()
+    );
+
+    // Unknown header protocol: tolerated by default, rejected by strict()
+    let data = build_sampled_header_datagram(99);
+    assert!(parse_datagram(&data).is_ok());
+    assert!(parse_datagram_with_options(&data, &strict).is_err());
+
+    // A record longer than its decoded content is rejected as well
+    let mut data = build_sampled_header_datagram(1);
+    data[32..36].copy_from_slice(&0x40u32.to_be_bytes()); // sample length = 64
+    data[72..76].copy_from_slice(&24u32.to_be_bytes()); // flow data length = 24
+    data.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+    assert!(parse_datagram(&data).is_ok());
+    let err = parse_datagram_with_options(&data, &strict).unwrap_err();
+    assert!(err.to_string().contains("(0,1)"), "{}", err);
+}
+
+#[test]
+fn test_strict_trailing_bytes() {
+    let mut data = build_sampled_header_datagram(1);
+    data.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]); // after the last sample
+
+    assert!(parse_datagram(&data).is_ok());
+    let options = ParseOptions {
+        strict_trailing_bytes: true,
+        ..ParseOptions::default()
+    };
+    let err = parse_datagram_with_options(&data, &options).unwrap_err();
+    assert!(matches!(err, ParseError::InvalidData(_)), "{}", err);
+    assert!(err.to_string().contains("4 trailing bytes"), "{}", err);
+}
+
+#[test]
+fn test_strict_record_padding() {
+    // 3 header bytes: the record declares 19 bytes, leaving out 1 byte of padding
+    let mut data = build_sampled_header_datagram(1);
+    data[72..76].copy_from_slice(&19u32.to_be_bytes()); // flow data length = 19
+    data[88..92].copy_from_slice(&3u32.to_be_bytes()); // header length = 3
+    data[95] = 0x00; // padding
+
+    assert!(parse_datagram(&data).is_ok());
+    let options = ParseOptions {
+        strict_record_padding: true,
+        ..ParseOptions::default()
+    };
+    let err = parse_datagram_with_options(&data, &options).unwrap_err();
+    assert!(matches!(err, ParseError::InvalidData(_)), "{}", err);
+    assert!(
+        err.to_string().contains("(0,1) declares 19 bytes"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_extended_gateway_huge_segment_count() {
+    use super::comprehensive::helpers::build_flow_sample_test;
+
+    let record_data = [
+        0x00, 0x00, 0x00, 0x01, // next_hop address type = IPv4
+        0xC0, 0xA8, 0x01, 0x01, // next_hop = 192.168.1.1
+        0x00, 0x00, 0xFD, 0xE8, // as_number = 65000
+        0x00, 0x00, 0xFD, 0xE9, // src_as = 65001
+        0x00, 0x00, 0xFD, 0xEA, // src_peer_as = 65002
+        0xFF, 0xFF, 0xFF, 0xFF, // num_segments = 4294967295
+        0x00, 0x00, 0x00, 0x02, // path_type = 2 (AS_SEQUENCE)
+        0x00, 0x00, 0x00, 0x00, // path_length = 0
+    ];
+    let data = build_flow_sample_test(0x03EB, &record_data); // record type = 1003
+
+    let err = parse_datagram(&data).unwrap_err();
+    assert!(matches!(err, ParseError::InvalidData(_)), "{}", err);
+    assert!(err.to_string().contains("4294967295 elements"));
+
+    // The nested AS path length is bounded the same way
+    let mut record_data = record_data;
+    record_data[20..24].copy_from_slice(&[0x00, 0x00, 0x00, 0x01]); // num_segments = 1
+    record_data[28..32].copy_from_slice(&[0x40, 0x00, 0x00, 0x00]); // path_length = 2^30
+    let data = build_flow_sample_test(0x03EB, &record_data);
+    assert!(matches!(
+        parse_datagram(&data).unwrap_err(),
+        ParseError::InvalidData(_)
+    ));
+}
+
+#[test]
+fn test_opaque_length_beyond_record_data() {
+    use super::comprehensive::helpers::build_flow_sample_test;
+
+    // Extended user (0,1004) whose source user claims 100MB within a 12 byte record
+    let record_data = [
+        0x00, 0x00, 0x00, 0x6A, // src_charset = UTF-8
+        0x06, 0x40, 0x00, 0x00, // src_user length = 100MB
+        0x61, 0x62, 0x63, 0x64, // "abcd"
+    ];
+    let data = build_flow_sample_test(0x03EC, &record_data);
+
+    match parse_datagram(&data).unwrap_err() {
+        ParseError::Io(e) => assert_eq!(e.kind(), std::io::ErrorKind::UnexpectedEof),
+        other => panic!("Expected UnexpectedEof, got {}", other),
+    }
+}
+
+#[test]
+fn test_verify_padding() {
+    use super::comprehensive::helpers::build_flow_sample_test;
+
+    // Extended user (0,1004) whose source user "bob" is padded with 0xFF
+    let record_data = [
+        0x00, 0x00, 0x00, 0x6A, // src_charset = UTF-8
+        0x00, 0x00, 0x00, 0x03, // src_user length = 3
+        0x62, 0x6F, 0x62, 0xFF, // "bob" + padding 0xFF
+        0x00, 0x00, 0x00, 0x6A, // dst_charset = UTF-8
+        0x00, 0x00, 0x00, 0x00, // dst_user length = 0
+    ];
+    let data = build_flow_sample_test(0x03EC, &record_data);
+
+    // Tolerated by default
+    assert!(parse_datagram(&data).is_ok());
+
+    let options = ParseOptions {
+        verify_padding: true,
+        ..Default::default()
+    };
+    let err = parse_datagram_with_options(&data, &options).unwrap_err();
+    assert!(matches!(err, ParseError::NonZeroPadding { length: 3 }));
+    assert_eq!(
+        err.to_string(),
+        "Non-zero XDR padding after 3 bytes of opaque data"
+    );
+}
+
+#[test]
+fn test_recover_bad_records() {
+    use super::comprehensive::helpers::{build_flow_sample_with_records, create_datagram_header};
+    use sflow_parser::models::record_flows::ExtendedSwitch;
+    use sflow_parser::models::{DataFormat, FlowData, SampleData};
+
+    let sampled_ipv4 = vec![
+        0x00, 0x00, 0x00, 0x40, // length = 64
+        0x00, 0x00, 0x00, 0x06, // protocol = TCP
+        0x0A, 0x00, 0x00, 0x01, // src_ip = 10.0.0.1
+        0x0A, 0x00, 0x00, 0x02, // dst_ip = 10.0.0.2
+        0x00, 0x00, 0x30, 0x39, // src_port = 12345
+        0x00, 0x00, 0x00, 0x50, // dst_port = 80
+        0x00, 0x00, 0x00, 0x18, // tcp_flags
+        0x00, 0x00, 0x00, 0x00, // tos
+    ];
+    // Extended router (0,1002) with a garbage next hop address type
+    let bad_router = vec![
+        0x00, 0x00, 0x00, 0x07, // next_hop address type = 7 (invalid)
+        0xC0, 0xA8, 0x01, 0x01, // next_hop
+        0x00, 0x00, 0x00, 0x18, // src_mask_len
+        0x00, 0x00, 0x00, 0x18, // dst_mask_len
+    ];
+    let switch = vec![
+        0x00, 0x00, 0x00, 0x0A, // src_vlan = 10
+        0x00, 0x00, 0x00, 0x00, // src_priority
+        0x00, 0x00, 0x00, 0x14, // dst_vlan = 20
+        0x00, 0x00, 0x00, 0x00, // dst_priority
+    ];
+    let mut data = create_datagram_header(1);
+    data.extend(build_flow_sample_with_records(&[
+        (3, sampled_ipv4),
+        (1002, bad_router.clone()),
+        (1001, switch),
+    ]));
+
+    // By default the bad record fails the datagram
+    assert!(matches!(
+        parse_datagram(&data).unwrap_err(),
+        ParseError::InvalidData(_)
+    ));
+
+    let options = ParseOptions {
+        recover_bad_records: true,
+        ..Default::default()
+    };
+    let (datagram, warnings) = parse_datagram_verbose(&data, &options).unwrap();
+    let SampleData::FlowSample(flow) = &datagram.samples[0].sample_data else {
+        panic!("Expected FlowSample");
+    };
+    assert_eq!(flow.flow_records.len(), 3);
+    assert!(matches!(
+        &flow.flow_records[0].flow_data,
+        FlowData::SampledIpv4(ip) if ip.dst_port == 80
+    ));
+    assert_eq!(
+        flow.flow_records[1].flow_data,
+        FlowData::Unknown {
+            format: DataFormat::new(0, 1002),
+            data: bad_router,
+        }
+    );
+    assert_eq!(
+        flow.flow_records[2].flow_data,
+        FlowData::ExtendedSwitch(ExtendedSwitch {
+            src_vlan: 10,
+            src_priority: 0,
+            dst_vlan: 20,
+            dst_priority: 0,
+        })
+    );
+
+    assert_eq!(warnings.len(), 1);
+    assert!(matches!(
+        &warnings[0],
+        ParseWarning::MalformedRecord { format, error }
+            if *format == DataFormat::new(0, 1002) && error.contains("address type")
+    ));
+}
+
+#[test]
+fn test_parse_error_into_io_error() {
+    let err = std::io::Error::from(ParseError::InvalidData("bad record".to_string()));
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "Invalid data: bad record");
+
+    // Running out of input stays an end of file error
+    let err = std::io::Error::from(parse_datagram(&[0x00, 0x00, 0x00, 0x05]).unwrap_err());
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+    let data = [0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x01];
+    let err = Parser::new(std::io::Cursor::new(&data))
+        .parse_datagram()
+        .unwrap_err();
+    assert_eq!(
+        std::io::Error::from(err).kind(),
+        std::io::ErrorKind::UnexpectedEof
+    );
+
+    // `?` works in functions returning io::Result
+    fn read(data: &[u8]) -> std::io::Result<u32> {
+        Ok(parse_datagram(data)?.sequence_number)
+    }
+    assert!(read(&[]).is_err());
+}
@@ -211,6 +211,7 @@ fn test_extended_gateway_simple() {
         dst_as_path: vec![],
         communities: vec![],
         local_pref: 100,
+        trailing: vec![],
     };
 
     assert_eq!(gateway.as_number, 65000);
@@ -240,6 +241,7 @@ fn test_extended_gateway_with_as_path() {
         ],
         communities: vec![100, 200, 300],
         local_pref: 150,
+        trailing: vec![],
     };
 
     assert_eq!(gateway.dst_as_path.len(), 2);
@@ -428,9 +430,9 @@ fn test_extended_80211_tx() {
         transmissions: 2, // Retried once
         packet_duration: 1000,
         retrans_duration: 500,
-        channel: 149, // 5 GHz
-        speed: 866,
-        power: 20, // 20 mW
+        channel: 149,       // 5 GHz
+        speed: 866_000_000, // 866 Mbps in bps
+        power: 20,          // 20 mW
     };
 
     assert_eq!(tx.ssid, "TestAP");
@@ -582,6 +584,7 @@ fn test_extended_gateway_max_as_path() {
         }],
         communities: vec![],
         local_pref: 100,
+        trailing: vec![],
     };
 
     assert_eq!(gateway.dst_as_path[0].path.len(), 255);
@@ -172,3 +172,135 @@ fn test_counter_record_structure() {
         _ => panic!("Expected Unknown counter data"),
     }
 }
+
+#[test]
+fn test_lag_port_state_flags() {
+    let state = PortState::from([0x3D, 0x3F, 0x05, 0x7C]);
+
+    // 0x3D: active, long timeout, aggregatable, in sync, collecting, distributing
+    let actor = state.actor_admin;
+    assert!(actor.lacp_activity());
+    assert!(!actor.lacp_timeout());
+    assert!(actor.aggregation());
+    assert!(actor.synchronization());
+    assert!(actor.collecting());
+    assert!(actor.distributing());
+    assert!(!actor.defaulted());
+    assert!(!actor.expired());
+
+    // 0x3F: same with short timeout
+    assert!(state.actor_oper.lacp_timeout());
+
+    // 0x05: active and aggregatable, not yet synchronized
+    assert!(state.partner_admin.lacp_activity());
+    assert!(state.partner_admin.aggregation());
+    assert!(!state.partner_admin.synchronization());
+    assert!(!state.partner_admin.collecting());
+
+    // 0x7C: passive partner running on defaulted information
+    assert!(!state.partner_oper.lacp_activity());
+    assert!(state.partner_oper.distributing());
+    assert!(state.partner_oper.defaulted());
+    assert!(!state.partner_oper.expired());
+}
+
+fn interface_counters(if_index: u32) -> GenericInterfaceCounters {
+    GenericInterfaceCounters {
+        if_index,
+        if_type: 6,
+        if_speed: 1_000_000_000,
+        if_direction: 1,
+        if_status: 3,
+        if_in_octets: 0,
+        if_in_ucast_pkts: 0,
+        if_in_multicast_pkts: 0,
+        if_in_broadcast_pkts: 0,
+        if_in_discards: 0,
+        if_in_errors: 0,
+        if_in_unknown_protos: 0,
+        if_out_octets: 0,
+        if_out_ucast_pkts: 0,
+        if_out_multicast_pkts: 0,
+        if_out_broadcast_pkts: 0,
+        if_out_discards: 0,
+        if_out_errors: 0,
+        if_promiscuous_mode: 2,
+    }
+}
+
+fn counters_sample(index: u32, counters: Vec<(DataFormat, CounterData)>) -> SampleRecord {
+    SampleRecord {
+        sample_type: DataFormat::new(0, 2),
+        sample_data: SampleData::CountersSample(CountersSample {
+            sequence_number: 1,
+            source_id: DataSource::new(0, index),
+            counters: counters
+                .into_iter()
+                .map(|(counter_format, counter_data)| CounterRecord {
+                    counter_format,
+                    counter_data,
+                })
+                .collect(),
+        }),
+    }
+}
+
+#[test]
+fn test_adapter_interfaces() {
+    let mac1 = MacAddress::from([0x00, 0x11, 0x22, 0x33, 0x44, 0x01]);
+    let mac2 = MacAddress::from([0x00, 0x11, 0x22, 0x33, 0x44, 0x02]);
+    let adapters = HostAdapters {
+        adapters: vec![
+            HostAdapter {
+                if_index: 1,
+                mac_addresses: vec![mac1],
+            },
+            HostAdapter {
+                if_index: 2,
+                mac_addresses: vec![mac2],
+            },
+            HostAdapter {
+                if_index: 9,
+                mac_addresses: vec![],
+            },
+        ],
+    };
+
+    let mut datagram = SFlowDatagram::new(
+        Address::IPv4(std::net::Ipv4Addr::new(192, 0, 2, 1)),
+        0,
+        1,
+        1000,
+    );
+    let mut records = vec![(
+        DataFormat::new(0, 2001),
+        CounterData::HostAdapters(adapters),
+    )];
+    for if_index in [2, 1, 3] {
+        records.push((
+            DataFormat::new(0, 1),
+            CounterData::GenericInterface(interface_counters(if_index)),
+        ));
+    }
+    datagram.samples.push(counters_sample(0, records));
+
+    // Another data source reusing ifIndex 1 is not paired with the adapters
+    // of the first sample
+    datagram.samples.push(counters_sample(
+        1,
+        vec![(
+            DataFormat::new(0, 1),
+            CounterData::GenericInterface(interface_counters(1)),
+        )],
+    ));
+
+    let associated = datagram.adapter_interfaces();
+    let pairs: Vec<(u32, MacAddress)> = associated
+        .iter()
+        .map(|(if_index, mac, _)| (*if_index, *mac))
+        .collect();
+    // ifIndex 3 has no adapter
+    assert_eq!(pairs, vec![(2, mac2), (1, mac1)]);
+    assert_eq!(associated[0].2.if_index, 2);
+    assert_eq!(associated[1].2.if_index, 1);
+}
@@ -55,6 +55,36 @@ fn test_sampled_ipv6() {
     assert_eq!(ipv6.dst_port, 53);
 }
 
+#[test]
+fn test_sampled_ip_dscp_ecn() {
+    let ipv4 = SampledIpv4 {
+        length: 40,
+        protocol: 17,
+        src_ip: Ipv4Addr::new(192, 168, 1, 1),
+        dst_ip: Ipv4Addr::new(10, 0, 0, 1),
+        src_port: 5004,
+        dst_port: 5004,
+        tcp_flags: 0,
+        tos: 0xB8, // EF
+    };
+    assert_eq!(ipv4.dscp(), 46);
+    assert_eq!(ipv4.ecn(), 0);
+    assert_eq!(ipv4.tos, 0xB8);
+
+    let ipv6 = SampledIpv6 {
+        length: 60,
+        protocol: 17,
+        src_ip: Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1),
+        dst_ip: Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 2),
+        src_port: 5004,
+        dst_port: 5004,
+        tcp_flags: 0,
+        priority: 0x2B, // AF11 with ECN CE
+    };
+    assert_eq!(ipv6.dscp(), 10);
+    assert_eq!(ipv6.ecn(), 3);
+}
+
 #[test]
 fn test_extended_switch() {
     let switch = ExtendedSwitch {
@@ -109,6 +139,7 @@ fn test_extended_gateway() {
         }],
         communities: vec![100, 200, 300],
         local_pref: 100,
+        trailing: vec![],
     };
     assert_eq!(gateway.as_number, 65000);
     assert_eq!(gateway.src_as, 100);
@@ -116,6 +147,43 @@ fn test_extended_gateway() {
     assert_eq!(gateway.communities.len(), 3);
 }
 
+#[test]
+fn test_extended_gateway_asn_rendering() {
+    let mut gateway = ExtendedGateway {
+        next_hop: Address::IPv4(Ipv4Addr::new(192, 168, 1, 1)),
+        as_number: 4_200_000_000,
+        src_as: 64512,
+        src_peer_as: 65001,
+        dst_as_path: vec![AsPathSegment {
+            path_type: AsPathType::AsSequence,
+            path_length: 2,
+            path: vec![65001, 13335],
+        }],
+        communities: vec![(65000 << 16) | 100, (13335 << 16) | 65535],
+        local_pref: 100,
+        trailing: vec![],
+    };
+    assert!(gateway.has_32bit_asn());
+    assert!(!gateway.has_as_trans());
+    assert_eq!(gateway.as_number_string(), "4200000000");
+    assert_eq!(
+        gateway.community_strings(),
+        vec!["65000:100", "13335:65535"]
+    );
+
+    // A 2-byte speaker replaced the 4-byte AS number with AS_TRANS
+    gateway.as_number = 65000;
+    gateway.dst_as_path[0].path = vec![65001, AS_TRANS];
+    assert!(!gateway.has_32bit_asn());
+    assert!(gateway.has_as_trans());
+    assert_eq!(asn_to_string(AS_TRANS), "23456 (AS_TRANS)");
+    assert_eq!(gateway.as_number_string(), "65000");
+
+    // 4-byte AS numbers in the path count as well
+    gateway.dst_as_path[0].path.push(4_200_000_001);
+    assert!(gateway.has_32bit_asn());
+}
+
 #[test]
 fn test_flow_record_structure() {
     let record = FlowRecord {
@@ -333,3 +401,447 @@ fn test_drop_reason_from_u32_method() {
     assert_eq!(DropReason::from_u32(303), Some(DropReason::SplitHorizon));
     assert_eq!(DropReason::from_u32(999), None); // Invalid value
 }
+
+#[test]
+fn test_ip_protocol_names() {
+    assert_eq!(IpProtocol::from(6), IpProtocol::Tcp);
+    assert_eq!(IpProtocol::from(6).to_string(), "TCP");
+    assert_eq!(IpProtocol::from(17), IpProtocol::Udp);
+    assert_eq!(IpProtocol::from(17).to_string(), "UDP");
+    assert_eq!(IpProtocol::from(58).to_string(), "ICMPv6");
+
+    // Unnamed protocol numbers keep their raw value
+    assert_eq!(IpProtocol::from(253), IpProtocol::Unknown(253));
+    assert_eq!(IpProtocol::from(253).to_string(), "Unknown (253)");
+    assert_eq!(IpProtocol::from(253).as_u32(), 253);
+}
+
+#[test]
+fn test_ip_protocol_accessors() {
+    let ipv6 = SampledIpv6 {
+        length: 60,
+        protocol: 17,
+        src_ip: Ipv6Addr::LOCALHOST,
+        dst_ip: Ipv6Addr::LOCALHOST,
+        src_port: 53,
+        dst_port: 53000,
+        tcp_flags: 0,
+        priority: 0,
+    };
+    assert_eq!(ipv6.ip_protocol(), IpProtocol::Udp);
+
+    let socket = ExtendedSocketIpv4 {
+        protocol: 6,
+        local_ip: Ipv4Addr::new(10, 0, 0, 1),
+        remote_ip: Ipv4Addr::new(10, 0, 0, 2),
+        local_port: 443,
+        remote_port: 51000,
+    };
+    assert_eq!(socket.ip_protocol(), IpProtocol::Tcp);
+    assert_eq!(socket.protocol, 6);
+}
+
+#[test]
+fn test_http_transaction_status_helpers() {
+    let mut http = ExtendedHttpTransaction {
+        url: "/".to_string(),
+        host: String::new(),
+        referer: String::new(),
+        user_agent: String::new(),
+        user: String::new(),
+        status: 200,
+    };
+    assert_eq!(http.status_class(), Some(2));
+    assert!(http.is_success());
+
+    http.status = 503;
+    assert_eq!(http.status_class(), Some(5));
+    assert!(!http.is_success());
+
+    // Out of range status codes have no class
+    for status in [0, 99, 600, u32::MAX] {
+        http.status = status;
+        assert_eq!(http.status_class(), None);
+        assert!(!http.is_success());
+    }
+}
+
+#[test]
+fn test_http_request_from_deprecated() {
+    let deprecated = HttpRequestDeprecated {
+        method: HttpMethod::Post,
+        uri: "/api/v1/items".to_string(),
+        host: "example.com".to_string(),
+        referer: "https://example.com/".to_string(),
+        useragent: "curl/8.0".to_string(),
+        xff: "203.0.113.7".to_string(),
+        authuser: "alice".to_string(),
+        mime_type: "application/json".to_string(),
+        req_bytes: 512,
+        resp_bytes: 2048,
+        duration_us: 1500,
+        status: 201,
+    };
+
+    let request = HttpRequest::from(deprecated.clone());
+    assert_eq!(request.protocol, 0);
+    assert_eq!(request.method, deprecated.method);
+    assert_eq!(request.uri, deprecated.uri);
+    assert_eq!(request.host, deprecated.host);
+    assert_eq!(request.referer, deprecated.referer);
+    assert_eq!(request.useragent, deprecated.useragent);
+    assert_eq!(request.xff, deprecated.xff);
+    assert_eq!(request.authuser, deprecated.authuser);
+    assert_eq!(request.mime_type, deprecated.mime_type);
+    assert_eq!(request.req_bytes, 512);
+    assert_eq!(request.resp_bytes, 2048);
+    assert_eq!(request.duration_us, 1500);
+    assert_eq!(request.status, 201);
+}
+
+#[test]
+fn test_extended_proxy_request_is_modified() {
+    let mut proxy = ExtendedProxyRequest {
+        uri: String::new(),
+        host: String::new(),
+    };
+    assert!(!proxy.is_modified());
+
+    proxy.host = "backend.internal".to_string();
+    assert!(proxy.is_modified());
+
+    proxy.host.clear();
+    proxy.uri = "/v2/items".to_string();
+    assert!(proxy.is_modified());
+}
+
+#[test]
+fn test_extended_80211_tx_retransmissions() {
+    let mut tx = Extended80211Tx {
+        ssid: "TestAP".to_string(),
+        bssid: MacAddress::from([0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF]),
+        version: 4,
+        transmissions: 0,
+        packet_duration: 1000,
+        retrans_duration: 0,
+        channel: 36,
+        speed: 300_000_000,
+        power: 20,
+    };
+    assert_eq!(tx.retransmission_count(), None);
+    assert!(!tx.was_successful_first_try());
+
+    tx.transmissions = 1;
+    assert_eq!(tx.retransmission_count(), Some(0));
+    assert!(tx.was_successful_first_try());
+
+    tx.transmissions = 3;
+    assert_eq!(tx.retransmission_count(), Some(2));
+    assert!(!tx.was_successful_first_try());
+    assert_eq!(tx.transmissions, 3);
+}
+
+#[test]
+fn test_extended_80211_payload_cipher_suite() {
+    let payload = Extended80211Payload {
+        cipher_suite: 0x000FAC04,
+        data: vec![],
+    };
+    assert_eq!(payload.cipher_suite_decoded(), CipherSuite::Ccmp128);
+    assert_eq!(CipherSuite::Ccmp128.as_u32(), 0x000FAC04);
+    assert_eq!(CipherSuite::from(0x000FAC03), CipherSuite::Ieee(3));
+
+    // WPA TKIP under the Microsoft OUI
+    let payload = Extended80211Payload {
+        cipher_suite: 0x0050F202,
+        data: vec![],
+    };
+    let suite = payload.cipher_suite_decoded();
+    assert_eq!(
+        suite,
+        CipherSuite::Vendor {
+            oui: [0x00, 0x50, 0xF2],
+            suite_type: 2
+        }
+    );
+    assert_eq!(suite.as_u32(), 0x0050F202);
+}
+
+#[test]
+fn test_extended_80211_speed_units() {
+    let rx = Extended80211Rx {
+        ssid: "TestAP".to_string(),
+        bssid: MacAddress::from([0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF]),
+        version: 4,
+        channel: 36,
+        speed: 54_000_000,
+        rsni: 180,
+        rcpi: 90,
+        packet_duration: 1000,
+    };
+    assert_eq!(rx.speed_bps(), 54_000_000);
+    assert_eq!(rx.speed_mbps(), 54.0);
+
+    let tx = Extended80211Tx {
+        ssid: "TestAP".to_string(),
+        bssid: MacAddress::from([0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF]),
+        version: 4,
+        transmissions: 1,
+        packet_duration: 1000,
+        retrans_duration: 0,
+        channel: 36,
+        speed: 866_700_000,
+        power: 20,
+    };
+    assert_eq!(tx.speed_bps(), 866_700_000);
+    assert_eq!(tx.speed_mbps(), 866.7);
+}
+
+#[test]
+fn test_extended_80211_rx_packet_duration() {
+    use std::time::Duration;
+
+    let rx = Extended80211Rx {
+        ssid: "TestAP".to_string(),
+        bssid: MacAddress::from([0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF]),
+        version: 4,
+        channel: 36,
+        speed: 54_000_000,
+        rsni: 180,
+        rcpi: 90,
+        packet_duration: 1_250,
+    };
+    assert_eq!(rx.packet_duration_us(), Duration::from_micros(1_250));
+    assert_eq!(rx.packet_duration_us().as_millis(), 1);
+}
+
+#[test]
+fn test_extended_openflow_v1_decoded_match() {
+    // OFPFW_ALL: everything wildcarded
+    let openflow = ExtendedOpenFlowV1 {
+        flow_cookie: 1,
+        flow_match: (1 << 22) - 1,
+        flow_actions: 0,
+    };
+    let wildcards = openflow.decoded_match();
+    assert!(wildcards.in_port && wildcards.dl_vlan && wildcards.tp_dst && wildcards.nw_tos);
+    assert_eq!(wildcards.nw_src_bits, 63);
+    assert_eq!(wildcards.nw_src_prefix_len(), 0);
+
+    // Match on input port, ethertype and a /24 destination only
+    let openflow = ExtendedOpenFlowV1 {
+        flow_cookie: 1,
+        flow_match: 0x0E | 0xE0 | (32 << 8) | (8 << 14) | (1 << 20) | (1 << 21),
+        flow_actions: 0,
+    };
+    assert_eq!(
+        openflow.decoded_match(),
+        OpenFlowWildcards {
+            in_port: false,
+            dl_vlan: true,
+            dl_src: true,
+            dl_dst: true,
+            dl_type: false,
+            nw_proto: true,
+            tp_src: true,
+            tp_dst: true,
+            nw_src_bits: 32,
+            nw_dst_bits: 8,
+            dl_vlan_pcp: true,
+            nw_tos: true,
+        }
+    );
+    assert_eq!(openflow.decoded_match().nw_dst_prefix_len(), 24);
+}
+
+#[test]
+fn test_extended_hw_trap_category() {
+    let trap = ExtendedHwTrap {
+        group: "l3_drops".to_string(),
+        trap: "blackhole_route".to_string(),
+    };
+    assert_eq!(trap.category(), TrapCategory::L3);
+    assert!(trap.is_drop_trap());
+    assert_eq!(trap.trap, "blackhole_route");
+
+    let trap = ExtendedHwTrap {
+        group: "l3_exceptions".to_string(),
+        trap: "mtu_value_is_too_small".to_string(),
+    };
+    assert_eq!(trap.category(), TrapCategory::L3);
+    assert!(!trap.is_drop_trap());
+
+    let trap = ExtendedHwTrap {
+        group: "buffer_drops".to_string(),
+        trap: "tail_drop".to_string(),
+    };
+    assert_eq!(trap.category(), TrapCategory::Buffer);
+    assert!(trap.is_drop_trap());
+
+    assert_eq!(TrapCategory::from_group("lldp"), TrapCategory::Control);
+    assert_eq!(
+        TrapCategory::from_group("vendor_group"),
+        TrapCategory::Other
+    );
+}
+
+#[test]
+fn test_extended_switch_typed_values() {
+    let switch = ExtendedSwitch {
+        src_vlan: 100,
+        src_priority: 3,
+        dst_vlan: 4095,
+        dst_priority: 0,
+    };
+    assert_eq!(switch.src_vlan_value(), Some(100));
+    assert_eq!(switch.src_priority_value(), Some(3));
+    assert_eq!(switch.dst_vlan_value(), Some(4095));
+    assert_eq!(switch.dst_priority_value(), Some(0));
+
+    // 0xFFFFFFFF means unknown, out of range values are not valid either
+    let unknown = ExtendedSwitch {
+        src_vlan: 0xFFFFFFFF,
+        src_priority: 0xFFFFFFFF,
+        dst_vlan: 4096,
+        dst_priority: 8,
+    };
+    assert_eq!(unknown.src_vlan_value(), None);
+    assert_eq!(unknown.src_priority_value(), None);
+    assert_eq!(unknown.dst_vlan_value(), None);
+    assert_eq!(unknown.dst_priority_value(), None);
+    assert_eq!(unknown.src_priority, 0xFFFFFFFF);
+}
+
+#[test]
+fn test_extended_switch_cos_names() {
+    let switch = ExtendedSwitch {
+        src_vlan: 100,
+        src_priority: 5,
+        dst_vlan: 100,
+        dst_priority: 0xFFFFFFFF,
+    };
+    assert_eq!(switch.src_cos_name(), Some("voice"));
+    assert_eq!(switch.dst_cos_name(), None);
+
+    assert_eq!(cos_name(0), "best effort");
+    assert_eq!(cos_name(1), "background");
+    assert_eq!(cos_name(7), "network control");
+    assert_eq!(cos_name(8), "unknown");
+}
+
+#[test]
+fn test_extended_router_next_hop_ip() {
+    use std::net::IpAddr;
+
+    let mut router = ExtendedRouter {
+        next_hop: Address::IPv4(Ipv4Addr::new(192, 168, 1, 1)),
+        src_mask_len: 24,
+        dst_mask_len: 16,
+    };
+    assert_eq!(
+        router.next_hop_ip(),
+        Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)))
+    );
+
+    router.next_hop = Address::Unknown;
+    assert_eq!(router.next_hop_ip(), None);
+}
+
+#[test]
+fn test_extended_mpls_top_labels() {
+    use std::net::IpAddr;
+
+    let mpls = ExtendedMpls {
+        next_hop: Address::IPv6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)),
+        // label 16000 TC 5 TTL 64, then label 24001 bottom of stack TTL 64
+        in_stack: vec![0x03E8_0A40, 0x05DC_1140],
+        out_stack: vec![0x0001_0140], // label 16 bottom of stack TTL 64
+    };
+    assert_eq!(
+        mpls.next_hop_ip(),
+        Some(IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)))
+    );
+    assert_eq!(
+        mpls.top_in_label(),
+        Some(MplsLabel {
+            label: 16000,
+            traffic_class: 5,
+            bottom_of_stack: false,
+            ttl: 64,
+        })
+    );
+    let out = mpls.top_out_label().unwrap();
+    assert_eq!(out.label, 16);
+    assert!(out.bottom_of_stack);
+    assert_eq!(out.to_entry(), 0x0001_0140);
+
+    let empty = ExtendedMpls {
+        next_hop: Address::Unknown,
+        in_stack: vec![],
+        out_stack: vec![],
+    };
+    assert_eq!(empty.next_hop_ip(), None);
+    assert_eq!(empty.top_in_label(), None);
+    assert_eq!(empty.top_out_label(), None);
+}
+
+#[test]
+fn test_enum_display_spec_names() {
+    assert_eq!(TransactionStatus::Succeeded.to_string(), "succeeded");
+    assert_eq!(TransactionStatus::OutOfMemory.to_string(), "outofmemory");
+    assert_eq!(ServiceDirection::Server.to_string(), "server");
+    assert_eq!(AppStatus::InternalError.to_string(), "INTERNAL_ERROR");
+    assert_eq!(AppStatus::from(8).to_string(), "NOT_FOUND");
+    assert_eq!(MemcacheCommand::Cas.to_string(), "CAS");
+    assert_eq!(MemcacheCommand::Touch.to_string(), "TOUCH");
+    assert_eq!(MemcacheStatus::NotStored.to_string(), "NOT_STORED");
+    assert_eq!(MemcacheProtocol::Binary.to_string(), "BINARY");
+    assert_eq!(PacketDirection::Received.to_string(), "received");
+    assert_eq!(AsPathType::AsSequence.to_string(), "AS_SEQUENCE");
+    assert_eq!(HttpMethod::Get.to_string(), "GET");
+}
+
+#[test]
+fn test_infiniband_gid_rendering() {
+    let grh = ExtendedInfiniBandGrh {
+        flow_label: 0,
+        tc: 0,
+        s_gid: [
+            0xFE, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // subnet prefix
+            0x00, 0x02, 0xC9, 0x03, 0x00, 0xA1, 0xB2, 0xC3, // port GUID
+        ],
+        d_gid: [0; 16],
+        next_header: 0x1B,
+        length: 256,
+    };
+
+    assert_eq!(
+        grh.s_gid_string(),
+        "fe80:0000:0000:0000:0002:c903:00a1:b2c3"
+    );
+    assert_eq!(
+        grh.d_gid_string(),
+        "0000:0000:0000:0000:0000:0000:0000:0000"
+    );
+    assert_eq!(
+        grh.s_gid_as_ipv6(),
+        "fe80::2:c903:a1:b2c3".parse::<Ipv6Addr>().unwrap()
+    );
+    assert_eq!(grh.d_gid_as_ipv6(), Ipv6Addr::UNSPECIFIED);
+}
+
+#[test]
+fn test_extended_scsi_names() {
+    let mut scsi = ExtendedScsiStorageTransaction {
+        lun: 1,
+        operation: 0x2A,
+        status: 0x02,
+    };
+    assert_eq!(scsi.operation_name(), Some("WRITE_10"));
+    assert_eq!(scsi.status_name(), Some("CHECK_CONDITION"));
+
+    scsi.operation = ExtendedScsiStorageTransaction::UNKNOWN_OPERATION;
+    scsi.status = 0xFF;
+    assert_eq!(scsi.operation_name(), None);
+    assert_eq!(scsi.status_name(), None);
+}